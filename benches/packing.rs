@@ -0,0 +1,67 @@
+use autosar_xsd_mangler::phases::{self, Input};
+use autosar_xsd_mangler::snapshot;
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::path::Path;
+
+// the number of versions in the real schema, and the number of synthetic lists of versions
+const VERSION_COUNT: u32 = 19;
+const SEQUENCE_COUNT: usize = 10000;
+
+// measure the packing of VERSION_INFO on the lists of versions of the real schemas in $AUTOSAR_XSD_DIR, which
+// contains the 19 versions of the full dataset, and on synthetic lists of versions with the same number of versions
+fn bench_packing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("pack-version-info");
+    group.sample_size(10);
+
+    let synthetic = synthetic_version_sequences();
+    let synthetic_refs: Vec<&[usize]> = synthetic.iter().map(Vec::as_slice).collect();
+    group.bench_function("synthetic", |b| {
+        b.iter(|| snapshot::pack_sequences(&synthetic_refs));
+    });
+
+    if let Some(xsd_dir) = std::env::var_os("AUTOSAR_XSD_DIR") {
+        let input = Input::find(Path::new(&xsd_dir)).unwrap();
+        let parsed = phases::parse(&input).unwrap();
+        let flat_schemas = phases::flatten(&parsed).unwrap();
+        let mut schema = phases::merge(&flat_schemas).unwrap();
+        phases::dedup(&mut schema);
+        let sequences = snapshot::version_info_sequences(&schema).unwrap();
+        let sequence_refs: Vec<&[usize]> = sequences.iter().map(Vec::as_slice).collect();
+        group.bench_function("autosar-xsd-dir", |b| {
+            b.iter(|| snapshot::pack_sequences(&sequence_refs));
+        });
+    }
+    group.finish();
+}
+
+// lists of version masks like those of the sub elements of the real schema: most elements exist in all versions
+// since the one in which they were added, and a few were removed again later
+fn synthetic_version_sequences() -> Vec<Vec<usize>> {
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let all_versions = (1usize << VERSION_COUNT) - 1;
+    (0..SEQUENCE_COUNT)
+        .map(|_| {
+            let len = 1 + next() % 30;
+            (0..len)
+                .map(|_| {
+                    let added = next() % u64::from(VERSION_COUNT);
+                    let mut mask = all_versions & !((1 << added) - 1);
+                    if next() % 8 == 0 {
+                        let removed = added + 1 + next() % (u64::from(VERSION_COUNT) - added);
+                        mask &= (1 << removed) - 1;
+                    }
+                    mask
+                })
+                .collect()
+        })
+        .collect()
+}
+
+criterion_group!(benches, bench_packing);
+criterion_main!(benches);
@@ -0,0 +1,273 @@
+use autosar_xsd_mangler::phases::{self, Input};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+// the number of element types in the older and in the newer version of the medium-sized fixture
+const FIXTURE_TYPES: [usize; 2] = [150, 180];
+
+// measure each phase separately on the medium-sized fixture, and on the real schemas in $AUTOSAR_XSD_DIR if it is set
+fn bench_phases(c: &mut Criterion) {
+    let fixture_dir = write_medium_fixture();
+    run_phases(c, "medium-fixture", &fixture_dir);
+    if let Some(xsd_dir) = std::env::var_os("AUTOSAR_XSD_DIR") {
+        run_phases(c, "autosar-xsd-dir", Path::new(&xsd_dir));
+    }
+    let _ = std::fs::remove_dir_all(fixture_dir);
+}
+
+fn run_phases(c: &mut Criterion, name: &str, xsd_path: &Path) {
+    let input = Input::find(xsd_path).unwrap();
+    assert!(
+        input.file_count() > 0,
+        "no xsd files in {}",
+        xsd_path.display()
+    );
+    let parsed = phases::parse(&input).unwrap();
+    let flat_schemas = phases::flatten(&parsed).unwrap();
+    let merged = phases::merge(&flat_schemas).unwrap();
+    let mut deduplicated = merged.clone();
+    phases::dedup(&mut deduplicated);
+
+    // the generated code is written to gen/ in the current directory
+    let workdir = std::env::temp_dir().join(format!(
+        "autosar-xsd-mangler-bench-{name}-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&workdir).unwrap();
+    let original_dir = std::env::current_dir().unwrap();
+
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    group.bench_function("parse", |b| b.iter(|| phases::parse(&input).unwrap()));
+    group.bench_function("flatten", |b| {
+        b.iter(|| phases::flatten(&parsed).unwrap());
+    });
+    group.bench_function("merge", |b| {
+        b.iter(|| phases::merge(&flat_schemas).unwrap());
+    });
+    group.bench_function("dedup", |b| {
+        b.iter_batched(
+            || merged.clone(),
+            |mut schema| phases::dedup(&mut schema),
+            BatchSize::LargeInput,
+        );
+    });
+    std::env::set_current_dir(&workdir).unwrap();
+    group.bench_function("generate", |b| {
+        b.iter(|| phases::generate(&input, &deduplicated).unwrap());
+    });
+    std::env::set_current_dir(original_dir).unwrap();
+    group.finish();
+
+    let _ = std::fs::remove_dir_all(workdir);
+}
+
+// write two versions of a generated schema into a temporary directory, together with their config.toml
+//
+// The schema has the same structure as tests/fixtures/single/mini.xsd, but it contains many element types.
+// The newer version adds element types, sub elements and enum items, so that the merge has some work to do.
+fn write_medium_fixture() -> PathBuf {
+    let fixture_dir = std::env::temp_dir().join(format!(
+        "autosar-xsd-mangler-bench-fixture-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&fixture_dir);
+    std::fs::create_dir_all(&fixture_dir).unwrap();
+
+    let mut config = String::new();
+    for (idx, type_count) in FIXTURE_TYPES.iter().enumerate() {
+        let filename = format!("AUTOSAR_0005{}.xsd", idx + 1);
+        std::fs::write(
+            fixture_dir.join(&filename),
+            medium_schema(*type_count, idx > 0),
+        )
+        .unwrap();
+        writeln!(
+            config,
+            "[[xsd]]\nname = \"{filename}\"\nident = \"Autosar_0005{}\"\ndesc = \"medium fixture {}\"\n",
+            idx + 1,
+            idx + 1
+        )
+        .unwrap();
+    }
+    std::fs::write(fixture_dir.join("config.toml"), config).unwrap();
+    fixture_dir
+}
+
+fn medium_schema(type_count: usize, newer: bool) -> String {
+    let mut xsd = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<xsd:schema xmlns:AR="http://autosar.org/schema/r4.0" xmlns:xsd="http://www.w3.org/2001/XMLSchema" targetNamespace="http://autosar.org/schema/r4.0" elementFormDefault="qualified" attributeFormDefault="unqualified">
+  <xsd:attributeGroup name="AR-OBJECT">
+    <xsd:attribute name="UUID" type="AR:STRING--SIMPLE"/>
+  </xsd:attributeGroup>
+  <xsd:group name="REFERRABLE">
+    <xsd:sequence>
+      <xsd:element name="SHORT-NAME" type="AR:IDENTIFIER" minOccurs="1" maxOccurs="1"/>
+    </xsd:sequence>
+  </xsd:group>
+  <xsd:complexType name="AUTOSAR" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:element name="AR-PACKAGES" minOccurs="0">
+        <xsd:complexType>
+          <xsd:choice minOccurs="0" maxOccurs="unbounded">
+            <xsd:element name="AR-PACKAGE" type="AR:AR-PACKAGE"/>
+          </xsd:choice>
+        </xsd:complexType>
+      </xsd:element>
+    </xsd:sequence>
+    <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+  </xsd:complexType>
+  <xsd:complexType name="AR-PACKAGE" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:group ref="AR:REFERRABLE"/>
+      <xsd:element name="ELEMENTS" minOccurs="0">
+        <xsd:complexType>
+          <xsd:choice minOccurs="0" maxOccurs="unbounded">
+"#,
+    );
+    for idx in 0..type_count {
+        writeln!(
+            xsd,
+            r#"            <xsd:element name="ELEMENT-{idx}" type="AR:ELEMENT-{idx}"/>"#
+        )
+        .unwrap();
+    }
+    xsd.push_str(
+        r#"          </xsd:choice>
+        </xsd:complexType>
+      </xsd:element>
+    </xsd:sequence>
+    <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+  </xsd:complexType>
+"#,
+    );
+
+    for idx in 0..type_count {
+        let kind = idx % 8;
+        let target = (idx + 1) % type_count;
+        // the newer version adds a sub element to every third type
+        let description = if newer && idx % 3 == 0 {
+            r#"
+      <xsd:element name="DESCRIPTION" type="AR:IDENTIFIER" minOccurs="0"/>"#
+        } else {
+            ""
+        };
+        write!(
+            xsd,
+            r#"  <xsd:group name="ELEMENT-{idx}-CONTENT">
+    <xsd:sequence>
+      <xsd:element name="VALUE-{kind}" type="AR:BOOLEAN" minOccurs="0"/>
+      <xsd:element name="ENABLED" type="AR:BOOLEAN" minOccurs="0"/>
+      <xsd:element name="KIND" type="AR:KIND-{kind}" minOccurs="0"/>
+    </xsd:sequence>
+  </xsd:group>
+  <xsd:complexType name="ELEMENT-{idx}" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:group ref="AR:REFERRABLE"/>{description}
+      <xsd:element name="TARGET-REF" minOccurs="0">
+        <xsd:complexType>
+          <xsd:simpleContent>
+            <xsd:extension base="AR:REF">
+              <xsd:attribute name="DEST" type="AR:ELEMENT-{target}--SUBTYPES-ENUM" use="required"/>
+            </xsd:extension>
+          </xsd:simpleContent>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:element name="CHILDREN" minOccurs="0">
+        <xsd:complexType>
+          <xsd:choice minOccurs="0" maxOccurs="unbounded">
+            <xsd:element name="ELEMENT-{target}" type="AR:ELEMENT-{target}"/>
+          </xsd:choice>
+        </xsd:complexType>
+      </xsd:element>
+      <xsd:group ref="AR:ELEMENT-{idx}-CONTENT"/>
+    </xsd:sequence>
+    <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+    <xsd:attribute name="T" type="AR:STRING--SIMPLE"/>
+  </xsd:complexType>
+  <xsd:simpleType name="ELEMENT-{idx}--SUBTYPES-ENUM">
+    <xsd:restriction base="xsd:string">
+      <xsd:enumeration value="ELEMENT-{idx}"/>
+    </xsd:restriction>
+  </xsd:simpleType>
+"#
+        )
+        .unwrap();
+    }
+
+    for kind in 0..8 {
+        // the newer version adds an enum item
+        let extra_item = if newer {
+            r#"
+        <xsd:enumeration value="EXTRA"/>"#
+        } else {
+            ""
+        };
+        write!(
+            xsd,
+            r#"  <xsd:complexType name="KIND-{kind}" abstract="false" mixed="false">
+    <xsd:simpleContent>
+      <xsd:restriction base="AR:IDENTIFIER">
+        <xsd:enumeration value="FIRST-{kind}"/>
+        <xsd:enumeration value="SECOND-{kind}"/>{extra_item}
+      </xsd:restriction>
+    </xsd:simpleContent>
+  </xsd:complexType>
+"#
+        )
+        .unwrap();
+    }
+
+    xsd.push_str(
+        r#"  <xsd:complexType name="REF" abstract="false" mixed="false">
+    <xsd:simpleContent>
+      <xsd:extension base="AR:REF--SIMPLE">
+        <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+        <xsd:attribute name="BASE" type="AR:IDENTIFIER--SIMPLE"/>
+      </xsd:extension>
+    </xsd:simpleContent>
+  </xsd:complexType>
+  <xsd:complexType name="IDENTIFIER" abstract="false" mixed="false">
+    <xsd:simpleContent>
+      <xsd:extension base="AR:IDENTIFIER--SIMPLE">
+        <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+      </xsd:extension>
+    </xsd:simpleContent>
+  </xsd:complexType>
+  <xsd:complexType name="BOOLEAN" abstract="false" mixed="false">
+    <xsd:simpleContent>
+      <xsd:extension base="AR:BOOLEAN--SIMPLE">
+        <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+      </xsd:extension>
+    </xsd:simpleContent>
+  </xsd:complexType>
+  <xsd:simpleType name="REF--SIMPLE">
+    <xsd:restriction base="xsd:string">
+      <xsd:pattern value="/?[a-zA-Z][a-zA-Z0-9_]{0,127}(/[a-zA-Z][a-zA-Z0-9_]{0,127})*"/>
+    </xsd:restriction>
+  </xsd:simpleType>
+  <xsd:simpleType name="IDENTIFIER--SIMPLE">
+    <xsd:restriction base="xsd:string">
+      <xsd:pattern value="[a-zA-Z][a-zA-Z0-9_]*"/>
+      <xsd:maxLength value="128"/>
+    </xsd:restriction>
+  </xsd:simpleType>
+  <xsd:simpleType name="STRING--SIMPLE">
+    <xsd:restriction base="xsd:string"/>
+  </xsd:simpleType>
+  <xsd:simpleType name="BOOLEAN--SIMPLE">
+    <xsd:restriction base="xsd:string">
+      <xsd:pattern value="0|1|true|false"/>
+    </xsd:restriction>
+  </xsd:simpleType>
+  <xsd:element name="AUTOSAR" type="AR:AUTOSAR"/>
+</xsd:schema>
+"#,
+    );
+    xsd
+}
+
+criterion_group!(benches, bench_phases);
+criterion_main!(benches);
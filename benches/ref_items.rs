@@ -0,0 +1,65 @@
+use autosar_xsd_mangler::snapshot;
+use criterion::{criterion_group, criterion_main, Criterion};
+
+// the number of data types and of distinct ref targets; the real merged schema has a similar number of data types
+const TYPE_COUNT: usize = 8000;
+const TARGET_COUNT: u64 = 1500;
+
+// build REF_ITEMS with the index of the ref strings, and with the full scan that was used before
+fn bench_ref_items(c: &mut Criterion) {
+    let ref_targets = ref_targets(TYPE_COUNT, TARGET_COUNT);
+    let mut group = c.benchmark_group("ref-items");
+    group.sample_size(10);
+    group.bench_function("indexed", |b| {
+        b.iter(|| snapshot::ref_items(&ref_targets));
+    });
+    group.bench_function("linear-scan", |b| b.iter(|| linear_scan(&ref_targets)));
+    group.finish();
+}
+
+// ref targets of many data types: most data types reference a few targets, many of them use the same targets as
+// another data type, and some use only a part of the targets of another data type
+fn ref_targets(type_count: usize, target_count: u64) -> Vec<Vec<String>> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut ref_targets: Vec<Vec<String>> = Vec::with_capacity(type_count);
+    for idx in 0..type_count {
+        let targets = if idx > 0 && idx % 4 == 0 {
+            ref_targets[next() as usize % idx].clone()
+        } else if idx > 0 && idx % 5 == 0 {
+            let other = &ref_targets[next() as usize % idx];
+            other[other.len() / 2..].to_vec()
+        } else {
+            let len = next() % 7;
+            let mut targets: Vec<String> = (0..len)
+                .map(|_| format!("EnumItem::Target{}", next() % target_count))
+                .collect();
+            targets.sort();
+            targets.dedup();
+            targets
+        };
+        ref_targets.push(targets);
+    }
+    ref_targets
+}
+
+// the search without the index, which scans all items for every data type
+fn linear_scan(ref_targets: &[Vec<String>]) -> Vec<String> {
+    let mut all_refstrings = Vec::<String>::new();
+    for targets in ref_targets {
+        if !targets.is_empty()
+            && !(0..all_refstrings.len()).any(|pos| all_refstrings[pos..].starts_with(targets))
+        {
+            all_refstrings.extend_from_slice(targets);
+        }
+    }
+    all_refstrings
+}
+
+criterion_group!(benches, bench_ref_items);
+criterion_main!(benches);
@@ -0,0 +1,9 @@
+#![no_main]
+
+use autosar_xsd_mangler::phases;
+use libfuzzer_sys::fuzz_target;
+
+// malformed and truncated schemas must be reported as an error; any panic is a bug
+fuzz_target!(|data: &[u8]| {
+    let _ = phases::parse_and_flatten_reader(data);
+});
@@ -0,0 +1,125 @@
+use crate::{AutosarDataTypes, XsdFileInfo};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+/// the content of the cache file
+///
+/// The schema is only valid for the exact set of input files that it was built from, which is identified
+/// by `input_hash`. The version of the tool is also stored, since the processing may change between versions.
+#[derive(Deserialize)]
+struct CacheContent {
+    tool_version: String,
+    input_hash: Vec<u8>,
+    namespaces: Vec<String>,
+    schema: AutosarDataTypes,
+}
+
+// same layout as CacheContent, but borrows the data while it is written
+#[derive(Serialize)]
+struct CacheContentRef<'a> {
+    tool_version: &'a str,
+    input_hash: &'a [u8],
+    namespaces: Vec<&'a str>,
+    schema: &'a AutosarDataTypes,
+}
+
+/// load the merged schema from the cache file
+///
+/// `input_files` contains the index of each loaded xsd file in `xsd_config` together with its path.
+/// The cache is only used if it is newer than all input files and the files that they include or redefine, and if
+/// the stored hash of these files matches. The namespaces that were read from the xsd files are applied to
+/// `xsd_config`.
+/// Returns `None` if the cache is missing, outdated or can't be read.
+pub(crate) fn load(
+    cache_path: &Path,
+    input_files: &[(usize, PathBuf)],
+    schema_hash: &[u8; 32],
+    xsd_config: &mut [XsdFileInfo],
+) -> Option<AutosarDataTypes> {
+    let cache_mtime = std::fs::metadata(cache_path).ok()?.modified().ok()?;
+    for filepath in crate::all_input_files(input_files).ok()? {
+        let mtime = std::fs::metadata(filepath).ok()?.modified().ok()?;
+        if mtime >= cache_mtime {
+            println!("cache file {} is outdated", cache_path.display());
+            return None;
+        }
+    }
+
+    let file = File::open(cache_path).ok()?;
+    let content: CacheContent = match bincode::deserialize_from(BufReader::new(file)) {
+        Ok(content) => content,
+        Err(err) => {
+            println!(
+                "Warning: failed to read cache file {}: {err}",
+                cache_path.display()
+            );
+            return None;
+        }
+    };
+    if content.tool_version != env!("CARGO_PKG_VERSION")
+        || content.input_hash != input_hash(input_files, schema_hash)
+        || content.namespaces.len() != xsd_config.len()
+    {
+        println!(
+            "cache file {} does not match the input files",
+            cache_path.display()
+        );
+        return None;
+    }
+
+    for (xsd_file_info, namespace) in xsd_config.iter_mut().zip(content.namespaces) {
+        if xsd_file_info.namespace != namespace {
+            // leaking is fine because the config lives until the program exits
+            xsd_file_info.namespace = namespace.leak();
+        }
+    }
+
+    Some(content.schema)
+}
+
+/// write the merged schema to the cache file
+pub(crate) fn store(
+    cache_path: &Path,
+    input_files: &[(usize, PathBuf)],
+    schema_hash: &[u8; 32],
+    xsd_config: &[XsdFileInfo],
+    schema: &AutosarDataTypes,
+) -> Result<(), String> {
+    let input_hash = input_hash(input_files, schema_hash);
+    let content = CacheContentRef {
+        tool_version: env!("CARGO_PKG_VERSION"),
+        input_hash: &input_hash,
+        namespaces: xsd_config.iter().map(|info| info.namespace).collect(),
+        schema,
+    };
+
+    let file = File::create(cache_path).map_err(|err| {
+        format!(
+            "Error: failed to create cache file {}: {err}",
+            cache_path.display()
+        )
+    })?;
+    bincode::serialize_into(BufWriter::new(file), &content).map_err(|err| {
+        format!(
+            "Error: failed to write cache file {}: {err}",
+            cache_path.display()
+        )
+    })
+}
+
+// the version bit of each file is derived from its index, so the index of each input file is part of the hash,
+// together with `schema_hash`, the hash of the contents of all files
+fn input_hash(input_files: &[(usize, PathBuf)], schema_hash: &[u8; 32]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(schema_hash);
+    for (index, filepath) in input_files {
+        let filename = filepath.file_name().unwrap_or_default();
+        hasher.update((*index as u64).to_le_bytes());
+        hasher.update((filename.len() as u64).to_le_bytes());
+        hasher.update(filename.as_encoded_bytes());
+    }
+    hasher.finalize().to_vec()
+}
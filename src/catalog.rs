@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+use xml::reader::{EventReader, XmlEvent};
+
+/// an entry of a catalog, which maps a public or system identifier to a file
+pub(crate) struct CatalogEntry {
+    pub(crate) identifier: String,
+    pub(crate) filepath: PathBuf,
+}
+
+/// load a catalog, e.g. the autosar.soc file that is part of the AUTOSAR standards
+///
+/// Both the SGML Open Catalog format (`PUBLIC "id" "file"`, `SYSTEM "id" "file"`) and the XML catalog
+/// format of OASIS (`<public publicId="id" uri="file"/>`, `<system systemId="id" uri="file"/>`,
+/// `<uri name="id" uri="file"/>`) are supported. Relative file names are resolved relative to the
+/// directory of the catalog.
+pub(crate) fn load_catalog(catalog_path: &Path) -> Result<Vec<CatalogEntry>, String> {
+    let content = std::fs::read_to_string(catalog_path)
+        .map_err(|err| format!("Error: failed to read {}: {err}", catalog_path.display()))?;
+    let entries = if content.trim_start().starts_with('<') {
+        parse_xml_catalog(&content)
+            .map_err(|err| format!("Error: failed to parse {}: {err}", catalog_path.display()))?
+    } else {
+        parse_sgml_catalog(&content)
+            .map_err(|err| format!("Error: failed to parse {}: {err}", catalog_path.display()))?
+    };
+
+    let base_dir = catalog_path.parent().unwrap_or(Path::new(""));
+    Ok(entries
+        .into_iter()
+        .map(|(identifier, filename)| CatalogEntry {
+            identifier,
+            filepath: base_dir.join(filename),
+        })
+        .collect())
+}
+
+/// find the catalog entry for the configured xsd file name
+///
+/// An entry matches if its identifier ends with the file name, e.g. "http://autosar.org/schema/r4.0/AUTOSAR_00046.xsd",
+/// or if it refers to a file with this name in a different directory.
+pub(crate) fn find_entry<'a>(
+    catalog: &'a [CatalogEntry],
+    xsd_name: &str,
+) -> Option<&'a CatalogEntry> {
+    catalog
+        .iter()
+        .find(|entry| {
+            entry
+                .identifier
+                .rsplit(['/', '\\', ':'])
+                .next()
+                .is_some_and(|last| last == xsd_name)
+        })
+        .or_else(|| {
+            catalog.iter().find(|entry| {
+                entry
+                    .filepath
+                    .file_name()
+                    .is_some_and(|name| name == xsd_name)
+            })
+        })
+}
+
+// SGML catalogs consist of keywords followed by their arguments, which may be quoted;
+// comments are delimited by "--"
+fn parse_sgml_catalog(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if c == '"' || c == '\'' {
+            let token: String = chars.by_ref().take_while(|&next| next != c).collect();
+            tokens.push(token);
+        } else if c == '-' && chars.peek() == Some(&'-') {
+            chars.next();
+            // skip the comment up to and including the closing "--"
+            let mut prev = ' ';
+            for next in chars.by_ref() {
+                if prev == '-' && next == '-' {
+                    break;
+                }
+                prev = next;
+            }
+        } else {
+            let mut token = String::from(c);
+            while let Some(next) = chars.next_if(|next| !next.is_whitespace()) {
+                token.push(next);
+            }
+            tokens.push(token);
+        }
+    }
+
+    let mut entries = Vec::new();
+    let mut tokens = tokens.into_iter();
+    while let Some(keyword) = tokens.next() {
+        // number of arguments of each keyword in the SGML Open catalog format
+        let arg_count = match keyword.to_ascii_uppercase().as_str() {
+            "PUBLIC" | "SYSTEM" | "DELEGATE" | "DOCTYPE" | "ENTITY" | "LINKTYPE" | "NOTATION"
+            | "DTDDECL" => 2,
+            "BASE" | "CATALOG" | "OVERRIDE" | "SGMLDECL" | "DOCUMENT" => 1,
+            _ => return Err(format!("unknown keyword \"{keyword}\"")),
+        };
+        let args: Vec<String> = tokens.by_ref().take(arg_count).collect();
+        if args.len() != arg_count {
+            return Err(format!("missing argument of \"{keyword}\""));
+        }
+        if keyword.eq_ignore_ascii_case("PUBLIC") || keyword.eq_ignore_ascii_case("SYSTEM") {
+            let [identifier, filename]: [String; 2] = args.try_into().unwrap();
+            entries.push((identifier, filename));
+        }
+    }
+    Ok(entries)
+}
+
+fn parse_xml_catalog(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for event in EventReader::new(content.as_bytes()) {
+        let event = event.map_err(|err| err.to_string())?;
+        if let XmlEvent::StartElement {
+            name, attributes, ..
+        } = event
+        {
+            let id_attr = match name.local_name.as_str() {
+                "public" => "publicId",
+                "system" => "systemId",
+                "uri" => "name",
+                _ => continue,
+            };
+            let get_attr = |attr_name: &str| {
+                attributes
+                    .iter()
+                    .find(|attr| attr.name.local_name == attr_name)
+                    .map(|attr| attr.value.clone())
+            };
+            if let (Some(identifier), Some(filename)) = (get_attr(id_attr), get_attr("uri")) {
+                entries.push((identifier, filename));
+            }
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,180 @@
+use crate::{Platforms, XsdFileInfo, AUTOSAR_NAMESPACE};
+use std::path::Path;
+
+/// name of the optional config file in the xsd directory
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// load the list of xsd files from config.toml in the xsd directory
+///
+/// The file contains one `[[xsd]]` table per version, ordered from oldest to newest, with the fields
+/// `name`, `ident` and `desc` of `XsdFileInfo`, and optionally `platforms`, which is a list containing
+/// "classic" and / or "adaptive", and `namespace`. Returns `Ok(None)` if there is no config file.
+pub(crate) fn load_xsd_config(xsd_path: &Path) -> Result<Option<Vec<XsdFileInfo>>, String> {
+    let config_path = xsd_path.join(CONFIG_FILE_NAME);
+    if !config_path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&config_path)
+        .map_err(|err| format!("Error: failed to read {}: {err}", config_path.display()))?;
+    let table = content
+        .parse::<toml::Table>()
+        .map_err(|err| format!("Error: failed to parse {}: {err}", config_path.display()))?;
+
+    let Some(entries) = table.get("xsd").and_then(toml::Value::as_array) else {
+        return Err(format!(
+            "Error: {} does not contain any [[xsd]] entries",
+            config_path.display()
+        ));
+    };
+    // the version of each xsd file is represented by one bit in a u32
+    if entries.len() > 32 {
+        return Err(format!(
+            "Error: {} contains {} xsd files, but at most 32 are supported",
+            config_path.display(),
+            entries.len()
+        ));
+    }
+
+    let mut xsd_config = Vec::with_capacity(entries.len());
+    for (idx, entry) in entries.iter().enumerate() {
+        xsd_config.push(XsdFileInfo {
+            name: get_string_field(entry, "name", idx)?,
+            ident: get_string_field(entry, "ident", idx)?,
+            desc: get_string_field(entry, "desc", idx)?,
+            platforms: get_platforms_field(entry, idx)?,
+            namespace: if entry.get("namespace").is_some() {
+                get_string_field(entry, "namespace", idx)?
+            } else {
+                AUTOSAR_NAMESPACE
+            },
+        });
+    }
+
+    Ok(Some(xsd_config))
+}
+
+fn get_string_field(entry: &toml::Value, field: &str, idx: usize) -> Result<&'static str, String> {
+    let value = entry
+        .get(field)
+        .and_then(toml::Value::as_str)
+        .ok_or_else(|| {
+            format!("Error: xsd entry {idx} in {CONFIG_FILE_NAME} has no string field \"{field}\"")
+        })?;
+    // the config is used until the program exits, so leaking the few strings it contains is harmless,
+    // and it allows the built-in XSD_CONFIG and the loaded config to share the XsdFileInfo type
+    Ok(value.to_owned().leak())
+}
+
+// all recent xsd files describe both platforms, so this is the default if the field is missing
+fn get_platforms_field(entry: &toml::Value, idx: usize) -> Result<Platforms, String> {
+    let Some(value) = entry.get("platforms") else {
+        return Ok(Platforms::ClassicAndAdaptive);
+    };
+    let invalid = || {
+        format!(
+            "Error: xsd entry {idx} in {CONFIG_FILE_NAME} has an invalid field \"platforms\", expected a list containing \"classic\" and / or \"adaptive\""
+        )
+    };
+    let mut classic = false;
+    let mut adaptive = false;
+    for platform in value.as_array().ok_or_else(invalid)? {
+        match platform.as_str() {
+            Some("classic") => classic = true,
+            Some("adaptive") => adaptive = true,
+            _ => return Err(invalid()),
+        }
+    }
+    match (classic, adaptive) {
+        (true, true) => Ok(Platforms::ClassicAndAdaptive),
+        (true, false) => Ok(Platforms::Classic),
+        (false, true) => Ok(Platforms::Adaptive),
+        (false, false) => Err(invalid()),
+    }
+}
+
+/// find the files named AUTOSAR_*.xsd in the xsd directory which are not part of the configuration
+///
+/// The names are sorted, which puts files like AUTOSAR_00053.xsd into the order of their release.
+pub(crate) fn find_unknown_xsd_files(
+    xsd_path: &Path,
+    xsd_config: &[XsdFileInfo],
+) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(xsd_path).map_err(|err| {
+        format!(
+            "Error: failed to read the directory {}: {err}",
+            xsd_path.display()
+        )
+    })?;
+    let mut unknown_files: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|filename| filename.starts_with("AUTOSAR_") && filename.ends_with(".xsd"))
+        .filter(|filename| !xsd_config.iter().any(|info| info.name == filename))
+        .collect();
+    unknown_files.sort();
+    Ok(unknown_files)
+}
+
+/// build the configuration for an xsd file that is not part of the configuration
+///
+/// The identifier is derived from the file name, e.g. "AUTOSAR_00053.xsd" becomes "Autosar_00053".
+/// Returns `None` if the file name can't be turned into a valid identifier.
+pub(crate) fn unknown_xsd_file_info(filename: &str) -> Option<XsdFileInfo> {
+    let suffix = filename.strip_prefix("AUTOSAR_")?.strip_suffix(".xsd")?;
+    let ident = format!("Autosar_{}", suffix.replace(['-', '.'], "_"));
+    if suffix.is_empty() || !ident.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+    // like the strings of the loaded config, these live until the program exits
+    Some(XsdFileInfo {
+        name: filename.to_owned().leak(),
+        ident: ident.leak(),
+        desc: format!("AUTOSAR {suffix} (unknown version)").leak(),
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    })
+}
+
+/// check that the configured xsd files are ordered from oldest to newest
+///
+/// The newest version is the base of the merge, and the order of the sub elements in the generated code depends
+/// on the order in which the older versions are merged into it. A wrongly ordered configuration would silently
+/// change the generated code, so it is rejected. The release is derived from file names like AUTOSAR_4-2-2.xsd
+/// and AUTOSAR_00046.xsd; files with other names can't be placed and are not checked.
+pub(crate) fn check_release_order(xsd_config: &[XsdFileInfo]) -> Result<(), String> {
+    let mut previous: Option<(&str, ReleaseKey)> = None;
+    for info in xsd_config {
+        let Some(key) = release_key(info.name) else {
+            continue;
+        };
+        if let Some((previous_name, previous_key)) = previous {
+            if previous_key >= key {
+                return Err(format!(
+                    "Error: the xsd files must be configured from oldest to newest, but {previous_name} is listed before {}",
+                    info.name
+                ));
+            }
+        }
+        previous = Some((info.name, key));
+    }
+    Ok(())
+}
+
+// the sort key of the release of an xsd file: all releases with a version number like 4-2-2 are older than the
+// releases with a revision number like 00046
+type ReleaseKey = (u32, u32, u32, u32);
+
+fn release_key(filename: &str) -> Option<ReleaseKey> {
+    let suffix = filename.strip_prefix("AUTOSAR_")?.strip_suffix(".xsd")?;
+    if let Some((major, rest)) = suffix.split_once('-') {
+        let (minor, patch) = rest.split_once('-')?;
+        Some((
+            0,
+            major.parse().ok()?,
+            minor.parse().ok()?,
+            patch.parse().ok()?,
+        ))
+    } else {
+        Some((1, suffix.parse().ok()?, 0, 0))
+    }
+}
@@ -1,4 +1,7 @@
-use super::{AutosarDataTypes, Element, ElementCollection, ElementCollectionItem, ElementDataType};
+use super::{
+    AutosarDataTypes, Element, ElementCollection, ElementCollectionItem, ElementDataType, Interner,
+    Symbol,
+};
 use rustc_hash::FxHashMap;
 
 pub(crate) fn dedup_types(autosar_types: &mut AutosarDataTypes) {
@@ -38,24 +41,24 @@ pub(crate) fn dedup_types(autosar_types: &mut AutosarDataTypes) {
     }
 }
 
-fn find_chartype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<String, String> {
+fn find_chartype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<Symbol, Symbol> {
     // build a table of character types to replace by another identical type
     let mut char_replacements = FxHashMap::default();
     let mut char_typenames = autosar_types
         .character_types
         .keys()
-        .cloned()
-        .collect::<Vec<String>>();
-    char_typenames.sort_by(dedup_keycmp);
+        .copied()
+        .collect::<Vec<Symbol>>();
+    char_typenames.sort_by(|key1, key2| dedup_keycmp(&autosar_types.names, *key1, *key2));
     for idx1 in 0..(char_typenames.len() - 1) {
         let typename1 = &char_typenames[idx1];
-        if char_replacements.get(typename1).is_none() {
+        if !char_replacements.contains_key(typename1) {
             for typename2 in char_typenames.iter().skip(idx1 + 1) {
-                if char_replacements.get(typename2).is_none()
+                if !char_replacements.contains_key(typename2)
                     && autosar_types.character_types.get(typename1)
                         == autosar_types.character_types.get(typename2)
                 {
-                    char_replacements.insert(typename2.to_owned(), typename1.to_owned());
+                    char_replacements.insert(*typename2, *typename1);
                 }
             }
         }
@@ -63,25 +66,25 @@ fn find_chartype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap
     char_replacements
 }
 
-fn find_group_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<String, String> {
+fn find_group_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<Symbol, Symbol> {
     // build a table of group types to replace by another identical type
     let mut group_replacements = FxHashMap::default();
     let mut group_typenames = autosar_types
         .group_types
         .keys()
-        .cloned()
-        .collect::<Vec<String>>();
-    group_typenames.sort_by(dedup_keycmp);
+        .copied()
+        .collect::<Vec<Symbol>>();
+    group_typenames.sort_by(|key1, key2| dedup_keycmp(&autosar_types.names, *key1, *key2));
 
     for idx1 in 0..(group_typenames.len() - 1) {
         let typename1 = &group_typenames[idx1];
-        if group_replacements.get(typename1).is_none() {
+        if !group_replacements.contains_key(typename1) {
             for typename2 in group_typenames.iter().skip(idx1 + 1) {
-                if group_replacements.get(typename2).is_none()
+                if !group_replacements.contains_key(typename2)
                     && autosar_types.group_types.get(typename1)
                         == autosar_types.group_types.get(typename2)
                 {
-                    group_replacements.insert(typename2.to_owned(), typename1.to_owned());
+                    group_replacements.insert(*typename2, *typename1);
                 }
             }
         }
@@ -89,24 +92,24 @@ fn find_group_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<St
     group_replacements
 }
 
-fn find_elemtype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<String, String> {
+fn find_elemtype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap<Symbol, Symbol> {
     // build a table of element types to replace by another identical type
     let mut elem_replacements = FxHashMap::default();
     let mut elem_typenames = autosar_types
         .element_types
         .keys()
-        .cloned()
-        .collect::<Vec<String>>();
-    elem_typenames.sort_by(dedup_keycmp);
+        .copied()
+        .collect::<Vec<Symbol>>();
+    elem_typenames.sort_by(|key1, key2| dedup_keycmp(&autosar_types.names, *key1, *key2));
     for idx1 in 0..(elem_typenames.len() - 1) {
         let typename1 = &elem_typenames[idx1];
-        if elem_replacements.get(typename1).is_none() {
+        if !elem_replacements.contains_key(typename1) {
             for typename2 in elem_typenames.iter().skip(idx1 + 1) {
-                if elem_replacements.get(typename2).is_none()
+                if !elem_replacements.contains_key(typename2)
                     && autosar_types.element_types.get(typename1)
                         == autosar_types.element_types.get(typename2)
                 {
-                    elem_replacements.insert(typename2.to_owned(), typename1.to_owned());
+                    elem_replacements.insert(*typename2, *typename1);
                 }
             }
         }
@@ -116,7 +119,7 @@ fn find_elemtype_replacements(autosar_types: &mut AutosarDataTypes) -> FxHashMap
 
 fn replace_element_chartypes(
     autosar_types: &mut AutosarDataTypes,
-    char_replacements: &FxHashMap<String, String>,
+    char_replacements: &FxHashMap<Symbol, Symbol>,
 ) {
     for artype in autosar_types.element_types.values_mut() {
         // replace character types for attributes
@@ -126,7 +129,7 @@ fn replace_element_chartypes(
             | ElementDataType::Mixed { attributes, .. } => {
                 for attr in attributes {
                     if let Some(rep) = char_replacements.get(&attr.attr_type) {
-                        attr.attr_type = rep.to_owned();
+                        attr.attr_type = *rep;
                     }
                 }
             }
@@ -136,7 +139,7 @@ fn replace_element_chartypes(
             ElementDataType::Characters { basetype, .. }
             | ElementDataType::Mixed { basetype, .. } => {
                 if let Some(rep) = char_replacements.get(basetype) {
-                    *basetype = rep.to_owned();
+                    *basetype = *rep;
                 }
             }
             ElementDataType::Elements { .. } => {}
@@ -146,8 +149,8 @@ fn replace_element_chartypes(
 
 fn replace_groupitem_types(
     autosar_types: &mut AutosarDataTypes,
-    elem_replacements: &FxHashMap<String, String>,
-    group_replacements: &FxHashMap<String, String>,
+    elem_replacements: &FxHashMap<Symbol, Symbol>,
+    group_replacements: &FxHashMap<Symbol, Symbol>,
 ) {
     for group_type in autosar_types.group_types.values_mut() {
         match group_type {
@@ -160,12 +163,12 @@ fn replace_groupitem_types(
                             ..
                         }) => {
                             if let Some(rep) = elem_replacements.get(element_typeref) {
-                                *element_typeref = rep.to_owned();
+                                *element_typeref = *rep;
                             }
                         }
                         ElementCollectionItem::GroupRef(group_ref) => {
                             if let Some(rep) = group_replacements.get(group_ref) {
-                                *group_ref = rep.to_owned();
+                                *group_ref = *rep;
                             }
                         }
                     }
@@ -177,7 +180,7 @@ fn replace_groupitem_types(
 
 fn replace_element_grouptypes(
     autosar_types: &mut AutosarDataTypes,
-    group_replacements: &FxHashMap<String, String>,
+    group_replacements: &FxHashMap<Symbol, Symbol>,
 ) {
     for artype in autosar_types.element_types.values_mut() {
         // replace group_refs inside an element type
@@ -185,7 +188,7 @@ fn replace_element_grouptypes(
             ElementDataType::Elements { group_ref, .. }
             | ElementDataType::Mixed { group_ref, .. } => {
                 if let Some(rep) = group_replacements.get(group_ref) {
-                    *group_ref = rep.to_owned();
+                    *group_ref = *rep;
                 }
             }
             ElementDataType::Characters { .. } => {}
@@ -193,7 +196,10 @@ fn replace_element_grouptypes(
     }
 }
 
-pub(crate) fn dedup_keycmp(key1: &String, key2: &String) -> std::cmp::Ordering {
+// shorter names come first, so that the shortest name of identical types is kept
+pub(crate) fn dedup_keycmp(names: &Interner, key1: Symbol, key2: Symbol) -> std::cmp::Ordering {
+    let key1 = names.resolve(key1);
+    let key2 = names.resolve(key2);
     match key1.len().cmp(&key2.len()) {
         std::cmp::Ordering::Equal => key1.cmp(key2),
         nonequal => nonequal,
@@ -1,21 +1,34 @@
 use super::xsd::{
-    Xsd, XsdAttribute, XsdChoice, XsdComplexType, XsdComplexTypeItem, XsdElement, XsdGroup,
-    XsdGroupItem, XsdModelGroupItem, XsdRestriction, XsdSequence, XsdSimpleContent, XsdSimpleType,
-    XsdType,
+    preferred_doctext, Xsd, XsdAttribute, XsdChoice, XsdComplexType, XsdComplexTypeItem,
+    XsdElement, XsdExtension, XsdGroup, XsdGroupItem, XsdModelGroupItem, XsdRestriction,
+    XsdSequence, XsdSimpleContent, XsdSimpleType, XsdType,
 };
 use super::{
     Attribute, AutosarDataTypes, CharacterDataType, Element, ElementAmount, ElementCollection,
-    ElementCollectionItem, ElementDataType, EnumDefinition, HashSet,
+    ElementCollectionItem, ElementDataType, EnumDefinition, HashSet, Interner, Symbol,
 };
+use std::collections::hash_map::Entry;
+use std::io::Write;
 
 #[derive(Debug)]
 enum WorkQueueItem {
-    ElementType(String),
-    CharacterType(String),
-    Group(String),
+    ElementType(Symbol),
+    CharacterType(Symbol),
+    Group(Symbol),
 }
 
 pub(crate) fn flatten_schema(data: &Xsd) -> Result<AutosarDataTypes, String> {
+    flatten_schema_verbose(data, &mut std::io::sink())
+}
+
+/// flatten the schema and write one line to `log` for each processed work queue item
+///
+/// Each line shows the name of the item, the type it was resolved to, and any simplifications
+/// that were applied to the content model while flattening it.
+pub(crate) fn flatten_schema_verbose(
+    data: &Xsd,
+    log: &mut dyn Write,
+) -> Result<AutosarDataTypes, String> {
     let mut autosar_schema = AutosarDataTypes::new();
     let mut work_queue = Vec::new();
 
@@ -26,59 +39,75 @@ pub(crate) fn flatten_schema(data: &Xsd) -> Result<AutosarDataTypes, String> {
         ));
     }
 
+    let names = &mut autosar_schema.names;
     for element in &data.root_elements {
-        work_queue.push(WorkQueueItem::ElementType(element.typeref.clone()));
+        work_queue.push(WorkQueueItem::ElementType(names.intern(&element.typeref)));
     }
 
     while !work_queue.is_empty() {
         match work_queue.pop().unwrap() {
             WorkQueueItem::ElementType(cur_element_typeref) => {
-                if autosar_schema
-                    .element_types
-                    .get(&cur_element_typeref)
-                    .is_none()
+                if let Entry::Vacant(entry) =
+                    autosar_schema.element_types.entry(cur_element_typeref)
                 {
-                    if let Some(XsdType::Complex(complex_type)) =
-                        data.types.get(&cur_element_typeref)
-                    {
-                        let elemtype =
-                            flatten_complex_type(data, complex_type, &cur_element_typeref)?;
+                    let typename = names.resolve(cur_element_typeref).to_string();
+                    if let Some(XsdType::Complex(complex_type)) = data.types.get(&typename) {
+                        let elemtype = flatten_complex_type(data, complex_type, &typename, names)?;
+                        log_step(
+                            log,
+                            &format!(
+                                "element type {typename} -> {}",
+                                describe_element_type(&elemtype, names)
+                            ),
+                            &[],
+                        )?;
 
                         enqueue_dependencies(&mut work_queue, &elemtype);
-                        autosar_schema
-                            .element_types
-                            .insert(cur_element_typeref, elemtype);
+                        entry.insert(elemtype);
                     } else {
-                        autosar_schema.element_types.insert(
-                            cur_element_typeref.clone(),
-                            ElementDataType::Characters {
-                                attributes: Vec::new(),
-                                basetype: cur_element_typeref.clone(),
-                            },
-                        );
-                        work_queue.push(WorkQueueItem::CharacterType(cur_element_typeref.clone()));
+                        log_step(
+                            log,
+                            &format!("element type {typename} -> characters({typename})"),
+                            &["simple type used directly as element content".to_string()],
+                        )?;
+                        entry.insert(ElementDataType::Characters {
+                            attributes: Vec::new(),
+                            basetype: cur_element_typeref,
+                        });
+                        work_queue.push(WorkQueueItem::CharacterType(cur_element_typeref));
                     }
                 }
             }
             WorkQueueItem::CharacterType(cur_char_typeref) => {
-                if autosar_schema
-                    .character_types
-                    .get(&cur_char_typeref)
-                    .is_none()
+                if let Entry::Vacant(entry) = autosar_schema.character_types.entry(cur_char_typeref)
                 {
-                    if let Some(XsdType::Simple(simple_type)) = data.types.get(&cur_char_typeref) {
-                        let chartype = flatten_simple_type(data, simple_type, &cur_char_typeref)?;
-                        autosar_schema
-                            .character_types
-                            .insert(cur_char_typeref, chartype);
+                    let typename = names.resolve(*entry.key());
+                    if let Some(XsdType::Simple(simple_type)) = data.types.get(typename) {
+                        let chartype = flatten_simple_type(data, simple_type, typename)?;
+                        log_step(
+                            log,
+                            &format!(
+                                "character type {typename} -> {}",
+                                describe_character_type(&chartype)
+                            ),
+                            &[],
+                        )?;
+                        entry.insert(chartype);
                     } else {
-                        return Err(format!("Error: unresolvable type {cur_char_typeref}"));
+                        return Err(format!("Error: unresolvable type {typename}"));
                     }
                 }
             }
             WorkQueueItem::Group(cur_group_typeref) => {
-                if let Some(xsd_group) = data.groups.get(&cur_group_typeref) {
-                    let group = flatten_group(data, xsd_group)?;
+                let groupname = names.resolve(cur_group_typeref).to_string();
+                if let Some(xsd_group) = data.groups.get(&groupname) {
+                    let mut notes = Vec::new();
+                    let group = flatten_group(data, xsd_group, names, &mut notes)?;
+                    log_step(
+                        log,
+                        &format!("group {groupname} -> {}", describe_group(&group)),
+                        &notes,
+                    )?;
 
                     enqueue_group_dependencies(&mut work_queue, &group);
                     autosar_schema.group_types.insert(cur_group_typeref, group);
@@ -88,41 +117,257 @@ pub(crate) fn flatten_schema(data: &Xsd) -> Result<AutosarDataTypes, String> {
     }
 
     // these attributes of the root data type, AR:AUTOSAR, are not defined in the xsd files
-    if let Some(ElementDataType::Elements { attributes, .. }) =
-        autosar_schema.element_types.get_mut("AR:AUTOSAR")
+    let string_type = names.intern("xsd:string");
+    if let Some(ElementDataType::Elements { attributes, .. }) = names
+        .get("AR:AUTOSAR")
+        .and_then(|typename| autosar_schema.element_types.get_mut(&typename))
     {
         attributes.push(Attribute {
             name: "xmlns".to_string(),
-            attr_type: "xsd:string".to_string(),
+            attr_type: string_type,
             required: true,
             version_info: data.version_info,
+            docstring: None,
         });
         attributes.push(Attribute {
             name: "xmlns:xsi".to_string(),
-            attr_type: "xsd:string".to_string(),
+            attr_type: string_type,
             required: true,
             version_info: data.version_info,
+            docstring: None,
         });
         attributes.push(Attribute {
             name: "xsi:schemaLocation".to_string(),
-            attr_type: "xsd:string".to_string(),
+            attr_type: string_type,
             required: true,
             version_info: data.version_info,
+            docstring: None,
         });
     }
 
     Ok(autosar_schema)
 }
 
+/// write the type graph of the loaded xsd file in the Graphviz DOT format
+///
+/// This shows the schema before it is flattened: there is one node for each type, group and attribute group,
+/// and the edges show which of them reference each other. Edges for elements and attributes are labeled
+/// with their name. The output can be rendered with e.g. `dot -Tsvg`.
+pub(crate) fn flatten_to_dot(xsd: &Xsd, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "digraph xsd {{")?;
+    writeln!(out, "    rankdir=LR;")?;
+    writeln!(out, "    node [shape=box];")?;
+
+    for element in &xsd.root_elements {
+        let root_id = format!("root:{}", element.name);
+        writeln!(out, "    {} [shape=doubleoctagon];", dot_id(&root_id))?;
+        dot_edge(out, &root_id, &format!("type:{}", element.typeref), None)?;
+    }
+
+    let mut typenames: Vec<&String> = xsd.types.keys().collect();
+    typenames.sort();
+    for typename in typenames {
+        let type_id = format!("type:{typename}");
+        match &xsd.types[typename] {
+            XsdType::Base(_) => {
+                writeln!(out, "    {} [shape=ellipse];", dot_id(&type_id))?;
+            }
+            XsdType::Simple(XsdSimpleType::Restriction(restriction)) => {
+                writeln!(out, "    {} [shape=ellipse];", dot_id(&type_id))?;
+                if let XsdRestriction::Plain { basetype } = restriction {
+                    dot_edge(out, &type_id, &format!("type:{basetype}"), None)?;
+                }
+            }
+            XsdType::Complex(complex_type) => {
+                writeln!(out, "    {};", dot_id(&type_id))?;
+                match &complex_type.item {
+                    XsdComplexTypeItem::SimpleContent(XsdSimpleContent {
+                        extension,
+                        restriction,
+                    }) => {
+                        dot_edge(out, &type_id, &format!("type:{}", extension.basetype), None)?;
+                        if let Some(restriction) = restriction {
+                            let restriction_type = format!("type:{restriction}");
+                            dot_edge(out, &type_id, &restriction_type, Some("restriction"))?;
+                        }
+                        for attr_group in &extension.attribute_groups {
+                            dot_edge(out, &type_id, &format!("attrgroup:{attr_group}"), None)?;
+                        }
+                        for attr in &extension.attributes {
+                            let attr_type = format!("type:{}", attr.typeref);
+                            dot_edge(out, &type_id, &attr_type, Some(&attr.name))?;
+                        }
+                    }
+                    XsdComplexTypeItem::Extension(extension) => {
+                        dot_edge(out, &type_id, &format!("type:{}", extension.basetype), None)?;
+                        if let Some(group) = &extension.group {
+                            dot_edge(out, &type_id, &format!("group:{group}"), None)?;
+                        }
+                        for attr_group in &extension.attribute_groups {
+                            dot_edge(out, &type_id, &format!("attrgroup:{attr_group}"), None)?;
+                        }
+                        for attr in &extension.attributes {
+                            let attr_type = format!("type:{}", attr.typeref);
+                            dot_edge(out, &type_id, &attr_type, Some(&attr.name))?;
+                        }
+                    }
+                    XsdComplexTypeItem::Group(group) => {
+                        dot_edge(out, &type_id, &format!("group:{group}"), None)?;
+                    }
+                    XsdComplexTypeItem::None => {}
+                }
+                for attr in &complex_type.attributes {
+                    let attr_type = format!("type:{}", attr.typeref);
+                    dot_edge(out, &type_id, &attr_type, Some(&attr.name))?;
+                }
+                for attr_group in &complex_type.attribute_groups {
+                    dot_edge(out, &type_id, &format!("attrgroup:{attr_group}"), None)?;
+                }
+            }
+        }
+    }
+
+    let mut groupnames: Vec<&String> = xsd.groups.keys().collect();
+    groupnames.sort();
+    for groupname in groupnames {
+        let group_id = format!("group:{groupname}");
+        writeln!(out, "    {} [shape=component];", dot_id(&group_id))?;
+        match &xsd.groups[groupname].item {
+            XsdGroupItem::Choice(XsdChoice { items, .. })
+            | XsdGroupItem::Sequence(XsdSequence { items }) => {
+                dot_model_group_edges(out, &group_id, items)?;
+            }
+            XsdGroupItem::None => {}
+        }
+    }
+
+    let mut attr_groupnames: Vec<&String> = xsd.attribute_groups.keys().collect();
+    attr_groupnames.sort();
+    for attr_groupname in attr_groupnames {
+        let attr_group_id = format!("attrgroup:{attr_groupname}");
+        writeln!(out, "    {} [shape=note];", dot_id(&attr_group_id))?;
+        for attr in &xsd.attribute_groups[attr_groupname].attributes {
+            let attr_type = format!("type:{}", attr.typeref);
+            dot_edge(out, &attr_group_id, &attr_type, Some(&attr.name))?;
+        }
+    }
+
+    writeln!(out, "}}")
+}
+
+// the items of a choice nested in a group are shown as direct references of the group
+fn dot_model_group_edges(
+    out: &mut dyn Write,
+    group_id: &str,
+    items: &[XsdModelGroupItem],
+) -> std::io::Result<()> {
+    for item in items {
+        match item {
+            XsdModelGroupItem::Group(group) => {
+                dot_edge(out, group_id, &format!("group:{group}"), None)?;
+            }
+            XsdModelGroupItem::Choice(choice) => {
+                dot_model_group_edges(out, group_id, &choice.items)?;
+            }
+            XsdModelGroupItem::Element(element) => {
+                let element_type = format!("type:{}", element.typeref);
+                dot_edge(out, group_id, &element_type, Some(&element.name))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn dot_edge(out: &mut dyn Write, from: &str, to: &str, label: Option<&str>) -> std::io::Result<()> {
+    if let Some(label) = label {
+        writeln!(
+            out,
+            "    {} -> {} [label={}];",
+            dot_id(from),
+            dot_id(to),
+            dot_id(label)
+        )
+    } else {
+        writeln!(out, "    {} -> {};", dot_id(from), dot_id(to))
+    }
+}
+
+// node names contain ':' and '-', so they are always quoted
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn log_step(log: &mut dyn Write, line: &str, notes: &[String]) -> Result<(), String> {
+    let result = if notes.is_empty() {
+        writeln!(log, "{line}")
+    } else {
+        writeln!(log, "{line} [{}]", notes.join("; "))
+    };
+    result.map_err(|err| format!("Error: could not write the flattening log: {err}"))
+}
+
+pub(crate) fn describe_element_type(elemtype: &ElementDataType, names: &Interner) -> String {
+    match elemtype {
+        ElementDataType::Elements { group_ref, .. } => {
+            format!("elements({})", names.resolve(*group_ref))
+        }
+        ElementDataType::Characters { basetype, .. } => {
+            format!("characters({})", names.resolve(*basetype))
+        }
+        ElementDataType::Mixed {
+            group_ref,
+            basetype,
+            ..
+        } => format!(
+            "mixed({}, {})",
+            names.resolve(*group_ref),
+            names.resolve(*basetype)
+        ),
+    }
+}
+
+pub(crate) fn describe_character_type(chartype: &CharacterDataType) -> String {
+    match chartype {
+        CharacterDataType::Pattern { pattern, .. } => format!("pattern({pattern})"),
+        CharacterDataType::Enum(enumdef) => format!("enum({} items)", enumdef.enumitems.len()),
+        CharacterDataType::String {
+            preserve_whitespace,
+            ..
+        } => {
+            if *preserve_whitespace {
+                "string(preserve whitespace)".to_string()
+            } else {
+                "string".to_string()
+            }
+        }
+        CharacterDataType::UnsignedInteger => "unsigned integer".to_string(),
+        CharacterDataType::Double => "double".to_string(),
+        CharacterDataType::DateTime { .. } => "date time".to_string(),
+    }
+}
+
+fn describe_group(group: &ElementCollection) -> String {
+    match group {
+        ElementCollection::Choice {
+            sub_elements,
+            amount,
+            ..
+        } => format!("choice({} items, {amount:?})", sub_elements.len()),
+        ElementCollection::Sequence { sub_elements, .. } => {
+            format!("sequence({} items)", sub_elements.len())
+        }
+    }
+}
+
 fn enqueue_dependencies(work_queue: &mut Vec<WorkQueueItem>, elemtype: &ElementDataType) {
     if let Some(group_ref) = elemtype.group_ref() {
-        work_queue.push(WorkQueueItem::Group(group_ref.clone()));
+        work_queue.push(WorkQueueItem::Group(group_ref));
     }
     for attribute in elemtype.attributes() {
-        work_queue.push(WorkQueueItem::CharacterType(attribute.attr_type.clone()));
+        work_queue.push(WorkQueueItem::CharacterType(attribute.attr_type));
     }
     if let Some(basetype) = elemtype.basetype() {
-        work_queue.push(WorkQueueItem::CharacterType(basetype.to_string()));
+        work_queue.push(WorkQueueItem::CharacterType(basetype));
     }
 }
 
@@ -130,10 +375,10 @@ fn enqueue_group_dependencies(work_queue: &mut Vec<WorkQueueItem>, group: &Eleme
     for item in group.items() {
         match item {
             ElementCollectionItem::Element(Element { typeref, .. }) => {
-                work_queue.push(WorkQueueItem::ElementType(typeref.clone()));
+                work_queue.push(WorkQueueItem::ElementType(*typeref));
             }
             ElementCollectionItem::GroupRef(typeref) => {
-                work_queue.push(WorkQueueItem::Group(typeref.clone()));
+                work_queue.push(WorkQueueItem::Group(*typeref));
             }
         }
     }
@@ -143,12 +388,19 @@ fn flatten_complex_type<'a>(
     data: &'a Xsd,
     complex_type: &'a XsdComplexType,
     complex_type_name: &str,
+    names: &mut Interner,
 ) -> Result<ElementDataType, String> {
-    let attributes = build_attribute_list(data, &Vec::new(), &complex_type.attribute_groups)?;
+    // the attributes declared directly in the complexType come before those of its attribute groups
+    let attributes = build_attribute_list(
+        data,
+        &complex_type.attributes,
+        &complex_type.attribute_groups,
+        names,
+    )?;
 
     match &complex_type.item {
         XsdComplexTypeItem::SimpleContent(simple_content) => {
-            flatten_simple_content(data, simple_content)
+            flatten_simple_content(data, simple_content, names)
         }
         XsdComplexTypeItem::Group(group_ref) => {
             if let Some(group) = data.groups.get(group_ref) {
@@ -164,7 +416,10 @@ fn flatten_complex_type<'a>(
                                 xsd_typenames.insert(strip_ar_prefix(groupname));
                             }
                         }
-                        if xsd_typenames.contains("REFERRABLE") {
+                        // a type can also be referenced if a keyref identity constraint selects its elements
+                        if xsd_typenames.contains("REFERRABLE")
+                            || data.reference_targets.contains(complex_type_name)
+                        {
                             // remove generic base types which are never relevant
                             xsd_typenames.remove("AR-OBJECT");
                             xsd_typenames.remove("REFERRABLE");
@@ -176,26 +431,30 @@ fn flatten_complex_type<'a>(
                         }
 
                         Ok(ElementDataType::Elements {
-                            group_ref: group_ref.clone(),
+                            group_ref: names.intern(group_ref),
                             attributes,
                             xsd_typenames,
-                            // mm_class: complex_type.mm_class.clone(),
+                            mm_class: complex_type.mm_class.clone(),
                         })
                     }
                     XsdGroupItem::Choice(_) => {
                         if complex_type.mixed_content {
                             Ok(ElementDataType::Mixed {
-                                group_ref: group_ref.clone(),
+                                group_ref: names.intern(group_ref),
                                 attributes,
-                                basetype: "xsd:string".to_string(),
-                                // mm_class: complex_type.mm_class.clone(),
+                                basetype: names.intern("xsd:string"),
+                                mm_class: complex_type.mm_class.clone(),
                             })
                         } else {
+                            let mut xsd_typenames = HashSet::default();
+                            if data.reference_targets.contains(complex_type_name) {
+                                xsd_typenames.insert(strip_ar_prefix(complex_type_name));
+                            }
                             Ok(ElementDataType::Elements {
-                                group_ref: group_ref.clone(),
+                                group_ref: names.intern(group_ref),
                                 attributes,
-                                xsd_typenames: HashSet::default(),
-                                // mm_class: complex_type.mm_class.clone(),
+                                xsd_typenames,
+                                mm_class: complex_type.mm_class.clone(),
                             })
                         }
                     }
@@ -211,28 +470,149 @@ fn flatten_complex_type<'a>(
                 ))
             }
         }
+        XsdComplexTypeItem::Extension(extension) => {
+            flatten_complex_content(data, complex_type, complex_type_name, extension, names)
+        }
         XsdComplexTypeItem::None => Err("Error: empty complexType".to_string()),
     }
 }
 
+// the extension inherits the content and the attributes of its base type; the combined content group is built when loading
+fn flatten_complex_content(
+    data: &Xsd,
+    complex_type: &XsdComplexType,
+    complex_type_name: &str,
+    extension: &XsdExtension,
+    names: &mut Interner,
+) -> Result<ElementDataType, String> {
+    let Some(XsdType::Complex(base_type)) = data.types.get(&extension.basetype) else {
+        return Err(format!(
+            "Error: the base type {} of complexType {} is not a complexType",
+            extension.basetype, complex_type.name
+        ));
+    };
+    let mut attributes = build_attribute_list(
+        data,
+        &complex_type.attributes,
+        &complex_type.attribute_groups,
+        names,
+    )?;
+    attributes.append(&mut build_attribute_list(
+        data,
+        &extension.attributes,
+        &extension.attribute_groups,
+        names,
+    )?);
+
+    let mut elemtype =
+        if let (XsdComplexTypeItem::None, Some(group_ref)) = (&base_type.item, &extension.group) {
+            // the base type only contributes attributes
+            ElementDataType::Elements {
+                group_ref: names.intern(group_ref),
+                attributes: build_attribute_list(
+                    data,
+                    &base_type.attributes,
+                    &base_type.attribute_groups,
+                    names,
+                )?,
+                xsd_typenames: HashSet::new(),
+                mm_class: None,
+            }
+        } else {
+            flatten_complex_type(data, base_type, &extension.basetype, names)?
+        };
+
+    match &mut elemtype {
+        ElementDataType::Elements {
+            group_ref,
+            attributes: inner_attributes,
+            xsd_typenames,
+            mm_class,
+        } => {
+            if let Some(extension_group) = &extension.group {
+                *group_ref = names.intern(extension_group);
+            }
+            // the class of the derived type replaces the class of the base type
+            mm_class.clone_from(&complex_type.mm_class);
+            // a reference to the base type may also point to the derived type, which may also be a keyref target itself
+            if !xsd_typenames.is_empty() || data.reference_targets.contains(complex_type_name) {
+                xsd_typenames.insert(strip_ar_prefix(complex_type_name));
+            }
+            inner_attributes.append(&mut attributes);
+        }
+        ElementDataType::Mixed {
+            group_ref,
+            attributes: inner_attributes,
+            mm_class,
+            ..
+        } => {
+            if let Some(extension_group) = &extension.group {
+                *group_ref = names.intern(extension_group);
+            }
+            mm_class.clone_from(&complex_type.mm_class);
+            inner_attributes.append(&mut attributes);
+        }
+        ElementDataType::Characters {
+            attributes: inner_attributes,
+            ..
+        } => {
+            if extension.group.is_some() {
+                return Err(format!(
+                    "Error: complexType {} adds elements to the character content of {}",
+                    complex_type.name, extension.basetype
+                ));
+            }
+            inner_attributes.append(&mut attributes);
+        }
+    }
+
+    Ok(elemtype)
+}
+
 fn flatten_simple_content(
     data: &Xsd,
     simple_content: &XsdSimpleContent,
+    names: &mut Interner,
+) -> Result<ElementDataType, String> {
+    let mut elemtype = flatten_simple_content_base(data, simple_content, names)?;
+    // the facets of a restriction replace the character content of the base type
+    if let Some(restriction) = &simple_content.restriction {
+        if let ElementDataType::Characters { basetype, .. } = &mut elemtype {
+            *basetype = names.intern(restriction);
+        } else {
+            return Err(format!(
+                "Error: simpleContent restriction of {}, which has no character content",
+                simple_content.extension.basetype
+            ));
+        }
+    }
+    Ok(elemtype)
+}
+
+fn flatten_simple_content_base(
+    data: &Xsd,
+    simple_content: &XsdSimpleContent,
+    names: &mut Interner,
 ) -> Result<ElementDataType, String> {
     if let Some(basetype) = data.types.get(&simple_content.extension.basetype) {
         let mut attributes = build_attribute_list(
             data,
             &simple_content.extension.attributes,
             &simple_content.extension.attribute_groups,
+            names,
         )?;
         match basetype {
             XsdType::Base(_) | XsdType::Simple(_) => Ok(ElementDataType::Characters {
                 attributes,
-                basetype: simple_content.extension.basetype.clone(),
+                basetype: names.intern(&simple_content.extension.basetype),
             }),
             XsdType::Complex(complex_type) => {
-                let mut complex_type =
-                    flatten_complex_type(data, complex_type, &simple_content.extension.basetype)?;
+                let mut complex_type = flatten_complex_type(
+                    data,
+                    complex_type,
+                    &simple_content.extension.basetype,
+                    names,
+                )?;
                 // append the attributes attached to the <extension> to the attributes gathered inside the <complexType>
                 match &mut complex_type {
                     ElementDataType::Elements {
@@ -258,15 +638,47 @@ fn flatten_simple_content(
     }
 }
 
-fn flatten_group(data: &Xsd, group: &XsdGroup) -> Result<ElementCollection, String> {
-    match &group.item {
-        XsdGroupItem::Sequence(sequence) => flatten_sequence(data, sequence),
-        XsdGroupItem::Choice(choice) => flatten_choice(data, choice),
-        XsdGroupItem::None => Err("Error: empty group".to_string()),
+fn flatten_group(
+    data: &Xsd,
+    group: &XsdGroup,
+    names: &mut Interner,
+    notes: &mut Vec<String>,
+) -> Result<ElementCollection, String> {
+    let mut collection = match &group.item {
+        XsdGroupItem::Sequence(sequence) => flatten_sequence(data, sequence, names, notes)?,
+        XsdGroupItem::Choice(choice) => flatten_choice(data, choice, names, notes)?,
+        XsdGroupItem::None => return Err("Error: empty group".to_string()),
+    };
+
+    // the tags of the group definition are combined with the tags of each element in the group.
+    // Elements of nested groups that were inlined are included, but groups that remain references are not
+    let ordered = group
+        .tags
+        .get("pureMM.isOrdered")
+        .is_some_and(|val| val == "true");
+    let splittable = group.tags.contains_key("atpSplitable");
+    if ordered || splittable {
+        let (ElementCollection::Choice { sub_elements, .. }
+        | ElementCollection::Sequence { sub_elements, .. }) = &mut collection;
+        for item in sub_elements {
+            if let ElementCollectionItem::Element(element) = item {
+                element.ordered |= ordered;
+                if splittable {
+                    element.splittable_ver = element.version_info;
+                }
+            }
+        }
     }
+
+    Ok(collection)
 }
 
-fn flatten_choice<'a>(data: &'a Xsd, choice: &'a XsdChoice) -> Result<ElementCollection, String> {
+fn flatten_choice<'a>(
+    data: &'a Xsd,
+    choice: &'a XsdChoice,
+    names: &mut Interner,
+    notes: &mut Vec<String>,
+) -> Result<ElementCollection, String> {
     let mut elements: Vec<ElementCollectionItem> = Vec::new();
     let mut outer_amount = occurs_to_amount(choice.min_occurs, choice.max_occurs);
     let mut name = String::new();
@@ -276,7 +688,7 @@ fn flatten_choice<'a>(data: &'a Xsd, choice: &'a XsdChoice) -> Result<ElementCol
         match item {
             XsdModelGroupItem::Group(group_ref) => {
                 if let Some(group) = data.groups.get(group_ref) {
-                    match flatten_group(data, group)? {
+                    match flatten_group(data, group, names, notes)? {
                         ElementCollection::Choice {
                             mut sub_elements,
                             amount: inner_choice_amount,
@@ -284,9 +696,9 @@ fn flatten_choice<'a>(data: &'a Xsd, choice: &'a XsdChoice) -> Result<ElementCol
                         } => {
                             if inner_name.is_empty() {
                                 // split off the prefix "AR:" from the group name and only use the remainder
-                                inner_name = group_ref.split_at(3).1.to_owned();
+                                inner_name = strip_ar_prefix(group_ref);
                             }
-                            flatten_choice_choice(
+                            let note = flatten_choice_choice(
                                 choice,
                                 &mut elements,
                                 &mut sub_elements,
@@ -294,34 +706,54 @@ fn flatten_choice<'a>(data: &'a Xsd, choice: &'a XsdChoice) -> Result<ElementCol
                                 inner_choice_amount,
                                 &mut name,
                                 inner_name,
-                            );
+                            )?;
+                            notes.push(note);
                         }
                         ElementCollection::Sequence {
                             mut sub_elements,
                             name: mut inner_name,
                         } => {
                             if inner_name.is_empty() {
-                                inner_name = group_ref.split_at(3).1.to_owned();
+                                inner_name = strip_ar_prefix(group_ref);
                             }
                             if sub_elements.len() == 1 {
+                                notes.push(format!(
+                                    "single-element sequence {inner_name} inlined into choice"
+                                ));
                                 elements.push(sub_elements[0].clone());
-                            } else if outer_amount == ElementAmount::Any {
+                            } else if amount_bounds(outer_amount).1 != Some(1) {
                                 // the outer type is a choice element that allows repetition.
                                 // In this situation there is no point in preserving the inner sequence:
                                 // sequence elements that occur out of order are equivalen to having multiple smaller ordered sequences
+                                notes.push(format!(
+                                    "sequence {inner_name} inside repeated choice dissolved into choice"
+                                ));
                                 elements.append(&mut sub_elements);
-                            } else if choice.items.len() == 1 && outer_amount != ElementAmount::Any
-                            {
+                            } else if choice.items.len() == 1 {
+                                notes.push(format!(
+                                    "combined choice+sequence {inner_name} → sequence"
+                                ));
+                                if outer_amount != ElementAmount::One {
+                                    // an optional sequence can't be represented directly, so each of its elements becomes optional
+                                    for item in &mut sub_elements {
+                                        if let ElementCollectionItem::Element(element) = item {
+                                            element.amount =
+                                                combine_amounts(outer_amount, element.amount);
+                                        }
+                                    }
+                                }
                                 replacement = Some(ElementCollection::Sequence {
                                     name: inner_name,
                                     sub_elements,
                                 });
                             } else if !sub_elements.is_empty() {
-                                elements.push(ElementCollectionItem::GroupRef(format!(
-                                    "AR:{inner_name}"
-                                )));
+                                elements.push(ElementCollectionItem::GroupRef(
+                                    names.intern(&format!("AR:{inner_name}")),
+                                ));
                             } else {
-                                todo!()
+                                return Err(format!(
+                                    "Error: group {group_ref} inside a choice contains no elements"
+                                ));
                             }
                         }
                     }
@@ -331,30 +763,38 @@ fn flatten_choice<'a>(data: &'a Xsd, choice: &'a XsdChoice) -> Result<ElementCol
                     ));
                 }
             }
-            XsdModelGroupItem::Choice(choice_inner) => match flatten_choice(data, choice_inner)? {
-                ElementCollection::Choice {
-                    mut sub_elements,
-                    amount: inner_choice_amount,
-                    name: inner_name,
-                } => {
-                    flatten_choice_choice(
-                        choice,
-                        &mut elements,
-                        &mut sub_elements,
-                        &mut outer_amount,
-                        inner_choice_amount,
-                        &mut name,
-                        inner_name,
-                    );
-                }
-                ElementCollection::Sequence { .. } => {
-                    todo!();
+            XsdModelGroupItem::Choice(choice_inner) => {
+                match flatten_choice(data, choice_inner, names, notes)? {
+                    ElementCollection::Choice {
+                        mut sub_elements,
+                        amount: inner_choice_amount,
+                        name: inner_name,
+                    } => {
+                        let note = flatten_choice_choice(
+                            choice,
+                            &mut elements,
+                            &mut sub_elements,
+                            &mut outer_amount,
+                            inner_choice_amount,
+                            &mut name,
+                            inner_name,
+                        )?;
+                        notes.push(note);
+                    }
+                    ElementCollection::Sequence {
+                        name: inner_name, ..
+                    } => {
+                        return Err(format!(
+                            "Error: unsupported structure: choice {inner_name} inside a choice was flattened to a sequence"
+                        ));
+                    }
                 }
-            },
+            }
             XsdModelGroupItem::Element(xsd_element) => {
                 elements.push(ElementCollectionItem::Element(Element::new(
                     xsd_element,
                     data.version_info,
+                    names,
                 )));
             }
         }
@@ -379,31 +819,40 @@ fn flatten_choice_choice(
     inner_amount: ElementAmount,
     outer_name: &mut String,
     inner_name: String,
-) {
+) -> Result<String, String> {
     if outer_choice.items.len() == 1 {
         // adjust the amount of the outer choice
+        let note = format!("combined choice+choice {inner_name} → choice");
         *outer_amount = combine_amounts(*outer_amount, inner_amount);
         elements.append(sub_elements);
         if outer_name.is_empty() && !inner_name.is_empty() {
             *outer_name = inner_name;
         }
+        Ok(note)
     } else if *outer_amount == inner_amount {
         elements.append(sub_elements);
+        Ok(format!(
+            "choice {inner_name} with the same amount merged into the outer choice"
+        ))
     } else {
-        todo!()
+        Err(format!(
+            "Error: unsupported structure: choice {inner_name} inside a choice with a different amount"
+        ))
     }
 }
 
 fn flatten_sequence<'a>(
     data: &'a Xsd,
     sequence: &'a XsdSequence,
+    names: &mut Interner,
+    notes: &mut Vec<String>,
 ) -> Result<ElementCollection, String> {
     let mut flat_items = Vec::new();
     for item in &sequence.items {
         match item {
             XsdModelGroupItem::Group(group_ref) => {
                 if let Some(group) = data.groups.get(group_ref) {
-                    flat_items.push(flatten_group(data, group)?);
+                    flat_items.push(flatten_group(data, group, names, notes)?);
                 } else {
                     return Err(format!(
                         "Error: unknown group ref {group_ref} found in sequence"
@@ -411,7 +860,7 @@ fn flatten_sequence<'a>(
                 }
             }
             XsdModelGroupItem::Choice(choice) => {
-                flat_items.push(flatten_choice(data, choice)?);
+                flat_items.push(flatten_choice(data, choice, names, notes)?);
             }
             XsdModelGroupItem::Element(xsd_element) => {
                 flat_items.push(ElementCollection::Sequence {
@@ -419,6 +868,7 @@ fn flatten_sequence<'a>(
                     sub_elements: vec![ElementCollectionItem::Element(Element::new(
                         xsd_element,
                         data.version_info,
+                        names,
                     ))],
                 });
             }
@@ -452,12 +902,16 @@ fn flatten_sequence<'a>(
                         {
                             *element_amount = combine_amounts(*amount, *element_amount);
                         }
+                        notes.push(format!(
+                            "single-element choice {name} inlined into sequence"
+                        ));
                         elements.append(sub_elements);
                     }
                     _ => {
                         // only do anything with this Choice item if it actually contains any elements
                         if nonempty_inputs == 1 {
                             // this Choice item is the only item in the sequence that contains any elements, so the sequence can be turned into a choice
+                            notes.push(format!("combined sequence+choice {name} → choice"));
                             replacement = Some(ElementCollection::Choice {
                                 sub_elements: sub_elements.clone(),
                                 amount: *amount,
@@ -465,18 +919,25 @@ fn flatten_sequence<'a>(
                             });
                         } else if let XsdModelGroupItem::Group(group_ref) = &sequence.items[idx] {
                             // the choice came from a group, we'll only keep a reference to that group here
-                            elements.push(ElementCollectionItem::GroupRef(group_ref.clone()));
-                        } else if data.groups.get(&format!("AR:{name}")).is_some() {
+                            elements.push(ElementCollectionItem::GroupRef(names.intern(group_ref)));
+                        } else if data.groups.contains_key(&format!("AR:{name}")) {
                             // the choice came from a group, we'll only keep a reference to that group here
-                            elements.push(ElementCollectionItem::GroupRef(format!("AR:{name}")));
+                            elements.push(ElementCollectionItem::GroupRef(
+                                names.intern(&format!("AR:{name}")),
+                            ));
                         } else {
-                            todo!()
+                            return Err(format!(
+                                "Error: unsupported structure: choice {name} inside a sequence does not come from a group"
+                            ));
                         }
                     }
                 }
             }
             // outer: Sequence - content item: Sequence
             ElementCollection::Sequence { sub_elements, .. } => {
+                if let XsdModelGroupItem::Group(group_ref) = &sequence.items[idx] {
+                    notes.push(format!("combined sequence+sequence {group_ref} → sequence"));
+                }
                 elements.append(sub_elements);
             }
         }
@@ -492,6 +953,46 @@ fn flatten_sequence<'a>(
     }
 }
 
+// a restriction without facets has the character content of its base type, which is either a built-in type or
+// another simple type, e.g. the original definition of a redefined simple type
+fn flatten_plain_restriction(
+    data: &Xsd,
+    basetype: &str,
+    typename: &str,
+) -> Result<CharacterDataType, String> {
+    let mut visited = vec![typename];
+    let mut basetype = basetype;
+    loop {
+        match basetype {
+            "xsd:double" => return Ok(CharacterDataType::Double),
+            "xsd:unsignedInt" => return Ok(CharacterDataType::UnsignedInteger),
+            "xsd:dateTime" => return Ok(CharacterDataType::DateTime { format: None }),
+            "xsd:string" | "xsd:NMTOKEN" | "xsd:NMTOKENS" => {
+                return Ok(CharacterDataType::String {
+                    max_length: None,
+                    preserve_whitespace: false,
+                })
+            }
+            _ => {}
+        }
+        if visited.contains(&basetype) {
+            return Err(format!(
+                "Error: simpleType {typename} is derived from itself"
+            ));
+        }
+        visited.push(basetype);
+        match data.types.get(basetype) {
+            Some(XsdType::Simple(XsdSimpleType::Restriction(XsdRestriction::Plain {
+                basetype: inner_basetype,
+            }))) => basetype = inner_basetype,
+            Some(XsdType::Simple(simple_type)) => {
+                return flatten_simple_type(data, simple_type, basetype)
+            }
+            _ => return Err(format!("Error: unknown base type {basetype}")),
+        }
+    }
+}
+
 fn flatten_simple_type(
     data: &Xsd,
     simple_type: &XsdSimpleType,
@@ -504,15 +1005,9 @@ fn flatten_simple_type(
                 max_length: *maxlength,
             })
         }
-        XsdSimpleType::Restriction(XsdRestriction::Plain { basetype }) => match &**basetype {
-            "xsd:double" => Ok(CharacterDataType::Double),
-            "xsd:unsignedInt" => Ok(CharacterDataType::UnsignedInteger),
-            "xsd:string" | "xsd:NMTOKEN" | "xsd:NMTOKENS" => Ok(CharacterDataType::String {
-                max_length: None,
-                preserve_whitespace: false,
-            }),
-            _ => Err(format!("Error: unknown base type {basetype}")),
-        },
+        XsdSimpleType::Restriction(XsdRestriction::Plain { basetype }) => {
+            flatten_plain_restriction(data, basetype, typename)
+        }
         XsdSimpleType::Restriction(XsdRestriction::Literal) => Ok(CharacterDataType::String {
             max_length: None,
             preserve_whitespace: true,
@@ -520,7 +1015,7 @@ fn flatten_simple_type(
         XsdSimpleType::Restriction(XsdRestriction::EnumValues { enumvalues }) => {
             let enumitems = enumvalues
                 .iter()
-                .map(|e| (e.clone(), data.version_info))
+                .map(|(value, doctext)| (value.clone(), data.version_info, doctext.clone()))
                 .collect();
             Ok(CharacterDataType::Enum(EnumDefinition {
                 name: typename.to_string(),
@@ -534,17 +1029,18 @@ fn build_attribute_list(
     data: &Xsd,
     xsd_attributes: &Vec<XsdAttribute>,
     xsd_attribute_groups: &Vec<String>,
+    names: &mut Interner,
 ) -> Result<Vec<Attribute>, String> {
     let mut attributes = Vec::new();
 
     for attr in xsd_attributes {
-        attributes.push(build_attribute(data, attr)?);
+        attributes.push(build_attribute(data, attr, names)?);
     }
 
     for attr_group_name in xsd_attribute_groups {
         if let Some(attr_group) = data.attribute_groups.get(attr_group_name) {
             for attr in &attr_group.attributes {
-                attributes.push(build_attribute(data, attr)?);
+                attributes.push(build_attribute(data, attr, names)?);
             }
         } else {
             return Err(format!(
@@ -556,10 +1052,14 @@ fn build_attribute_list(
     Ok(attributes)
 }
 
-fn build_attribute(data: &Xsd, attr: &XsdAttribute) -> Result<Attribute, String> {
+fn build_attribute(
+    data: &Xsd,
+    attr: &XsdAttribute,
+    names: &mut Interner,
+) -> Result<Attribute, String> {
     let attr_type = if let Some(attr_type) = data.types.get(&attr.typeref) {
         match attr_type {
-            XsdType::Base(_) | XsdType::Simple(_) => attr.typeref.clone(),
+            XsdType::Base(_) | XsdType::Simple(_) => names.intern(&attr.typeref),
             XsdType::Complex(_) => {
                 return Err("Error: Complex type for attribute ?!?!".to_string());
             }
@@ -576,21 +1076,28 @@ fn build_attribute(data: &Xsd, attr: &XsdAttribute) -> Result<Attribute, String>
         attr_type,
         required: attr.required,
         version_info: data.version_info,
+        docstring: attr.doctext.clone(),
     })
 }
 
 pub(crate) fn combine_amounts(amount_1: ElementAmount, amount_2: ElementAmount) -> ElementAmount {
-    match (amount_1, amount_2) {
-        (ElementAmount::ZeroOrOne | ElementAmount::One, ElementAmount::ZeroOrOne)
-        | (ElementAmount::ZeroOrOne, ElementAmount::One) => ElementAmount::ZeroOrOne,
-
-        (ElementAmount::One, ElementAmount::One) => ElementAmount::One,
+    // the minimum and maximum numbers of occurrences are multiplied; None means unbounded
+    let (min_1, max_1) = amount_bounds(amount_1);
+    let (min_2, max_2) = amount_bounds(amount_2);
+    let min_occurs = min_1.saturating_mul(min_2);
+    let max_occurs = max_1
+        .zip(max_2)
+        .map_or(usize::MAX, |(max_1, max_2)| max_1.saturating_mul(max_2));
+    occurs_to_amount(min_occurs, max_occurs)
+}
 
-        (
-            ElementAmount::ZeroOrOne | ElementAmount::One | ElementAmount::Any,
-            ElementAmount::Any,
-        )
-        | (ElementAmount::Any, ElementAmount::ZeroOrOne | ElementAmount::One) => ElementAmount::Any,
+fn amount_bounds(amount: ElementAmount) -> (usize, Option<usize>) {
+    match amount {
+        ElementAmount::ZeroOrOne => (0, Some(1)),
+        ElementAmount::One => (1, Some(1)),
+        ElementAmount::Any => (0, None),
+        ElementAmount::Exactly(count) => (count as usize, Some(count as usize)),
+        ElementAmount::AtLeast(count) => (count as usize, None),
     }
 }
 
@@ -599,13 +1106,21 @@ fn occurs_to_amount(min_occurs: usize, max_occurs: usize) -> ElementAmount {
         ElementAmount::One
     } else if min_occurs == 0 && max_occurs == 1 {
         ElementAmount::ZeroOrOne
+    } else if let Ok(count) = u32::try_from(min_occurs) {
+        if min_occurs > 1 && max_occurs == min_occurs {
+            ElementAmount::Exactly(count)
+        } else if min_occurs > 1 && max_occurs == usize::MAX {
+            ElementAmount::AtLeast(count)
+        } else {
+            ElementAmount::Any
+        }
     } else {
         ElementAmount::Any
     }
 }
 
 impl Element {
-    fn new(xsd_element: &XsdElement, version_info: usize) -> Self {
+    fn new(xsd_element: &XsdElement, version_info: usize, names: &mut Interner) -> Self {
         let splittable_ver = if xsd_element.splittable {
             version_info
         } else {
@@ -613,21 +1128,17 @@ impl Element {
         };
         Self {
             name: xsd_element.name.clone(),
-            typeref: xsd_element.typeref.clone(),
+            typeref: names.intern(&xsd_element.typeref),
             amount: occurs_to_amount(xsd_element.min_occurs, xsd_element.max_occurs),
             version_info,
             splittable_ver,
             ordered: xsd_element.ordered,
             restrict_std: xsd_element.restrict_std,
-            docstring: xsd_element.doctext.clone(),
+            docstring: preferred_doctext(&xsd_element.doctext),
         }
     }
 }
 
 fn strip_ar_prefix(typename: &str) -> String {
-    if typename.starts_with("AR:") {
-        typename.strip_prefix("AR:").unwrap().to_string()
-    } else {
-        typename.to_string()
-    }
+    typename.strip_prefix("AR:").unwrap_or(typename).to_string()
 }
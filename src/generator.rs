@@ -1,29 +1,50 @@
 use super::{
-    Attribute, AutosarDataTypes, Element, ElementAmount, ElementCollection, ElementCollectionItem,
-    ElementDataType, XsdFileInfo, XsdRestrictToStandard,
+    AutosarDataTypes, CharacterDataType, ElementAmount, ElementDataType, XsdFileInfo,
+    XsdRestrictToStandard,
 };
 use rustc_hash::FxHashMap;
 use std::collections::HashSet;
+use std::fmt::Write as _;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 mod attributes;
 mod character_types;
 mod element_definitions;
-mod element_types;
-mod identifier_enums;
-mod perfect_hash;
+pub(crate) mod element_types;
+mod html;
+mod html_site;
+pub(crate) mod identifier_enums;
+pub(crate) mod packing;
+mod parent_types;
+mod paths;
+pub(crate) mod perfect_hash;
+mod provenance;
+mod removed_elements;
+mod reports;
 mod subelements;
-mod xsd_versions;
+mod summarize;
+mod template;
+pub(crate) mod xsd_versions;
+
+pub(crate) use character_types::fullmatch_pattern;
+use identifier_enums::IdentifierValues;
+
+// the generator works with a copy of the types in which the type names are resolved
+type Attribute = super::Attribute<String>;
+type Element = super::Element<String>;
+type ElementCollection = super::ElementCollection<String>;
+type ElementCollectionItem = super::ElementCollectionItem<String>;
 
 struct SubelementsInfo {
-    versions_array: Vec<usize>,
-    versions_index_info: FxHashMap<String, usize>,
+    version_sequences: Vec<(String, Vec<usize>)>,
     item_ref_array: Vec<GroupItem>,
     item_ref_info: FxHashMap<String, usize>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum GroupItem {
     ElementRef(usize),
     GroupRef(usize),
@@ -41,9 +62,9 @@ pub(crate) struct SimpleElement {
 }
 
 struct AttributeInfo {
+    version_sequences: Vec<(String, Vec<usize>)>,
     attributes_array: Vec<Attribute>,
     attributes_index_info: FxHashMap<String, (usize, usize)>,
-    attr_ver_index_info: FxHashMap<String, usize>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -52,7 +73,7 @@ pub(crate) enum MergedElementDataType {
         element_collection: ElementCollection,
         attributes: Vec<Attribute>,
         xsd_typenames: HashSet<String>,
-        // mm_class: Option<String>,
+        mm_class: Option<Vec<String>>,
     },
     Characters {
         attributes: Vec<Attribute>,
@@ -62,45 +83,356 @@ pub(crate) enum MergedElementDataType {
         element_collection: ElementCollection,
         attributes: Vec<Attribute>,
         basetype: String,
-        // mm_class: Option<String>,
+        mm_class: Option<Vec<String>>,
     },
     ElementsGroup {
         element_collection: ElementCollection,
     },
 }
 
-pub(crate) fn generate(xsd_config: &[XsdFileInfo], autosar_schema: &AutosarDataTypes) {
-    create_output_dir();
+/// options that control the content of the generated code
+#[derive(Debug, Default)]
+pub(crate) struct GeneratorOptions {
+    /// generate serde Serialize and Deserialize impls for the name enums
+    pub(crate) serde: bool,
+    /// generate an array of &str for the names of the enum items, instead of the compact string table
+    pub(crate) plain_string_tables: bool,
+    /// the input xsd files, which are listed in the header of each generated file
+    pub(crate) input_files: Vec<PathBuf>,
+    /// leave the generation time out of the header, so that the output is reproducible
+    pub(crate) no_timestamp: bool,
+    /// additional (constant name, character type name) pairs, whose index in CHARACTER_DATA is exported
+    pub(crate) chartype_constants: Vec<(String, String)>,
+    /// generate a sorted attribute index for each data type with more than this number of attributes
+    pub(crate) attribute_index_threshold: Option<usize>,
+    /// indices of the configured versions whose xsd file was missing, so that no data was merged for them
+    pub(crate) missing_versions: Vec<usize>,
+    /// index of the version of the vendor extension schema, if there is one
+    pub(crate) extension_version: Option<usize>,
+    /// leave out the compile-time checks of the structure of DATATYPES
+    pub(crate) no_const_assertions: bool,
+    /// write gen/removed_elements.txt and generate the ELEMENT_LAST_VERSION table
+    pub(crate) removed_report: bool,
+    /// write all generated rust modules into this single file, instead of one file per module in gen/
+    pub(crate) rust_module: Option<PathBuf>,
+    /// write the generated files to this directory instead of gen/ in the current directory
+    pub(crate) output_dir: Option<PathBuf>,
+    /// write the html schema browser to this directory
+    pub(crate) html_site: Option<PathBuf>,
+    /// write the availability of the elements in each version to this csv file
+    pub(crate) availability_csv: Option<PathBuf>,
+}
 
-    xsd_versions::generate(xsd_config);
+/// statistics of a run of the generator, which are printed with --stats
+#[derive(Debug, Default)]
+pub struct GeneratorReport {
+    /// all files that were written, in the order in which they were written
+    pub files_written: Vec<PathBuf>,
+    /// the total size of the written files in bytes
+    pub total_bytes: usize,
+    /// the written files which already existed and were replaced
+    pub overwritten_files: Vec<PathBuf>,
+    /// the number of element data types in DATATYPES
+    pub element_type_count: usize,
+    /// the number of element definitions in ELEMENTS
+    pub element_definition_count: usize,
+    /// the number of character data types in CHARACTER_DATA
+    pub character_type_count: usize,
+    /// the number of items of the AttributeName enum
+    pub attribute_name_count: usize,
+    /// the number of items of the EnumItem enum
+    pub enum_item_count: usize,
+}
 
-    identifier_enums::generate(autosar_schema);
+/// the destination of the generated rust modules and reports
+///
+/// By default each module is written to its own file in the output directory. With --emit-rust-module the modules
+/// are only collected, and they are combined into a single file by `finish`. Every written file is recorded in
+/// the report.
+pub(crate) struct CodeOutput<'a> {
+    output_dir: &'a Path,
+    rust_module: Option<&'a Path>,
+    header: &'a str,
+    modules: Vec<(String, String)>,
+    report: GeneratorReport,
+}
 
-    generate_types(autosar_schema);
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    autosar_schema: &AutosarDataTypes,
+    options: &GeneratorOptions,
+    schema_hash: &[u8; 32],
+) -> Result<GeneratorReport, String> {
+    let output_dir = options.output_dir();
+    create_output_dir(output_dir);
+
+    let header = provenance::build_header(&options.input_files, !options.no_timestamp)?;
+
+    let mut output = CodeOutput::new(output_dir, options.rust_module.as_deref(), &header);
+
+    output.write(
+        "autosarversion.rs",
+        &xsd_versions::generate(xsd_config, options, schema_hash, &header),
+    );
+
+    let identifier_values =
+        identifier_enums::generate(xsd_config, autosar_schema, options, &header, &mut output)?;
+
+    let unmatched_ref_targets = generate_types(
+        autosar_schema,
+        xsd_config,
+        options,
+        &identifier_values,
+        &header,
+        &mut output,
+    )?;
+
+    paths::generate(&header, &mut output);
+
+    summarize::generate(
+        xsd_config,
+        autosar_schema,
+        &unmatched_ref_targets,
+        &header,
+        &mut output,
+    )?;
+
+    output.finish()?;
+
+    let mut report = output.report;
+    report.character_type_count = autosar_schema.character_types.len();
+    report.attribute_name_count = identifier_values.attribute_names.len();
+    report.enum_item_count = identifier_values.enum_items.len();
+    Ok(report)
 }
 
-fn create_output_dir() {
-    let _ = std::fs::create_dir("gen");
+/// find the DEST values that don't match the xsd type name of any element type, and print a warning for each of them
+///
+/// This is the same check as in the generation of specification.rs, for --validate-only.
+pub(crate) fn unmatched_ref_targets(autosar_schema: &AutosarDataTypes) -> Vec<(String, String)> {
+    let element_types = merge_element_groups(autosar_schema);
+    let unmatched_ref_targets = element_types::find_unmatched_ref_targets(
+        &element_types,
+        &resolve_character_types(autosar_schema),
+    );
+    print_unmatched_ref_targets(&unmatched_ref_targets);
+    unmatched_ref_targets
 }
 
-/// generate the information about element data types in specification.rs
-pub(crate) fn generate_types(autosar_schema: &AutosarDataTypes) {
-    let mut generated = String::from(
-        r#"// This file is @generated
+fn print_unmatched_ref_targets(unmatched_ref_targets: &[(String, String)]) {
+    for (dest_value, chartype) in unmatched_ref_targets {
+        println!("Warning: the DEST value {dest_value} of {chartype} does not match the xsd type name of any element type; references with this DEST value can't be validated");
+    }
+}
+
+/// check that the regex of every pattern in the schema has a matching validator in the generated code
+pub(crate) fn check_regexes(autosar_schema: &AutosarDataTypes) -> Result<(), String> {
+    character_types::check_regexes(autosar_schema)
+}
+
+fn create_output_dir(output_dir: &Path) {
+    let _ = std::fs::create_dir(output_dir);
+}
+
+impl GeneratorOptions {
+    /// the directory of the generated files, gen/ in the current directory unless it was set
+    pub(crate) fn output_dir(&self) -> &Path {
+        self.output_dir.as_deref().unwrap_or(Path::new("gen"))
+    }
+}
+
+impl<'a> CodeOutput<'a> {
+    fn new(output_dir: &'a Path, rust_module: Option<&'a Path>, header: &'a str) -> Self {
+        Self {
+            output_dir,
+            rust_module,
+            header,
+            modules: Vec::new(),
+            report: GeneratorReport::default(),
+        }
+    }
+
+    /// write the generated module `filename`, e.g. "paths.rs"; `content` starts with the header
+    pub(crate) fn write(&mut self, filename: &str, content: &str) {
+        if self.rust_module.is_none() {
+            let path = self.output_dir.join(filename);
+            let formatted = format_or_warn(&path.to_string_lossy(), content);
+            self.record(&path, formatted.len());
+            let mut file = File::create(path).unwrap();
+            file.write_all(formatted.as_bytes()).unwrap();
+        }
+        self.modules.push((filename.to_owned(), content.to_owned()));
+    }
+
+    /// the file names and the unformatted content of all modules that were written so far
+    pub(crate) fn modules(&self) -> &[(String, String)] {
+        &self.modules
+    }
+
+    /// write a report like schema_stats.txt to the output directory; unlike the modules, it is never combined
+    pub(crate) fn write_text(&mut self, filename: &str, content: &str) -> Result<(), String> {
+        let path = self.output_dir.join(filename);
+        self.write_to(&path, content)
+    }
 
-use crate::*;
+    /// write a file outside of the output directory, e.g. a page of the html schema browser
+    pub(crate) fn write_to(&mut self, path: &Path, content: &str) -> Result<(), String> {
+        self.record(path, content.len());
+        std::fs::write(path, content)
+            .map_err(|err| format!("Error: could not write {}: {err}", path.display()))
+    }
+
+    // with --emit-rust-module, write the combined modules to the single output file
+    fn finish(&mut self) -> Result<(), String> {
+        let Some(rust_module) = self.rust_module else {
+            return Ok(());
+        };
+        let filename = rust_module.to_string_lossy();
+        let combined = combine_modules(self.header, &self.modules);
+        let formatted = format_or_warn(&filename, &combined);
+        self.record(rust_module, formatted.len());
+        let file = File::create(rust_module)
+            .map_err(|err| format!("Error: could not create {filename}: {err}"))?;
+        let mut writer = BufWriter::new(file);
+        writer
+            .write_all(formatted.as_bytes())
+            .and_then(|()| writer.flush())
+            .map_err(|err| format!("Error: could not write {filename}: {err}"))
+    }
+
+    // add a file to the report before it is written, so that it can be seen whether it existed already
+    fn record(&mut self, path: &Path, size: usize) {
+        if path.exists() {
+            self.report.overwritten_files.push(path.to_path_buf());
+        }
+        self.report.files_written.push(path.to_path_buf());
+        self.report.total_bytes += size;
+    }
+}
+
+/// combine the generated modules into the content of a single module
+///
+/// The header is only placed once at the top, followed by the use and extern crate declarations of all modules without
+/// duplicates.
+/// Imports from the other generated modules are left out, since their items are now part of the same module.
+/// The test modules are renamed after their module, because each module has a test module named `test`.
+/// The combined module refers to the crate root like the separate modules, so it can't be included at the crate root
+/// itself, but e.g. as `mod specification { include!("...") }`, with `pub use specification::*` at the crate root.
+fn combine_modules(header: &str, modules: &[(String, String)]) -> String {
+    let module_names: Vec<String> = modules
+        .iter()
+        .map(|(filename, _)| {
+            Path::new(filename)
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    let is_generated_import = |line: &str| {
+        module_names
+            .iter()
+            .any(|name| line.starts_with(&format!("use crate::{name}::")))
+    };
+
+    let mut use_lines: Vec<&str> = Vec::new();
+    let mut sections = String::new();
+    for ((_, content), module_name) in modules.iter().zip(&module_names) {
+        let content = content.strip_prefix(header).unwrap_or(content);
+        let mut lines = content.lines().peekable();
+        let is_declaration =
+            |line: &str| line.starts_with("use ") || line.starts_with("extern crate ");
+        while let Some(line) = lines.next_if(|line| line.is_empty() || is_declaration(line)) {
+            if is_declaration(line) && !is_generated_import(line) && !use_lines.contains(&line) {
+                use_lines.push(line);
+            }
+        }
+
+        writeln!(sections, "\n// ---- module {module_name} ----\n").unwrap();
+        for line in lines {
+            if line == "mod test {" {
+                writeln!(sections, "mod {module_name}_test {{").unwrap();
+            } else {
+                sections.push_str(line);
+                sections.push('\n');
+            }
+        }
+    }
+
+    format!("{header}{}\n{sections}", use_lines.join("\n"))
+}
+
+// the content is formatted with rustfmt if it is available; otherwise it is written unchanged.
+// Large tables are marked with #[rustfmt::skip] by the generator, so they keep their compact layout.
+fn format_or_warn(filename: &str, content: &str) -> String {
+    match format_generated_code(content) {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            println!("Warning: {filename} was not formatted: {err}");
+            content.to_owned()
+        }
+    }
+}
+
+fn format_generated_code(content: &str) -> Result<String, String> {
+    let mut child = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("could not run rustfmt: {err}"))?;
+
+    // the input is written from a separate thread, since rustfmt might start writing its output before it has
+    // read all of the input. Writing everything first could block both processes if the pipe buffers are full.
+    let mut stdin = child.stdin.take().unwrap();
+    let input = content.to_owned();
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child
+        .wait_with_output()
+        .map_err(|err| format!("rustfmt failed: {err}"))?;
+    writer
+        .join()
+        .unwrap()
+        .map_err(|err| format!("could not write to rustfmt: {err}"))?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .map_err(|err| format!("invalid output from rustfmt: {err}"))
+    } else {
+        Err(format!(
+            "rustfmt failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// generate the information about element data types in specification.rs
+///
+/// Returns the DEST values that don't match any element type, see `element_types::find_unmatched_ref_targets`
+pub(crate) fn generate_types(
+    autosar_schema: &AutosarDataTypes,
+    xsd_config: &[XsdFileInfo],
+    options: &GeneratorOptions,
+    identifier_values: &IdentifierValues,
+    header: &str,
+    output: &mut CodeOutput,
+) -> Result<Vec<(String, String)>, String> {
+    let mut generated = String::from(header);
+    generated.push_str(
+        r#"use crate::*;
 use crate::regex::*;
 
 #[cfg(feature = "docstrings")]
 macro_rules! element {
-    ($namepart:ident, $etype:literal, $mult:ident, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
-        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict, docstring: $docid}
+    ($namepart:ident, $etype:literal, $mult:ident $(($count:literal))?, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
+        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult $(($count))?, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict, docstring: $docid}
     };
 }
 #[cfg(not(feature = "docstrings"))]
 macro_rules! element {
-    ($namepart:ident, $etype:literal, $mult:ident, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
-        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict}
+    ($namepart:ident, $etype:literal, $mult:ident $(($count:literal))?, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
+        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult $(($count))?, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict}
     };
 }
 
@@ -116,26 +448,55 @@ macro_rules! g {
     };
 }
 
+macro_rules! spec {
+    (sub_elements: $sub_elements:expr, sub_element_ver: $sub_element_ver:literal, attributes: $attributes:expr, attributes_ver: $attributes_ver:literal, character_data: $character_data:expr, mode: $mode:ident, ref_info: $ref_info:expr) => {
+        ElementSpec{sub_elements: $sub_elements, sub_element_ver: $sub_element_ver, attributes: $attributes, attributes_ver: $attributes_ver, character_data: $character_data, mode: ContentMode::$mode, ref_info: $ref_info}
+    };
+}
+
+macro_rules! character_data {
+    (Pattern{check_fn: $check_fn:ident, regex: $regex:literal, max_length: $max_length:expr}) => {
+        CharacterDataSpec::Pattern{check_fn: $check_fn, regex: $regex, max_length: $max_length}
+    };
+    (Enum{items: $items:expr}) => {
+        CharacterDataSpec::Enum{items: $items}
+    };
+    (String{preserve_whitespace: $preserve_whitespace:literal, max_length: $max_length:expr}) => {
+        CharacterDataSpec::String{preserve_whitespace: $preserve_whitespace, max_length: $max_length}
+    };
+    (UnsignedInteger) => {
+        CharacterDataSpec::UnsignedInteger
+    };
+    (Double) => {
+        CharacterDataSpec::Double
+    };
+    (DateTime{format: $format:expr}) => {
+        CharacterDataSpec::DateTime{format: $format}
+    };
+}
+
 
 
 "#,
     );
 
-    let element_types =
-        merge_element_groups(&autosar_schema.element_types, &autosar_schema.group_types);
+    let element_types = merge_element_groups(autosar_schema);
+    let chartypes = resolve_character_types(autosar_schema);
 
-    let character_types = character_types::generate(autosar_schema);
+    let character_types = character_types::generate(autosar_schema, &options.chartype_constants)?;
     generated.push_str(&character_types);
 
     let element_definitions_array = element_definitions::build_info(&element_types);
-    let docstring_ids = element_definitions::build_docstrings_info(&element_definitions_array);
+    output.report.element_type_count = element_types.len();
+    output.report.element_definition_count = element_definitions_array.len();
+    let docstring_ids =
+        element_definitions::build_docstrings_info(&element_definitions_array, &element_types);
 
     let SubelementsInfo {
-        mut versions_array,
-        versions_index_info,
+        version_sequences: subelement_version_sequences,
         item_ref_array,
         item_ref_info,
-    } = subelements::build_info(&element_types, &element_definitions_array);
+    } = subelements::build_info(&element_types, &element_definitions_array)?;
 
     generated.push_str(&element_definitions::generate(
         &element_types,
@@ -143,84 +504,185 @@ macro_rules! g {
         &docstring_ids,
     ));
 
+    let all_versions_mask = (0..xsd_config.len())
+        .filter(|idx| !options.missing_versions.contains(idx))
+        .fold(0, |mask, idx| mask | (1 << idx));
+    let version_masks = removed_elements::element_version_masks(
+        &element_types,
+        &element_definitions_array,
+        all_versions_mask,
+    );
+    if options.removed_report {
+        let standard_mask = options
+            .extension_version
+            .map_or(all_versions_mask, |idx| all_versions_mask & !(1 << idx));
+        generated.push_str(&removed_elements::generate_last_version_table(
+            &version_masks,
+            standard_mask,
+        ));
+        removed_elements::write_report(
+            xsd_config,
+            &element_definitions_array,
+            &version_masks,
+            &options.missing_versions,
+            options.extension_version,
+            output,
+        )?;
+    }
+
     generated.push_str(&subelements::generate(&item_ref_array));
 
+    let parent_types = parent_types::build_info(&element_types, &element_definitions_array);
+    generated.push_str(&parent_types::generate(&parent_types));
+
+    let present_versions: Vec<usize> = (0..xsd_config.len())
+        .filter(|idx| !options.missing_versions.contains(idx))
+        .collect();
+    html::generate(xsd_config, &element_types, &present_versions, output)?;
+    if let Some(site_dir) = &options.html_site {
+        html_site::generate(
+            xsd_config,
+            &element_types,
+            &chartypes,
+            &present_versions,
+            site_dir,
+            output,
+        )?;
+    }
+    if let Some(csv_path) = &options.availability_csv {
+        reports::write_availability_csv(
+            xsd_config,
+            &element_types,
+            &present_versions,
+            csv_path,
+            output,
+        )?;
+    }
+
     let AttributeInfo {
+        version_sequences: attribute_version_sequences,
         attributes_array,
         attributes_index_info,
-        attr_ver_index_info,
-    } = attributes::build_info(&element_types, &mut versions_array);
+    } = attributes::build_info(&element_types)?;
 
-    generated.push_str(&attributes::generate(autosar_schema, &attributes_array));
+    // the version lists of the sub elements and of the attributes share the VERSION_INFO array
+    let (versions_array, versions_index_info, attr_ver_index_info) =
+        pack_version_sequences(&subelement_version_sequences, &attribute_version_sequences)?;
+
+    generated.push_str(&attributes::generate(
+        autosar_schema,
+        &attributes_array,
+        &docstring_ids,
+    ));
+
+    if let Some(threshold) = options.attribute_index_threshold {
+        generated.push_str(&attributes::generate_attribute_index(
+            &element_types,
+            &attributes_array,
+            &attributes_index_info,
+            &identifier_values.attribute_names,
+            threshold,
+        ));
+    }
 
     generated.push_str(&generate_versions_array(&versions_array));
 
+    generated.push_str(&generate_element_count_per_version(
+        &version_masks,
+        xsd_config.len(),
+    ));
+
     generated.push_str(&element_types::generate(
         &element_types,
-        &autosar_schema.character_types,
+        &chartypes,
         &item_ref_info,
         &versions_index_info,
         &attributes_index_info,
         &attr_ver_index_info,
     ));
 
+    generated.push_str(&element_types::generate_ref_targets(
+        &element_types,
+        &chartypes,
+        &identifier_values.enum_items,
+    ));
+
+    let unmatched_ref_targets =
+        element_types::find_unmatched_ref_targets(&element_types, &chartypes);
+    print_unmatched_ref_targets(&unmatched_ref_targets);
+
+    generated.push_str(&element_types::generate_stable_ids(&element_types)?);
+
     generated.push_str(&element_definitions::generate_docstrings(&docstring_ids));
 
-    let mut file = File::create("gen/specification.rs").unwrap();
-    file.write_all(generated.as_bytes()).unwrap();
+    if !options.no_const_assertions {
+        generated.push_str(element_types::DATATYPES_ASSERTIONS);
+    }
+
+    output.write("specification.rs", &generated);
+
+    Ok(unmatched_ref_targets)
 }
 
 /// merge the group types into the element types
 /// This removes one layer of indirection in the generated output and simplifies
 /// the common case - with few exceptions an element type contains a list of
 /// elements and no additional groups are involved
+///
+/// The type names of the merged element types are resolved.
 fn merge_element_groups(
-    element_types: &FxHashMap<String, ElementDataType>,
-    group_types: &FxHashMap<String, ElementCollection>,
+    autosar_schema: &AutosarDataTypes,
 ) -> FxHashMap<String, MergedElementDataType> {
+    let names = &autosar_schema.names;
     let mut merged_element_types = FxHashMap::default();
     let mut needed_groups = HashSet::new();
-    for (ename, etype) in element_types {
+    for (ename, etype) in &autosar_schema.element_types {
         merged_element_types.insert(
-            ename.clone(),
-            match etype {
+            names.resolve(*ename).to_string(),
+            match etype.resolve(names) {
                 ElementDataType::Elements {
                     group_ref,
                     attributes,
                     xsd_typenames,
-                    // mm_class,
+                    mm_class,
                 } => {
-                    let mut element_collection = group_types.get(group_ref).unwrap().clone();
+                    let mut element_collection = autosar_schema
+                        .group_type(&group_ref)
+                        .unwrap()
+                        .resolve(names);
                     update_group_deps(&mut element_collection, &mut needed_groups);
 
                     MergedElementDataType::Elements {
                         element_collection,
-                        attributes: attributes.clone(),
-                        xsd_typenames: xsd_typenames.clone(),
-                        // mm_class: mm_class.clone(),
+                        attributes,
+                        xsd_typenames,
+                        mm_class,
                     }
                 }
                 ElementDataType::Characters {
                     attributes,
                     basetype,
                 } => MergedElementDataType::Characters {
-                    attributes: attributes.clone(),
-                    basetype: basetype.clone(),
+                    attributes,
+                    basetype,
                 },
                 ElementDataType::Mixed {
                     group_ref,
                     attributes,
                     basetype,
-                    // mm_class,
+                    mm_class,
                 } => {
-                    let mut element_collection = group_types.get(group_ref).unwrap().clone();
+                    let mut element_collection = autosar_schema
+                        .group_type(&group_ref)
+                        .unwrap()
+                        .resolve(names);
                     update_group_deps(&mut element_collection, &mut needed_groups);
 
                     MergedElementDataType::Mixed {
                         element_collection,
-                        attributes: attributes.clone(),
-                        basetype: basetype.clone(),
-                        // mm_class: mm_class.clone(),
+                        attributes,
+                        basetype,
+                        mm_class,
                     }
                 }
             },
@@ -235,9 +697,9 @@ fn merge_element_groups(
         // with existing names, the names of group elements end with ":GROUP"
         let element_type_name = format!("{groupname}:GROUP");
         // get the referenced group
-        let group = group_types.get(&groupname).unwrap();
+        let group = autosar_schema.group_type(&groupname).unwrap();
         // copy the element collection; the copy is updated with the modified group names
-        let mut element_collection = group.clone();
+        let mut element_collection = group.resolve(names);
         update_group_deps(&mut element_collection, &mut needed_groups);
         let old = merged_element_types.insert(
             element_type_name,
@@ -249,6 +711,22 @@ fn merge_element_groups(
     merged_element_types
 }
 
+// the character types by their resolved names
+fn resolve_character_types(
+    autosar_schema: &AutosarDataTypes,
+) -> FxHashMap<String, CharacterDataType> {
+    autosar_schema
+        .character_types
+        .iter()
+        .map(|(typename, chartype)| {
+            (
+                autosar_schema.names.resolve(*typename).to_string(),
+                chartype.clone(),
+            )
+        })
+        .collect()
+}
+
 fn update_group_deps(
     element_collection: &mut ElementCollection,
     needed_groups: &mut HashSet<String>,
@@ -266,9 +744,76 @@ fn update_group_deps(
     }
 }
 
+/// the lists of versions of the sub elements and of the attributes of each element type, which are packed into
+/// VERSION_INFO
+#[cfg(feature = "testing")]
+pub(crate) fn version_sequences(
+    autosar_schema: &AutosarDataTypes,
+) -> Result<Vec<Vec<usize>>, String> {
+    let element_types = merge_element_groups(autosar_schema);
+    let element_definitions_array = element_definitions::build_info(&element_types);
+    let SubelementsInfo {
+        version_sequences: subelement_version_sequences,
+        ..
+    } = subelements::build_info(&element_types, &element_definitions_array)?;
+    let AttributeInfo {
+        version_sequences: attribute_version_sequences,
+        ..
+    } = attributes::build_info(&element_types)?;
+    Ok(subelement_version_sequences
+        .into_iter()
+        .chain(attribute_version_sequences)
+        .map(|(_, seq)| seq)
+        .collect())
+}
+
+/// VERSION_INFO, and the offsets of the lists of versions of the sub elements and of the attributes of each element type
+pub(crate) type PackedVersions = (
+    Vec<usize>,
+    FxHashMap<String, usize>,
+    FxHashMap<String, usize>,
+);
+
+// pack all lists of versions into one array, and return the offset of the lists of each element type
+pub(crate) fn pack_version_sequences(
+    subelement_version_sequences: &[(String, Vec<usize>)],
+    attribute_version_sequences: &[(String, Vec<usize>)],
+) -> Result<PackedVersions, String> {
+    let sequence_refs: Vec<&[usize]> = subelement_version_sequences
+        .iter()
+        .chain(attribute_version_sequences)
+        .map(|(_, seq)| &**seq)
+        .collect();
+    let (versions_array, offsets) = packing::pack_sequences(&sequence_refs);
+    println!(
+        "VERSION_INFO: {} entries, packed from {} entries in all lists of versions",
+        versions_array.len(),
+        sequence_refs.iter().map(|seq| seq.len()).sum::<usize>()
+    );
+
+    let mut index_infos = [FxHashMap::default(), FxHashMap::default()];
+    let all_sequences = subelement_version_sequences
+        .iter()
+        .map(|item| (0, item))
+        .chain(attribute_version_sequences.iter().map(|item| (1, item)));
+    for ((info_idx, (typename, seq)), offset) in all_sequences.zip(offsets) {
+        // re-expand the slice to make sure the packing preserved the list of versions
+        packing::check_packed(
+            &versions_array,
+            offset,
+            seq,
+            &format!("versions of {typename}"),
+        )?;
+        index_infos[info_idx].insert(typename.clone(), offset);
+    }
+    let [versions_index_info, attr_ver_index_info] = index_infos;
+
+    Ok((versions_array, versions_index_info, attr_ver_index_info))
+}
+
 fn generate_versions_array(versions_array: &[usize]) -> String {
     let mut generated = format!(
-        "\npub(crate) static VERSION_INFO: [u32; {}] = [",
+        "\n#[rustfmt::skip]\npub(crate) static VERSION_INFO: [u32; {}] = [",
         versions_array.len()
     );
     let ver_str = versions_array
@@ -281,15 +826,73 @@ fn generate_versions_array(versions_array: &[usize]) -> String {
     generated
 }
 
+// the number of entries of ELEMENTS that are available in each version, indexed by the version bit
+fn generate_element_count_per_version(version_masks: &[usize], num_versions: usize) -> String {
+    let counts: Vec<usize> = (0..num_versions)
+        .map(|bit_idx| {
+            version_masks
+                .iter()
+                .filter(|mask| *mask & (1 << bit_idx) != 0)
+                .count()
+        })
+        .collect();
+    let counts_str = counts
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!(
+        "
+pub const ELEMENT_COUNT_PER_VERSION: [u32; {num_versions}] = [{counts_str}];
+"
+    )
+}
+
+/// letter that is prepended to identifiers whose name starts with a digit, e.g. "1-BASED" -> "N1Based"
+const LEADING_DIGIT_PREFIX: char = 'N';
+
+/// the keywords of Rust 2021, including the reserved ones, which can't be used as identifiers
+///
+/// Converted names usually start with a capital letter, so only `Self` and names that are already lowercase in the
+/// xsd, like "type", can be keywords.
+const RUST_KEYWORDS: [&str; 52] = [
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
 /// generate a CamelCase identifier for an enum variant in Rust from an ALL-CAPS name in the xsd
+///
+/// All names are checked by `try_name_to_identifier` before the enums are generated, so this can't fail.
 fn name_to_identifier(name: &str) -> String {
+    try_name_to_identifier(name).unwrap()
+}
+
+/// generate a CamelCase identifier for an enum variant in Rust from an ALL-CAPS name in the xsd
+///
+/// '-' separates the words, and ':' starts a new word as well; all other characters except ASCII letters and digits
+/// are dropped. A leading digit gets the prefix `N`, any other leading character that is not a letter gets the
+/// prefix `_`. Identifiers which are Rust keywords get a trailing `_`, e.g. "SELF" -> "Self_".
+/// Names without any ASCII letters or digits, e.g. "" or "-", are rejected, since no identifier can be derived
+/// from them.
+pub(crate) fn try_name_to_identifier(name: &str) -> Result<String, String> {
+    if !name.chars().any(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "no identifier can be derived from the name \"{name}\", because it contains no letters or digits"
+        ));
+    }
+
     let mut keep_capital = true;
     let mut force_capital = false;
     let mut result = String::new();
     let mut prev_is_digit = false;
 
     if let Some(firstchar) = name.chars().next() {
-        if !firstchar.is_ascii_alphabetic() {
+        if firstchar.is_ascii_digit() {
+            result.push(LEADING_DIGIT_PREFIX);
+        } else if !firstchar.is_ascii_alphabetic() {
             result.push('_');
         }
     }
@@ -322,7 +925,10 @@ fn name_to_identifier(name: &str) -> String {
         }
     }
 
-    result
+    if RUST_KEYWORDS.contains(&result.as_str()) {
+        result.push('_');
+    }
+    Ok(result)
 }
 
 impl From<&Element> for SimpleElement {
@@ -370,6 +976,15 @@ impl MergedElementDataType {
         }
     }
 
+    fn mm_class(&self) -> Option<&[String]> {
+        match self {
+            MergedElementDataType::Elements { mm_class, .. }
+            | MergedElementDataType::Mixed { mm_class, .. } => mm_class.as_deref(),
+            MergedElementDataType::ElementsGroup { .. }
+            | MergedElementDataType::Characters { .. } => None,
+        }
+    }
+
     fn basetype(&self) -> Option<&str> {
         match self {
             MergedElementDataType::Characters { basetype, .. }
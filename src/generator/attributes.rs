@@ -1,83 +1,58 @@
-use crate::generator::{name_to_identifier, AttributeInfo, FxHashMap, MergedElementDataType};
-use crate::{Attribute, AutosarDataTypes};
+use crate::generator::packing::{check_packed, pack_sequences};
+use crate::generator::{
+    name_to_identifier, Attribute, AttributeInfo, FxHashMap, MergedElementDataType,
+};
+use crate::AutosarDataTypes;
 
 pub(crate) fn build_info(
     element_types: &FxHashMap<String, MergedElementDataType>,
-    versions_array: &mut Vec<usize>,
-) -> AttributeInfo {
+) -> Result<AttributeInfo, String> {
     let mut elemtypenames: Vec<&String> = element_types.keys().collect();
-    let mut attributes_array = Vec::new();
-    let mut attributes_index_info = FxHashMap::default();
-    let mut attr_ver_index_info = FxHashMap::default();
+    let mut version_sequences = Vec::new();
+    let mut attr_sequences: Vec<(&String, Vec<Attribute>)> = Vec::new();
 
     // sort the element type names so that the element types with the most sub elements are first
     elemtypenames.sort_by(|k1, k2| cmp_elemtypenames_attrs(k1, k2, element_types));
     for etypename in elemtypenames {
-        if let Some(attrs) = element_types
+        let attrs = element_types
             .get(etypename)
-            .map(MergedElementDataType::attributes)
-        {
-            if !attrs.is_empty() {
-                // build a list of versions from the list of items
-                let attr_versions: Vec<usize> =
-                    attrs.iter().map(|attr| attr.version_info).collect();
-                // check if this exact sequence of version information already exists within the versions_array
-                if let Some(existing_version_position) = versions_array
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, ver)| **ver == attr_versions[0])
-                    .map(|(pos, _)| pos)
-                    .find(|pos| versions_array[*pos..].starts_with(&attr_versions))
-                {
-                    // exact sequence was found, store the position of the existing data
-                    attr_ver_index_info.insert(etypename.to_owned(), existing_version_position);
-                } else {
-                    // the exact sequence was not found, append it to the end of versions_array and store the position
-                    attr_ver_index_info.insert(etypename.to_owned(), versions_array.len());
-                    versions_array.extend(attr_versions.iter());
-                }
-
-                // create a copy of the items and strip the version_info from the copied items
-                // the version info is handled separately and this makes it more likely that identical sequences can be found
-                let mut attrs_copy = attrs.to_owned();
-                attrs_copy.iter_mut().for_each(|attr| attr.version_info = 0);
-                // as for the versions above, try to find the exact sequene of items in the overall list of subelements
-                if let Some(existing_position) = attributes_array
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, ec)| *ec == &attrs_copy[0])
-                    .map(|(pos, _)| pos)
-                    .find(|pos| attributes_array[*pos..].starts_with(&attrs_copy))
-                {
-                    attributes_index_info.insert(
-                        etypename.to_owned(),
-                        (existing_position, existing_position + attrs_copy.len()),
-                    );
-                } else {
-                    attributes_index_info.insert(
-                        etypename.to_owned(),
-                        (
-                            attributes_array.len(),
-                            attributes_array.len() + attrs_copy.len(),
-                        ),
-                    );
-                    attributes_array.append(&mut attrs_copy);
-                }
-            } else {
-                attributes_index_info.insert(etypename.to_owned(), (0, 0));
-                attr_ver_index_info.insert(etypename.to_owned(), 0);
-            }
-        } else {
-            attributes_index_info.insert(etypename.to_owned(), (0, 0));
-            attr_ver_index_info.insert(etypename.to_owned(), 0);
-        }
+            .map_or(&[][..], MergedElementDataType::attributes);
+        // build a list of versions from the list of items
+        let attr_versions: Vec<usize> = attrs.iter().map(|attr| attr.version_info).collect();
+        version_sequences.push((etypename.to_owned(), attr_versions));
+
+        // create a copy of the items and strip the version_info from the copied items
+        // the version info is handled separately and this makes it more likely that identical sequences can be found
+        let mut attrs_copy = attrs.to_owned();
+        attrs_copy.iter_mut().for_each(|attr| attr.version_info = 0);
+        attr_sequences.push((etypename, attrs_copy));
+    }
+
+    // pack the lists of attributes into one array, reusing overlapping parts
+    let sequence_refs: Vec<&[Attribute]> = attr_sequences.iter().map(|(_, seq)| &**seq).collect();
+    let (attributes_array, offsets) = pack_sequences(&sequence_refs);
+    let mut attributes_index_info = FxHashMap::default();
+    for ((etypename, attrs), offset) in attr_sequences.iter().zip(offsets) {
+        // re-expand the slice to make sure the packing preserved the list of attributes
+        check_packed(
+            &attributes_array,
+            offset,
+            attrs,
+            &format!("attributes of {etypename}"),
+        )?;
+        attributes_index_info.insert((*etypename).clone(), (offset, offset + attrs.len()));
     }
+    println!(
+        "ATTRIBUTES: {} entries, packed from {} entries in all lists of attributes",
+        attributes_array.len(),
+        sequence_refs.iter().map(|seq| seq.len()).sum::<usize>()
+    );
 
-    AttributeInfo {
+    Ok(AttributeInfo {
+        version_sequences,
         attributes_array,
         attributes_index_info,
-        attr_ver_index_info,
-    }
+    })
 }
 
 fn cmp_elemtypenames_attrs(
@@ -104,17 +79,22 @@ fn cmp_elemtypenames_attrs(
 pub(crate) fn generate(
     autosar_schema: &AutosarDataTypes,
     attributes_array: &[Attribute],
+    docstring_ids: &FxHashMap<String, usize>,
 ) -> String {
-    let mut chartypenames: Vec<&String> = autosar_schema.character_types.keys().collect();
+    let mut chartypenames: Vec<&str> = autosar_schema
+        .character_types
+        .keys()
+        .map(|typename| autosar_schema.names.resolve(*typename))
+        .collect();
     chartypenames.sort();
     // map each character type name to an index
     let chartype_nameidx: FxHashMap<&str, usize> = chartypenames
         .iter()
         .enumerate()
-        .map(|(idx, name)| (&***name, idx))
+        .map(|(idx, name)| (*name, idx))
         .collect();
     let mut generated = format!(
-        "\npub(crate) const ATTRIBUTES: [(AttributeName, u16, bool); {}] = [\n",
+        "\n#[rustfmt::skip]\npub(crate) const ATTRIBUTES: [(AttributeName, u16, bool); {}] = [\n",
         attributes_array.len()
     );
     generated.push_str(&build_attributes_string(
@@ -122,10 +102,132 @@ pub(crate) fn generate(
         &chartype_nameidx,
     ));
     generated.push_str("\n];\n");
+    generated.push_str(IS_REQUIRED_IN);
+
+    // the docstrings of the attributes are stored in ELEMENT_DOCSTRINGS, together with the docstrings of the elements
+    let docstring_strs: Vec<String> = attributes_array
+        .iter()
+        .map(|attr| {
+            attr.docstring.as_ref().map_or("None".to_string(), |ds| {
+                format!("Some({})", docstring_ids.get(ds).unwrap())
+            })
+        })
+        .collect();
+    generated.push_str(&format!(
+        "\n#[cfg(feature = \"docstrings\")]\n#[rustfmt::skip]\npub(crate) const ATTRIBUTE_DOCSTRINGS: [Option<u16>; {}] = [{}];\n",
+        docstring_strs.len(),
+        docstring_strs.join(", ")
+    ));
 
     generated
 }
 
+/// generate ATTRIBUTE_INDEX and ATTRIBUTE_INDEX_ITEMS, which allow a binary search for an attribute of a data type
+///
+/// Only data types with more than `threshold` attributes get an entry in the index; the attributes of
+/// all other data types are found by a linear search in their range of ATTRIBUTES.
+pub(crate) fn generate_attribute_index(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    attributes_array: &[Attribute],
+    attributes_index_info: &FxHashMap<String, (usize, usize)>,
+    attribute_values: &FxHashMap<String, usize>,
+    threshold: usize,
+) -> String {
+    let mut elemtypenames: Vec<&String> = element_types.keys().collect();
+    elemtypenames.sort();
+
+    let mut index = Vec::new();
+    let mut index_items: Vec<(usize, &str, usize)> = Vec::new();
+    for (type_idx, etypename) in elemtypenames.iter().enumerate() {
+        let (low, high) = *attributes_index_info.get(*etypename).unwrap();
+        if high - low > threshold {
+            let mut items: Vec<(usize, &str, usize)> = attributes_array[low..high]
+                .iter()
+                .enumerate()
+                .map(|(pos, attr)| {
+                    (
+                        *attribute_values.get(&attr.name).unwrap(),
+                        &*attr.name,
+                        low + pos,
+                    )
+                })
+                .collect();
+            // the lookup does a binary search on the value of the AttributeName
+            items.sort_unstable();
+            index.push((type_idx, index_items.len(), index_items.len() + items.len()));
+            index_items.append(&mut items);
+        }
+    }
+
+    // each entry of ATTRIBUTE_INDEX is (u16, u16, u16) and each entry of ATTRIBUTE_INDEX_ITEMS is (AttributeName, u16)
+    println!(
+        "ATTRIBUTE_INDEX: {} data types with more than {threshold} attributes, {} bytes",
+        index.len(),
+        index.len() * 6 + index_items.len() * 4
+    );
+
+    let index_str = index
+        .iter()
+        .map(|(type_idx, low, high)| format!("({type_idx}, {low}, {high})"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let items_str = index_items
+        .iter()
+        .map(|(_, name, pos)| format!("(AttributeName::{}, {pos})", name_to_identifier(name)))
+        .collect::<Vec<String>>()
+        .join(",\n    ");
+
+    format!(
+        r#"
+// (index in DATATYPES, range in ATTRIBUTE_INDEX_ITEMS) of each data type with more than {threshold} attributes
+#[rustfmt::skip]
+const ATTRIBUTE_INDEX: [(u16, u16, u16); {}] = [{index_str}];
+
+// (attribute name, position in ATTRIBUTES) of the attributes of the indexed data types, sorted by the attribute name value
+#[rustfmt::skip]
+const ATTRIBUTE_INDEX_ITEMS: [(AttributeName, u16); {}] = [
+    {items_str}
+];
+
+/// find the position in ATTRIBUTES of the attribute `name` of the data type `type_idx`
+#[allow(dead_code)]
+pub(crate) fn find_attribute(type_idx: usize, name: AttributeName) -> Option<usize> {{
+    if let Ok(pos) = ATTRIBUTE_INDEX.binary_search_by_key(&type_idx, |(idx, _, _)| *idx as usize) {{
+        let (_, low, high) = ATTRIBUTE_INDEX[pos];
+        let items = &ATTRIBUTE_INDEX_ITEMS[low as usize..high as usize];
+        items
+            .binary_search_by_key(&(name as u32), |(attr, _)| *attr as u32)
+            .ok()
+            .map(|item_pos| items[item_pos].1 as usize)
+    }} else {{
+        let (low, high) = DATATYPES[type_idx].attributes;
+        ATTRIBUTES[low as usize..high as usize]
+            .iter()
+            .position(|(attr, _, _)| *attr == name)
+            .map(|attr_pos| low as usize + attr_pos)
+    }}
+}}
+
+#[cfg(test)]
+mod attribute_index_test {{
+    use super::*;
+
+    #[test]
+    fn find_attribute_test() {{
+        for (type_idx, spec) in DATATYPES.iter().enumerate() {{
+            let (low, high) = spec.attributes;
+            for pos in low as usize..high as usize {{
+                assert_eq!(find_attribute(type_idx, ATTRIBUTES[pos].0), Some(pos));
+            }}
+        }}
+    }}
+}}
+"#,
+        index.len(),
+        index_items.len()
+    )
+}
+
 fn build_attributes_string(
     attrs: &[Attribute],
     chartype_nameidx: &FxHashMap<&str, usize>,
@@ -142,3 +244,53 @@ fn build_attributes_string(
     }
     attr_strings.join(",\n")
 }
+
+// the attributes of an element are not sorted, since their order is used when writing files,
+// so the lookup is a linear search in the attribute range of the element
+const IS_REQUIRED_IN: &str = r#"
+impl AttributeName {
+    /// check if this attribute is required in elements with the specification `spec`
+    ///
+    /// Returns false if the attribute is optional or not allowed at all.
+    #[must_use]
+    pub(crate) fn is_required_in(self, spec: &ElementSpec) -> bool {
+        let (low, high) = spec.attributes;
+        ATTRIBUTES[low as usize..high as usize]
+            .iter()
+            .find(|(name, _, _)| *name == self)
+            .is_some_and(|(_, _, required)| *required)
+    }
+
+    /// iterate over the names of all attributes that are allowed in elements with the specification `spec`
+    pub(crate) fn for_element(spec: &ElementSpec) -> impl Iterator<Item = AttributeName> {
+        let (low, high) = spec.attributes;
+        ATTRIBUTES[low as usize..high as usize]
+            .iter()
+            .map(|(name, _, _)| *name)
+    }
+}
+
+#[cfg(test)]
+mod is_required_in_test {
+    use super::*;
+
+    #[test]
+    fn is_required_in() {
+        for spec in &DATATYPES {
+            let (low, high) = spec.attributes;
+            for (name, _, required) in &ATTRIBUTES[low as usize..high as usize] {
+                assert_eq!(name.is_required_in(spec), *required);
+            }
+        }
+    }
+
+    #[test]
+    fn for_element() {
+        for spec in &DATATYPES {
+            let (low, high) = spec.attributes;
+            assert!(AttributeName::for_element(spec)
+                .eq(ATTRIBUTES[low as usize..high as usize].iter().map(|(name, _, _)| *name)));
+        }
+    }
+}
+"#;
@@ -1,9 +1,34 @@
 use crate::generator::name_to_identifier;
 use crate::{AutosarDataTypes, CharacterDataType};
 use rustc_hash::FxHashMap;
+use std::collections::HashSet;
 use std::fmt::Write;
 
-pub(crate) fn generate(autosar_schema: &AutosarDataTypes) -> String {
+/// character types whose index in CHARACTER_DATA is exported as a named constant
+///
+/// Further constants can be added on the command line with `--chartype-const=NAME=TYPE`.
+/// Types that are identical to another type are removed by the deduplication, so only the
+/// remaining type names can be used here; e.g. xsd:NMTOKENS is replaced by xsd:string.
+const CHARTYPE_CONSTANTS: [(&str, &str); 3] = [
+    ("REFERENCE_TYPE_IDX", "AR:REF--SIMPLE"),
+    ("IDENTIFIER_TYPE_IDX", "AR:IDENTIFIER--SIMPLE"),
+    ("STRING_PLAIN_IDX", "xsd:string"),
+];
+
+/// the constant for the index of the string type that preserves whitespace, i.e. a restriction with
+/// `<xsd:whiteSpace value="preserve"/>` and without a maxLength
+///
+/// This type can't be found by its name: each such simple type of the xsd files is identical, so the deduplication
+/// only keeps the one with the shortest name, and that changes whenever a type is added or renamed. It is found by
+/// its definition instead, which is unique after the deduplication.
+const STRING_PRESERVE_WHITESPACE_CONSTANT: &str = "STRING_PRESERVE_WHITESPACE_IDX";
+
+pub(crate) fn generate(
+    autosar_schema: &AutosarDataTypes,
+    extra_constants: &[(String, String)],
+) -> Result<String, String> {
+    crate::verify::verify_patterns(autosar_schema)?;
+
     let mut generated = String::new();
 
     let regexes: FxHashMap<String, String> = VALIDATOR_REGEX_MAPPING
@@ -11,24 +36,28 @@ pub(crate) fn generate(autosar_schema: &AutosarDataTypes) -> String {
         .map(|(regex, name)| ((*regex).to_string(), (*name).to_string()))
         .collect();
 
-    let mut ctnames: Vec<&String> = autosar_schema.character_types.keys().collect();
+    let mut ctnames: Vec<&str> = autosar_schema
+        .character_types
+        .keys()
+        .map(|typename| autosar_schema.names.resolve(*typename))
+        .collect();
     ctnames.sort();
 
     writeln!(
         generated,
-        "pub(crate) const CHARACTER_DATA: [CharacterDataSpec; {}] = [",
+        "#[rustfmt::skip]\n#[allow(deprecated)]\npub(crate) const CHARACTER_DATA: [CharacterDataSpec; {}] = [",
         ctnames.len()
     )
     .unwrap();
     for ctname in &ctnames {
-        let chtype = autosar_schema.character_types.get(*ctname).unwrap();
+        let chtype = autosar_schema.character_type(ctname).unwrap();
 
         let chdef = match chtype {
             CharacterDataType::Pattern {
                 pattern,
                 max_length,
             } => {
-                let fullmatch_pattern = format!("^({pattern})$");
+                let fullmatch_pattern = fullmatch_pattern(pattern);
                 // no longer using proc-macro-regex due to unacceptably long run-times of the proc macro (> 5 Minutes!)
                 // if regexes.get(&fullmatch_pattern).is_none() {
                 //     let regex_validator_name = format!("validate_regex_{}", regexes.len() + 1);
@@ -39,49 +68,135 @@ pub(crate) fn generate(autosar_schema: &AutosarDataTypes) -> String {
                     .get(&fullmatch_pattern)
                     .unwrap_or_else(|| panic!("missing regex: {fullmatch_pattern}"));
                 format!(
-                    r#"CharacterDataSpec::Pattern{{check_fn: {regex_validator_name}, regex: r"{pattern}", max_length: {max_length:?}}}"#
+                    r#"Pattern{{check_fn: {regex_validator_name}, regex: r"{pattern}", max_length: {max_length:?}}}"#
                 )
             }
             CharacterDataType::Enum(enumdef) => {
                 let enumitem_strs: Vec<String> = enumdef
                     .enumitems
                     .iter()
-                    .map(|(name, ver)| {
+                    .map(|(name, ver, _)| {
                         format!("(EnumItem::{}, 0x{ver:x})", name_to_identifier(name))
                     })
                     .collect();
-                format!(
-                    r#"CharacterDataSpec::Enum{{items: &[{}]}}"#,
-                    enumitem_strs.join(", ")
-                )
+                format!(r#"Enum{{items: &[{}]}}"#, enumitem_strs.join(", "))
             }
             CharacterDataType::String {
                 max_length,
                 preserve_whitespace,
             } => {
                 format!(
-                    r#"CharacterDataSpec::String{{preserve_whitespace: {preserve_whitespace}, max_length: {max_length:?}}}"#
+                    r#"String{{preserve_whitespace: {preserve_whitespace}, max_length: {max_length:?}}}"#
                 )
             }
-            CharacterDataType::UnsignedInteger => "CharacterDataSpec::UnsignedInteger".to_string(),
-            CharacterDataType::Double => "CharacterDataSpec::Double".to_string(),
+            CharacterDataType::UnsignedInteger => "UnsignedInteger".to_string(),
+            CharacterDataType::Double => "Double".to_string(),
+            CharacterDataType::DateTime { format } => {
+                format!("DateTime{{format: {format:?}}}")
+            }
         };
-        generated.push_str("    ");
-        generated.push_str(&chdef);
-        generated.push_str(",\n");
+        writeln!(generated, "    character_data!({chdef}),").unwrap();
     }
     generated.push_str("];\n");
 
-    let (reference_type_idx, _) = ctnames
+    let constants = CHARTYPE_CONSTANTS
         .iter()
-        .enumerate()
-        .find(|(_, name)| **name == "AR:REF--SIMPLE")
-        .expect("reference type \"AR:REF--SIMPLE\" not found ?!");
-    generated.push_str(&format!(
-        "pub(crate) const REFERENCE_TYPE_IDX: u16 = {reference_type_idx};\n"
-    ));
-
-    generated
+        .map(|(const_name, typename)| (*const_name, *typename))
+        .chain(
+            extra_constants
+                .iter()
+                .map(|(const_name, typename)| (&**const_name, &**typename)),
+        );
+    for (const_name, typename) in constants {
+        let Some(type_idx) = ctnames.iter().position(|name| *name == typename) else {
+            return Err(format!(
+                "Error: the character type {typename} for the constant {const_name} does not exist in the schema"
+            ));
+        };
+        writeln!(
+            generated,
+            "pub(crate) const {const_name}: u16 = {type_idx};"
+        )
+        .unwrap();
+    }
+
+    let Some(type_idx) = ctnames.iter().position(|name| {
+        autosar_schema.character_type(name)
+            == Some(&CharacterDataType::String {
+                max_length: None,
+                preserve_whitespace: true,
+            })
+    }) else {
+        return Err(format!(
+            "Error: the schema has no string type that preserves whitespace for the constant {STRING_PRESERVE_WHITESPACE_CONSTANT}"
+        ));
+    };
+    writeln!(
+        generated,
+        "pub(crate) const {STRING_PRESERVE_WHITESPACE_CONSTANT}: u16 = {type_idx};"
+    )
+    .unwrap();
+
+    Ok(generated)
+}
+
+/// check that every pattern used by the character types has an entry in `VALIDATOR_REGEX_MAPPING`
+///
+/// Missing entries are an error, since generate() can't work without them.
+/// Entries of `VALIDATOR_REGEX_MAPPING` that are not used by any pattern are only reported.
+pub(crate) fn check_regexes(autosar_schema: &AutosarDataTypes) -> Result<(), String> {
+    let used_patterns: HashSet<String> = autosar_schema
+        .character_types
+        .values()
+        .filter_map(|chtype| {
+            if let CharacterDataType::Pattern { pattern, .. } = chtype {
+                Some(fullmatch_pattern(pattern))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let known_patterns: HashSet<&str> = VALIDATOR_REGEX_MAPPING
+        .iter()
+        .map(|(regex, _)| *regex)
+        .collect();
+
+    let mut missing: Vec<&String> = used_patterns
+        .iter()
+        .filter(|pattern| !known_patterns.contains(pattern.as_str()))
+        .collect();
+    missing.sort();
+
+    println!(
+        "{} distinct patterns in the schema, {} regexes in VALIDATOR_REGEX_MAPPING",
+        used_patterns.len(),
+        VALIDATOR_REGEX_MAPPING.len()
+    );
+    for (regex, name) in &VALIDATOR_REGEX_MAPPING {
+        if !used_patterns.contains(*regex) {
+            println!("unused: {name} is no longer referenced and can be removed: {regex}");
+        }
+    }
+
+    if missing.is_empty() {
+        println!("all patterns have a validator");
+        Ok(())
+    } else {
+        let missing_list: Vec<String> = missing
+            .iter()
+            .map(|pattern| format!("    {pattern}\n"))
+            .collect();
+        Err(format!(
+            "Error: {} patterns are missing from VALIDATOR_REGEX_MAPPING:\n{}",
+            missing.len(),
+            missing_list.concat()
+        ))
+    }
+}
+
+/// anchor a pattern, so that it only matches complete strings
+pub(crate) fn fullmatch_pattern(pattern: &str) -> String {
+    format!("^({pattern})$")
 }
 
 // map a regex to a validation function name
@@ -1,5 +1,5 @@
-use crate::generator::{name_to_identifier, SimpleElement};
-use crate::{ElementAmount, ElementCollectionItem, XsdRestrictToStandard};
+use crate::generator::{name_to_identifier, ElementCollectionItem, SimpleElement};
+use crate::{ElementAmount, XsdRestrictToStandard};
 use rustc_hash::FxHashMap;
 use std::collections::HashSet;
 
@@ -49,11 +49,18 @@ pub(crate) fn build_info(
 
 pub(crate) fn build_docstrings_info(
     element_definitions_array: &[SimpleElement],
+    element_types: &FxHashMap<String, MergedElementDataType>,
 ) -> FxHashMap<String, usize> {
-    // first, put all docstrings into a HashSet to elimitate duplicates
+    // first, put all docstrings of elements and attributes into a HashSet to elimitate duplicates
     let docstrings: HashSet<String> = element_definitions_array
         .iter()
         .filter_map(|e| e.docstring.clone())
+        .chain(
+            element_types
+                .values()
+                .flat_map(MergedElementDataType::attributes)
+                .filter_map(|attr| attr.docstring.clone()),
+        )
         .collect();
     // transform the HashSet into a Vec and sort the list
     let mut docstrings: Vec<String> = docstrings.into_iter().collect();
@@ -80,7 +87,7 @@ pub(crate) fn generate(
         .map(|(idx, name)| (&***name, idx))
         .collect();
     let mut generated = format!(
-        "\npub(crate) const ELEMENTS: [ElementDefinition; {}] = [\n",
+        "\n#[rustfmt::skip]\npub(crate) const ELEMENTS: [ElementDefinition; {}] = [\n",
         elements.len()
     );
     for (idx, elem) in elements.iter().enumerate() {
@@ -97,13 +104,77 @@ pub(crate) fn generate(
         .iter()
         .position(|elem| elem.name == "AUTOSAR")
         .unwrap();
+    // AUTOSAR_ELEMENT is a const rather than a static, so that it can be used in const contexts like this check
     generated.push_str(&format!(
-        "\npub(crate) const AUTOSAR_ELEMENT: u16 = {autosar_idx};\n"
+        "\npub(crate) const AUTOSAR_ELEMENT: u16 = {autosar_idx};\nconst _: () = assert!((AUTOSAR_ELEMENT as usize) < ELEMENTS.len());\n"
     ));
 
+    generated.push_str(&generate_element_flags(elements));
+
     generated
 }
 
+/// generate SPLITTABLE_VERSIONS and ORDERED_ELEMENTS, which are aligned with the ELEMENTS array
+///
+/// Both tables are built from the same list of elements as ELEMENTS, so the index of an element is the
+/// same in all three tables. The generated test verifies this.
+fn generate_element_flags(elements: &[SimpleElement]) -> String {
+    let splittable_str = elements
+        .iter()
+        .map(|elem| format!("0x{:X}", elem.splittable_ver))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    let mut ordered_bits = vec![0u32; elements.len().div_ceil(32)];
+    for (idx, elem) in elements.iter().enumerate() {
+        if elem.ordered {
+            ordered_bits[idx / 32] |= 1 << (idx % 32);
+        }
+    }
+    let ordered_str = ordered_bits
+        .iter()
+        .map(|bits| format!("0x{bits:08X}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        r#"
+// the versions in which each element in ELEMENTS is splittable
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const SPLITTABLE_VERSIONS: [u32; {elem_count}] = [{splittable_str}];
+
+// bitset of the ordered elements in ELEMENTS: bit (idx % 32) of ORDERED_ELEMENTS[idx / 32] is set if ELEMENTS[idx] is ordered
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const ORDERED_ELEMENTS: [u32; {ordered_count}] = [{ordered_str}];
+
+#[cfg(test)]
+mod element_flags_test {{
+    use super::*;
+
+    #[test]
+    fn element_flags_consistency() {{
+        assert_eq!(SPLITTABLE_VERSIONS.len(), ELEMENTS.len());
+        assert!(ORDERED_ELEMENTS.len() * 32 >= ELEMENTS.len());
+        assert!(ORDERED_ELEMENTS.len() * 32 < ELEMENTS.len() + 32);
+        for (idx, elem) in ELEMENTS.iter().enumerate() {{
+            assert_eq!(SPLITTABLE_VERSIONS[idx], elem.splittable);
+            let ordered = ORDERED_ELEMENTS[idx / 32] & (1 << (idx % 32)) != 0;
+            assert_eq!(ordered, elem.ordered);
+        }}
+        // bits beyond the end of ELEMENTS must not be set
+        for idx in ELEMENTS.len()..ORDERED_ELEMENTS.len() * 32 {{
+            assert_eq!(ORDERED_ELEMENTS[idx / 32] & (1 << (idx % 32)), 0);
+        }}
+    }}
+}}
+"#,
+        elem_count = elements.len(),
+        ordered_count = ordered_bits.len()
+    )
+}
+
 fn build_element_string(
     elem: &SimpleElement,
     elemtype_nameidx: &FxHashMap<&str, usize>,
@@ -129,7 +200,7 @@ fn build_element_string(
     )
 }
 
-fn restrict_std_to_text(restrict_std: XsdRestrictToStandard) -> &'static str {
+pub(crate) fn restrict_std_to_text(restrict_std: XsdRestrictToStandard) -> &'static str {
     match restrict_std {
         XsdRestrictToStandard::NotSet | XsdRestrictToStandard::Both => "NotRestricted",
         XsdRestrictToStandard::ClassicPlatform => "ClassicPlatform",
@@ -1,7 +1,10 @@
-use crate::generator::{name_to_identifier, MergedElementDataType};
-use crate::{CharacterDataType, Element, ElementAmount, ElementCollection, ElementCollectionItem};
+use crate::generator::packing::pack_sequences;
+use crate::generator::{
+    name_to_identifier, Element, ElementCollection, ElementCollectionItem, MergedElementDataType,
+};
+use crate::{CharacterDataType, ElementAmount};
 use rustc_hash::FxHashMap;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
 
 pub(crate) fn generate(
     element_types: &FxHashMap<String, MergedElementDataType>,
@@ -13,7 +16,10 @@ pub(crate) fn generate(
 ) -> String {
     let mut generated = String::new();
     let mut elemtypes = String::new();
-    let mut all_refstrings = Vec::<String>::new();
+    let mut ref_items = RefItems::default();
+    let mut datatype_names = Vec::with_capacity(element_types.len());
+    let mut max_subelement_count = 0;
+    let mut max_attribute_count = 0;
 
     let mut elemtypenames: Vec<&String> = element_types.keys().collect();
     elemtypenames.sort();
@@ -41,7 +47,7 @@ pub(crate) fn generate(
     let element_names_of_typename = build_elementnames_of_type_list(element_types);
 
     elemtypes.push_str(&format!(
-        "\npub(crate) const DATATYPES: [ElementSpec; {}] = [\n",
+        "\n#[rustfmt::skip]\npub(crate) const DATATYPES: [ElementSpec; {}] = [\n",
         element_types.len()
     ));
     for (idx, etypename) in elemtypenames.iter().enumerate() {
@@ -54,6 +60,8 @@ pub(crate) fn generate(
         let subelement_ver_info_low = subelements_ver_index_info.get(*etypename).unwrap();
         let (attrs_limit_low, attrs_limit_high) = attributes_index_info.get(*etypename).unwrap();
         let attrs_ver_info_low = attr_ver_index_info.get(*etypename).unwrap();
+        max_subelement_count = max_subelement_count.max(subelem_limit_high - subelem_limit_low);
+        max_attribute_count = max_attribute_count.max(attrs_limit_high - attrs_limit_low);
         let chartype = if let Some(name) = elemtype.basetype() {
             format!("Some({})", *chartype_nameidx.get(name).unwrap())
         } else {
@@ -62,8 +70,11 @@ pub(crate) fn generate(
         let infostring = if let Some(elems) = element_names_of_typename.get(*etypename) {
             let mut elemlist: Vec<String> = elems.iter().cloned().collect();
             elemlist.sort();
+            datatype_names.push(elemlist[0].clone());
             elemlist.join(", ")
         } else {
+            // groups are not used by any element, so the group name is used to identify them
+            datatype_names.push((*etypename).clone());
             "(sub-group)".to_owned()
         };
 
@@ -77,39 +88,75 @@ pub(crate) fn generate(
                 })
                 .collect();
             namevec.sort();
-
-            if namevec.is_empty() {
-                (0, 0)
-            } else if let Some(existing_pos) = all_refstrings
-                .iter()
-                .enumerate()
-                .filter(|(_, item)| **item == namevec[0])
-                .map(|(pos, _)| pos)
-                .find(|pos| all_refstrings[*pos..].starts_with(&namevec))
-            {
-                (existing_pos, existing_pos + namevec.len())
-            } else {
-                let len = namevec.len();
-                let pos = all_refstrings.len();
-                all_refstrings.append(&mut namevec);
-                (pos, pos + len)
-            }
+            ref_items.insert(namevec)
         } else {
             (0, 0)
         };
 
         elemtypes.push_str(&format!(
-            "    /* {idx:4} */ ElementSpec {{sub_elements: ({subelem_limit_low}, {subelem_limit_high}), \
+            "    /* {idx:4} */ spec!(sub_elements: ({subelem_limit_low}, {subelem_limit_high}), \
                             sub_element_ver: {subelement_ver_info_low}, \
                             attributes: ({attrs_limit_low}, {attrs_limit_high}), attributes_ver: {attrs_ver_info_low}, \
-                            character_data: {chartype}, mode: {mode}, ref_info: ({ref_info_low}, {ref_info_high})}}, // {infostring}\n"));
+                            character_data: {chartype}, mode: {mode}, ref_info: ({ref_info_low}, {ref_info_high})), // {infostring}\n"));
     }
     elemtypes.push_str("];\n");
 
+    // the primary element name of each data type, for use in diagnostic messages
+    elemtypes.push_str(&format!(
+        "\n#[allow(dead_code)]\npub(crate) const DATATYPE_NAMES: [&str; {}] = [\n    {}\n];\n",
+        datatype_names.len(),
+        datatype_names
+            .iter()
+            .map(|name| format!("{name:?}"))
+            .collect::<Vec<_>>()
+            .join(",\n    ")
+    ));
+
+    elemtypes.push_str(&format!(
+        "\n#[allow(deprecated)]\npub(crate) const REF_ITEMS: [EnumItem; {}] = [\n    {}\n];\n",
+        ref_items.items.len(),
+        ref_items.items.join(",\n    ")
+    ));
+
+    // size hints for the consumer, which can pre-allocate the lists of child elements and attributes
     elemtypes.push_str(&format!(
-        "\npub(crate) const REF_ITEMS: [EnumItem; {}] = [\n    {}\n];\n",
-        all_refstrings.len(),
-        all_refstrings.join(",\n    ")
+        r#"
+/// the largest number of sub elements of any data type
+#[allow(dead_code)]
+pub(crate) const MAX_SUBELEMENT_COUNT: u16 = {max_subelement_count};
+
+/// the largest number of attributes of any data type
+#[allow(dead_code)]
+pub(crate) const MAX_ATTRIBUTE_COUNT: u16 = {max_attribute_count};
+
+/// get the number of sub elements of a data type, which is a useful capacity for its list of child elements
+///
+/// A sub element may also be a group, so this is only a hint. Use the mode of the data type to find out if
+/// the sub elements form a sequence, a choice or a bag.
+#[allow(dead_code)]
+pub(crate) const fn expected_children(type_idx: u16) -> u16 {{
+    let (low, high) = DATATYPES[type_idx as usize].sub_elements;
+    high - low
+}}
+
+#[cfg(test)]
+mod size_hints_test {{
+    use super::*;
+
+    #[test]
+    fn size_hints() {{
+        let mut max_subelements = 0;
+        let mut max_attributes = 0;
+        for (type_idx, spec) in DATATYPES.iter().enumerate() {{
+            assert_eq!(expected_children(type_idx as u16), spec.sub_elements.1 - spec.sub_elements.0);
+            max_subelements = max_subelements.max(spec.sub_elements.1 - spec.sub_elements.0);
+            max_attributes = max_attributes.max(spec.attributes.1 - spec.attributes.0);
+        }}
+        assert_eq!(MAX_SUBELEMENT_COUNT, max_subelements);
+        assert_eq!(MAX_ATTRIBUTE_COUNT, max_attributes);
+    }}
+}}
+"#
     ));
 
     generated.push_str(&elemtypes);
@@ -117,14 +164,247 @@ pub(crate) fn generate(
     generated
 }
 
+/// the entries of REF_ITEMS, which contains the sorted list of ref targets of each data type
+///
+/// The ref targets of a data type reuse an existing range if the same sequence is already present.
+#[derive(Default)]
+pub(crate) struct RefItems {
+    pub(crate) items: Vec<String>,
+    // positions in items of each ref string, so that existing sequences can be found without a full scan
+    positions: FxHashMap<String, Vec<usize>>,
+}
+
+impl RefItems {
+    /// add the ref targets of a data type and return their range in the items
+    pub(crate) fn insert(&mut self, mut namevec: Vec<String>) -> (usize, usize) {
+        if namevec.is_empty() {
+            (0, 0)
+        } else if let Some(existing_pos) = self.positions.get(&namevec[0]).and_then(|positions| {
+            positions
+                .iter()
+                .copied()
+                .find(|pos| self.items[*pos..].starts_with(&namevec))
+        }) {
+            (existing_pos, existing_pos + namevec.len())
+        } else {
+            let len = namevec.len();
+            let pos = self.items.len();
+            for (offset, name) in namevec.iter().enumerate() {
+                self.positions
+                    .entry(name.clone())
+                    .or_default()
+                    .push(pos + offset);
+            }
+            self.items.append(&mut namevec);
+            (pos, pos + len)
+        }
+    }
+}
+
+/// generate REF_TARGET_TYPES, the inverse of the ref_info ranges in DATATYPES
+///
+/// For each EnumItem that is a valid DEST value it lists the indices of the data types that a reference
+/// with this DEST value may point to.
+pub(crate) fn generate_ref_targets(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    character_types: &FxHashMap<String, CharacterDataType>,
+    enum_item_values: &FxHashMap<String, usize>,
+) -> String {
+    let mut elemtypenames: Vec<&String> = element_types.keys().collect();
+    elemtypenames.sort();
+    let ref_attribute_types = find_ref_attribute_types(element_types, character_types);
+
+    // the same matching of xsd type names and DEST values as in the ref_info of each data type
+    let mut target_types = vec![BTreeSet::new(); enum_item_values.len()];
+    for (type_idx, etypename) in elemtypenames.iter().enumerate() {
+        if let Some(xsd_typenames) = element_types.get(*etypename).unwrap().xsd_typenames() {
+            for name in xsd_typenames
+                .iter()
+                .filter_map(|xtn| ref_attribute_types.get(xtn))
+            {
+                target_types[*enum_item_values.get(name).unwrap()].insert(type_idx);
+            }
+        }
+    }
+
+    let sequences: Vec<Vec<usize>> = target_types
+        .iter()
+        .map(|types| types.iter().copied().collect())
+        .collect();
+    let sequence_refs: Vec<&[usize]> = sequences.iter().map(|seq| &**seq).collect();
+    let (items, offsets) = pack_sequences(&sequence_refs);
+
+    let ranges_str = sequences
+        .iter()
+        .zip(offsets)
+        .map(|(seq, offset)| format!("({offset}, {})", offset + seq.len()))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let items_str = items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        r#"
+// for each EnumItem, by its value: the range of REF_TARGET_TYPE_ITEMS which lists the indices of all
+// data types in DATATYPES that a reference with this DEST value may point to
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const REF_TARGET_TYPES: [(u16, u16); {}] = [{ranges_str}];
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const REF_TARGET_TYPE_ITEMS: [u16; {}] = [{items_str}];
+
+#[cfg(test)]
+mod ref_target_types_test {{
+    use super::*;
+
+    #[test]
+    fn ref_target_types() {{
+        assert_eq!(REF_TARGET_TYPES.len(), EnumItem::COUNT);
+        // every ref_info entry of a data type is listed in REF_TARGET_TYPES
+        for (type_idx, spec) in DATATYPES.iter().enumerate() {{
+            for item in &REF_ITEMS[spec.ref_info.0 as usize..spec.ref_info.1 as usize] {{
+                let (low, high) = REF_TARGET_TYPES[*item as usize];
+                assert!(REF_TARGET_TYPE_ITEMS[low as usize..high as usize].contains(&(type_idx as u16)));
+            }}
+        }}
+        // every entry of REF_TARGET_TYPES is part of the ref_info of the data type
+        for item in EnumItem::variants() {{
+            let (low, high) = REF_TARGET_TYPES[item as usize];
+            for type_idx in &REF_TARGET_TYPE_ITEMS[low as usize..high as usize] {{
+                let (ref_low, ref_high) = DATATYPES[*type_idx as usize].ref_info;
+                assert!(REF_ITEMS[ref_low as usize..ref_high as usize].contains(&item));
+            }}
+        }}
+    }}
+}}
+"#,
+        sequences.len(),
+        items.len()
+    )
+}
+
+/// generate TYPE_STABLE_IDS, which assigns an ID to each entry of DATATYPES that does not depend on its position
+///
+/// The ID of a type is the 32-bit FNV-1a hash of its name, so it only changes if the type is renamed.
+pub(crate) fn generate_stable_ids(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+) -> Result<String, String> {
+    let mut elemtypenames: Vec<&String> = element_types.keys().collect();
+    elemtypenames.sort();
+
+    let stable_ids: Vec<u32> = elemtypenames
+        .iter()
+        .map(|name| stable_type_id(name))
+        .collect();
+    let mut lookup: Vec<(u32, usize)> = stable_ids
+        .iter()
+        .enumerate()
+        .map(|(idx, id)| (*id, idx))
+        .collect();
+    lookup.sort_unstable();
+    if let Some(pair) = lookup.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        return Err(format!(
+            "Error: the types {} and {} have the same stable id {:#010X}",
+            elemtypenames[pair[0].1], elemtypenames[pair[1].1], pair[0].0
+        ));
+    }
+
+    let ids_str = stable_ids
+        .iter()
+        .map(|id| format!("0x{id:08X}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let lookup_str = lookup
+        .iter()
+        .map(|(id, idx)| format!("(0x{id:08X}, {idx})"))
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    Ok(format!(
+        r#"
+// stable ids of the types in DATATYPES: the FNV-1a hash of the type name, which does not change when types are added or removed
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const TYPE_STABLE_IDS: [u32; {count}] = [{ids_str}];
+
+// (stable id, index in DATATYPES), sorted by the stable id
+#[rustfmt::skip]
+const TYPE_STABLE_ID_LOOKUP: [(u32, u16); {count}] = [{lookup_str}];
+
+/// get the index in DATATYPES of the type with the given stable id
+#[allow(dead_code)]
+pub(crate) fn stable_id_to_spec_index(id: u32) -> Option<usize> {{
+    TYPE_STABLE_ID_LOOKUP
+        .binary_search_by_key(&id, |(stable_id, _)| *stable_id)
+        .ok()
+        .map(|pos| TYPE_STABLE_ID_LOOKUP[pos].1 as usize)
+}}
+
+#[cfg(test)]
+mod stable_id_test {{
+    use super::*;
+
+    #[test]
+    fn stable_id_lookup() {{
+        assert_eq!(TYPE_STABLE_IDS.len(), DATATYPES.len());
+        for (idx, id) in TYPE_STABLE_IDS.iter().enumerate() {{
+            assert_eq!(stable_id_to_spec_index(*id), Some(idx));
+        }}
+    }}
+}}
+"#,
+        count = stable_ids.len()
+    ))
+}
+
+/// compile-time checks that the content mode of each entry of DATATYPES matches its content
+///
+/// A failure here is a bug in the generator; with `--no-const-assertions` the checks are left out.
+pub(crate) const DATATYPES_ASSERTIONS: &str = r#"
+// every data type with sub elements has a mode for elements, and a character type only if it is Mixed
+const _: () = {
+    let mut idx = 0;
+    while idx < DATATYPES.len() {
+        let spec = &DATATYPES[idx];
+        let has_sub_elements = spec.sub_elements.0 < spec.sub_elements.1;
+        match spec.mode {
+            ContentMode::Sequence | ContentMode::Choice | ContentMode::Bag => {
+                assert!(has_sub_elements, "a Sequence, Choice or Bag data type has no sub elements");
+            }
+            ContentMode::Characters => {
+                assert!(!has_sub_elements, "a Characters data type has sub elements");
+                assert!(spec.character_data.is_some(), "a Characters data type has no character type");
+            }
+            ContentMode::Mixed => {
+                assert!(has_sub_elements, "a Mixed data type has no sub elements");
+                assert!(spec.character_data.is_some(), "a Mixed data type has no character type");
+            }
+        }
+        idx += 1;
+    }
+};
+"#;
+
+// 32-bit FNV-1a hash of the type name
+fn stable_type_id(typename: &str) -> u32 {
+    typename.bytes().fold(0x811C_9DC5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
 /// collect the enum items of DEST attributes of all elements
 fn find_ref_attribute_types(
     element_types: &FxHashMap<String, MergedElementDataType>,
     character_types: &FxHashMap<String, CharacterDataType>,
 ) -> HashSet<String> {
     let ref_attribute_types: HashSet<String> = element_types
-        .iter() // iterate over all element types
-        .filter_map(|(_, et)| {
+        .values()
+        .filter_map(|et| {
             // filtering to get only those which have a DEST attribute
             et.attributes()
                 .iter()
@@ -143,11 +423,44 @@ fn find_ref_attribute_types(
                 })
         })
         .flatten() // flatten the two-level iterator
-        .map(|(name, _)| name.to_owned())
+        .map(|(name, _, _)| name.to_owned())
         .collect();
     ref_attribute_types
 }
 
+/// find the DEST enum items which don't match the xsd type names of any element type
+///
+/// References with such a DEST value get an empty ref_info range, so their targets can't be validated.
+/// This happens if the collection of the xsd type names misses some of the base types.
+/// Each result is the enum item together with the name of the character type of the DEST attribute.
+pub(crate) fn find_unmatched_ref_targets(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    character_types: &FxHashMap<String, CharacterDataType>,
+) -> Vec<(String, String)> {
+    let all_xsd_typenames: HashSet<&String> = element_types
+        .values()
+        .filter_map(MergedElementDataType::xsd_typenames)
+        .flatten()
+        .collect();
+    let dest_types: BTreeSet<&String> = element_types
+        .values()
+        .filter_map(|et| et.attributes().iter().find(|attr| attr.name == "DEST"))
+        .map(|attr| &attr.attr_type)
+        .collect();
+
+    let mut unmatched = Vec::new();
+    for dest_type in dest_types {
+        if let Some(CharacterDataType::Enum(enumdef)) = character_types.get(dest_type) {
+            for (name, _, _) in &enumdef.enumitems {
+                if !all_xsd_typenames.contains(name) {
+                    unmatched.push((name.clone(), dest_type.clone()));
+                }
+            }
+        }
+    }
+    unmatched
+}
+
 fn build_elementnames_of_type_list(
     element_types: &FxHashMap<String, MergedElementDataType>,
 ) -> FxHashMap<String, HashSet<String>> {
@@ -176,23 +489,26 @@ fn build_elementnames_of_type_list(
     map
 }
 
-fn calc_element_mode(elemtype: &MergedElementDataType) -> &'static str {
+pub(crate) fn calc_element_mode(elemtype: &MergedElementDataType) -> &'static str {
     match elemtype {
         MergedElementDataType::ElementsGroup { element_collection }
         | MergedElementDataType::Elements {
             element_collection, ..
         } => {
             if let ElementCollection::Choice { amount, .. } = element_collection {
-                if let ElementAmount::Any = amount {
-                    "ContentMode::Bag"
+                // a choice that may occur more than once allows the sub elements in any order
+                if let ElementAmount::Any | ElementAmount::Exactly(_) | ElementAmount::AtLeast(_) =
+                    amount
+                {
+                    "Bag"
                 } else {
-                    "ContentMode::Choice"
+                    "Choice"
                 }
             } else {
-                "ContentMode::Sequence"
+                "Sequence"
             }
         }
-        MergedElementDataType::Characters { .. } => "ContentMode::Characters",
-        MergedElementDataType::Mixed { .. } => "ContentMode::Mixed",
+        MergedElementDataType::Characters { .. } => "Characters",
+        MergedElementDataType::Mixed { .. } => "Mixed",
     }
 }
@@ -0,0 +1,243 @@
+use crate::generator::element_types::calc_element_mode;
+use crate::generator::parent_types::collect_sub_elements;
+use crate::generator::removed_elements::describe_version_ranges;
+use crate::generator::{Attribute, Element};
+use crate::generator::{CodeOutput, MergedElementDataType};
+use crate::{ElementAmount, XsdFileInfo};
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+
+pub(crate) const STYLE: &str = r"body { font-family: sans-serif; margin: 2em; line-height: 1.4; }
+h3 { border-top: 1px solid #ccc; padding-top: 1em; font-family: monospace; }
+table { border-collapse: collapse; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.6em; text-align: left; vertical-align: top; }
+th { background: #eee; }
+td.name, .typename { font-family: monospace; }
+ul.index { columns: 3; font-family: monospace; }
+.doc { color: #444; }";
+
+/// write schema_reference.html in the output directory, a browsable reference of all element types
+///
+/// The file is self-contained: it only uses inline CSS and no JavaScript, so it can be opened directly in a browser.
+/// Each type lists its attributes and sub elements, and links to the types of the sub elements and to the types of
+/// the elements that contain it.
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    present_versions: &[usize],
+    output: &mut CodeOutput,
+) -> Result<(), String> {
+    // groups are shown as part of the types that reference them
+    let mut typenames: Vec<&String> = element_types
+        .iter()
+        .filter(|(_, elemtype)| !matches!(elemtype, MergedElementDataType::ElementsGroup { .. }))
+        .map(|(typename, _)| typename)
+        .collect();
+    typenames.sort();
+    let anchors: FxHashMap<&str, String> = typenames
+        .iter()
+        .enumerate()
+        .map(|(idx, typename)| (typename.as_str(), format!("t{idx}")))
+        .collect();
+    let links: FxHashMap<&str, String> = anchors
+        .iter()
+        .map(|(typename, anchor)| (*typename, format!("#{anchor}")))
+        .collect();
+
+    // the sub elements of each type, with the content of referenced groups resolved
+    let mut sub_elements: FxHashMap<&str, Vec<&Element>> = FxHashMap::default();
+    let mut parents: FxHashMap<&str, BTreeSet<&str>> = FxHashMap::default();
+    for typename in &typenames {
+        let mut elements = Vec::new();
+        collect_sub_elements(
+            element_types,
+            &element_types[*typename],
+            &mut HashSet::new(),
+            &mut |element| elements.push(element),
+        );
+        for element in &elements {
+            parents
+                .entry(element.typeref.as_str())
+                .or_default()
+                .insert(typename.as_str());
+        }
+        sub_elements.insert(typename.as_str(), elements);
+    }
+
+    // index of all element names; an element name can be used with several types
+    let mut index: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    index.entry("AUTOSAR").or_default().insert("AR:AUTOSAR");
+    for element in sub_elements.values().flatten() {
+        index
+            .entry(element.name.as_str())
+            .or_default()
+            .insert(element.typeref.as_str());
+    }
+
+    let versions: Vec<&str> = present_versions
+        .iter()
+        .map(|idx| xsd_config[*idx].desc)
+        .collect();
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>AUTOSAR schema reference</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n<h1>AUTOSAR schema reference</h1>\n<p>{} element types, generated from: {}</p>\n",
+        typenames.len(),
+        escape(&versions.join(", "))
+    );
+
+    html.push_str("<h2 id=\"index\">Elements</h2>\n<ul class=\"index\">\n");
+    for (name, element_typenames) in &index {
+        for typename in element_typenames {
+            if let Some(link) = links.get(typename) {
+                writeln!(
+                    html,
+                    "<li><a href=\"{link}\">{}</a>{}</li>",
+                    escape(name),
+                    if element_typenames.len() > 1 {
+                        format!(" ({})", escape(typename))
+                    } else {
+                        String::new()
+                    }
+                )
+                .unwrap();
+            }
+        }
+    }
+    html.push_str("</ul>\n<h2>Element types</h2>\n");
+
+    for typename in &typenames {
+        let elemtype = &element_types[*typename];
+        writeln!(
+            html,
+            "<h3 id=\"{}\">{}</h3>",
+            anchors[typename.as_str()],
+            escape(typename)
+        )
+        .unwrap();
+        let mut content = calc_element_mode(elemtype).to_string();
+        if let Some(basetype) = elemtype.basetype() {
+            write!(content, ", character data: {basetype}").unwrap();
+        }
+        if let Some(mm_class) = elemtype.mm_class() {
+            write!(content, ", class: {}", mm_class.join(" / ")).unwrap();
+        }
+        writeln!(html, "<p>Content: {}</p>", escape(&content)).unwrap();
+
+        if let Some(parent_typenames) = parents.get(typename.as_str()) {
+            let links: Vec<String> = parent_typenames
+                .iter()
+                .map(|parent| type_link(&links, parent))
+                .collect();
+            writeln!(html, "<p>Contained in: {}</p>", links.join(", ")).unwrap();
+        }
+
+        write_attributes(
+            &mut html,
+            elemtype.attributes(),
+            xsd_config,
+            present_versions,
+        );
+        write_sub_elements(
+            &mut html,
+            &sub_elements[typename.as_str()],
+            &links,
+            xsd_config,
+            present_versions,
+        );
+    }
+    html.push_str("</body>\n</html>\n");
+
+    output.write_text("schema_reference.html", &html)
+}
+
+/// write a table of the attributes of a type, unless it has none
+pub(crate) fn write_attributes(
+    html: &mut String,
+    attributes: &[Attribute],
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) {
+    if attributes.is_empty() {
+        return;
+    }
+    html.push_str("<table>\n<tr><th>Attribute</th><th>Type</th><th>Required</th><th>Versions</th><th>Description</th></tr>\n");
+    for attr in attributes {
+        writeln!(
+            html,
+            "<tr><td class=\"name\">{}</td><td class=\"typename\">{}</td><td>{}</td><td>{}</td><td class=\"doc\">{}</td></tr>",
+            escape(&attr.name),
+            escape(&attr.attr_type),
+            if attr.required { "yes" } else { "no" },
+            escape(&version_text(attr.version_info, xsd_config, present_versions)),
+            escape(attr.docstring.as_deref().unwrap_or_default())
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+/// write a table of the sub elements of a type, which links each element to the `links` entry of its type
+pub(crate) fn write_sub_elements(
+    html: &mut String,
+    elements: &[&Element],
+    links: &FxHashMap<&str, String>,
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) {
+    if elements.is_empty() {
+        return;
+    }
+    html.push_str("<table>\n<tr><th>Sub element</th><th>Type</th><th>Multiplicity</th><th>Versions</th><th>Description</th></tr>\n");
+    for element in elements {
+        writeln!(
+            html,
+            "<tr><td class=\"name\">{}</td><td class=\"typename\">{}</td><td>{}</td><td>{}</td><td class=\"doc\">{}</td></tr>",
+            escape(&element.name),
+            type_link(links, &element.typeref),
+            multiplicity(element.amount),
+            escape(&version_text(element.version_info, xsd_config, present_versions)),
+            escape(element.docstring.as_deref().unwrap_or_default())
+        )
+        .unwrap();
+    }
+    html.push_str("</table>\n");
+}
+
+pub(crate) fn type_link(links: &FxHashMap<&str, String>, typename: &str) -> String {
+    if let Some(link) = links.get(typename) {
+        format!("<a href=\"{link}\">{}</a>", escape(typename))
+    } else {
+        escape(typename)
+    }
+}
+
+/// the multiplicity of an element, e.g. "0..1"
+pub(crate) fn multiplicity(amount: ElementAmount) -> String {
+    match amount {
+        ElementAmount::ZeroOrOne => "0..1".to_string(),
+        ElementAmount::One => "1".to_string(),
+        ElementAmount::Any => "0..*".to_string(),
+        ElementAmount::Exactly(count) => count.to_string(),
+        ElementAmount::AtLeast(count) => format!("{count}..*"),
+    }
+}
+
+// most items exist in all versions, so only the exceptions show the version ranges
+pub(crate) fn version_text(
+    mask: usize,
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) -> String {
+    if present_versions.iter().all(|idx| mask & (1 << idx) != 0) {
+        "all".to_string()
+    } else {
+        describe_version_ranges(mask, xsd_config, present_versions).join(", ")
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
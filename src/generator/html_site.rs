@@ -0,0 +1,388 @@
+use crate::generator::element_types::calc_element_mode;
+use crate::generator::html::{
+    escape, type_link, version_text, write_attributes, write_sub_elements, STYLE,
+};
+use crate::generator::parent_types::collect_sub_elements;
+use crate::generator::template::render;
+use crate::generator::Element;
+use crate::generator::{CodeOutput, MergedElementDataType};
+use crate::{CharacterDataType, XsdFileInfo};
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+
+// the layout of all pages; root is the relative path from the page to the top directory of the site
+const PAGE_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>{{title}}</title>
+<link rel="stylesheet" href="{{root}}style.css">
+</head>
+<body>
+<nav><a href="{{root}}index.html">Index</a></nav>
+<h1>{{title}}</h1>
+{{body}}</body>
+</html>
+"#;
+
+const SITE_STYLE: &str = r"nav { margin-bottom: 1em; }
+h2 { border-top: 1px solid #ccc; padding-top: 0.5em; }
+td.available { text-align: center; }";
+
+/// write the html schema browser for --emit-html: an index page, and a page for each element type in types/
+///
+/// Like schema_reference.html, the pages only use CSS and no JavaScript. They contain no timestamp, so the output
+/// only changes if the schema changes. Pages of types that no longer exist are not removed from the directory.
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    character_types: &FxHashMap<String, CharacterDataType>,
+    present_versions: &[usize],
+    site_dir: &Path,
+    output: &mut CodeOutput,
+) -> Result<(), String> {
+    let types_dir = site_dir.join("types");
+    std::fs::create_dir_all(&types_dir)
+        .map_err(|err| format!("Error: could not create {}: {err}", types_dir.display()))?;
+
+    // groups are shown as part of the types that reference them
+    let mut typenames: Vec<&String> = element_types
+        .iter()
+        .filter(|(_, elemtype)| !matches!(elemtype, MergedElementDataType::ElementsGroup { .. }))
+        .map(|(typename, _)| typename)
+        .collect();
+    typenames.sort();
+    // the type pages are all in the same directory, so the file names are also the links between them
+    let links = page_filenames(&typenames);
+
+    // the sub elements of each type with the content of referenced groups, and the reverse relation
+    let mut sub_elements: FxHashMap<&str, Vec<&Element>> = FxHashMap::default();
+    let mut parents: FxHashMap<&str, BTreeSet<&str>> = FxHashMap::default();
+    let mut uses: FxHashMap<&str, Vec<&Element>> = FxHashMap::default();
+    for typename in &typenames {
+        let mut elements = Vec::new();
+        collect_sub_elements(
+            element_types,
+            &element_types[*typename],
+            &mut HashSet::new(),
+            &mut |element| elements.push(element),
+        );
+        for element in &elements {
+            parents
+                .entry(element.typeref.as_str())
+                .or_default()
+                .insert(typename.as_str());
+            uses.entry(element.typeref.as_str())
+                .or_default()
+                .push(element);
+        }
+        sub_elements.insert(typename.as_str(), elements);
+    }
+
+    // a reference may point to each type whose xsd type names contain the DEST value
+    let mut ref_targets: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    for typename in &typenames {
+        if let Some(xsd_typenames) = element_types[*typename].xsd_typenames() {
+            for xsd_typename in xsd_typenames {
+                ref_targets
+                    .entry(xsd_typename.as_str())
+                    .or_default()
+                    .insert(typename.as_str());
+            }
+        }
+    }
+    let dest_values: BTreeSet<&str> = typenames
+        .iter()
+        .filter_map(|typename| dest_items(&element_types[*typename], character_types))
+        .flatten()
+        .map(|(name, _, _)| name.as_str())
+        .collect();
+
+    output.write_to(
+        &site_dir.join("style.css"),
+        &format!("{STYLE}\n{SITE_STYLE}\n"),
+    )?;
+    let index = generate_index(xsd_config, present_versions, &typenames, &uses, &links);
+    output.write_to(&site_dir.join("index.html"), &index)?;
+
+    for typename in &typenames {
+        let elemtype = &element_types[*typename];
+        let mut body = String::new();
+        write_summary(&mut body, elemtype, uses.get(typename.as_str()));
+
+        // the root element is not contained in any other element
+        let version_mask = if typename.as_str() == "AR:AUTOSAR" {
+            usize::MAX
+        } else {
+            uses.get(typename.as_str())
+                .into_iter()
+                .flatten()
+                .fold(0, |mask, element| mask | element.version_info)
+        };
+        write_versions(&mut body, version_mask, xsd_config, present_versions);
+
+        if let Some(parent_typenames) = parents.get(typename.as_str()) {
+            let parent_links: Vec<String> = parent_typenames
+                .iter()
+                .map(|parent| type_link(&links, parent))
+                .collect();
+            writeln!(
+                body,
+                "<h2>Contained in</h2>\n<p>{}</p>",
+                parent_links.join(", ")
+            )
+            .unwrap();
+        }
+
+        if !elemtype.attributes().is_empty() {
+            body.push_str("<h2>Attributes</h2>\n");
+            write_attributes(
+                &mut body,
+                elemtype.attributes(),
+                xsd_config,
+                present_versions,
+            );
+        }
+        let elements = &sub_elements[typename.as_str()];
+        if !elements.is_empty() {
+            body.push_str("<h2>Sub elements</h2>\n");
+            write_sub_elements(&mut body, elements, &links, xsd_config, present_versions);
+        }
+
+        write_references(
+            &mut body,
+            elemtype,
+            character_types,
+            &ref_targets,
+            &dest_values,
+            &links,
+            xsd_config,
+            present_versions,
+        );
+
+        let page = render(
+            PAGE_TEMPLATE,
+            &[
+                ("title", &escape(typename)),
+                ("root", "../"),
+                ("body", &body),
+            ],
+        );
+        output.write_to(&types_dir.join(&links[typename.as_str()]), &page)?;
+    }
+    Ok(())
+}
+
+// the file names of the type pages, e.g. AR_SYSTEM-SIGNAL.html; characters that are not safe in urls are replaced
+fn page_filenames<'a>(typenames: &[&'a String]) -> FxHashMap<&'a str, String> {
+    let mut used = HashSet::new();
+    let mut filenames = FxHashMap::default();
+    for typename in typenames {
+        let base: String = typename
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let mut filename = format!("{base}.html");
+        let mut counter = 2;
+        while !used.insert(filename.clone()) {
+            filename = format!("{base}-{counter}.html");
+            counter += 1;
+        }
+        filenames.insert(typename.as_str(), filename);
+    }
+    filenames
+}
+
+fn generate_index(
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+    typenames: &[&String],
+    uses: &FxHashMap<&str, Vec<&Element>>,
+    links: &FxHashMap<&str, String>,
+) -> String {
+    let mut body = String::from("<h2>Versions</h2>\n<ul>\n");
+    for idx in present_versions {
+        writeln!(
+            body,
+            "<li>{} ({})</li>",
+            escape(xsd_config[*idx].desc),
+            escape(xsd_config[*idx].name)
+        )
+        .unwrap();
+    }
+    writeln!(
+        body,
+        "</ul>\n<h2>Element types</h2>\n<p>{} element types</p>\n<ul class=\"index\">",
+        typenames.len()
+    )
+    .unwrap();
+    for typename in typenames {
+        // list the names of the elements of each type, since these are more familiar than the xsd type names
+        let element_names: BTreeSet<&str> = uses
+            .get(typename.as_str())
+            .into_iter()
+            .flatten()
+            .map(|element| element.name.as_str())
+            .collect();
+        let element_names: Vec<&str> = element_names.into_iter().collect();
+        writeln!(
+            body,
+            "<li><a href=\"types/{}\">{}</a>{}</li>",
+            links[typename.as_str()],
+            escape(typename),
+            if element_names.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", escape(&element_names.join(", ")))
+            }
+        )
+        .unwrap();
+    }
+    body.push_str("</ul>\n");
+
+    render(
+        PAGE_TEMPLATE,
+        &[
+            ("title", "AUTOSAR schema reference"),
+            ("root", ""),
+            ("body", &body),
+        ],
+    )
+}
+
+// the content model of the type, the names of the elements with this type, and their descriptions
+fn write_summary(
+    body: &mut String,
+    elemtype: &MergedElementDataType,
+    uses: Option<&Vec<&Element>>,
+) {
+    let mut content = calc_element_mode(elemtype).to_string();
+    if let Some(basetype) = elemtype.basetype() {
+        write!(content, ", character data: {basetype}").unwrap();
+    }
+    if let Some(mm_class) = elemtype.mm_class() {
+        write!(content, ", class: {}", mm_class.join(" / ")).unwrap();
+    }
+    writeln!(body, "<p>Content: {}</p>", escape(&content)).unwrap();
+
+    let uses = uses.map(Vec::as_slice).unwrap_or_default();
+    let element_names: BTreeSet<&str> = uses.iter().map(|element| element.name.as_str()).collect();
+    if !element_names.is_empty() {
+        let names: Vec<&str> = element_names.into_iter().collect();
+        writeln!(
+            body,
+            "<p>Elements: <span class=\"typename\">{}</span></p>",
+            escape(&names.join(", "))
+        )
+        .unwrap();
+    }
+    let docstrings: BTreeSet<&str> = uses
+        .iter()
+        .filter_map(|element| element.docstring.as_deref())
+        .collect();
+    for docstring in docstrings {
+        writeln!(body, "<p class=\"doc\">{}</p>", escape(docstring)).unwrap();
+    }
+}
+
+// the availability of the type in each version, which is taken from the elements that use it
+fn write_versions(
+    body: &mut String,
+    version_mask: usize,
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) {
+    body.push_str("<h2>Versions</h2>\n<table>\n<tr><th>Version</th><th>Available</th></tr>\n");
+    for idx in present_versions {
+        writeln!(
+            body,
+            "<tr><td>{}</td><td class=\"available\">{}</td></tr>",
+            escape(xsd_config[*idx].desc),
+            if version_mask & (1 << idx) != 0 {
+                "yes"
+            } else {
+                "no"
+            }
+        )
+        .unwrap();
+    }
+    body.push_str("</table>\n");
+}
+
+// for a reference type, the types that each DEST value may point to; for any other type, the DEST values that
+// can be used to refer to it
+#[allow(clippy::too_many_arguments)]
+fn write_references(
+    body: &mut String,
+    elemtype: &MergedElementDataType,
+    character_types: &FxHashMap<String, CharacterDataType>,
+    ref_targets: &BTreeMap<&str, BTreeSet<&str>>,
+    dest_values: &BTreeSet<&str>,
+    links: &FxHashMap<&str, String>,
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) {
+    if let Some(items) = dest_items(elemtype, character_types) {
+        body.push_str("<h2>Reference targets</h2>\n<table>\n<tr><th>DEST</th><th>Versions</th><th>Target types</th></tr>\n");
+        let mut items: Vec<&(String, usize, Option<String>)> = items.iter().collect();
+        items.sort();
+        for (name, version_info, _) in items {
+            let targets: Vec<String> = ref_targets
+                .get(name.as_str())
+                .into_iter()
+                .flatten()
+                .map(|target| type_link(links, target))
+                .collect();
+            writeln!(
+                body,
+                "<tr><td class=\"name\">{}</td><td>{}</td><td>{}</td></tr>",
+                escape(name),
+                escape(&version_text(*version_info, xsd_config, present_versions)),
+                targets.join(", ")
+            )
+            .unwrap();
+        }
+        body.push_str("</table>\n");
+    }
+
+    if let Some(xsd_typenames) = elemtype.xsd_typenames() {
+        let referenced_as: BTreeSet<&str> = xsd_typenames
+            .iter()
+            .map(String::as_str)
+            .filter(|name| dest_values.contains(name))
+            .collect();
+        if !referenced_as.is_empty() {
+            let names: Vec<&str> = referenced_as.into_iter().collect();
+            writeln!(
+                body,
+                "<h2>References</h2>\n<p>Referenced with DEST: <span class=\"typename\">{}</span></p>",
+                escape(&names.join(", "))
+            )
+            .unwrap();
+        }
+    }
+}
+
+// the enum items of the DEST attribute of a reference type
+fn dest_items<'a>(
+    elemtype: &MergedElementDataType,
+    character_types: &'a FxHashMap<String, CharacterDataType>,
+) -> Option<&'a [(String, usize, Option<String>)]> {
+    let attr = elemtype
+        .attributes()
+        .iter()
+        .find(|attr| attr.name == "DEST")?;
+    if let Some(CharacterDataType::Enum(enumdef)) = character_types.get(&attr.attr_type) {
+        Some(&enumdef.enumitems)
+    } else {
+        None
+    }
+}
@@ -1,13 +1,34 @@
-use crate::generator::{name_to_identifier, perfect_hash};
-use crate::{AutosarDataTypes, CharacterDataType, ElementCollectionItem, HashSet};
+use crate::generator::perfect_hash::{HashConstants, PerfectHash};
+use crate::generator::{
+    name_to_identifier, perfect_hash, try_name_to_identifier, CodeOutput, GeneratorOptions,
+};
+use crate::{
+    AutosarDataTypes, CharacterDataType, ElementCollectionItem, FxHashMap, HashSet, XsdFileInfo,
+};
 use std::fmt::Write;
-use std::fs::File;
-use std::io::Write as IoWrite;
 
-pub(crate) fn generate(autosar_schema: &AutosarDataTypes) {
+/// the numerical values of the variants of the generated enums, which are needed by tables indexed by these values
+#[derive(Default)]
+pub(crate) struct IdentifierValues {
+    pub(crate) attribute_names: FxHashMap<String, usize>,
+    pub(crate) enum_items: FxHashMap<String, usize>,
+}
+
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    autosar_schema: &AutosarDataTypes,
+    options: &GeneratorOptions,
+    header: &str,
+    output: &mut CodeOutput,
+) -> Result<IdentifierValues, String> {
     let mut attribute_names = HashSet::new();
     let mut element_names = HashSet::new();
     let mut enum_items = HashSet::new();
+    let mut enum_item_versions = FxHashMap::<String, usize>::default();
+    let mut enum_item_docstrings = FxHashMap::<String, Vec<String>>::default();
+    let mut attribute_versions = FxHashMap::<String, usize>::default();
+    // all versions that contribute to the schema
+    let mut all_versions = 0;
 
     element_names.insert("AUTOSAR".to_string());
 
@@ -16,28 +37,50 @@ pub(crate) fn generate(autosar_schema: &AutosarDataTypes) {
         for ec_item in group_type.items() {
             // for each sub-element of the current element type (skipping groups)
             if let ElementCollectionItem::Element(elem) = ec_item {
-                if element_names.get(&elem.name).is_none() {
+                all_versions |= elem.version_info;
+                if !element_names.contains(&elem.name) {
                     element_names.insert(elem.name.clone());
                 }
             }
         }
     }
     // for each element data type in the schema: collect attribute names
+    // an attribute is valid in every version in which it is part of any of the element types
     for artype in autosar_schema.element_types.values() {
         for attr in artype.attributes() {
             attribute_names.insert(attr.name.clone());
+            *attribute_versions.entry(attr.name.clone()).or_default() |= attr.version_info;
         }
     }
 
     // collect all enum values in use by any character data type
-    for artype in autosar_schema.character_types.values() {
-        if let CharacterDataType::Enum(enumdef) = &artype {
-            for (itemname, _) in &enumdef.enumitems {
+    // an enum item is valid in every version in which it is part of any of the enum types
+    // the types are sorted, so that the docstrings of items which are documented by several types have a stable order
+    let mut chartype_names: Vec<&str> = autosar_schema
+        .character_types
+        .keys()
+        .map(|typename| autosar_schema.names.resolve(*typename))
+        .collect();
+    chartype_names.sort();
+    for typename in chartype_names {
+        if let Some(CharacterDataType::Enum(enumdef)) = autosar_schema.character_type(typename) {
+            for (itemname, version, docstring) in &enumdef.enumitems {
                 enum_items.insert(itemname.to_owned());
+                *enum_item_versions.entry(itemname.to_owned()).or_default() |= version;
+                if let Some(docstring) = docstring.as_deref().map(str::trim) {
+                    let item_docstrings =
+                        enum_item_docstrings.entry(itemname.to_owned()).or_default();
+                    if !docstring.is_empty() && !item_docstrings.iter().any(|doc| doc == docstring)
+                    {
+                        item_docstrings.push(docstring.to_string());
+                    }
+                }
             }
         }
     }
 
+    let enum_item_deprecations = removed_items(&enum_item_versions, all_versions, xsd_config);
+
     let mut element_names: Vec<String> = element_names
         .iter()
         .map(std::borrow::ToOwned::to_owned)
@@ -54,64 +97,212 @@ pub(crate) fn generate(autosar_schema: &AutosarDataTypes) {
         .collect();
     enum_items.sort();
 
-    let element_name_refs: Vec<&str> = element_names.iter().map(|name| &**name).collect();
-    let disps = perfect_hash::make_perfect_hash(&element_name_refs, 7)
-        .map_err(|err| format!("element names: {err}"))
-        .unwrap();
-    let enumstr = generate_enum(
-        "ElementName",
-        "Enum of all element names in Autosar",
-        &element_name_refs,
-        &disps,
-    );
-
-    let mut file = File::create("gen/elementname.rs").unwrap();
-    file.write_all(enumstr.as_bytes()).unwrap();
+    for (what, count) in [
+        ("element names", element_names.len()),
+        ("attribute names", attribute_names.len()),
+        ("enum items", enum_items.len()),
+    ] {
+        if u32::try_from(count).is_err() {
+            return Err(format!(
+                "Error: there are {count} {what}, which is more than a u32 enum can represent"
+            ));
+        }
+    }
 
-    let attribute_name_refs: Vec<&str> = attribute_names.iter().map(|name| &**name).collect();
-    let disps = perfect_hash::make_perfect_hash(&attribute_name_refs, 5)
-        .map_err(|err| format!("attribute names: {err}"))
-        .unwrap();
-    let enumstr = generate_enum(
-        "AttributeName",
-        "Enum of all attribute names in Autosar",
-        &attribute_name_refs,
-        &disps,
+    output.write(
+        "hashfunc.rs",
+        &format!("{header}{}", generate_hashfunc_module()),
     );
-    let mut file = File::create("gen/attributename.rs").unwrap();
-    file.write_all(enumstr.as_bytes()).unwrap();
 
+    let element_name_refs: Vec<&str> = element_names.iter().map(|name| &**name).collect();
+    let attribute_name_refs: Vec<&str> = attribute_names.iter().map(|name| &**name).collect();
     let enum_item_refs: Vec<&str> = enum_items.iter().map(|name| &**name).collect();
-    let disps = perfect_hash::make_perfect_hash(&enum_item_refs, 5)
-        .map_err(|err| format!("enum item names: {err}"))
-        .unwrap();
 
-    let enumstr = generate_enum(
-        "EnumItem",
-        "Enum of all possible enum values in Autosar",
-        &enum_item_refs,
-        &disps,
-    );
-    let mut file = File::create("gen/enumitem.rs").unwrap();
-    file.write_all(enumstr.as_bytes()).unwrap();
+    let mut values = IdentifierValues::default();
+    for (
+        enum_name,
+        enum_docstring,
+        item_names,
+        item_versions,
+        item_docstrings,
+        item_deprecations,
+        filename,
+    ) in [
+        (
+            "ElementName",
+            "Enum of all element names in Autosar",
+            &element_name_refs,
+            None,
+            None,
+            None,
+            "elementname.rs",
+        ),
+        (
+            "AttributeName",
+            "Enum of all attribute names in Autosar",
+            &attribute_name_refs,
+            Some(&attribute_versions),
+            None,
+            None,
+            "attributename.rs",
+        ),
+        (
+            "EnumItem",
+            "Enum of all possible enum values in Autosar",
+            &enum_item_refs,
+            Some(&enum_item_versions),
+            Some(&enum_item_docstrings),
+            Some(&enum_item_deprecations),
+            "enumitem.rs",
+        ),
+    ] {
+        check_identifiers(item_names).map_err(|err| format!("Error: {enum_name}: {err}"))?;
+        let phash = perfect_hash::find_perfect_hash(item_names)
+            .map_err(|err| format!("Error: {enum_name}: {err}"))?;
+        let item_values = item_names
+            .iter()
+            .map(|name| {
+                let idx = perfect_hash::get_index(name, &phash, item_names.len());
+                ((*name).to_string(), idx)
+            })
+            .collect();
+        match enum_name {
+            "AttributeName" => values.attribute_names = item_values,
+            "EnumItem" => values.enum_items = item_values,
+            _ => {}
+        }
+        print!(
+            "{enum_name}: {} items, lambda {}, {} displacements",
+            item_names.len(),
+            phash.lambda,
+            phash.disps.len()
+        );
+        if phash.constants == perfect_hash::DEFAULT_HASH_CONSTANTS {
+            println!();
+        } else {
+            let HashConstants {
+                mul1,
+                mul2,
+                init1,
+                init2,
+                finalize,
+            } = phash.constants;
+            let finalized = if finalize { ", finalized" } else { "" };
+            println!(
+                ", alternate hash constants ({mul1:#010X}, {mul2:#010X}, {init1:#010X}, {init2:#010X}{finalized})"
+            );
+        }
+
+        let (plain_size, compact_size) = string_table_sizes(item_names);
+        if options.plain_string_tables {
+            println!("    plain string table: {plain_size} bytes (compact: {compact_size} bytes)");
+        } else {
+            println!(
+                "    compact string table: {compact_size} bytes, {} bytes smaller than the plain string table",
+                plain_size - compact_size
+            );
+        }
+
+        let enumstr = generate_enum(
+            enum_name,
+            enum_docstring,
+            item_names,
+            item_versions,
+            item_docstrings,
+            item_deprecations,
+            all_versions,
+            &phash,
+            options,
+        );
+        output.write(filename, &format!("{header}{enumstr}"));
+    }
+
+    Ok(values)
 }
 
-fn generate_enum(
+// check that an identifier can be derived from each item name, and that no two items get the same identifier
+fn check_identifiers(item_names: &[&str]) -> Result<(), String> {
+    let mut identifiers = FxHashMap::<String, &str>::default();
+    for item_name in item_names {
+        let ident = try_name_to_identifier(item_name)?;
+        if let Some(other_name) = identifiers.insert(ident.clone(), item_name) {
+            return Err(format!(
+                "the items \"{other_name}\" and \"{item_name}\" both have the identifier {ident}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+// find the items that are not valid in the newest version, together with the description of the version
+// in which they were removed, i.e. the first version with data after the last version that contains them
+fn removed_items<'a>(
+    item_versions: &FxHashMap<String, usize>,
+    all_versions: usize,
+    xsd_config: &'a [XsdFileInfo],
+) -> FxHashMap<String, &'a str> {
+    let mut removed = FxHashMap::default();
+    if all_versions == 0 {
+        return removed;
+    }
+    let newest_version = all_versions.ilog2();
+    for (item_name, versions) in item_versions {
+        if *versions == 0 || versions & (1 << newest_version) != 0 {
+            continue;
+        }
+        let later_versions = all_versions & !((2 << versions.ilog2()) - 1);
+        let removed_idx = later_versions.trailing_zeros() as usize;
+        if let Some(xsd_file_info) = xsd_config.get(removed_idx) {
+            removed.insert(item_name.clone(), xsd_file_info.desc);
+        }
+    }
+    removed
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn generate_enum(
     enum_name: &str,
     enum_docstring: &str,
     item_names: &[&str],
-    disps: &[(u32, u32)],
+    item_versions: Option<&FxHashMap<String, usize>>,
+    item_docstrings: Option<&FxHashMap<String, Vec<String>>>,
+    item_deprecations: Option<&FxHashMap<String, &str>>,
+    all_versions: usize,
+    phash: &PerfectHash,
+    options: &GeneratorOptions,
 ) -> String {
     let mut generated = String::new();
+    let disps = &phash.disps;
     let displen = disps.len();
+    let repr = enum_repr(item_names.len());
+    let repr_max = if repr == "u16" {
+        u32::from(u16::MAX)
+    } else {
+        u32::MAX
+    };
+    assert!(
+        disps
+            .iter()
+            .all(|&(d1, d2)| d1 <= repr_max && d2 <= repr_max),
+        "{enum_name}: the displacement values don't fit into {repr}"
+    );
+    // the values in DISPLACEMENTS are only converted if they are not u32 already; From can't be used in a const fn
+    let (d1_u32, d2_u32, d1_const, d2_const) = if repr == "u32" {
+        ("d1", "d2", "d1", "d2")
+    } else {
+        ("u32::from(d1)", "u32::from(d2)", "d1 as u32", "(d2 as u32)")
+    };
 
     let width = item_names.iter().map(|name| name.len()).max().unwrap();
 
+    if phash.constants == perfect_hash::DEFAULT_HASH_CONSTANTS {
+        generated.push_str("use crate::hashfunc::hashfunc;\n\n");
+    } else {
+        generated.push_str(&generate_hashfunc_wrapper(&phash.constants));
+    }
     writeln!(
         generated,
-        "use crate::hashfunc;
-
-#[derive(Debug)]
+        "#[derive(Debug)]
 /// The error type `Parse{enum_name}Error` is returned when `from_str()` / `parse()` fails for `{enum_name}`
 pub struct Parse{enum_name}Error;
 "
@@ -121,34 +312,119 @@ pub struct Parse{enum_name}Error;
         .write_str(
             "#[allow(dead_code, non_camel_case_types)]
 #[derive(Clone, Copy, Eq, PartialEq, Hash)]
-#[repr(u16)]
 #[non_exhaustive]
 ",
         )
         .unwrap();
+    writeln!(generated, "#[repr({repr})]").unwrap();
     writeln!(generated, "/// {enum_docstring}\npub enum {enum_name} {{").unwrap();
     let mut hash_sorted_item_names = item_names.to_owned();
     hash_sorted_item_names.sort_by(|k1, k2| {
-        perfect_hash::get_index(k1, disps, item_names.len()).cmp(&perfect_hash::get_index(
+        perfect_hash::get_index(k1, phash, item_names.len()).cmp(&perfect_hash::get_index(
             k2,
-            disps,
+            phash,
             item_names.len(),
         ))
     });
     for item_name in item_names {
-        let idx = perfect_hash::get_index(item_name, disps, item_names.len());
+        let idx = perfect_hash::get_index(item_name, phash, item_names.len());
         let ident = name_to_identifier(item_name);
-        writeln!(generated, "    /// {item_name}").unwrap();
+        match item_docstrings.and_then(|docs| docs.get(*item_name)) {
+            Some(docstrings) if !docstrings.is_empty() => {
+                // items that are documented differently by several types get all of the texts
+                for (doc_idx, docstring) in docstrings.iter().enumerate() {
+                    if doc_idx > 0 {
+                        writeln!(generated, "    ///").unwrap();
+                    }
+                    for line in docstring.lines() {
+                        writeln!(generated, "    /// {}", line.trim()).unwrap();
+                    }
+                }
+            }
+            _ => writeln!(generated, "    /// {item_name}").unwrap(),
+        }
+        if let Some(removed_in) = item_deprecations.and_then(|deps| deps.get(*item_name)) {
+            // the description comes from config.toml, so it is escaped like any other generated string literal
+            let since = removed_in.strip_prefix("AUTOSAR ").unwrap_or(removed_in);
+            let note = format!("Removed in {removed_in}");
+            writeln!(
+                generated,
+                "    #[deprecated(since = {since:?}, note = {note:?})]"
+            )
+            .unwrap();
+        }
         writeln!(generated, "    {ident:width$}= {idx},").unwrap();
     }
     writeln!(generated, "}}").unwrap();
 
     let length = item_names.len();
+    // a few items with their index; if the hashfunc of the generated code is different, then this will fail
+    let known_indices: String = item_names
+        .iter()
+        .take(4)
+        .map(|item_name| {
+            let idx = perfect_hash::get_index(item_name, phash, item_names.len());
+            format!("        assert_eq!({enum_name}::from_bytes(b{item_name:?}).unwrap() as usize, {idx});\n")
+        })
+        .collect();
+    let (string_table, name_of_item_idx, name_of_self) = if options.plain_string_tables {
+        (
+            format!(
+                "    #[rustfmt::skip]\n    const STRING_TABLE: [&'static str; {length}] = {hash_sorted_item_names:?};"
+            ),
+            format!("{enum_name}::STRING_TABLE[item_idx]"),
+            format!("{enum_name}::STRING_TABLE[*self as usize]"),
+        )
+    } else {
+        (
+            generate_compact_string_table(enum_name, &hash_sorted_item_names),
+            format!("{enum_name}::item_name(item_idx)"),
+            format!("{enum_name}::item_name(*self as usize)"),
+        )
+    };
+    // str slicing is not possible in a const fn, so from_bytes_checked compares the bytes from start to end
+    let name_bytes_of_item_idx = if options.plain_string_tables {
+        format!("let name = {enum_name}::STRING_TABLE[item_idx].as_bytes();\n        let (start, end) = (0, name.len());")
+    } else {
+        format!("let name = {enum_name}::NAMES.as_bytes();\n        let start = {enum_name}::NAME_OFFSETS[item_idx] as usize;\n        let end = {enum_name}::NAME_OFFSETS[item_idx + 1] as usize;")
+    };
+    let first_item = item_names[0];
+    let version_info = if let Some(item_versions) = item_versions {
+        generate_version_info(
+            enum_name,
+            &hash_sorted_item_names,
+            item_versions,
+            all_versions,
+        )
+    } else {
+        String::new()
+    };
+    // to_str() doesn't make it obvious that the name is the xml name and not the rust identifier
+    let xml_name_doc = match enum_name {
+        "ElementName" => {
+            "Returns the XML element name as it appears in AUTOSAR files, e.g., `SHORT-NAME`."
+        }
+        "AttributeName" => {
+            "Returns the XML attribute name as it appears in AUTOSAR files, e.g., `DEST`."
+        }
+        _ => "Returns the value as it appears in AUTOSAR files, e.g., `ECU-INSTANCE`.",
+    };
+    let serde_impls = if options.serde {
+        generate_serde_impls(enum_name)
+    } else {
+        String::new()
+    };
     writeln!(
         generated,
         r##"
 impl {enum_name} {{
-    const STRING_TABLE: [&'static str; {length}] = {hash_sorted_item_names:?};
+{string_table}
+
+    /// number of items in `{enum_name}`
+    pub const COUNT: usize = {length};
+
+    #[rustfmt::skip]
+    const DISPLACEMENTS: [({repr}, {repr}); {displen}] = {disps:?};
 
     /// derive an enum entry from an input string using a perfect hash function
     ///
@@ -156,43 +432,167 @@ impl {enum_name} {{
     ///
     /// [`Parse{enum_name}Error`]: The input string did not match the name of any enum item
     pub fn from_bytes(input: &[u8]) -> Result<Self, Parse{enum_name}Error> {{
-        static DISPLACEMENTS: [(u16, u16); {displen}] = {disps:?};
+        static DISPLACEMENTS: [({repr}, {repr}); {displen}] = {enum_name}::DISPLACEMENTS;
         let (g, f1, f2) = hashfunc(input);
         let (d1, d2) = DISPLACEMENTS[(g % {displen}) as usize];
-        let item_idx = u32::from(d2).wrapping_add(f1.wrapping_mul(u32::from(d1))).wrapping_add(f2) as usize % {length};
-        if {enum_name}::STRING_TABLE[item_idx].as_bytes() != input {{
+        let item_idx = {d2_u32}.wrapping_add(f1.wrapping_mul({d1_u32})).wrapping_add(f2) as usize % {length};
+        if {name_of_item_idx}.as_bytes() != input {{
             return Err(Parse{enum_name}Error);
         }}
         Ok(unsafe {{
-            std::mem::transmute::<u16, Self>(item_idx as u16)
+            core::mem::transmute::<{repr}, Self>(item_idx as {repr})
         }})
     }}
 
+    /// derive an enum entry from an input string, in a const context
+    ///
+    /// This is the const version of `from_bytes`, e.g. for constants and const generic arguments:
+    /// `const ITEM: {enum_name} = {enum_name}::from_bytes_checked(b{first_item:?}).unwrap();`
+    #[must_use]
+    pub const fn from_bytes_checked(input: &[u8]) -> Option<Self> {{
+        let (g, f1, f2) = hashfunc(input);
+        let (d1, d2) = {enum_name}::DISPLACEMENTS[(g % {displen}) as usize];
+        let item_idx = {d2_const}.wrapping_add(f1.wrapping_mul({d1_const})).wrapping_add(f2) as usize % {length};
+        {name_bytes_of_item_idx}
+        if end - start != input.len() {{
+            return None;
+        }}
+        let mut pos = 0;
+        while pos < input.len() {{
+            if name[start + pos] != input[pos] {{
+                return None;
+            }}
+            pos += 1;
+        }}
+        Some(unsafe {{ core::mem::transmute::<{repr}, Self>(item_idx as {repr}) }})
+    }}
+
+    /// derive an enum entry from an input string, ignoring case and accepting '_' in place of '-'
+    ///
+    /// This is intended for user input, e.g. "ar_package" will be accepted for AR-PACKAGE.
+    /// Items whose names contain lowercase characters are only found if the input matches them exactly.
+    ///
+    /// # Errors
+    ///
+    /// [`Parse{enum_name}Error`]: The input string did not match the name of any enum item
+    pub fn from_str_relaxed(input: &str) -> Result<Self, Parse{enum_name}Error> {{
+        if let Ok(item) = Self::from_bytes(input.as_bytes()) {{
+            return Ok(item);
+        }}
+        // no item name is longer than the buffer, so longer inputs can be rejected immediately
+        let mut buffer = [0u8; {width}];
+        if input.len() > buffer.len() {{
+            return Err(Parse{enum_name}Error);
+        }}
+        for (dest, src) in buffer.iter_mut().zip(input.bytes()) {{
+            *dest = if src == b'_' {{ b'-' }} else {{ src.to_ascii_uppercase() }};
+        }}
+        Self::from_bytes(&buffer[..input.len()])
+    }}
+
     /// get the str corresponding to an item
     ///
     /// The returned &str has static lifetime, becasue it is a reference to an entry in a list of constants
     #[must_use]
     pub fn to_str(&self) -> &'static str {{
-        {enum_name}::STRING_TABLE[*self as usize]
+        {name_of_self}
+    }}
+
+    /// {xml_name_doc}
+    #[must_use]
+    #[inline]
+    pub fn xml_name(self) -> &'static str {{
+        self.to_str()
+    }}
+
+{version_info}    /// iterate over all items of `{enum_name}`
+    ///
+    /// The items are returned in the order of their numerical values, which is not alphabetical
+    pub fn variants() -> impl ExactSizeIterator<Item = Self> {{
+        (0..Self::COUNT).map(|idx| unsafe {{ core::mem::transmute::<{repr}, Self>(idx as {repr}) }})
     }}
 }}
 
-impl std::str::FromStr for {enum_name} {{
+impl core::str::FromStr for {enum_name} {{
     type Err = Parse{enum_name}Error;
     fn from_str(input: &str) -> Result<Self, Self::Err> {{
         Self::from_bytes(input.as_bytes())
     }}
 }}
 
-impl std::fmt::Debug for {enum_name} {{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        f.write_str({enum_name}::STRING_TABLE[*self as usize])
+impl TryFrom<{repr}> for {enum_name} {{
+    type Error = Parse{enum_name}Error;
+    /// get the enum item with the numerical value `value`, e.g. to restore an item that was stored as an integer
+    fn try_from(value: {repr}) -> Result<Self, Self::Error> {{
+        if (value as usize) < Self::COUNT {{
+            Ok(unsafe {{ core::mem::transmute::<{repr}, Self>(value) }})
+        }} else {{
+            Err(Parse{enum_name}Error)
+        }}
+    }}
+}}
+
+impl core::fmt::Debug for {enum_name} {{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
+        f.write_str({name_of_self})
     }}
 }}
 
-impl std::fmt::Display for {enum_name} {{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
-        f.write_str({enum_name}::STRING_TABLE[*self as usize])
+impl core::fmt::Display for {enum_name} {{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
+        f.write_str({name_of_self})
+    }}
+}}
+
+{serde_impls}#[cfg(test)]
+mod test {{
+    use super::*;
+
+    #[test]
+    fn variants() {{
+        assert_eq!({enum_name}::variants().len(), {enum_name}::COUNT);
+        for item in {enum_name}::variants() {{
+            assert_eq!({enum_name}::from_bytes(item.to_str().as_bytes()).unwrap(), item);
+            assert_eq!(item.xml_name(), item.to_str());
+        }}
+    }}
+
+    #[test]
+    fn known_indices() {{
+{known_indices}    }}
+
+    #[test]
+    fn from_bytes_checked() {{
+        const ITEM: Option<{enum_name}> = {enum_name}::from_bytes_checked(b{first_item:?});
+        assert_eq!(ITEM.map({enum_name}::xml_name), Some({first_item:?}));
+        for item in {enum_name}::variants() {{
+            assert_eq!({enum_name}::from_bytes_checked(item.to_str().as_bytes()), Some(item));
+        }}
+        assert_eq!({enum_name}::from_bytes_checked("A".repeat({width} + 1).as_bytes()), None);
+    }}
+
+    #[test]
+    fn try_from_int() {{
+        for item in {enum_name}::variants() {{
+            assert_eq!({enum_name}::try_from(item as {repr}).unwrap(), item);
+        }}
+        assert!({enum_name}::try_from({enum_name}::COUNT as {repr}).is_err());
+        assert!({enum_name}::try_from({repr}::MAX).is_err());
+    }}
+
+    #[test]
+    fn from_str_relaxed() {{
+        for item in {enum_name}::variants() {{
+            let name = item.to_str();
+            assert_eq!({enum_name}::from_str_relaxed(name).unwrap(), item);
+            let relaxed_name = name.to_ascii_lowercase().replace('-', "_");
+            if !name.bytes().any(|c| c.is_ascii_lowercase() || c == b'_')
+                && {enum_name}::from_bytes(relaxed_name.as_bytes()).is_err()
+            {{
+                assert_eq!({enum_name}::from_str_relaxed(&relaxed_name).unwrap(), item);
+            }}
+        }}
+        assert!({enum_name}::from_str_relaxed(&"A".repeat({width} + 1)).is_err());
     }}
 }}
 "##
@@ -201,3 +601,253 @@ impl std::fmt::Display for {enum_name} {{
 
     generated
 }
+
+// generate a string table in which all names are concatenated into a single string
+// this avoids storing a pointer and a length for each item
+fn generate_compact_string_table(enum_name: &str, hash_sorted_item_names: &[&str]) -> String {
+    let length = hash_sorted_item_names.len();
+    let names = hash_sorted_item_names.concat();
+    let offsets = std::iter::once(0)
+        .chain(hash_sorted_item_names.iter().scan(0, |offset, name| {
+            *offset += name.len();
+            Some(*offset)
+        }))
+        .map(|offset| offset.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r##"    #[rustfmt::skip]
+    const NAMES: &'static str = {names:?};
+    #[rustfmt::skip]
+    const NAME_OFFSETS: [u32; {}] = [{offsets}];
+
+    fn item_name(idx: usize) -> &'static str {{
+        let start = {enum_name}::NAME_OFFSETS[idx] as usize;
+        let end = {enum_name}::NAME_OFFSETS[idx + 1] as usize;
+        &{enum_name}::NAMES[start..end]
+    }}"##,
+        length + 1
+    )
+}
+
+/// estimate the size in bytes of the plain and the compact string table on a 64 bit target
+fn string_table_sizes(item_names: &[&str]) -> (usize, usize) {
+    let names_len: usize = item_names.iter().map(|name| name.len()).sum();
+    let plain_size = names_len + item_names.len() * 2 * std::mem::size_of::<u64>();
+    let compact_size = names_len + (item_names.len() + 1) * std::mem::size_of::<u32>();
+    (plain_size, compact_size)
+}
+
+// generate the VERSION_INFO table and the methods to access it
+// the table is parallel to the string table, i.e. it is indexed by the numerical value of each item
+fn generate_version_info(
+    enum_name: &str,
+    hash_sorted_item_names: &[&str],
+    item_versions: &FxHashMap<String, usize>,
+    all_versions: usize,
+) -> String {
+    let length = hash_sorted_item_names.len();
+    let version_masks = hash_sorted_item_names
+        .iter()
+        .map(|name| format!("0x{:x}", item_versions.get(*name).copied().unwrap_or(0)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        r##"    #[rustfmt::skip]
+    const VERSION_INFO: [u32; {length}] = [{version_masks}];
+
+    /// get the bitmask of all Autosar versions in which this item is valid
+    ///
+    /// An item is valid in a version if `item.version_info() & version as u32 != 0`
+    #[must_use]
+    pub fn version_info(self) -> u32 {{
+        {enum_name}::VERSION_INFO[self as usize]
+    }}
+
+    /// check if this item is valid in the given Autosar version
+    #[must_use]
+    pub fn is_valid_for_version(self, version: crate::AutosarVersion) -> bool {{
+        self.version_info() & version as u32 != 0
+    }}
+
+    /// check if this item is only valid in some of the Autosar versions
+    ///
+    /// Tools that work independently of the version can use this to filter out version specific items.
+    #[must_use]
+    pub fn is_version_specific(self) -> bool {{
+        self.version_info() != 0x{all_versions:x}
+    }}
+
+"##
+    )
+}
+
+// generate hashfunc.rs, which contains the hash function that was used to build the perfect hash tables
+pub(crate) fn generate_hashfunc_module() -> String {
+    let HashConstants {
+        mul1,
+        mul2,
+        init1,
+        init2,
+        ..
+    } = perfect_hash::DEFAULT_HASH_CONSTANTS;
+    // the self-test checks the hashes of some known strings, so that any difference is detected
+    let known_hashes: String = ["", "A", "AB", "ABC", "AUTOSAR", "SHORT-NAME"]
+        .iter()
+        .map(|input| {
+            let (g, f1, f2) =
+                perfect_hash::hashfunc(input.as_bytes(), &perfect_hash::DEFAULT_HASH_CONSTANTS);
+            format!(
+                "        assert_eq!(hashfunc(b{input:?}), ({g:#010X}, {f1:#010X}, {f2:#010X}));\n"
+            )
+        })
+        .collect();
+    format!(
+        r##"/// hash function of the perfect hash tables in the generated enums
+///
+/// The tables were built using exactly this function, so any modification invalidates them.
+/// It is a const fn, so that the enum items can also be looked up at compile time.
+#[inline]
+pub(crate) const fn hashfunc(data: &[u8]) -> (u32, u32, u32) {{
+    hashfunc_with_constants(data, {mul1:#010X}, {mul2:#010X}, {init1:#010X}, {init2:#010X})
+}}
+
+/// hash function with configurable constants, for perfect hash tables that were built with alternate constants
+#[allow(dead_code)]
+#[inline]
+pub(crate) const fn hashfunc_with_constants(
+    mut data: &[u8],
+    mul1: u32,
+    mul2: u32,
+    init1: u32,
+    init2: u32,
+) -> (u32, u32, u32) {{
+    let mut f1 = init1;
+    let mut f2 = init2;
+    while let [b0, b1, b2, b3, rest @ ..] = data {{
+        let val = u32::from_ne_bytes([*b0, *b1, *b2, *b3]);
+        f1 = (f1.rotate_left(5) ^ val).wrapping_mul(mul1);
+        f2 = (f2.rotate_left(6) ^ val).wrapping_mul(mul2);
+        data = rest;
+    }}
+    if let [b0, b1, rest @ ..] = data {{
+        let val = u16::from_ne_bytes([*b0, *b1]) as u32;
+        f1 = (f1.rotate_left(5) ^ val).wrapping_mul(mul1);
+        f2 = (f2.rotate_left(6) ^ val).wrapping_mul(mul2);
+        data = rest;
+    }}
+    if let [b0] = data {{
+        f1 = (f1.rotate_left(5) ^ *b0 as u32).wrapping_mul(mul1);
+        f2 = (f2.rotate_left(6) ^ *b0 as u32).wrapping_mul(mul2);
+    }}
+    (f1 ^ f2, f1, f2)
+}}
+
+#[cfg(test)]
+mod test {{
+    use super::*;
+
+    #[test]
+    fn known_hashes() {{
+{known_hashes}    }}
+}}
+"##
+    )
+}
+
+// generate a wrapper for the hashfunc, which uses alternate hash constants
+// finalized hash values are mixed with a copy of perfect_hash::fmix32
+fn generate_hashfunc_wrapper(constants: &HashConstants) -> String {
+    let HashConstants {
+        mul1,
+        mul2,
+        init1,
+        init2,
+        finalize,
+    } = constants;
+    if *finalize {
+        format!(
+            r##"const fn hashfunc(data: &[u8]) -> (u32, u32, u32) {{
+    let (_, f1, f2) =
+        crate::hashfunc::hashfunc_with_constants(data, {mul1:#010X}, {mul2:#010X}, {init1:#010X}, {init2:#010X});
+    let f1 = fmix32(f1);
+    let f2 = fmix32(f2);
+    (f1 ^ f2, f1, f2)
+}}
+
+const fn fmix32(mut h: u32) -> u32 {{
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h
+}}
+
+"##
+        )
+    } else {
+        format!(
+            r##"const fn hashfunc(data: &[u8]) -> (u32, u32, u32) {{
+    crate::hashfunc::hashfunc_with_constants(data, {mul1:#010X}, {mul2:#010X}, {init1:#010X}, {init2:#010X})
+}}
+
+"##
+        )
+    }
+}
+
+// the enum items are numbered from 0 to item_count - 1. The repr must also be able to hold item_count, so that
+// COUNT and MAX are out of range, which the generated test of try_from checks
+fn enum_repr(item_count: usize) -> &'static str {
+    if item_count <= usize::from(u16::MAX) {
+        "u16"
+    } else {
+        "u32"
+    }
+}
+
+// the serde impls are only compiled if the crate that contains the generated code enables its "serde" feature
+fn generate_serde_impls(enum_name: &str) -> String {
+    format!(
+        r##"#[cfg(feature = "serde")]
+impl serde::Serialize for {enum_name} {{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {{
+        serializer.serialize_str(self.to_str())
+    }}
+}}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for {enum_name} {{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {{
+        struct {enum_name}Visitor;
+
+        impl serde::de::Visitor<'_> for {enum_name}Visitor {{
+            type Value = {enum_name};
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
+                formatter.write_str("a string containing the name of a {enum_name}")
+            }}
+
+            fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {{
+                {enum_name}::from_bytes(value.as_bytes())
+                    .map_err(|_| E::custom(format_args!("invalid {enum_name} \"{{value}}\"")))
+            }}
+
+            fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<Self::Value, E> {{
+                {enum_name}::from_bytes(value).map_err(|_| {{
+                    E::custom(format_args!(
+                        "invalid {enum_name} \"{{}}\"",
+                        value.escape_ascii()
+                    ))
+                }})
+            }}
+        }}
+
+        deserializer.deserialize_str({enum_name}Visitor)
+    }}
+}}
+
+"##
+    )
+}
@@ -0,0 +1,198 @@
+use rustc_hash::FxHashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// pack a list of sequences into one array, so that every sequence is a contiguous slice of the array
+///
+/// Sequences that are contained in a longer sequence are stored only once. The remaining sequences
+/// are merged greedily: sequences where the suffix of one equals the prefix of another are chained,
+/// beginning with the longest overlaps.
+/// Returns the packed array and the offset of each input sequence in it. Empty sequences get offset 0.
+pub(crate) fn pack_sequences<T: Clone + Eq + Hash>(sequences: &[&[T]]) -> (Vec<T>, Vec<usize>) {
+    // unique non-empty sequences, longest first
+    let mut unique = HashSet::new();
+    let mut candidates: Vec<&[T]> = sequences
+        .iter()
+        .copied()
+        .filter(|seq| !seq.is_empty() && unique.insert(*seq))
+        .collect();
+    candidates.sort_by_key(|seq| std::cmp::Reverse(seq.len()));
+
+    // drop all sequences that are contained in one of the longer sequences
+    // All candidates are concatenated with a unique separator after each one, so that a match in the
+    // suffix array can't span two candidates. Every candidate matches itself; any further match means
+    // that it is contained in another (longer) candidate, and thus also in one of the kept fragments.
+    let mut item_ids: FxHashMap<&T, usize> = FxHashMap::default();
+    let candidate_ids: Vec<Vec<usize>> = candidates
+        .iter()
+        .map(|seq| {
+            seq.iter()
+                .map(|item| item_id(&mut item_ids, item))
+                .collect()
+        })
+        .collect();
+    let separator_base = item_ids.len();
+    let mut candidate_text = Vec::new();
+    for (cand_idx, ids) in candidate_ids.iter().enumerate() {
+        candidate_text.extend_from_slice(ids);
+        candidate_text.push(separator_base + cand_idx);
+    }
+    let candidate_index = SuffixArray::new(candidate_text);
+    let fragments: Vec<&[T]> = candidates
+        .iter()
+        .zip(&candidate_ids)
+        .filter(|(_, ids)| candidate_index.find(ids).len() == 1)
+        .map(|(seq, _)| *seq)
+        .collect();
+
+    // chain the fragments, beginning with the longest overlaps
+    let count = fragments.len();
+    let mut successor: Vec<Option<(usize, usize)>> = vec![None; count];
+    let mut has_predecessor = vec![false; count];
+    // the first fragment of the chain each fragment belongs to; this prevents cycles
+    let mut chain_start: Vec<usize> = (0..count).collect();
+    let max_overlap = fragments.iter().map(|frag| frag.len()).max().unwrap_or(0);
+    for overlap in (1..max_overlap).rev() {
+        let mut prefixes: FxHashMap<&[T], Vec<usize>> = FxHashMap::default();
+        for (frag_idx, frag) in fragments.iter().enumerate() {
+            if !has_predecessor[frag_idx] && frag.len() > overlap {
+                prefixes.entry(&frag[..overlap]).or_default().push(frag_idx);
+            }
+        }
+        for frag_idx in 0..count {
+            let frag = fragments[frag_idx];
+            if successor[frag_idx].is_some() || frag.len() <= overlap {
+                continue;
+            }
+            if let Some(next_candidates) = prefixes.get_mut(&frag[frag.len() - overlap..]) {
+                let start = find_chain_start(&mut chain_start, frag_idx);
+                if let Some(pos) = next_candidates
+                    .iter()
+                    .position(|next_idx| find_chain_start(&mut chain_start, *next_idx) != start)
+                {
+                    let next_idx = next_candidates.remove(pos);
+                    successor[frag_idx] = Some((next_idx, overlap));
+                    has_predecessor[next_idx] = true;
+                    chain_start[next_idx] = start;
+                }
+            }
+        }
+    }
+
+    // concatenate the chains
+    let mut packed: Vec<T> = Vec::new();
+    for first_idx in (0..count).filter(|idx| !has_predecessor[*idx]) {
+        packed.extend_from_slice(fragments[first_idx]);
+        let mut cur_idx = first_idx;
+        while let Some((next_idx, overlap)) = successor[cur_idx] {
+            packed.extend_from_slice(&fragments[next_idx][overlap..]);
+            cur_idx = next_idx;
+        }
+    }
+
+    // find the offset of each input sequence in the packed array; the first occurrence is used
+    let packed_ids: Vec<usize> = packed.iter().map(|item| item_ids[item]).collect();
+    let packed_index = SuffixArray::new(packed_ids);
+    let offsets = sequences
+        .iter()
+        .map(|seq| {
+            if seq.is_empty() {
+                0
+            } else {
+                let ids: Vec<usize> = seq.iter().map(|item| item_ids[item]).collect();
+                *packed_index
+                    .find(&ids)
+                    .iter()
+                    .min()
+                    .expect("every sequence is part of the packed array")
+            }
+        })
+        .collect();
+
+    (packed, offsets)
+}
+
+/// check that the slice of `packed` at `offset` re-expands to `sequence`; `description` names the sequence in the error
+pub(crate) fn check_packed<T: PartialEq>(
+    packed: &[T],
+    offset: usize,
+    sequence: &[T],
+    description: &str,
+) -> Result<(), String> {
+    if packed.get(offset..offset + sequence.len()) == Some(sequence) {
+        Ok(())
+    } else {
+        Err(format!("Error: the packed {description} don't match"))
+    }
+}
+
+fn find_chain_start(chain_start: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while chain_start[root] != root {
+        root = chain_start[root];
+    }
+    // shorten the path for later lookups
+    let mut cur = idx;
+    while chain_start[cur] != root {
+        let next = chain_start[cur];
+        chain_start[cur] = root;
+        cur = next;
+    }
+    root
+}
+
+// get a numeric id for an item; the suffix arrays operate on these ids instead of the items
+fn item_id<'a, T: Eq + Hash>(item_ids: &mut FxHashMap<&'a T, usize>, item: &'a T) -> usize {
+    let next_id = item_ids.len();
+    *item_ids.entry(item).or_insert(next_id)
+}
+
+/// suffix array of a sequence of ids
+///
+/// The suffixes are sorted, so all occurrences of a subsequence form a contiguous range,
+/// which is found with a binary search in O(m log n).
+///
+/// The suffix array is built in one step instead of incrementally: in both uses the whole text is known before
+/// the first lookup, since all candidates are concatenated before any of them is checked, and the packed array is
+/// complete before the offsets are searched. Lookups are never mixed with additions, so an incremental
+/// construction would only be slower; it would have to keep the suffixes sorted after every addition.
+pub(crate) struct SuffixArray {
+    text: Vec<usize>,
+    suffixes: Vec<usize>,
+}
+
+impl SuffixArray {
+    // construction by prefix doubling: in each round the suffixes are sorted by the ranks of their
+    // first k and the following k entries, which gives their order by the first 2k entries
+    pub(crate) fn new(text: Vec<usize>) -> Self {
+        let len = text.len();
+        let mut suffixes: Vec<usize> = (0..len).collect();
+        let mut rank: Vec<usize> = text.clone();
+        let mut next_rank = vec![0; len];
+        let mut k = 1;
+        while k < len {
+            let sort_key = |pos: usize| (rank[pos], rank.get(pos + k).map_or(0, |r| r + 1));
+            suffixes.sort_unstable_by_key(|pos| sort_key(*pos));
+            next_rank[suffixes[0]] = 0;
+            for idx in 1..len {
+                let increment = usize::from(sort_key(suffixes[idx - 1]) != sort_key(suffixes[idx]));
+                next_rank[suffixes[idx]] = next_rank[suffixes[idx - 1]] + increment;
+            }
+            std::mem::swap(&mut rank, &mut next_rank);
+            if rank[suffixes[len - 1]] == len - 1 {
+                // all ranks are distinct, so the suffixes are fully sorted
+                break;
+            }
+            k *= 2;
+        }
+        Self { text, suffixes }
+    }
+
+    // get the start positions of all occurrences of the pattern in the text
+    pub(crate) fn find(&self, pattern: &[usize]) -> &[usize] {
+        let prefix = |pos: &usize| &self.text[*pos..(*pos + pattern.len()).min(self.text.len())];
+        let start = self.suffixes.partition_point(|pos| prefix(pos) < pattern);
+        let end = self.suffixes.partition_point(|pos| prefix(pos) <= pattern);
+        &self.suffixes[start..end]
+    }
+}
@@ -0,0 +1,153 @@
+use crate::generator::{Element, ElementCollectionItem, MergedElementDataType, SimpleElement};
+use rustc_hash::FxHashMap;
+use std::collections::{BTreeSet, HashSet};
+
+/// invert the sub element graph: for each element definition, find all the element types that may contain it
+///
+/// Groups are resolved, so that the result only contains the concrete types of the containing elements.
+pub(crate) fn build_info(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    element_definitions_array: &[SimpleElement],
+) -> Vec<BTreeSet<usize>> {
+    let elem_idx: FxHashMap<SimpleElement, usize> = element_definitions_array
+        .iter()
+        .enumerate()
+        .map(|(pos, elem)| (elem.clone(), pos))
+        .collect();
+    let mut elemtypenames: Vec<&String> = element_types.keys().collect();
+    elemtypenames.sort();
+
+    let mut parent_types = vec![BTreeSet::new(); element_definitions_array.len()];
+    for (type_idx, elemtypename) in elemtypenames.iter().enumerate() {
+        let elemtype = element_types.get(*elemtypename).unwrap();
+        if let MergedElementDataType::ElementsGroup { .. } = elemtype {
+            // groups are only reachable through the types that reference them
+            continue;
+        }
+        let mut visited_groups = HashSet::new();
+        collect_sub_elements(
+            element_types,
+            elemtype,
+            &mut visited_groups,
+            &mut |element| {
+                let idx = *elem_idx.get(&SimpleElement::from(element)).unwrap();
+                parent_types[idx].insert(type_idx);
+            },
+        );
+    }
+
+    parent_types
+}
+
+/// call `add_element` for each sub element of the type, including the elements of all referenced groups
+pub(crate) fn collect_sub_elements<'a>(
+    element_types: &'a FxHashMap<String, MergedElementDataType>,
+    elemtype: &'a MergedElementDataType,
+    visited_groups: &mut HashSet<&'a str>,
+    add_element: &mut dyn FnMut(&'a Element),
+) {
+    if let Some(element_collection) = elemtype.collection() {
+        for item in element_collection.items() {
+            match item {
+                ElementCollectionItem::Element(element) => add_element(element),
+                ElementCollectionItem::GroupRef(group_ref) => {
+                    if visited_groups.insert(group_ref) {
+                        if let Some(group_type) = element_types.get(group_ref) {
+                            collect_sub_elements(
+                                element_types,
+                                group_type,
+                                visited_groups,
+                                add_element,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// generate the PARENT_TYPES table and the PARENT_TYPE_ITEMS array it points into
+pub(crate) fn generate(parent_types: &[BTreeSet<usize>]) -> String {
+    let mut all_items: Vec<usize> = Vec::new();
+    let mut ranges = Vec::with_capacity(parent_types.len());
+
+    for parents in parent_types {
+        let parentvec: Vec<usize> = parents.iter().copied().collect();
+        if parentvec.is_empty() {
+            ranges.push((0, 0));
+        } else if let Some(existing_pos) = all_items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| **item == parentvec[0])
+            .map(|(pos, _)| pos)
+            .find(|pos| all_items[*pos..].starts_with(&parentvec))
+        {
+            // reuse an existing identical sequence of parent types
+            ranges.push((existing_pos, existing_pos + parentvec.len()));
+        } else {
+            let pos = all_items.len();
+            all_items.extend(parentvec.iter());
+            ranges.push((pos, pos + parentvec.len()));
+        }
+    }
+
+    let range_type = if all_items.len() <= usize::from(u16::MAX) {
+        "u16"
+    } else {
+        "u32"
+    };
+    let ranges_str = ranges
+        .iter()
+        .map(|(low, high)| format!("({low}, {high})"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let items_str = all_items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        r#"
+// for each element definition in ELEMENTS: the range of PARENT_TYPE_ITEMS which lists the indices
+// of all data types in DATATYPES that can contain the element
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const PARENT_TYPES: [({range_type}, {range_type}); {}] = [{ranges_str}];
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const PARENT_TYPE_ITEMS: [u16; {}] = [{items_str}];
+
+#[cfg(test)]
+mod parent_types_test {{
+    use super::*;
+
+    fn parent_type_names(name: ElementName) -> Vec<&'static str> {{
+        let mut names = Vec::new();
+        for (idx, elem) in ELEMENTS.iter().enumerate() {{
+            if elem.name == name {{
+                let (low, high) = PARENT_TYPES[idx];
+                for type_idx in &PARENT_TYPE_ITEMS[low as usize..high as usize] {{
+                    names.push(DATATYPE_NAMES[*type_idx as usize]);
+                }}
+            }}
+        }}
+        names
+    }}
+
+    #[test]
+    fn parent_types() {{
+        assert!(parent_type_names(ElementName::Autosar).is_empty());
+        assert!(parent_type_names(ElementName::ArPackages).contains(&"AUTOSAR"));
+        assert!(parent_type_names(ElementName::ArPackage).contains(&"AR-PACKAGES"));
+        assert!(parent_type_names(ElementName::ShortName).contains(&"AR-PACKAGE"));
+        assert!(parent_type_names(ElementName::Elements).contains(&"AR-PACKAGE"));
+    }}
+}}
+"#,
+        ranges.len(),
+        all_items.len()
+    )
+}
@@ -0,0 +1,136 @@
+use crate::generator::CodeOutput;
+
+/// generate paths.rs, which contains functions to validate element paths in the element tree
+pub(crate) fn generate(header: &str, output: &mut CodeOutput) {
+    // Vec is not in the prelude of a no_std crate
+    let generated = r#"extern crate alloc;
+
+use crate::*;
+use alloc::vec::Vec;
+
+/// check if a sequence of element names is a valid path in the AUTOSAR element tree
+///
+/// The path must start at the root element, e.g. `[AUTOSAR, AR-PACKAGES, AR-PACKAGE, SHORT-NAME]`
+pub fn is_valid_path(path: &[ElementName]) -> bool {
+    match path.split_first() {
+        Some((first, rest)) => {
+            let root_idx = AUTOSAR_ELEMENT as usize;
+            ELEMENTS[root_idx].name == *first && path_matches(root_idx, rest, &|name, element_idx| ELEMENTS[element_idx].name == name)
+        }
+        None => false,
+    }
+}
+
+/// check if a sequence of element names is a valid path in the AUTOSAR element tree
+///
+/// A `None` entry in the path is a wildcard, which accepts any element name at this position.
+pub fn is_valid_path_pattern(path: &[Option<ElementName>]) -> bool {
+    match path.split_first() {
+        Some((first, rest)) => {
+            let root_idx = AUTOSAR_ELEMENT as usize;
+            name_matches(*first, root_idx) && path_matches(root_idx, rest, &name_matches)
+        }
+        None => false,
+    }
+}
+
+/// get the index in ELEMENTS of each element along a path in the AUTOSAR element tree
+///
+/// The path must start at the root element. Returns `None` if the path is not valid.
+pub fn element_path_to_spec_indices(path: &[ElementName]) -> Option<Vec<u16>> {
+    let (first, rest) = path.split_first()?;
+    let root_idx = AUTOSAR_ELEMENT as usize;
+    let mut indices = Vec::with_capacity(path.len());
+    indices.push(AUTOSAR_ELEMENT);
+    if ELEMENTS[root_idx].name == *first && collect_path_indices(root_idx, rest, &mut indices) {
+        Some(indices)
+    } else {
+        None
+    }
+}
+
+fn name_matches(name: Option<ElementName>, element_idx: usize) -> bool {
+    match name {
+        Some(name) => ELEMENTS[element_idx].name == name,
+        None => true,
+    }
+}
+
+// check the path below the element element_idx; matches decides if a path entry accepts an element
+fn path_matches<T: Copy>(element_idx: usize, path: &[T], matches: &dyn Fn(T, usize) -> bool) -> bool {
+    match path.split_first() {
+        Some((name, rest)) => {
+            let type_idx = ELEMENTS[element_idx].elemtype as usize;
+            any_sub_element(type_idx, &mut |sub_idx| {
+                matches(*name, sub_idx) && path_matches(sub_idx, rest, matches)
+            })
+        }
+        None => true,
+    }
+}
+
+// like path_matches, but also records the index of each element along the path
+fn collect_path_indices(element_idx: usize, path: &[ElementName], indices: &mut Vec<u16>) -> bool {
+    match path.split_first() {
+        Some((name, rest)) => {
+            let type_idx = ELEMENTS[element_idx].elemtype as usize;
+            any_sub_element(type_idx, &mut |sub_idx| {
+                if ELEMENTS[sub_idx].name == *name {
+                    indices.push(sub_idx as u16);
+                    if collect_path_indices(sub_idx, rest, indices) {
+                        return true;
+                    }
+                    indices.pop();
+                }
+                false
+            })
+        }
+        None => true,
+    }
+}
+
+// walk the sub elements of a data type, descending into groups, until the predicate returns true
+fn any_sub_element(type_idx: usize, pred: &mut dyn FnMut(usize) -> bool) -> bool {
+    let (start, end) = DATATYPES[type_idx].sub_elements;
+    SUBELEMENTS[start as usize..end as usize]
+        .iter()
+        .any(|sub_element| match sub_element {
+            SubElement::Element(idx) => pred(*idx as usize),
+            SubElement::Group(idx) => any_sub_element(*idx as usize, pred),
+        })
+}
+
+#[cfg(test)]
+mod paths_test {
+    use super::*;
+
+    #[test]
+    fn path_to_spec_indices() {
+        let valid_paths: [&[ElementName]; 3] = [
+            &[ElementName::Autosar],
+            &[ElementName::Autosar, ElementName::ArPackages, ElementName::ArPackage, ElementName::ShortName],
+            &[ElementName::Autosar, ElementName::ArPackages, ElementName::ArPackage, ElementName::Elements],
+        ];
+        for path in valid_paths {
+            let indices = element_path_to_spec_indices(path).unwrap();
+            assert_eq!(indices.len(), path.len());
+            for (idx, name) in indices.iter().zip(path) {
+                assert_eq!(ELEMENTS[*idx as usize].name, *name);
+            }
+        }
+
+        let invalid_paths: [&[ElementName]; 4] = [
+            &[],
+            &[ElementName::ArPackages],
+            &[ElementName::Autosar, ElementName::ArPackage],
+            &[ElementName::Autosar, ElementName::ArPackages, ElementName::ShortName],
+        ];
+        for path in invalid_paths {
+            assert_eq!(element_path_to_spec_indices(path), None);
+        }
+    }
+}
+"#;
+
+    output.write("paths.rs", &format!("{header}{generated}"));
+}
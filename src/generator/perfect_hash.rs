@@ -1,42 +1,177 @@
-use std::{num::Wrapping, ops::BitXor};
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// constants of the hash function
+///
+/// The generated code must use exactly the same values as the generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HashConstants {
+    pub(crate) mul1: u32,
+    pub(crate) mul2: u32,
+    pub(crate) init1: u32,
+    pub(crate) init2: u32,
+    // mix the hash values with fmix32 at the end
+    pub(crate) finalize: bool,
+}
+
+// these 4 constant values are not special, just random values
+// they are used by the hashfunc in autosar-data-specification
+pub(crate) const DEFAULT_HASH_CONSTANTS: HashConstants = HashConstants {
+    mul1: 0x541C_69B2,
+    mul2: 0x3B17_161B,
+    init1: 0x3314_3C63,
+    init2: 0x88B0_B21E,
+    finalize: false,
+};
+
+// alternate constants, which are only used if no perfect hash can be found with the default constants
+const ALTERNATE_HASH_CONSTANTS: [HashConstants; 3] = [
+    HashConstants {
+        mul1: 0x9E37_79B9,
+        mul2: 0x85EB_CA6B,
+        init1: 0x3314_3C63,
+        init2: 0x88B0_B21E,
+        finalize: false,
+    },
+    HashConstants {
+        mul1: 0xC2B2_AE35,
+        mul2: 0x27D4_EB2F,
+        init1: 0x1656_67B1,
+        init2: 0xD3A2_646C,
+        finalize: false,
+    },
+    HashConstants {
+        mul1: 0x7FEB_352D,
+        mul2: 0x846C_A68B,
+        init1: 0x6A09_E667,
+        init2: 0xBB67_AE85,
+        finalize: false,
+    },
+];
+
+// the number of further sets of hash constants that are derived by derived_hash_constants, after the fixed sets
+const DERIVED_HASH_CONSTANTS_COUNT: u32 = 64;
+
+// the largest lambda (average number of items per displacement bucket) that is tried
+const MAX_LAMBDA: usize = 8;
+
+// tables with at least this many entries only try lambda LARGE_TABLE_MAX_LAMBDA and below: with larger lambdas the
+// search time grows quadratically, and 70000 entries take minutes instead of a fraction of a second
+// this only affects enums with a u32 repr, so the tables of the smaller enums stay the same
+const LARGE_TABLE_LEN: usize = 1 << 16;
+const LARGE_TABLE_MAX_LAMBDA: usize = 5;
+
+/// the result of the perfect hash generation
+pub(crate) struct PerfectHash {
+    pub(crate) disps: Vec<(u32, u32)>,
+    pub(crate) lambda: usize,
+    pub(crate) constants: HashConstants,
+}
 
 // hashfunc inspired by FxHasher (rustc-hash)
 // unlike FxHasher, this code can't do 64bit ops, because the generated
 // perfect hash table should also work if compiled as 32 bit
-fn hashfunc(mut data: &[u8]) -> (u32, u32, u32) {
-    const HASHCONST1: u32 = 0x541C_69B2; // these 4 constant values are not special, just random values
-    const HASHCONST2: u32 = 0x3B17_161B;
-
-    let mut f1 = 0x3314_3C63_u32;
-    let mut f2 = 0x88B0_B21E_u32;
-    while data.len() >= 4 {
-        let val = u32::from_ne_bytes(data[..4].try_into().unwrap());
-        f1 = f1.rotate_left(5).bitxor(val).wrapping_mul(HASHCONST1);
-        f2 = f2.rotate_left(6).bitxor(val).wrapping_mul(HASHCONST2);
-        data = &data[4..];
+// it is a const fn like the hashfunc in the generated code, which must produce the same values
+pub(crate) const fn hashfunc(mut data: &[u8], constants: &HashConstants) -> (u32, u32, u32) {
+    let mut f1 = constants.init1;
+    let mut f2 = constants.init2;
+    while let [b0, b1, b2, b3, rest @ ..] = data {
+        let val = u32::from_ne_bytes([*b0, *b1, *b2, *b3]);
+        f1 = (f1.rotate_left(5) ^ val).wrapping_mul(constants.mul1);
+        f2 = (f2.rotate_left(6) ^ val).wrapping_mul(constants.mul2);
+        data = rest;
+    }
+    if let [b0, b1, rest @ ..] = data {
+        let val = u16::from_ne_bytes([*b0, *b1]) as u32;
+        f1 = (f1.rotate_left(5) ^ val).wrapping_mul(constants.mul1);
+        f2 = (f2.rotate_left(6) ^ val).wrapping_mul(constants.mul2);
+        data = rest;
     }
-    if data.len() >= 2 {
-        let val = u32::from(u16::from_ne_bytes(data[..2].try_into().unwrap()));
-        f1 = f1.rotate_left(5).bitxor(val).wrapping_mul(HASHCONST1);
-        f2 = f2.rotate_left(6).bitxor(val).wrapping_mul(HASHCONST2);
-        data = &data[2..];
+    if let [b0] = data {
+        f1 = (f1.rotate_left(5) ^ *b0 as u32).wrapping_mul(constants.mul1);
+        f2 = (f2.rotate_left(6) ^ *b0 as u32).wrapping_mul(constants.mul2);
     }
-    if !data.is_empty() {
-        f1 = f1
-            .rotate_left(5)
-            .bitxor(u32::from(data[0]))
-            .wrapping_mul(HASHCONST1);
-        f2 = f2
-            .rotate_left(6)
-            .bitxor(u32::from(data[0]))
-            .wrapping_mul(HASHCONST2);
+    if constants.finalize {
+        f1 = fmix32(f1);
+        f2 = fmix32(f2);
     }
-    let g = f1.bitxor(f2);
+    let g = f1 ^ f2;
     (g, f1, f2)
 }
 
-fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
-    (Wrapping(d2) + Wrapping(f1) * Wrapping(d1) + Wrapping(f2)).0
+/// find the perfect hash with the smallest displacement table for the given entries
+///
+/// All lambda values are tried with the default hash constants, starting with the largest lambda,
+/// which gives the smallest table. The alternate hash constants are only tried if this fails, followed by
+/// the derived hash constants.
+/// The search is deterministic, so the same input always produces the same output.
+/// The entries must be unique, since duplicates can never be placed in different slots.
+///
+/// The fixed constants fail quite often for small tables whose size is a power of two: the modulo then only keeps
+/// the low bits of the hash values, which depend on very few bits of the input, and the bucket index g = f1 ^ f2
+/// uses the same bits. Two entries in the same bucket frequently have the same f1 and f2 modulo the table size, and
+/// no displacement can separate them, however large d1 and d2 are. The size of the table can't be changed either,
+/// because the slot of an entry is the value of its enum item. The derived constants finalize the hash values with
+/// fmix32, so that every set of them is an independent chance, which makes a failure practically impossible.
+pub(crate) fn find_perfect_hash(entries: &[&str]) -> Result<PerfectHash, String> {
+    let mut unique_entries = HashSet::with_capacity(entries.len());
+    if let Some(duplicate) = entries.iter().find(|entry| !unique_entries.insert(**entry)) {
+        return Err(format!(
+            "perfect hash generation failed: the item \"{duplicate}\" is not unique"
+        ));
+    }
+
+    let all_constants = std::iter::once(DEFAULT_HASH_CONSTANTS)
+        .chain(ALTERNATE_HASH_CONSTANTS)
+        .chain((1..=DERIVED_HASH_CONSTANTS_COUNT).map(derived_hash_constants));
+    let max_lambda = if entries.len() >= LARGE_TABLE_LEN {
+        LARGE_TABLE_MAX_LAMBDA
+    } else {
+        MAX_LAMBDA
+    };
+    for constants in all_constants {
+        for lambda in (1..=max_lambda).rev() {
+            if let Some(disps) = make_perfect_hash(entries, lambda, &constants) {
+                return Ok(PerfectHash {
+                    disps,
+                    lambda,
+                    constants,
+                });
+            }
+        }
+    }
+
+    Err(format!(
+        "perfect hash generation failed ({} items); tried lambda {max_lambda} to 1 with {} sets of hash constants",
+        entries.len(),
+        ALTERNATE_HASH_CONSTANTS.len() + 1 + DERIVED_HASH_CONSTANTS_COUNT as usize
+    ))
+}
+
+// hash constants that are derived from the index; the multipliers must be odd
+const fn derived_hash_constants(idx: u32) -> HashConstants {
+    HashConstants {
+        mul1: fmix32(4 * idx) | 1,
+        mul2: fmix32(4 * idx + 1) | 1,
+        init1: fmix32(4 * idx + 2),
+        init2: fmix32(4 * idx + 3),
+        finalize: true,
+    }
+}
+
+// the finalizer of murmur3, which mixes all bits of the input into every bit of the output
+// the generated code contains a copy of it (see identifier_enums::generate_hashfunc_wrapper)
+pub(crate) const fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85EB_CA6B);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xC2B2_AE35);
+    h ^= h >> 16;
+    h
+}
+
+const fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
+    d2.wrapping_add(f1.wrapping_mul(d1)).wrapping_add(f2)
 }
 
 // this code was copied from rust-phf 0.11 and then modified to better suit the use here
@@ -45,7 +180,12 @@ fn displace(f1: u32, f2: u32, d1: u32, d2: u32) -> u32 {
 // changes:
 // - don't use siphash, instead use the above home-grown hash func, which is inspired by FxHasher (rustc-hash)
 // - make lambda a parameter instead of a constant. for some of the input data this allows more compact tables to be generated
-pub(crate) fn make_perfect_hash(entries: &[&str], lambda: usize) -> Result<Vec<(u32, u32)>, String> {
+// - make the hash constants a parameter, so that alternate values can be tried
+fn make_perfect_hash(
+    entries: &[&str],
+    lambda: usize,
+    constants: &HashConstants,
+) -> Option<Vec<(u32, u32)>> {
     struct Bucket {
         idx: usize,
         keys: Vec<usize>,
@@ -53,10 +193,10 @@ pub(crate) fn make_perfect_hash(entries: &[&str], lambda: usize) -> Result<Vec<(
 
     let hashes: Vec<_> = entries
         .iter()
-        .map(|entry| hashfunc(entry.as_bytes()))
+        .map(|entry| hashfunc(entry.as_bytes(), constants))
         .collect();
 
-    let buckets_len = (hashes.len() + lambda - 1) / lambda;
+    let buckets_len = hashes.len().div_ceil(lambda);
     let mut buckets = (0..buckets_len)
         .map(|i| Bucket {
             idx: i,
@@ -95,7 +235,10 @@ pub(crate) fn make_perfect_hash(entries: &[&str], lambda: usize) -> Result<Vec<(
 
     'buckets: for bucket in &buckets {
         for d1 in 0..table_len_u32 {
-            'disps: for d2 in 0..table_len_u32 {
+            let Some(d2_range) = usable_d2_range(&bucket.keys, &hashes, d1, table_len_u32) else {
+                continue;
+            };
+            'disps: for d2 in d2_range {
                 values_to_add.clear();
                 generation += 1;
 
@@ -119,16 +262,52 @@ pub(crate) fn make_perfect_hash(entries: &[&str], lambda: usize) -> Result<Vec<(
         }
 
         // Unable to find displacements for a bucket
-        return Err(format!("perfect hash generation failed ({} items, lambda {lambda})",
-        entries.len()));
+        return None;
     }
 
-    Ok(disps)
+    Some(disps)
+}
+
+// the range of d2 values that can place all keys of a bucket with the displacement d1, or None if there is no such value
+//
+// Two keys whose values of f1 * d1 + f2 are equal modulo the table length land in the same slot for every d2, unless
+// exactly one of the two sums wraps around when d2 is added. Only the d2 values where this happens are left.
+// The search finds the same displacements without this, but it takes much longer if a bucket can't be placed.
+fn usable_d2_range(
+    keys: &[usize],
+    hashes: &[(u32, u32, u32)],
+    d1: u32,
+    table_len: u32,
+) -> Option<Range<u32>> {
+    let wrap_remainder = (1u64 << 32) % u64::from(table_len);
+    let mut range = 0..table_len;
+    for (pos, &key_a) in keys.iter().enumerate() {
+        let value_a = displace(hashes[key_a].1, hashes[key_a].2, d1, 0);
+        for &key_b in &keys[pos + 1..] {
+            let value_b = displace(hashes[key_b].1, hashes[key_b].2, d1, 0);
+            if value_a % table_len != value_b % table_len {
+                continue;
+            }
+            if wrap_remainder == 0 {
+                return None;
+            }
+            // value + d2 wraps around for d2 >= 2^32 - value
+            let wrap_a = (1u64 << 32) - u64::from(value_a);
+            let wrap_b = (1u64 << 32) - u64::from(value_b);
+            let start = u32::try_from(wrap_a.min(wrap_b)).unwrap_or(u32::MAX);
+            let end = u32::try_from(wrap_a.max(wrap_b)).unwrap_or(u32::MAX);
+            range = range.start.max(start)..range.end.min(end);
+            if range.is_empty() {
+                return None;
+            }
+        }
+    }
+    Some(range)
 }
 
 #[inline]
-pub(crate) fn get_index(item: &str, disps: &[(u32, u32)], len: usize) -> usize {
-    let (g, f1, f2) = hashfunc(item.as_bytes());
-    let (d1, d2) = disps[(g % (disps.len() as u32)) as usize];
+pub(crate) fn get_index(item: &str, phash: &PerfectHash, len: usize) -> usize {
+    let (g, f1, f2) = hashfunc(item.as_bytes(), &phash.constants);
+    let (d1, d2) = phash.disps[(g % (phash.disps.len() as u32)) as usize];
     (displace(f1, f2, d1, d2) % (len as u32)) as usize
 }
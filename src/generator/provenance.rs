@@ -0,0 +1,68 @@
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// build the header comment that is placed at the top of every generated file
+///
+/// The header names the tool and its version, lists the input xsd files together with their SHA-256,
+/// and optionally contains the generation time. Leaving out the time makes the output reproducible.
+pub(crate) fn build_header(input_files: &[PathBuf], timestamp: bool) -> Result<String, String> {
+    let mut header = format!(
+        "// This file is @generated by {} {}\n// Do not edit it manually, regenerate it instead.\n//\n// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.\n//\n// Input files:\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for filepath in input_files {
+        let content = std::fs::read(filepath)
+            .map_err(|err| format!("Error: failed to read {}: {err}", filepath.display()))?;
+        let filename = filepath
+            .file_name()
+            .map_or_else(|| filepath.to_string_lossy(), |name| name.to_string_lossy());
+        writeln!(header, "//   {filename}  sha256: {}", sha256_hex(&content)).unwrap();
+    }
+
+    if timestamp {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        writeln!(header, "//\n// Generated at: {}", format_utc_time(seconds)).unwrap();
+    }
+    header.push('\n');
+
+    Ok(header)
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .fold(String::new(), |mut hex, byte| {
+            write!(hex, "{byte:02x}").unwrap();
+            hex
+        })
+}
+
+/// format a unix timestamp as an ISO 8601 date and time in UTC
+fn format_utc_time(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let secs_of_day = seconds % 86400;
+
+    // convert the day count to a civil date; see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3600,
+        (secs_of_day % 3600) / 60,
+        secs_of_day % 60
+    )
+}
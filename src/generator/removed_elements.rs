@@ -0,0 +1,178 @@
+use crate::generator::{CodeOutput, ElementCollectionItem, MergedElementDataType, SimpleElement};
+use crate::XsdFileInfo;
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// build the version mask of each element definition in ELEMENTS
+///
+/// An element definition can be used in several data types, possibly in different versions, so its mask is
+/// the union of the masks of all uses. The AUTOSAR root element exists in all versions with data.
+pub(crate) fn element_version_masks(
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    elements: &[SimpleElement],
+    all_versions_mask: usize,
+) -> Vec<usize> {
+    let element_idx: FxHashMap<&SimpleElement, usize> = elements
+        .iter()
+        .enumerate()
+        .map(|(idx, elem)| (elem, idx))
+        .collect();
+    let mut masks = vec![0; elements.len()];
+    for ec in element_types
+        .values()
+        .filter_map(MergedElementDataType::collection)
+    {
+        for item in ec.items() {
+            if let ElementCollectionItem::Element(element) = item {
+                let idx = element_idx[&SimpleElement::from(element)];
+                masks[idx] |= element.version_info;
+            }
+        }
+    }
+    if let Some(autosar_idx) = elements.iter().position(|elem| elem.name == "AUTOSAR") {
+        masks[autosar_idx] = all_versions_mask;
+    }
+    masks
+}
+
+/// generate ELEMENT_LAST_VERSION, which is aligned with the ELEMENTS array
+///
+/// Downstream code can use it to warn about elements that are no longer part of the newest version.
+/// `standard_mask` contains the standard versions, i.e. all versions except for the vendor extension, which
+/// includes the newest standard version. The extension is only the last version of the elements that
+/// exist in no standard version.
+pub(crate) fn generate_last_version_table(version_masks: &[usize], standard_mask: usize) -> String {
+    let last_version_str = version_masks
+        .iter()
+        .map(|mask| {
+            let mask = if mask & standard_mask != 0 {
+                mask & standard_mask
+            } else {
+                *mask
+            };
+            // the highest bit of the mask is the newest version
+            let last_version = if mask == 0 {
+                0
+            } else {
+                1 << (usize::BITS - 1 - mask.leading_zeros())
+            };
+            format!("0x{last_version:x}")
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+
+    format!(
+        r#"
+// for each element in ELEMENTS: the AutosarVersion of the newest version in which the element exists
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const ELEMENT_LAST_VERSION: [u32; {elem_count}] = [{last_version_str}];
+
+#[cfg(test)]
+mod element_last_version_test {{
+    use super::*;
+
+    #[test]
+    fn element_last_version() {{
+        assert_eq!(ELEMENT_LAST_VERSION.len(), ELEMENTS.len());
+        for last_version in ELEMENT_LAST_VERSION {{
+            assert_eq!(last_version.count_ones(), 1);
+            assert_ne!(last_version & AutosarVersion::ALL_VERSIONS_MASK, 0);
+        }}
+    }}
+}}
+"#,
+        elem_count = version_masks.len()
+    )
+}
+
+/// write removed_elements.txt in the output directory, which lists the elements that are not part of the newest standard version
+///
+/// Elements are listed by name and type, together with the ranges of consecutive versions that contain them,
+/// and the last version that contained them. Missing versions and the vendor extension are not considered.
+pub(crate) fn write_report(
+    xsd_config: &[XsdFileInfo],
+    elements: &[SimpleElement],
+    version_masks: &[usize],
+    missing_versions: &[usize],
+    extension_version: Option<usize>,
+    output: &mut CodeOutput,
+) -> Result<(), String> {
+    let present_versions: Vec<usize> = (0..xsd_config.len())
+        .filter(|idx| !missing_versions.contains(idx) && Some(*idx) != extension_version)
+        .collect();
+    let Some(&latest_version) = present_versions.last() else {
+        return Ok(());
+    };
+
+    // element definitions which only differ in their other properties are combined
+    let mut removed: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for (elem, mask) in elements.iter().zip(version_masks) {
+        *removed.entry((&elem.name, &elem.typeref)).or_default() |= mask;
+    }
+    removed.retain(|_, mask| {
+        *mask & (1 << latest_version) == 0
+            && present_versions.iter().any(|idx| *mask & (1 << idx) != 0)
+    });
+
+    let mut report = format!(
+        "# elements that are not part of the latest version {}\n# {} elements\n\n",
+        xsd_config[latest_version].desc,
+        removed.len()
+    );
+    for ((name, typeref), mask) in &removed {
+        let ranges = describe_version_ranges(*mask, xsd_config, &present_versions);
+        let last_version = present_versions
+            .iter()
+            .rev()
+            .find(|idx| *mask & (1 << *idx) != 0)
+            .unwrap();
+        writeln!(
+            report,
+            "{name} ({typeref}): {}; last version: {}",
+            ranges.join(", "),
+            xsd_config[*last_version].desc
+        )
+        .unwrap();
+    }
+
+    output.write_text("removed_elements.txt", &report)
+}
+
+/// describe the ranges of consecutive versions in the mask, e.g. "AUTOSAR 4.0.1 - AUTOSAR 4.2.2"
+pub(crate) fn describe_version_ranges(
+    mask: usize,
+    xsd_config: &[XsdFileInfo],
+    present_versions: &[usize],
+) -> Vec<String> {
+    version_ranges(mask, present_versions)
+        .iter()
+        .map(|(first, last)| {
+            if first == last {
+                xsd_config[*first].desc.to_string()
+            } else {
+                format!("{} - {}", xsd_config[*first].desc, xsd_config[*last].desc)
+            }
+        })
+        .collect()
+}
+
+// split the versions in the mask into ranges of consecutive versions; versions without data are skipped
+fn version_ranges(mask: usize, present_versions: &[usize]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    let mut in_range = false;
+    for idx in present_versions {
+        if mask & (1 << idx) != 0 {
+            if in_range {
+                ranges.last_mut().unwrap().1 = *idx;
+            } else {
+                ranges.push((*idx, *idx));
+                in_range = true;
+            }
+        } else {
+            in_range = false;
+        }
+    }
+    ranges
+}
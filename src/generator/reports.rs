@@ -0,0 +1,100 @@
+use crate::generator::element_definitions::restrict_std_to_text;
+use crate::generator::html::multiplicity;
+use crate::generator::parent_types::collect_sub_elements;
+use crate::generator::removed_elements::describe_version_ranges;
+use crate::generator::{CodeOutput, MergedElementDataType};
+use crate::XsdFileInfo;
+use rustc_hash::FxHashMap;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// write the availability of each element in each version as csv, for --emit-csv
+///
+/// There is one row for each sub element of each type, with the content of referenced groups resolved. The rows
+/// are sorted by the name of the containing type, and the elements keep their order within the type. Each configured
+/// version has a column, in the order of XSD_CONFIG; it contains "yes" or "no", or nothing for versions without an
+/// xsd file.
+pub(crate) fn write_availability_csv(
+    xsd_config: &[XsdFileInfo],
+    element_types: &FxHashMap<String, MergedElementDataType>,
+    present_versions: &[usize],
+    path: &Path,
+    output: &mut CodeOutput,
+) -> Result<(), String> {
+    let mut header = vec![
+        "type",
+        "element",
+        "element type",
+        "multiplicity",
+        "splittable",
+        "restrict_std",
+    ];
+    header.extend(xsd_config.iter().map(|info| info.desc));
+    let mut csv = csv_line(&header);
+
+    // groups have no elements of their own, their content is listed in the types that reference them
+    let mut typenames: Vec<&String> = element_types
+        .iter()
+        .filter(|(_, elemtype)| !matches!(elemtype, MergedElementDataType::ElementsGroup { .. }))
+        .map(|(typename, _)| typename)
+        .collect();
+    typenames.sort();
+
+    for typename in typenames {
+        let mut elements = Vec::new();
+        collect_sub_elements(
+            element_types,
+            &element_types[typename],
+            &mut HashSet::new(),
+            &mut |element| elements.push(element),
+        );
+        for element in elements {
+            let splittable_ver = element.splittable_ver & element.version_info;
+            let splittable = if splittable_ver == 0 {
+                "no".to_string()
+            } else if splittable_ver == element.version_info {
+                "yes".to_string()
+            } else {
+                describe_version_ranges(splittable_ver, xsd_config, present_versions).join("; ")
+            };
+            let mut fields = vec![
+                typename.clone(),
+                element.name.clone(),
+                element.typeref.to_string(),
+                multiplicity(element.amount),
+                splittable,
+                restrict_std_to_text(element.restrict_std).to_string(),
+            ];
+            fields.extend((0..xsd_config.len()).map(|idx| {
+                if !present_versions.contains(&idx) {
+                    String::new()
+                } else if element.version_info & (1 << idx) != 0 {
+                    "yes".to_string()
+                } else {
+                    "no".to_string()
+                }
+            }));
+            csv.push_str(&csv_line(&fields));
+        }
+    }
+
+    output.write_to(path, &csv)
+}
+
+// a line of the csv file; fields that contain a separator, a quote or a line break are quoted (RFC 4180)
+fn csv_line<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut line = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    line.push_str("\r\n");
+    line
+}
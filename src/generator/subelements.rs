@@ -1,15 +1,16 @@
-use crate::generator::{GroupItem, MergedElementDataType, SimpleElement, SubelementsInfo};
-use crate::{Element, ElementCollectionItem};
+use crate::generator::packing::{check_packed, pack_sequences};
+use crate::generator::{
+    Element, ElementCollectionItem, GroupItem, MergedElementDataType, SimpleElement,
+    SubelementsInfo,
+};
 use rustc_hash::FxHashMap;
 
 pub(crate) fn build_info(
     element_types: &FxHashMap<String, MergedElementDataType>,
     element_definitions_array: &[SimpleElement],
-) -> SubelementsInfo {
-    let mut versions_array = Vec::new();
-    let mut versions_index_info: FxHashMap<String, usize> = FxHashMap::default();
-    let mut item_ref_array: Vec<GroupItem> = vec![];
-    let mut item_ref_info: FxHashMap<String, usize> = FxHashMap::default();
+) -> Result<SubelementsInfo, String> {
+    let mut version_sequences = Vec::new();
+    let mut item_sequences: Vec<(&String, Vec<GroupItem>)> = Vec::new();
 
     let elem_idx: FxHashMap<SimpleElement, usize> = element_definitions_array
         .iter()
@@ -23,86 +24,66 @@ pub(crate) fn build_info(
     let mut elemtypenames_alphabetical = elemtypenames_bysize.clone();
     elemtypenames_alphabetical.sort();
 
-    // iterate over the groups according to the sorted type name order
+    // collect the list of versions and the list of sub elements of each element type
     for elemtypename in elemtypenames_bysize {
-        if let Some(element_collection) = element_types
+        let items = element_types
             .get(elemtypename)
             .and_then(|et| et.collection())
-        {
-            let items = element_collection.items();
-            if !items.is_empty() {
-                // build a list of versions from the list of items
-                let item_versions: Vec<usize> = items
-                    .iter()
-                    .map(|item| match item {
-                        ElementCollectionItem::Element(Element { version_info, .. }) => {
-                            *version_info
-                        }
-                        ElementCollectionItem::GroupRef(_) => 0,
-                    })
-                    .collect();
-                // check if this exact sequence of version information already exists within the versions_array
-                if let Some(existing_version_position) = versions_array
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, ver)| **ver == item_versions[0])
-                    .map(|(pos, _)| pos)
-                    .find(|pos| versions_array[*pos..].starts_with(&item_versions))
-                {
-                    // exact sequence was found, store the position of the existing data
-                    versions_index_info.insert(elemtypename.to_owned(), existing_version_position);
-                } else {
-                    // the exact sequence was not found, append it to the end of versions_array and store the position
-                    versions_index_info.insert(elemtypename.to_owned(), versions_array.len());
-                    versions_array.extend(item_versions.iter());
-                }
+            .map_or(&[][..], |ec| ec.items());
+        // build a list of versions from the list of items
+        let item_versions: Vec<usize> = items
+            .iter()
+            .map(|item| match item {
+                ElementCollectionItem::Element(Element { version_info, .. }) => *version_info,
+                ElementCollectionItem::GroupRef(_) => 0,
+            })
+            .collect();
+        version_sequences.push((elemtypename.to_owned(), item_versions));
 
-                // try to reuse group item lists
-                let grpitems: Vec<GroupItem> = items
-                    .iter()
-                    .map(|item| match item {
-                        ElementCollectionItem::Element(element) => GroupItem::ElementRef(
-                            *elem_idx.get(&SimpleElement::from(element)).unwrap(),
-                        ),
+        let grpitems: Vec<GroupItem> = items
+            .iter()
+            .map(|item| match item {
+                ElementCollectionItem::Element(element) => {
+                    GroupItem::ElementRef(*elem_idx.get(&SimpleElement::from(element)).unwrap())
+                }
 
-                        ElementCollectionItem::GroupRef(group_ref) => {
-                            let grouptype_idx = elemtypenames_alphabetical
-                                .iter()
-                                .position(|name| *name == group_ref)
-                                .unwrap();
-                            GroupItem::GroupRef(grouptype_idx)
-                        }
-                    })
-                    .collect();
-                if let Some(existing_position) = item_ref_array
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, item)| **item == grpitems[0])
-                    .map(|(pos, _)| pos)
-                    .find(|pos| item_ref_array[*pos..].starts_with(&grpitems))
-                {
-                    item_ref_info.insert(elemtypename.clone(), existing_position);
-                } else {
-                    item_ref_info.insert(elemtypename.clone(), item_ref_array.len());
-                    item_ref_array.extend(grpitems.iter().cloned());
+                ElementCollectionItem::GroupRef(group_ref) => {
+                    let grouptype_idx = elemtypenames_alphabetical
+                        .iter()
+                        .position(|name| *name == group_ref)
+                        .unwrap();
+                    GroupItem::GroupRef(grouptype_idx)
                 }
-            } else {
-                // number of subelements = 0
-                versions_index_info.insert(elemtypename.to_owned(), 0);
-                item_ref_info.insert(elemtypename.clone(), 0);
-            }
-        } else {
-            versions_index_info.insert(elemtypename.to_owned(), 0);
-            item_ref_info.insert(elemtypename.clone(), 0);
-        }
+            })
+            .collect();
+        item_sequences.push((elemtypename, grpitems));
     }
 
-    SubelementsInfo {
-        versions_array,
-        versions_index_info,
+    // pack the lists of sub elements into one array, reusing overlapping parts
+    let sequence_refs: Vec<&[GroupItem]> = item_sequences.iter().map(|(_, seq)| &**seq).collect();
+    let (item_ref_array, offsets) = pack_sequences(&sequence_refs);
+    let mut item_ref_info: FxHashMap<String, usize> = FxHashMap::default();
+    for ((elemtypename, grpitems), offset) in item_sequences.iter().zip(offsets) {
+        // re-expand the slice to make sure the packing preserved the list of sub elements
+        check_packed(
+            &item_ref_array,
+            offset,
+            grpitems,
+            &format!("sub elements of {elemtypename}"),
+        )?;
+        item_ref_info.insert((*elemtypename).clone(), offset);
+    }
+    println!(
+        "SUBELEMENTS: {} entries, packed from {} entries in all lists of sub elements",
+        item_ref_array.len(),
+        sequence_refs.iter().map(|seq| seq.len()).sum::<usize>()
+    );
+
+    Ok(SubelementsInfo {
+        version_sequences,
         item_ref_array,
         item_ref_info,
-    }
+    })
 }
 
 fn cmp_grouptypenames_subelems(
@@ -128,7 +109,7 @@ fn cmp_grouptypenames_subelems(
 
 pub(crate) fn generate(items: &[GroupItem]) -> String {
     let mut generated = format!(
-        "\npub(crate) const SUBELEMENTS: [SubElement; {}] = [\n",
+        "\n#[rustfmt::skip]\npub(crate) const SUBELEMENTS: [SubElement; {}] = [\n",
         items.len()
     );
     let mut item_strings = vec![];
@@ -0,0 +1,240 @@
+use crate::generator::CodeOutput;
+use crate::{AutosarDataTypes, CharacterDataType, ElementCollectionItem, XsdFileInfo};
+use crate::{FxHashMap, HashSet, Symbol, XsdRestrictToStandard};
+use std::fmt::Write;
+
+/// a constant or static array in the generated code
+struct GeneratedArray {
+    name: String,
+    filename: String,
+    item_type: String,
+    length: usize,
+}
+
+/// write schema_stats.txt in the output directory, which summarizes the size and the complexity of the generated code
+///
+/// The arrays are found in the modules that were generated before, so this must run after all other modules
+/// have been generated. Their sizes in bytes depend on the types of the consuming crate, so they are computed by
+/// the test `array_sizes` in the generated module schema_stats.rs with `size_of`. Comparing the statistics between
+/// AUTOSAR releases shows how the schema grows, and sudden jumps in the array sizes can point to problems like
+/// failed type deduplication.
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    autosar_schema: &AutosarDataTypes,
+    unmatched_ref_targets: &[(String, String)],
+    header: &str,
+    output: &mut CodeOutput,
+) -> Result<(), String> {
+    let mut arrays = Vec::new();
+    for (filename, content) in output.modules() {
+        find_arrays(content, filename, &mut arrays);
+    }
+
+    let mut stats = String::from("# statistics of the generated code\n");
+
+    // the number of constants by the type of their items
+    let mut type_counts: FxHashMap<&str, usize> = FxHashMap::default();
+    for array in &arrays {
+        *type_counts.entry(&array.item_type).or_default() += 1;
+    }
+    let mut type_counts: Vec<(&str, usize)> = type_counts.into_iter().collect();
+    type_counts
+        .sort_by(|(name1, count1), (name2, count2)| count2.cmp(count1).then(name1.cmp(name2)));
+    writeln!(stats, "\n## generated constant arrays by item type").unwrap();
+    for (item_type, count) in type_counts {
+        writeln!(stats, "{item_type}: {count}").unwrap();
+    }
+
+    writeln!(
+        stats,
+        "\n## array lengths\n(the sizes in bytes are printed by the test array_sizes in schema_stats.rs)"
+    )
+    .unwrap();
+    for array in &arrays {
+        writeln!(
+            stats,
+            "{} ({}): {} x {}",
+            array.name, array.filename, array.length, array.item_type
+        )
+        .unwrap();
+    }
+
+    let patterns: HashSet<&str> = autosar_schema
+        .character_types
+        .values()
+        .filter_map(|chartype| match chartype {
+            CharacterDataType::Pattern { pattern, .. } => Some(&**pattern),
+            _ => None,
+        })
+        .collect();
+    writeln!(
+        stats,
+        "\n## regex patterns\n{} distinct patterns",
+        patterns.len()
+    )
+    .unwrap();
+
+    writeln!(stats, "\n## elements by platform").unwrap();
+    let (classic_count, adaptive_count) = count_elements_by_platform(xsd_config, autosar_schema);
+    writeln!(
+        stats,
+        "classic: {classic_count}\nadaptive: {adaptive_count}"
+    )
+    .unwrap();
+
+    writeln!(stats, "\n## elements with the most attributes").unwrap();
+    for (name, count) in elements_by_attribute_count(autosar_schema).iter().take(10) {
+        writeln!(stats, "{name}: {count}").unwrap();
+    }
+
+    // DEST values without any matching element type disable the validation of the reference targets
+    writeln!(
+        stats,
+        "\n## DEST values without target types\n{} unmatched",
+        unmatched_ref_targets.len()
+    )
+    .unwrap();
+    for (dest_value, chartype) in unmatched_ref_targets {
+        writeln!(stats, "{dest_value} ({chartype})").unwrap();
+    }
+
+    output.write("schema_stats.rs", &generate_size_test(header, &arrays));
+    output.write_text("schema_stats.txt", &stats)
+}
+
+// generate a test which prints the size of each generated array, as computed by the compiler for the target.
+// Each item of an array takes size_of bytes, which includes the padding that is needed for the alignment.
+fn generate_size_test(header: &str, arrays: &[GeneratedArray]) -> String {
+    let mut generated = String::from(header);
+    generated.push_str(
+        "
+#[cfg(test)]
+mod test {
+    use crate::*;
+    use core::mem::size_of;
+
+    #[test]
+    fn array_sizes() {
+",
+    );
+    writeln!(
+        generated,
+        "        #[rustfmt::skip]\n        let arrays: [(&str, &str, usize, usize); {}] = [",
+        arrays.len()
+    )
+    .unwrap();
+    for array in arrays {
+        writeln!(
+            generated,
+            "            (\"{}\", \"{}\", {}, size_of::<{}>()),",
+            array.name, array.filename, array.length, array.item_type
+        )
+        .unwrap();
+    }
+    generated.push_str(
+        r#"        ];
+        let mut total_size = 0;
+        for (name, filename, length, item_size) in arrays {
+            let bytes = length * item_size;
+            total_size += bytes;
+            println!("{name} ({filename}): {length} x {item_size} = {bytes} bytes");
+        }
+        println!("total: {total_size} bytes");
+    }
+}
+"#,
+    );
+    generated
+}
+
+// find all array declarations of the form "const NAME: [TYPE; LENGTH]" or "static NAME: [TYPE; LENGTH]"
+fn find_arrays(content: &str, filename: &str, arrays: &mut Vec<GeneratedArray>) {
+    for line in content.lines() {
+        let Some(pos) = line.find("const ").or_else(|| line.find("static ")) else {
+            continue;
+        };
+        let decl = &line[pos..];
+        let Some((name, rest)) = decl
+            .split_once(' ')
+            .and_then(|(_, rest)| rest.split_once(": ["))
+        else {
+            continue;
+        };
+        let Some((item_type, length)) = rest
+            .split_once(']')
+            .and_then(|(array_type, _)| array_type.rsplit_once("; "))
+        else {
+            continue;
+        };
+        if let Ok(length) = length.parse() {
+            arrays.push(GeneratedArray {
+                name: name.to_string(),
+                filename: filename.to_string(),
+                item_type: item_type.to_string(),
+                length,
+            });
+        }
+    }
+}
+
+// count the element types that are valid in any version of each platform, in the same way as the element type counts
+// that are printed for each version
+fn count_elements_by_platform(
+    xsd_config: &[XsdFileInfo],
+    autosar_schema: &AutosarDataTypes,
+) -> (usize, usize) {
+    let version_mask = |is_platform: fn(&XsdFileInfo) -> bool| {
+        xsd_config
+            .iter()
+            .enumerate()
+            .filter(|(_, info)| is_platform(info))
+            .fold(0, |mask, (idx, _)| mask | (1 << idx))
+    };
+    let classic_mask = version_mask(|info| info.platforms.is_classic());
+    let adaptive_mask = version_mask(|info| info.platforms.is_adaptive());
+
+    // the platform restriction of an element type is the union of the restrictions of all elements using it
+    let mut type_platforms: FxHashMap<Symbol, (usize, usize)> = FxHashMap::default();
+    for group in autosar_schema.group_types.values() {
+        for item in group.items() {
+            if let ElementCollectionItem::Element(elem) = item {
+                let (classic, adaptive) = type_platforms.entry(elem.typeref).or_default();
+                if elem.restrict_std != XsdRestrictToStandard::AdaptivePlatform {
+                    *classic |= elem.version_info & classic_mask;
+                }
+                if elem.restrict_std != XsdRestrictToStandard::ClassicPlatform {
+                    *adaptive |= elem.version_info & adaptive_mask;
+                }
+            }
+        }
+    }
+    let classic_count = type_platforms
+        .values()
+        .filter(|(classic, _)| *classic != 0)
+        .count();
+    let adaptive_count = type_platforms
+        .values()
+        .filter(|(_, adaptive)| *adaptive != 0)
+        .count();
+    (classic_count, adaptive_count)
+}
+
+// list the element names with the number of attributes of their data type, sorted by the number of attributes
+fn elements_by_attribute_count(autosar_schema: &AutosarDataTypes) -> Vec<(&str, usize)> {
+    let mut attribute_counts: FxHashMap<&str, usize> = FxHashMap::default();
+    for group in autosar_schema.group_types.values() {
+        for item in group.items() {
+            if let ElementCollectionItem::Element(elem) = item {
+                if let Some(elemtype) = autosar_schema.element_types.get(&elem.typeref) {
+                    // an element name can be used with different types; the largest count is listed
+                    let count = attribute_counts.entry(&elem.name).or_default();
+                    *count = (*count).max(elemtype.attributes().len());
+                }
+            }
+        }
+    }
+    let mut attribute_counts: Vec<(&str, usize)> = attribute_counts.into_iter().collect();
+    attribute_counts
+        .sort_by(|(name1, count1), (name2, count2)| count2.cmp(count1).then(name1.cmp(name2)));
+    attribute_counts
+}
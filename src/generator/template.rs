@@ -0,0 +1,25 @@
+/// fill in the placeholders of a template
+///
+/// Each `{{name}}` in the template is replaced by the value of `name`. The values are inserted unchanged, so text
+/// must be escaped by the caller; placeholders inside the values are not expanded. All templates are part of the
+/// generator, so a placeholder without a value is a bug.
+pub(crate) fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let end = start
+            + rest[start..]
+                .find("}}")
+                .expect("unterminated placeholder in template");
+        let name = &rest[start + 2..end];
+        let (_, value) = values
+            .iter()
+            .find(|(placeholder, _)| *placeholder == name)
+            .unwrap_or_else(|| panic!("no value for the template placeholder {name}"));
+        result.push_str(value);
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+    result
+}
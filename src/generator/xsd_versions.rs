@@ -1,13 +1,25 @@
-use crate::XsdFileInfo;
+use crate::generator::GeneratorOptions;
+use crate::{FxHashMap, XsdFileInfo};
 use std::fmt::Write;
-use std::fs::File;
-use std::io::Write as IoWrite;
 
-pub(crate) fn generate(xsd_config: &[XsdFileInfo]) {
+/// generate autosarversion.rs, which contains the enum of all configured versions
+pub(crate) fn generate(
+    xsd_config: &[XsdFileInfo],
+    options: &GeneratorOptions,
+    schema_hash: &[u8; 32],
+    header: &str,
+) -> String {
+    let missing_versions = &options.missing_versions;
     let mut match_lines = String::new();
     let mut filename_lines = String::new();
     let mut desc_lines = String::new();
-    let mut generated = String::from(
+    let mut all_lines = String::new();
+    let mut namespace_lines = String::new();
+    let mut schema_location_lines = String::new();
+    let mut classic_idents = Vec::new();
+    let mut adaptive_idents = Vec::new();
+    let mut generated = String::from(header);
+    generated.push_str(
         r"use num_derive::FromPrimitive;
 use num_traits::cast::FromPrimitive;
 
@@ -31,6 +43,16 @@ pub enum AutosarVersion {
             xsd_file_info.desc, xsd_file_info.name
         )
         .unwrap();
+        if missing_versions.contains(&idx) {
+            generated.push_str(
+                "    ///\n    /// **Note:** the xsd file of this version was not available when the specification was generated.\n    /// No elements or attributes are valid in this version, and it is not part of `ALL_VERSIONS_MASK`.\n",
+            );
+        }
+        if options.extension_version == Some(idx) {
+            generated.push_str(
+                "    ///\n    /// This is not a standard version: it contains the newest standard version and the elements of a vendor extension.\n",
+            );
+        }
         writeln!(
             generated,
             r#"    {} = 0x{:x},"#,
@@ -56,8 +78,48 @@ pub enum AutosarVersion {
             xsd_file_info.ident, xsd_file_info.desc
         )
         .unwrap();
+        writeln!(all_lines, "        Self::{},", xsd_file_info.ident).unwrap();
+        writeln!(
+            namespace_lines,
+            r#"            Self::{} => "{}","#,
+            xsd_file_info.ident, xsd_file_info.namespace
+        )
+        .unwrap();
+        writeln!(
+            schema_location_lines,
+            r#"            Self::{} => "{} {}","#,
+            xsd_file_info.ident, xsd_file_info.namespace, xsd_file_info.name
+        )
+        .unwrap();
+        if xsd_file_info.platforms.is_classic() {
+            classic_idents.push(format!("Self::{}", xsd_file_info.ident));
+        }
+        if xsd_file_info.platforms.is_adaptive() {
+            adaptive_idents.push(format!("Self::{}", xsd_file_info.ident));
+        }
     }
-    let lastident = xsd_config[xsd_config.len() - 1].ident;
+    let version_string_lines = version_string_match_lines(xsd_config);
+    // an extension is never the latest standard version
+    let lastident = xsd_config
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(idx, _)| options.extension_version != Some(*idx))
+        .map_or(xsd_config[xsd_config.len() - 1].ident, |(_, info)| {
+            info.ident
+        });
+    let all_versions_mask = missing_versions
+        .iter()
+        .fold((1u64 << xsd_config.len()) - 1, |mask, idx| {
+            mask & !(1 << idx)
+        });
+    let schema_hash_str = schema_hash
+        .iter()
+        .map(|byte| format!("0x{byte:02x}"))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let is_classic = platform_match(&classic_idents);
+    let is_adaptive = platform_match(&adaptive_idents);
     writeln!(
         generated,
         r#"}}
@@ -82,6 +144,24 @@ impl AutosarVersion {{
         }}
     }}
 
+    /// the xml namespace of the Autosar version, taken from the targetNamespace of the xsd file
+    #[must_use]
+    pub fn namespace(&self) -> &'static str {{
+        match self {{
+{namespace_lines}
+        }}
+    }}
+
+    /// the value of the xsi:schemaLocation attribute of an arxml file for the Autosar version
+    ///
+    /// For example `Autosar_00051` -> "http://autosar.org/schema/r4.0 AUTOSAR_00051.xsd"
+    #[must_use]
+    pub fn schema_location(&self) -> &'static str {{
+        match self {{
+{schema_location_lines}
+        }}
+    }}
+
     /// make an `AutosarVersion` from a u32 value
     ///
     /// All `AutosarVersion`s are associated with a power of two u32 value, for example `Autosar_4_3_0` == 0x100
@@ -93,11 +173,66 @@ impl AutosarVersion {{
         Self::from_u32(n)
     }}
 
-    /// `AutosarVersion::LATEST` is an alias of which ever is the latest version
+    /// parse the human readable version strings that are used by AUTOSAR tools, e.g. in the ADMIN-DATA of a file
+    ///
+    /// The accepted strings are derived from the descriptions of the versions, for example
+    /// "4.3.0", "AUTOSAR 4.3.0", "R22-11" and "AUTOSAR R22-11". Use `from_str()` to parse the name of an xsd file.
+    #[must_use]
+    pub fn from_schema_version_string(input: &str) -> Option<Self> {{
+        match input.trim() {{
+{version_string_lines}
+            _ => None,
+        }}
+    }}
+
+    /// `AutosarVersion::LATEST` is an alias of which ever is the latest standard version
     pub const LATEST: AutosarVersion = AutosarVersion::{lastident};
+
+    /// a version mask that contains all Autosar versions for which the specification contains data
+    pub const ALL_VERSIONS_MASK: u32 = 0x{all_versions_mask:x};
+
+    /// all Autosar versions, from oldest to newest
+    #[must_use]
+    pub fn all() -> &'static [Self] {{
+        &[
+{all_lines}        ]
+    }}
+
+    /// make a version mask that contains all versions from `first` to `last`, inclusive
+    ///
+    /// The mask is empty if `first` is newer than `last`
+    #[must_use]
+    pub const fn mask_from_range(first: Self, last: Self) -> u32 {{
+        let first = first as u32;
+        let last = last as u32;
+        if first > last {{
+            0
+        }} else {{
+            // all bits up to and including last, minus all bits below first
+            (last | (last - 1)) & !(first - 1)
+        }}
+    }}
+
+    /// check if the Autosar version describes the classic platform
+    #[must_use]
+    pub fn is_classic(&self) -> bool {{
+        {is_classic}
+    }}
+
+    /// check if the Autosar version describes the adaptive platform
+    #[must_use]
+    pub fn is_adaptive(&self) -> bool {{
+        {is_adaptive}
+    }}
 }}
 
-impl std::str::FromStr for AutosarVersion {{
+/// SHA-256 fingerprint of the xsd files that the specification was generated from
+///
+/// The hash covers the contents of all input xsd files, concatenated in the order of their file names.
+/// It can be compared with a known value to check that the specification matches the expected schema files.
+pub const SCHEMA_HASH: [u8; 32] = [{schema_hash_str}];
+
+impl core::str::FromStr for AutosarVersion {{
     type Err = ParseAutosarVersionError;
     fn from_str(input: &str) -> Result<Self, Self::Err> {{
         match input {{
@@ -107,8 +242,8 @@ impl std::str::FromStr for AutosarVersion {{
     }}
 }}
 
-impl std::fmt::Display for AutosarVersion {{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+impl core::fmt::Display for AutosarVersion {{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {{
         f.write_str(self.describe())
     }}
 }}
@@ -116,6 +251,74 @@ impl std::fmt::Display for AutosarVersion {{
     )
     .unwrap();
 
-    let mut file = File::create("gen/autosarversion.rs").unwrap();
-    file.write_all(generated.as_bytes()).unwrap();
+    generated
+}
+
+// generate the match arms of from_schema_version_string; strings that would match several versions are left out
+fn version_string_match_lines(xsd_config: &[XsdFileInfo]) -> String {
+    let mut version_strings: Vec<(String, &str)> = Vec::new();
+    for xsd_file_info in xsd_config {
+        for version_string in schema_version_strings(xsd_file_info.desc) {
+            if !version_strings.contains(&(version_string.clone(), xsd_file_info.ident)) {
+                version_strings.push((version_string, xsd_file_info.ident));
+            }
+        }
+    }
+    let mut string_counts: FxHashMap<&str, usize> = FxHashMap::default();
+    for (version_string, _) in &version_strings {
+        *string_counts.entry(version_string).or_default() += 1;
+    }
+
+    let mut match_lines = String::new();
+    for (version_string, ident) in &version_strings {
+        if string_counts[version_string.as_str()] == 1 {
+            writeln!(
+                match_lines,
+                r#"            "{version_string}" => Some(Self::{ident}),"#
+            )
+            .unwrap();
+        }
+    }
+    match_lines
+}
+
+// derive the version strings from a description like "AUTOSAR R22-11" or "AUTOSAR Classic 4.4.0 / Adaptive 18-10"
+fn schema_version_strings(desc: &str) -> Vec<String> {
+    let mut version_strings = vec![desc.to_string()];
+    let Some(versions) = desc.strip_prefix("AUTOSAR ") else {
+        return version_strings;
+    };
+    for part in versions.split(" / ") {
+        let version = part
+            .strip_prefix("Classic ")
+            .or_else(|| part.strip_prefix("Adaptive "))
+            .unwrap_or(part);
+        let number = version.strip_prefix('R').unwrap_or(version);
+        // only plain version numbers like "4.3.0" or "22-11" are used on their own
+        if number.is_empty()
+            || !number
+                .chars()
+                .all(|c| c.is_ascii_digit() || c == '.' || c == '-')
+        {
+            continue;
+        }
+        version_strings.push(format!("AUTOSAR {part}"));
+        version_strings.push(version.to_string());
+        if number.contains('-') {
+            // release numbers are written both with and without the leading 'R'
+            version_strings.push(number.to_string());
+            version_strings.push(format!("R{number}"));
+            version_strings.push(format!("AUTOSAR R{number}"));
+        }
+    }
+    version_strings
+}
+
+// generate the body of is_classic / is_adaptive from the list of matching versions
+fn platform_match(idents: &[String]) -> String {
+    if idents.is_empty() {
+        "false".to_string()
+    } else {
+        format!("matches!(self, {})", idents.join(" | "))
+    }
 }
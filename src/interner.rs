@@ -0,0 +1,65 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::sync::Arc;
+
+/// a handle for a type name, which is stored in the [`Interner`] of the schema
+///
+/// A symbol is cheap to copy, hash and compare, but it only has a meaning together with the interner that created
+/// it; symbols of different schemas can't be compared. Symbols are not ordered, since the order of the numbers
+/// is the order in which the names were added. Type names are sorted by their text instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct Symbol(u32);
+
+/// the type names of a schema; each name is stored once
+///
+/// The names are serialized as a list of strings, and the symbols as their index in the list.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "Vec<String>")]
+pub(crate) struct Interner {
+    names: Vec<Arc<str>>,
+    symbols: FxHashMap<Arc<str>, Symbol>,
+}
+
+impl Interner {
+    /// the symbol of `name`, which is added if it is new
+    pub(crate) fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(symbol) = self.symbols.get(name) {
+            return *symbol;
+        }
+        let symbol = Symbol(u32::try_from(self.names.len()).expect("too many type names"));
+        let name: Arc<str> = Arc::from(name);
+        self.names.push(name.clone());
+        self.symbols.insert(name, symbol);
+        symbol
+    }
+
+    /// the symbol of `name`, if the name was added before
+    pub(crate) fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    pub(crate) fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.0 as usize]
+    }
+
+    /// the symbol of the name of `symbol` from the interner `other`
+    pub(crate) fn translate(&mut self, symbol: Symbol, other: &Interner) -> Symbol {
+        self.intern(other.resolve(symbol))
+    }
+}
+
+impl From<Vec<String>> for Interner {
+    fn from(names: Vec<String>) -> Self {
+        let mut interner = Interner::default();
+        for name in names {
+            interner.intern(&name);
+        }
+        interner
+    }
+}
+
+impl Serialize for Interner {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.names.iter().map(|name| &**name))
+    }
+}
@@ -0,0 +1,1825 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use interner::{Interner, Symbol};
+use xsd::Xsd;
+
+pub use merge::{
+    diff, merge_all, verify_superset, MergePolicy, MergePolicyKind, SchemaDiff, TypeNameDiff,
+};
+pub use xsd::XsdRestrictToStandard;
+
+mod cache;
+mod catalog;
+mod config;
+mod dedup;
+mod flatten;
+mod generator;
+mod interner;
+mod merge;
+pub mod phases;
+mod profile;
+#[cfg(feature = "testing")]
+#[doc(hidden)]
+pub mod snapshot;
+mod verify;
+mod xsd;
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+struct EnumDefinition {
+    name: String,
+    /// name, version info and docstring of each enum item
+    enumitems: Vec<(String, usize, Option<String>)>,
+}
+
+/// an attribute of an element type
+///
+/// The type name is a [`Symbol`] in the schema; the generator works with a copy in which the names are resolved.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) struct Attribute<N = Symbol> {
+    pub(crate) name: String,
+    pub(crate) attr_type: N,
+    pub(crate) required: bool,
+    pub(crate) version_info: usize,
+    pub(crate) docstring: Option<String>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub(crate) struct Element<N = Symbol> {
+    pub(crate) name: String,
+    pub(crate) typeref: N,
+    pub(crate) amount: ElementAmount,
+    pub(crate) version_info: usize,
+    /// bitmask of the versions in which the element is splittable; this is a subset of version_info
+    pub(crate) splittable_ver: usize,
+    pub(crate) ordered: bool,
+    pub(crate) restrict_std: XsdRestrictToStandard,
+    pub(crate) docstring: Option<String>,
+}
+
+/// the multiplicity of an element, from minOccurs and maxOccurs
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ElementAmount {
+    ZeroOrOne,
+    One,
+    Any,
+    Exactly(u32),
+    AtLeast(u32),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+enum ElementCollectionItem<N = Symbol> {
+    Element(Element<N>),
+    GroupRef(N),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ElementCollection<N = Symbol> {
+    Choice {
+        name: String,
+        sub_elements: Vec<ElementCollectionItem<N>>,
+        amount: ElementAmount,
+    },
+    Sequence {
+        name: String,
+        sub_elements: Vec<ElementCollectionItem<N>>,
+    },
+}
+
+// PartialEq is implemented below, because the class of the meta model is not part of the comparison
+#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
+pub(crate) enum ElementDataType<N = Symbol> {
+    Elements {
+        group_ref: N,
+        attributes: Vec<Attribute<N>>,
+        xsd_typenames: HashSet<String>,
+        mm_class: Option<Vec<String>>,
+    },
+    Characters {
+        attributes: Vec<Attribute<N>>,
+        basetype: N,
+    },
+    Mixed {
+        group_ref: N,
+        attributes: Vec<Attribute<N>>,
+        basetype: N,
+        mm_class: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub(crate) enum CharacterDataType {
+    Pattern {
+        pattern: String,
+        max_length: Option<usize>,
+    },
+    Enum(EnumDefinition),
+    String {
+        max_length: Option<usize>,
+        preserve_whitespace: bool,
+    },
+    UnsignedInteger,
+    Double,
+    DateTime {
+        format: Option<String>,
+    },
+}
+
+/// the element types, character types and groups of a schema
+///
+/// The schemas are created by [`phases`]: [`phases::FlatSchemas::schemas`] contains one for each xsd file, and
+/// [`phases::Schema::types`] is the merged schema.
+// PartialEq is implemented below, because the symbols of two schemas can only be compared by their names
+#[derive(Clone, Eq, Serialize, Deserialize)]
+pub struct AutosarDataTypes {
+    // the names of all types and groups; the maps and the references between the types use their symbols
+    names: Interner,
+    element_types: FxHashMap<Symbol, ElementDataType>,
+    character_types: FxHashMap<Symbol, CharacterDataType>,
+    group_types: FxHashMap<Symbol, ElementCollection>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct XsdFileInfo {
+    name: &'static str,
+    ident: &'static str,
+    desc: &'static str,
+    platforms: Platforms,
+    /// the xml namespace of the xsd file; this is replaced by the targetNamespace of the loaded file
+    namespace: &'static str,
+}
+
+/// the xml namespace of all AUTOSAR 4 xsd files
+const AUTOSAR_NAMESPACE: &str = "http://autosar.org/schema/r4.0";
+
+/// the maximum number of types in the paths found by --find-paths
+const MAX_PATH_LENGTH: usize = 20;
+
+/// the maximum number of paths printed by --find-paths
+const MAX_PATHS: usize = 1000;
+
+// the state of the depth-first search of find_all_paths_to
+struct PathSearch<'a> {
+    target_typename: &'a str,
+    sub_types: &'a FxHashMap<&'a str, BTreeSet<&'a str>>,
+    reaches_target: &'a HashSet<&'a str>,
+    max_paths: usize,
+    paths: Vec<Vec<String>>,
+    truncated: bool,
+}
+
+impl<'a> PathSearch<'a> {
+    fn collect_paths(&mut self, path: &mut Vec<&'a str>) {
+        let typename = *path.last().unwrap();
+        if typename == self.target_typename {
+            if self.paths.len() < self.max_paths {
+                self.paths
+                    .push(path.iter().map(ToString::to_string).collect());
+            } else {
+                self.truncated = true;
+            }
+            return;
+        }
+        if path.len() >= MAX_PATH_LENGTH {
+            return;
+        }
+        let Some(sub_types) = self.sub_types.get(typename) else {
+            return;
+        };
+        for sub_type in sub_types {
+            if self.truncated {
+                return;
+            }
+            if self.reaches_target.contains(sub_type) && !path.contains(sub_type) {
+                path.push(sub_type);
+                self.collect_paths(path);
+                path.pop();
+            }
+        }
+    }
+}
+
+/// the AUTOSAR platforms that are described by an xsd file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Platforms {
+    Classic,
+    Adaptive,
+    ClassicAndAdaptive,
+}
+
+impl Platforms {
+    fn is_classic(self) -> bool {
+        matches!(self, Platforms::Classic | Platforms::ClassicAndAdaptive)
+    }
+
+    fn is_adaptive(self) -> bool {
+        matches!(self, Platforms::Adaptive | Platforms::ClassicAndAdaptive)
+    }
+}
+
+const XSD_CONFIG: [XsdFileInfo; 20] = [
+    XsdFileInfo {
+        name: "AUTOSAR_4-0-1.xsd",
+        ident: "Autosar_4_0_1",
+        desc: "AUTOSAR 4.0.1",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-0-2.xsd",
+        ident: "Autosar_4_0_2",
+        desc: "AUTOSAR 4.0.2",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-0-3.xsd",
+        ident: "Autosar_4_0_3",
+        desc: "AUTOSAR 4.0.3",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-1-1.xsd",
+        ident: "Autosar_4_1_1",
+        desc: "AUTOSAR 4.1.1",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-1-2.xsd",
+        ident: "Autosar_4_1_2",
+        desc: "AUTOSAR 4.1.2",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-1-3.xsd",
+        ident: "Autosar_4_1_3",
+        desc: "AUTOSAR 4.1.3",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-2-1.xsd",
+        ident: "Autosar_4_2_1",
+        desc: "AUTOSAR 4.2.1",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-2-2.xsd",
+        ident: "Autosar_4_2_2",
+        desc: "AUTOSAR 4.2.2",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_4-3-0.xsd",
+        ident: "Autosar_4_3_0",
+        desc: "AUTOSAR 4.3.0",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00042.xsd",
+        ident: "Autosar_00042",
+        desc: "AUTOSAR Adaptive 17-03",
+        platforms: Platforms::Adaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00043.xsd",
+        ident: "Autosar_00043",
+        desc: "AUTOSAR Adaptive 17-10",
+        platforms: Platforms::Adaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00044.xsd",
+        ident: "Autosar_00044",
+        desc: "AUTOSAR Classic 4.3.1",
+        platforms: Platforms::Classic,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00045.xsd",
+        ident: "Autosar_00045",
+        desc: "AUTOSAR Adaptive 18-03",
+        platforms: Platforms::Adaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00046.xsd",
+        ident: "Autosar_00046",
+        desc: "AUTOSAR Classic 4.4.0 / Adaptive 18-10",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00047.xsd",
+        ident: "Autosar_00047",
+        desc: "AUTOSAR Adaptive 19-03",
+        platforms: Platforms::Adaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00048.xsd",
+        ident: "Autosar_00048",
+        desc: "AUTOSAR 4.5.0",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00049.xsd",
+        ident: "Autosar_00049",
+        desc: "AUTOSAR R20-11",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00050.xsd",
+        ident: "Autosar_00050",
+        desc: "AUTOSAR R21-11",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00051.xsd",
+        ident: "Autosar_00051",
+        desc: "AUTOSAR R22-11",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+    XsdFileInfo {
+        name: "AUTOSAR_00052.xsd",
+        ident: "Autosar_00052",
+        desc: "AUTOSAR R23-11",
+        platforms: Platforms::ClassicAndAdaptive,
+        namespace: AUTOSAR_NAMESPACE,
+    },
+];
+
+#[derive(Default)]
+struct CmdlineOptions {
+    xsd_path: String,
+    check_regexes: bool,
+    verify: bool,
+    validate_only: bool,
+    list_versions: bool,
+    verbose_flatten: bool,
+    verify_merge_order: bool,
+    verify_merge: bool,
+    find_paths: Option<String>,
+    cache_file: Option<PathBuf>,
+    catalog_file: Option<PathBuf>,
+    extension_file: Option<PathBuf>,
+    single_file: Option<PathBuf>,
+    debug_dot_file: Option<PathBuf>,
+    allow_unknown: bool,
+    skip_missing: bool,
+    merge_duplicates: bool,
+    merge_policy: merge::MergePolicyKind,
+    profile: bool,
+    stats: bool,
+    generator_options: generator::GeneratorOptions,
+}
+
+impl CmdlineOptions {
+    // the first option that can't be combined with --validate-only: the other modes would be skipped silently,
+    // and the reports and the cache are only written together with the generated code
+    fn validate_only_conflict(&self) -> Option<&'static str> {
+        if !self.validate_only {
+            return None;
+        }
+        let generator_options = &self.generator_options;
+        [
+            (self.check_regexes, "--check-regexes"),
+            (self.verify, "--verify"),
+            (self.list_versions, "--list-versions"),
+            (self.find_paths.is_some(), "--find-paths"),
+            (self.cache_file.is_some(), "--cache-file"),
+            (self.stats, "--stats"),
+            (generator_options.removed_report, "--removed-report"),
+            (
+                generator_options.rust_module.is_some(),
+                "--emit-rust-module",
+            ),
+            (generator_options.html_site.is_some(), "--emit-html"),
+            (generator_options.availability_csv.is_some(), "--emit-csv"),
+        ]
+        .into_iter()
+        .find_map(|(is_set, option)| is_set.then_some(option))
+    }
+}
+
+fn parse_cmdline(args: &[String]) -> Option<CmdlineOptions> {
+    let mut xsd_path = None;
+    let mut check_regexes = false;
+    let mut verify = false;
+    let mut validate_only = false;
+    let mut list_versions = false;
+    let mut verbose_flatten = false;
+    let mut verify_merge_order = false;
+    let mut verify_merge = false;
+    let mut find_paths = None;
+    let mut cache_file = None;
+    let mut catalog_file = None;
+    let mut extension_file = None;
+    let mut single_file = None;
+    let mut debug_dot_file = None;
+    let mut allow_unknown = false;
+    let mut skip_missing = false;
+    let mut merge_duplicates = false;
+    let mut merge_policy = merge::MergePolicyKind::default();
+    let mut profile = false;
+    let mut stats = false;
+    let mut generator_options = generator::GeneratorOptions::default();
+
+    for arg in args.iter().skip(1) {
+        match arg.as_str() {
+            "--check-regexes" => check_regexes = true,
+            "--verify" => verify = true,
+            "--validate-only" => validate_only = true,
+            "--list-versions" => list_versions = true,
+            "--verbose-flatten" => verbose_flatten = true,
+            "--verify-merge-order" => verify_merge_order = true,
+            "--verify-merge" => verify_merge = true,
+            "--allow-unknown" => allow_unknown = true,
+            "--skip-missing" => skip_missing = true,
+            "--merge-duplicates" => merge_duplicates = true,
+            "--profile" => profile = true,
+            "--stats" => stats = true,
+            "--serde" => generator_options.serde = true,
+            "--plain-string-tables" => generator_options.plain_string_tables = true,
+            "--no-timestamp" => generator_options.no_timestamp = true,
+            "--no-const-assertions" => generator_options.no_const_assertions = true,
+            "--removed-report" => generator_options.removed_report = true,
+            _ if arg.starts_with("--chartype-const=") => {
+                let (const_name, typename) = arg["--chartype-const=".len()..].split_once('=')?;
+                if !is_constant_name(const_name) || typename.is_empty() {
+                    return None;
+                }
+                generator_options
+                    .chartype_constants
+                    .push((const_name.to_string(), typename.to_string()));
+            }
+            _ if arg.starts_with("--find-paths=") => {
+                let typename = &arg["--find-paths=".len()..];
+                if typename.is_empty() {
+                    return None;
+                }
+                find_paths = Some(typename.to_string());
+            }
+            _ if arg.starts_with("--cache-file=") => {
+                let cache_path = &arg["--cache-file=".len()..];
+                if cache_path.is_empty() {
+                    return None;
+                }
+                cache_file = Some(PathBuf::from(cache_path));
+            }
+            _ if arg.starts_with("--catalog=") => {
+                let catalog_path = &arg["--catalog=".len()..];
+                if catalog_path.is_empty() {
+                    return None;
+                }
+                catalog_file = Some(PathBuf::from(catalog_path));
+            }
+            _ if arg.starts_with("--extension=") => {
+                let extension_path = &arg["--extension=".len()..];
+                if extension_path.is_empty() {
+                    return None;
+                }
+                extension_file = Some(PathBuf::from(extension_path));
+            }
+            _ if arg.starts_with("--single=") => {
+                let single_path = &arg["--single=".len()..];
+                if single_path.is_empty() {
+                    return None;
+                }
+                single_file = Some(PathBuf::from(single_path));
+            }
+            _ if arg.starts_with("--merge-policy=") => {
+                merge_policy = merge::MergePolicyKind::from_name(&arg["--merge-policy=".len()..])?;
+            }
+            _ if arg.starts_with("--debug-dot=") => {
+                let dot_path = &arg["--debug-dot=".len()..];
+                if dot_path.is_empty() {
+                    return None;
+                }
+                debug_dot_file = Some(PathBuf::from(dot_path));
+            }
+            _ if arg.starts_with("--emit-rust-module=") => {
+                let module_path = &arg["--emit-rust-module=".len()..];
+                if module_path.is_empty() {
+                    return None;
+                }
+                generator_options.rust_module = Some(PathBuf::from(module_path));
+            }
+            _ if arg.starts_with("--emit-html=") => {
+                let site_path = &arg["--emit-html=".len()..];
+                if site_path.is_empty() {
+                    return None;
+                }
+                generator_options.html_site = Some(PathBuf::from(site_path));
+            }
+            _ if arg.starts_with("--emit-csv=") => {
+                let csv_path = &arg["--emit-csv=".len()..];
+                if csv_path.is_empty() {
+                    return None;
+                }
+                generator_options.availability_csv = Some(PathBuf::from(csv_path));
+            }
+            _ if arg.starts_with("--attribute-index=") => {
+                let threshold = arg["--attribute-index=".len()..].parse().ok()?;
+                generator_options.attribute_index_threshold = Some(threshold);
+            }
+            _ if arg.starts_with("--") => return None,
+            _ => {
+                if xsd_path.is_some() {
+                    return None;
+                }
+                xsd_path = Some(arg.clone());
+            }
+        }
+    }
+
+    // the xsd path is optional for --list-versions, which only needs it to find a config.toml,
+    // and it is not used at all with --single
+    let xsd_path = if list_versions || single_file.is_some() {
+        xsd_path.unwrap_or_default()
+    } else {
+        xsd_path?
+    };
+
+    Some(CmdlineOptions {
+        xsd_path,
+        check_regexes,
+        verify,
+        validate_only,
+        list_versions,
+        verbose_flatten,
+        verify_merge_order,
+        verify_merge,
+        find_paths,
+        cache_file,
+        catalog_file,
+        extension_file,
+        single_file,
+        debug_dot_file,
+        allow_unknown,
+        skip_missing,
+        merge_duplicates,
+        merge_policy,
+        profile,
+        stats,
+        generator_options,
+    })
+}
+
+// check that the name is a valid SCREAMING_SNAKE_CASE identifier for a constant
+fn is_constant_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn usage(progname: &str) {
+    println!(
+        "usage: {progname} [--check-regexes] [--verify] [--validate-only] [--list-versions] [--verbose-flatten] [--verify-merge-order] [--verify-merge] [--find-paths=TYPE] [--serde] [--plain-string-tables] [--no-timestamp] [--no-const-assertions] [--removed-report] [--chartype-const=NAME=TYPE]... [--attribute-index=N] [--emit-rust-module=PATH] [--emit-html=DIR] [--emit-csv=PATH] [--cache-file=PATH] [--catalog=PATH] [--extension=PATH] [--single=PATH] [--debug-dot=PATH] [--allow-unknown] [--skip-missing] [--merge-duplicates] [--merge-policy=latest-wins|lenient|strict] [--profile] [--stats] <input xsd path>"
+    );
+    println!("  --check-regexes   verify that all patterns in the xsd files have a known validator regex, then exit");
+    println!(
+        "  --verify          check the merged schema, e.g. that all patterns compile, then exit"
+    );
+    println!("  --validate-only   load, flatten and merge the xsd files and run the sanity checks, then exit; no code is generated, and any warning fails the validation");
+    println!("  --list-versions   print the configured xsd versions, then exit; the xsd path is optional");
+    println!("  --verbose-flatten log each step of the flattening of the xsd files");
+    println!("  --verify-merge-order  also merge the versions from oldest to newest, and report groups whose order differs");
+    println!("  --verify-merge    check that every item of each xsd file is part of the merged schema, and report dropped items");
+    println!("  --find-paths=TYPE print all paths from AR:AUTOSAR to the element type TYPE in the merged schema, then exit");
+    println!("  --serde           generate serde impls for the name enums, gated behind the \"serde\" feature");
+    println!("  --plain-string-tables  store the names of the enum items in an array of &str, which is easier to debug");
+    println!(
+        "  --no-timestamp    do not put the generation time into the header of the generated files"
+    );
+    println!("  --no-const-assertions  leave out the compile-time checks of the data types in specification.rs");
+    println!("  --removed-report  list the elements that were removed in newer versions in gen/removed_elements.txt, and generate ELEMENT_LAST_VERSION");
+    println!("  --chartype-const=NAME=TYPE  export the index of the character type TYPE as the constant NAME");
+    println!("  --attribute-index=N  generate a sorted attribute index for the data types with more than N attributes");
+    println!("  --emit-rust-module=PATH  write all generated rust code into the single module PATH instead of separate files in gen/");
+    println!("  --emit-html=DIR   write a browsable html reference of the schema to DIR, with a page for each element type");
+    println!("  --emit-csv=PATH   write the availability of each element in each version to the csv file PATH");
+    println!("  --cache-file=PATH store the merged schema in PATH, and load it from there while the xsd files are unchanged");
+    println!("  --catalog=PATH    locate the xsd files with the catalog PATH, e.g. the autosar.soc of a standards checkout");
+    println!("  --extension=PATH  merge the vendor extension schema PATH on top of the standard versions");
+    println!("  --single=PATH     generate the specification from only the xsd file PATH; the xsd path is not needed");
+    println!("  --debug-dot=PATH  write the type graph of each xsd file to PATH as a Graphviz dot file, for debugging");
+    println!(
+        "  --allow-unknown   also load AUTOSAR_*.xsd files which are not part of the configuration"
+    );
+    println!("  --skip-missing    generate the specification even if some of the configured xsd files are missing");
+    println!("  --merge-duplicates  combine elements that appear twice in the same group with the same type");
+    println!("  --merge-policy=NAME  resolve merge conflicts with latest-wins (default), lenient or strict; lenient and strict write gen/merge_report.txt");
+    println!("  --profile         print the wall-clock time and the peak memory use of each processing phase");
+    println!("  --stats           print the written files and the sizes of the generated tables");
+}
+
+/// run the command line tool with the arguments of the process
+///
+/// Errors are returned as a message which is ready to be printed.
+pub fn run() -> Result<(), String> {
+    let args: Vec<String> = env::args().collect();
+
+    let Some(mut options) = parse_cmdline(&args) else {
+        usage(&args[0]);
+        std::process::exit(1);
+    };
+
+    if let Some(conflict) = options.validate_only_conflict() {
+        return Err(format!(
+            "Error: --validate-only can't be combined with {conflict}"
+        ));
+    }
+
+    if options.list_versions {
+        return list_versions(&options.xsd_path);
+    }
+
+    let mut profile = profile::Profile::new(options.profile);
+
+    let (mut xsd_config, mut input_files) = if let Some(single_path) = &options.single_file {
+        single_input_file(single_path)?
+    } else {
+        let path = Path::new(&options.xsd_path);
+        if !path.exists() {
+            println!("Error: path \"{}\" does not exist.", &options.xsd_path);
+            usage(&args[0]);
+            std::process::exit(2);
+        }
+        let (xsd_config, input_files, missing_versions) = configured_input_files(path, &options)?;
+        options.generator_options.missing_versions = missing_versions;
+        (xsd_config, input_files)
+    };
+
+    config::check_release_order(&xsd_config)?;
+
+    // the extension gets the next free version bit, and it is merged after all standard versions
+    let extension_version = if let Some(extension_path) = &options.extension_file {
+        if !extension_path.exists() {
+            return Err(format!(
+                "Error: extension schema \"{}\" does not exist",
+                extension_path.to_string_lossy()
+            ));
+        }
+        if xsd_config.len() >= 32 {
+            return Err(
+                "Error: the extension can't be loaded, because all 32 version bits are in use"
+                    .to_string(),
+            );
+        }
+        let filename = extension_path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        xsd_config.push(XsdFileInfo {
+            name: filename.clone().leak(),
+            ident: "Extension",
+            desc: format!("vendor extension {filename}").leak(),
+            platforms: Platforms::ClassicAndAdaptive,
+            namespace: AUTOSAR_NAMESPACE,
+        });
+        input_files.push((xsd_config.len() - 1, extension_path.clone()));
+        Some(xsd_config.len() - 1)
+    } else {
+        None
+    };
+    options.generator_options.extension_version = extension_version;
+
+    options.generator_options.input_files = input_files
+        .iter()
+        .map(|(_, filepath)| filepath.clone())
+        .collect();
+
+    let schema_hash = schema_hash(&input_files)?;
+
+    // the dot output, the flatten log, the merge verifications and the validation are part of loading the xsd files,
+    // so the cache can't be used; the cache also only contains schemas that were merged with the default merge policy
+    let cached_schema = options
+        .cache_file
+        .as_deref()
+        .filter(|_| {
+            !options.validate_only
+                && options.debug_dot_file.is_none()
+                && !options.verbose_flatten
+                && !options.verify_merge_order
+                && !options.verify_merge
+                && options.merge_policy == merge::MergePolicyKind::default()
+        })
+        .and_then(|cache_path| {
+            profile.time("cache", || {
+                cache::load(cache_path, &input_files, &schema_hash, &mut xsd_config)
+            })
+        });
+    let (mut autosar_schema, merge_report, load_warning_count) = if let Some(schema) = cached_schema
+    {
+        println!("loaded the merged schema from the cache");
+        (schema, None, 0)
+    } else {
+        let (schema, merge_report, warning_count) = load_schema(
+            &input_files,
+            &mut xsd_config,
+            extension_version,
+            &options,
+            &mut profile,
+        )?;
+        if let Some(cache_path) = options.cache_file.as_ref().filter(|_| {
+            !options.validate_only && options.merge_policy == merge::MergePolicyKind::default()
+        }) {
+            profile.time("cache", || {
+                cache::store(cache_path, &input_files, &schema_hash, &xsd_config, &schema)
+            })?;
+        }
+        (schema, Some(merge_report), warning_count)
+    };
+
+    // this is not part of load_schema, so that the cached schema does not depend on the option
+    if options.merge_duplicates {
+        let merged_count = merge::merge_duplicate_elements(&mut autosar_schema);
+        println!("merged {merged_count} duplicate elements");
+        if merged_count > 0 {
+            dedup::dedup_types(&mut autosar_schema);
+            sanity_check(&autosar_schema);
+        }
+    }
+
+    for (bit_idx, exclusive_count, total_count) in autosar_schema.element_count_by_version() {
+        let desc = xsd_config
+            .get(bit_idx)
+            .map_or("unknown version", |info| info.desc);
+        println!(
+            "{desc}: {total_count} element types, {exclusive_count} of them only in this version"
+        );
+    }
+
+    if options.check_regexes {
+        profile.time("check regexes", || {
+            generator::check_regexes(&autosar_schema)
+        })?;
+    } else if options.verify {
+        profile.time("verify", || verify::verify(&autosar_schema))?;
+    } else if let Some(typename) = &options.find_paths {
+        print_paths_to(&autosar_schema, typename)?;
+    } else if options.validate_only {
+        // the problems and the warnings of loading the schema were already printed
+        let problem_count = find_sanity_problems(&autosar_schema).len();
+        let pattern_warning_count = verify::verify_patterns(&autosar_schema)?;
+        let unmatched_ref_targets = generator::unmatched_ref_targets(&autosar_schema);
+        let warning_count =
+            load_warning_count + pattern_warning_count + unmatched_ref_targets.len();
+        if problem_count > 0 || warning_count > 0 {
+            return Err(format!(
+                "Error: the validation failed, the merged schema has {problem_count} problems and there were {warning_count} warnings"
+            ));
+        }
+        println!("validation successful, no code was generated");
+    } else {
+        let report = profile.time("generate", || {
+            generator::generate(
+                &xsd_config,
+                &autosar_schema,
+                &options.generator_options,
+                &schema_hash,
+            )
+        })?;
+        if options.stats {
+            print_generator_report(&report);
+        }
+        // latest-wins keeps the merge base like the merge always did, so only the other policies have a report
+        if let Some(merge_report) = merge_report {
+            if options.merge_policy != merge::MergePolicyKind::default() {
+                write_merge_report(options.merge_policy, merge_report)?;
+            }
+        }
+    }
+
+    println!("versions with data:");
+    for (index, _) in &input_files {
+        println!("    {}", xsd_config[*index].desc);
+    }
+    if !options.generator_options.missing_versions.is_empty() {
+        println!("skipped versions without an xsd file:");
+        for index in &options.generator_options.missing_versions {
+            println!("    {}", xsd_config[*index].desc);
+        }
+    }
+    profile.print();
+    Ok(())
+}
+
+// print the statistics of the generator, for --stats
+fn print_generator_report(report: &generator::GeneratorReport) {
+    println!("written files:");
+    for path in &report.files_written {
+        let replaced = if report.overwritten_files.contains(path) {
+            " (replaced)"
+        } else {
+            ""
+        };
+        println!("    {}{replaced}", path.display());
+    }
+    println!(
+        "{} files, {} bytes; {} existing files were replaced",
+        report.files_written.len(),
+        report.total_bytes,
+        report.overwritten_files.len()
+    );
+    println!(
+        "{} element types, {} element definitions, {} character types, {} attribute names, {} enum items",
+        report.element_type_count,
+        report.element_definition_count,
+        report.character_type_count,
+        report.attribute_name_count,
+        report.enum_item_count
+    );
+}
+
+// print the paths from AR:AUTOSAR to the element type `typename`, for --find-paths
+fn print_paths_to(autosar_schema: &AutosarDataTypes, typename: &str) -> Result<(), String> {
+    if autosar_schema.element_type(typename).is_none() {
+        return Err(format!("Error: there is no element type {typename}"));
+    }
+    let (paths, truncated) = autosar_schema.find_all_paths_to(typename, MAX_PATHS);
+    println!(
+        "{} paths from AR:AUTOSAR to {typename}, with at most {MAX_PATH_LENGTH} types:",
+        paths.len()
+    );
+    for path in &paths {
+        println!("    {}", path.join(" -> "));
+    }
+    if truncated {
+        println!("the search was stopped after {MAX_PATHS} paths, there are more paths");
+    }
+    Ok(())
+}
+
+/// build the list of xsd files from the configuration of the xsd directory
+///
+/// Returns the configuration, the index and path of each xsd file that was found, and the indices of
+/// the configured versions whose xsd file is missing.
+#[allow(clippy::type_complexity)]
+fn configured_input_files(
+    path: &Path,
+    options: &CmdlineOptions,
+) -> Result<(Vec<XsdFileInfo>, Vec<(usize, PathBuf)>, Vec<usize>), String> {
+    // a config.toml in the xsd directory replaces the built-in list of xsd files
+    let mut xsd_config: Vec<XsdFileInfo> =
+        config::load_xsd_config(path)?.unwrap_or_else(|| XSD_CONFIG.to_vec());
+
+    // new releases need a configuration entry; until it exists, they can only be used with --allow-unknown
+    for filename in config::find_unknown_xsd_files(path, &xsd_config)? {
+        if !options.allow_unknown {
+            println!("Warning: {filename} is not configured and will be ignored; use --allow-unknown to load it");
+        } else if xsd_config.len() >= 32 {
+            println!("Warning: {filename} is ignored, because all 32 version bits are in use");
+        } else if let Some(xsd_file_info) = config::unknown_xsd_file_info(&filename) {
+            println!(
+                "Warning: {filename} is not configured, it is loaded as {}",
+                xsd_file_info.ident
+            );
+            xsd_config.push(xsd_file_info);
+        } else {
+            println!("Warning: {filename} is ignored, because no identifier can be derived from its name");
+        }
+    }
+
+    let catalog = if let Some(catalog_path) = &options.catalog_file {
+        let catalog = catalog::load_catalog(catalog_path)?;
+        for entry in &catalog {
+            if !entry.filepath.exists() {
+                println!(
+                    "Warning: the catalog entry \"{}\" references the file \"{}\", which does not exist",
+                    entry.identifier,
+                    entry.filepath.to_string_lossy()
+                );
+            }
+        }
+        catalog
+    } else {
+        Vec::new()
+    };
+
+    let mut input_files = Vec::new();
+    let mut missing_versions = Vec::new();
+    for (index, xsd_file_info) in xsd_config.iter().enumerate() {
+        let filepath = path.join(Path::new(xsd_file_info.name));
+        let catalog_entry = catalog::find_entry(&catalog, xsd_file_info.name);
+        if filepath.exists() {
+            input_files.push((index, filepath));
+        } else if let Some(entry) = catalog_entry {
+            if entry.filepath.exists() {
+                println!(
+                    "using \"{}\" for {} from the catalog",
+                    entry.filepath.to_string_lossy(),
+                    xsd_file_info.name
+                );
+                input_files.push((index, entry.filepath.clone()));
+            } else {
+                println!(
+                    "Error: XSD file \"{}\" for the standard {} was not found; the catalog entry \"{}\" references the missing file \"{}\"",
+                    filepath.to_string_lossy(),
+                    xsd_file_info.desc,
+                    entry.identifier,
+                    entry.filepath.to_string_lossy()
+                );
+                missing_versions.push(index);
+            }
+        } else {
+            println!(
+                "Error: XSD file \"{}\" for the standard {} was not found",
+                filepath.to_string_lossy(),
+                xsd_file_info.desc
+            );
+            missing_versions.push(index);
+        }
+    }
+    // without the xsd file, the generated AutosarVersion would contain a version that is not valid anywhere
+    if !missing_versions.is_empty() && !options.skip_missing {
+        return Err(format!(
+            "Error: {} of the configured xsd files are missing; use --skip-missing to generate the specification without them",
+            missing_versions.len()
+        ));
+    }
+
+    Ok((xsd_config, input_files, missing_versions))
+}
+
+/// build the configuration for --single, where only one xsd file is loaded
+///
+/// The version of the file is taken from the config.toml next to it or from the built-in configuration,
+/// and otherwise derived from the file name like for --allow-unknown.
+#[allow(clippy::type_complexity)]
+fn single_input_file(
+    single_path: &Path,
+) -> Result<(Vec<XsdFileInfo>, Vec<(usize, PathBuf)>), String> {
+    if !single_path.is_file() {
+        return Err(format!(
+            "Error: XSD file \"{}\" was not found",
+            single_path.to_string_lossy()
+        ));
+    }
+    let filename = single_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let dir = single_path.parent().unwrap_or(Path::new(""));
+    let xsd_config = config::load_xsd_config(dir)?.unwrap_or_else(|| XSD_CONFIG.to_vec());
+    let xsd_file_info = xsd_config
+        .iter()
+        .find(|info| info.name == filename)
+        .copied()
+        .or_else(|| config::unknown_xsd_file_info(&filename))
+        .ok_or_else(|| {
+            format!("Error: the version of {filename} is not configured, and no identifier can be derived from its name")
+        })?;
+    Ok((vec![xsd_file_info], vec![(0, single_path.to_path_buf())]))
+}
+
+// print the xsd files of the config.toml in xsd_path, or of the built-in XSD_CONFIG
+fn list_versions(xsd_path: &str) -> Result<(), String> {
+    let xsd_config = if xsd_path.is_empty() {
+        None
+    } else {
+        config::load_xsd_config(Path::new(xsd_path))?
+    };
+    for xsd_file_info in xsd_config.as_deref().unwrap_or(&XSD_CONFIG) {
+        println!(
+            "{}\t{}\t{}",
+            xsd_file_info.name, xsd_file_info.ident, xsd_file_info.desc
+        );
+    }
+    Ok(())
+}
+
+/// the input xsd files together with all files that they include or redefine, sorted by their file names
+fn all_input_files(input_files: &[(usize, PathBuf)]) -> Result<Vec<PathBuf>, String> {
+    let mut canonical_paths = HashSet::new();
+    let mut filepaths = Vec::new();
+    for (_, input_file) in input_files {
+        for filepath in xsd::schema_files(input_file)? {
+            let canonical_path = filepath
+                .canonicalize()
+                .map_err(|err| format!("Error: could not open {}: {err}", filepath.display()))?;
+            if canonical_paths.insert(canonical_path) {
+                filepaths.push(filepath);
+            }
+        }
+    }
+    filepaths.sort_by_key(|filepath| filepath.file_name().map(ToOwned::to_owned));
+    Ok(filepaths)
+}
+
+/// calculate the SHA-256 of the contents of all input xsd files and of the files that they include or redefine,
+/// concatenated in the order of their file names
+///
+/// The hash is also calculated if the merged schema is loaded from the cache, which uses it to check that the input
+/// files did not change.
+fn schema_hash(input_files: &[(usize, PathBuf)]) -> Result<[u8; 32], String> {
+    let mut hasher = Sha256::new();
+    for filepath in all_input_files(input_files)? {
+        let content = std::fs::read(&filepath)
+            .map_err(|err| format!("Error: failed to read {}: {err}", filepath.display()))?;
+        hasher.update(&content);
+    }
+    Ok(hasher.finalize().into())
+}
+
+/// load and flatten the input xsd files, then merge them into a single schema
+///
+/// `input_files` contains the index of each xsd file in `xsd_config` together with its path.
+/// If `extension_version` is set, the xsd file with this index is a vendor extension, which is merged after all
+/// standard versions. Conflicts between the versions are resolved by `merge_policy`; the descriptions of the
+/// conflicts are returned together with the merged schema. The time of each step is recorded in `profile`.
+fn load_schema(
+    input_files: &[(usize, PathBuf)],
+    xsd_config: &mut [XsdFileInfo],
+    extension_version: Option<usize>,
+    options: &CmdlineOptions,
+    profile: &mut profile::Profile,
+) -> Result<(AutosarDataTypes, Vec<String>, usize), String> {
+    let merge_policy = options.merge_policy;
+    // the warnings fail the validation of --validate-only
+    let mut warning_count = 0;
+    let mut autosar_schema_version = Vec::new();
+    let mut extension_schema = None;
+    // the extension is loaded last; it refers to the types of the newest standard version
+    let mut newest_standard_xsd = None;
+    for (index, filepath) in input_files {
+        let xsd_file_info = &mut xsd_config[*index];
+        println!("loading {}", xsd_file_info.name);
+        let xsd = if extension_version == Some(*index) {
+            let standard = newest_standard_xsd.as_ref().ok_or_else(|| {
+                "Error: the extension can't be loaded without a standard version".to_string()
+            })?;
+            profile.time("parse", || {
+                Xsd::load_extension(filepath, 1 << index, standard)
+            })?
+        } else {
+            profile.time("parse", || Xsd::load(filepath, 1 << index))?
+        };
+        println!(
+            "    {} types, {} groups, {} attribute groups, {} root elements",
+            xsd.type_count(),
+            xsd.group_count(),
+            xsd.attribute_group_count(),
+            xsd.root_element_count()
+        );
+        if let Some(dot_path) = &options.debug_dot_file {
+            // each xsd file gets its own dot file if there are several
+            let dot_path = if input_files.len() > 1 {
+                versioned_path(dot_path, xsd_file_info.ident)
+            } else {
+                dot_path.to_path_buf()
+            };
+            write_dot_file(&xsd, &dot_path)?;
+        }
+        if let Some(namespace) = &xsd.target_namespace {
+            // the namespace of an extension is not known in advance
+            if namespace != xsd_file_info.namespace && extension_version != Some(*index) {
+                println!(
+                    "Warning: the targetNamespace of {} is \"{namespace}\", but \"{}\" was configured",
+                    xsd_file_info.name, xsd_file_info.namespace
+                );
+                warning_count += 1;
+                // the xsd file is authoritative; leaking is fine because the config lives until the program exits
+                xsd_file_info.namespace = namespace.clone().leak();
+            }
+        }
+        let flat_schema = profile.time("flatten", || {
+            if options.verbose_flatten {
+                flatten::flatten_schema_verbose(&xsd, &mut std::io::stdout().lock())
+            } else {
+                flatten::flatten_schema(&xsd)
+            }
+        })?;
+        if extension_version == Some(*index) {
+            extension_schema = Some(flat_schema);
+        } else {
+            autosar_schema_version.push((xsd_file_info.desc, flat_schema));
+            newest_standard_xsd = Some(xsd);
+        }
+    }
+
+    // the newest version is the merge base, all older versions are merged into it
+    let (version_names, schemas): (Vec<&str>, Vec<AutosarDataTypes>) =
+        autosar_schema_version.into_iter().rev().unzip();
+    if let Some((base_name, older_names)) = version_names.split_first() {
+        println!("merge base: {base_name}");
+        for (idx, input_name) in older_names.iter().enumerate() {
+            // compare each version to the next newer version
+            let diff = merge::diff(&schemas[idx], &schemas[idx + 1]);
+            let kinds = [diff.element_types, diff.character_types, diff.group_types];
+            let added: usize = kinds.iter().map(|kind| kind.added.len()).sum();
+            let removed: usize = kinds.iter().map(|kind| kind.removed.len()).sum();
+            println!(
+                "merging: {input_name} ({added} types added and {removed} types removed in {})",
+                version_names[idx]
+            );
+            // AUTOSAR rarely removes anything, so every type that disappears is reported
+            for missing in merge::verify_superset(&schemas[idx], &schemas[idx + 1]) {
+                println!(
+                    "Warning: the {missing} of {input_name} is missing in {}",
+                    version_names[idx]
+                );
+                warning_count += 1;
+            }
+        }
+    }
+    let mut merge_report = Vec::new();
+    let mut policy = merge_policy.build(Box::new(|message| merge_report.push(message)));
+    let mut autosar_schema =
+        profile.time("merge", || merge::merge_all(&schemas, policy.as_mut()))?;
+
+    if options.verify_merge_order {
+        // merging from oldest to newest must give the same order of the sub elements;
+        // the conflicts of this merge are not part of the merge report
+        let reversed_schemas: Vec<AutosarDataTypes> = schemas.iter().rev().cloned().collect();
+        let mut reversed_policy = merge_policy.build(Box::new(|_| {}));
+        let reversed_schema = merge::merge_all(&reversed_schemas, reversed_policy.as_mut())?;
+        let differences = merge::compare_group_order(&autosar_schema, &reversed_schema);
+        println!(
+            "merge order verification: {} groups are ordered differently when merging from oldest to newest",
+            differences.len()
+        );
+        for difference in differences {
+            println!("    {difference}");
+        }
+    }
+
+    if let (Some(extension_idx), Some(extension_schema)) = (extension_version, &extension_schema) {
+        let conflicts = merge::find_conflicts(&autosar_schema, extension_schema);
+        if !conflicts.is_empty() {
+            return Err(format!(
+                "Error: the extension {} redefines standard types:\n    {}",
+                xsd_config[extension_idx].name,
+                conflicts.join("\n    ")
+            ));
+        }
+        println!("merging extension: {}", xsd_config[extension_idx].name);
+        autosar_schema = profile.time("merge", || {
+            autosar_schema.merge_with(extension_schema, policy.as_mut())
+        })?;
+        // everything in the newest standard version is also valid in the extension
+        if let Some((newest_idx, _)) = input_files
+            .iter()
+            .rev()
+            .find(|(index, _)| *index != extension_idx)
+        {
+            merge::extend_version(&mut autosar_schema, 1 << newest_idx, 1 << extension_idx);
+        }
+    }
+
+    drop(policy);
+
+    // this must run before the deduplication, which renames types in the merged schema
+    if options.verify_merge {
+        let extension = extension_version
+            .zip(extension_schema.as_ref())
+            .map(|(extension_idx, schema)| (xsd_config[extension_idx].desc, schema));
+        for (version_name, schema) in version_names.iter().copied().zip(&schemas).chain(extension) {
+            let missing = verify::verify_merge(&autosar_schema, schema);
+            println!(
+                "merge verification: {} items of {version_name} are missing in the merged schema",
+                missing.len()
+            );
+            for item in missing {
+                println!("    {item}");
+            }
+        }
+    }
+
+    profile.time("dedup", || dedup::dedup_types(&mut autosar_schema));
+    sanity_check(&autosar_schema);
+
+    Ok((autosar_schema, merge_report, warning_count))
+}
+
+// write gen/merge_report.txt, which lists the merge conflicts together with their resolution
+fn write_merge_report(
+    merge_policy: merge::MergePolicyKind,
+    mut merge_report: Vec<String>,
+) -> Result<(), String> {
+    // the same conflict is usually found again in each older version
+    merge_report.sort();
+    merge_report.dedup();
+    let mut report = format!(
+        "# merge conflicts, resolved with the merge policy {}\n# {} conflicts\n\n",
+        merge_policy.name(),
+        merge_report.len()
+    );
+    for message in &merge_report {
+        report.push_str(message);
+        report.push('\n');
+    }
+
+    std::fs::write("gen/merge_report.txt", report)
+        .map_err(|err| format!("Error: could not write gen/merge_report.txt: {err}"))
+}
+
+// write the unflattened type graph of the xsd file to dot_path
+fn write_dot_file(xsd: &Xsd, dot_path: &Path) -> Result<(), String> {
+    let file = std::fs::File::create(dot_path)
+        .map_err(|err| format!("Error: failed to create {}: {err}", dot_path.display()))?;
+    let mut writer = std::io::BufWriter::new(file);
+    flatten::flatten_to_dot(xsd, &mut writer)
+        .and_then(|()| std::io::Write::flush(&mut writer))
+        .map_err(|err| format!("Error: failed to write {}: {err}", dot_path.display()))
+}
+
+// insert the version ident before the extension, e.g. "types.dot" -> "types.Autosar_00052.dot"
+fn versioned_path(path: &Path, ident: &str) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => {
+            path.with_file_name(format!("{stem}.{ident}.{}", extension.to_string_lossy()))
+        }
+        None => path.with_file_name(format!("{stem}.{ident}")),
+    }
+}
+
+/// sanity check: see if all references are still ok after merging / deduplication
+fn sanity_check(autosar_types: &AutosarDataTypes) {
+    for problem in find_sanity_problems(autosar_types) {
+        println!("sanity check failed - {problem}");
+    }
+}
+
+// the problems that are reported by `sanity_check`
+fn find_sanity_problems(autosar_types: &AutosarDataTypes) -> Vec<String> {
+    let mut problems = Vec::new();
+    let names = &autosar_types.names;
+    for (groupname, group) in &autosar_types.group_types {
+        let groupname = names.resolve(*groupname);
+        check_duplicate_elements(autosar_types, groupname, group, &mut problems);
+        for item in group.items() {
+            match item {
+                ElementCollectionItem::Element(elem) => {
+                    if !autosar_types.element_types.contains_key(&elem.typeref) {
+                        problems.push(format!(
+                            "in group [{groupname}] element <{:#?}> references non-existent type [{}]",
+                            elem.resolve(names),
+                            names.resolve(elem.typeref)
+                        ));
+                    }
+                }
+                ElementCollectionItem::GroupRef(gref) => {
+                    if !autosar_types.group_types.contains_key(gref) {
+                        problems.push(format!(
+                            "in group [{groupname}] group ref {} has no target",
+                            names.resolve(*gref)
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    for (typename, elemcontent) in &autosar_types.element_types {
+        let typename = names.resolve(*typename);
+        if let Some(group_name) = elemcontent.group_ref() {
+            if !autosar_types.group_types.contains_key(&group_name) {
+                problems.push(format!(
+                    "type [{typename}] references non-existent group [{}]",
+                    names.resolve(group_name)
+                ));
+            }
+        }
+        for attr in elemcontent.attributes() {
+            if !autosar_types.character_types.contains_key(&attr.attr_type) {
+                problems.push(format!(
+                    "in type [{typename}] attribute {} references non-existent type [{}]",
+                    attr.name,
+                    names.resolve(attr.attr_type)
+                ));
+            }
+        }
+    }
+    problems
+}
+
+// report elements with the same name in a group, including the elements of the groups it references directly
+//
+// Elements whose version masks don't overlap are not reported: the merge keeps an element twice if its
+// type changed between versions, and in each version only one of them is valid.
+fn check_duplicate_elements(
+    autosar_types: &AutosarDataTypes,
+    groupname: &str,
+    group: &ElementCollection,
+    problems: &mut Vec<String>,
+) {
+    let names = &autosar_types.names;
+    let mut elements: Vec<(&Element, Option<&str>)> = Vec::new();
+    for item in group.items() {
+        match item {
+            ElementCollectionItem::Element(elem) => elements.push((elem, None)),
+            ElementCollectionItem::GroupRef(gref) => {
+                if let Some(ref_group) = autosar_types.group_types.get(gref) {
+                    for ref_item in ref_group.items() {
+                        if let ElementCollectionItem::Element(elem) = ref_item {
+                            elements.push((elem, Some(names.resolve(*gref))));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let describe = |elem: &Element, source: Option<&str>| {
+        let via = source.map_or(String::new(), |gref| format!(" via group ref {gref}"));
+        format!(
+            "type [{}] in versions {:#x}{via}",
+            names.resolve(elem.typeref),
+            elem.version_info
+        )
+    };
+    for (idx, (elem, source)) in elements.iter().enumerate() {
+        if let Some((other_elem, other_source)) =
+            elements[idx + 1..].iter().find(|(other_elem, _)| {
+                other_elem.name == elem.name && other_elem.version_info & elem.version_info != 0
+            })
+        {
+            problems.push(format!(
+                "in group [{groupname}] the element {} appears twice: {} and {}",
+                elem.name,
+                describe(elem, *source),
+                describe(other_elem, *other_source)
+            ));
+        }
+    }
+}
+
+// the class of the meta model is only used for documentation, so element types that only differ in their class are
+// equal; when they are deduplicated, the remaining type keeps its own class
+impl<N: PartialEq> PartialEq for ElementDataType<N> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                ElementDataType::Elements {
+                    group_ref,
+                    attributes,
+                    xsd_typenames,
+                    mm_class: _,
+                },
+                ElementDataType::Elements {
+                    group_ref: group_ref_other,
+                    attributes: attributes_other,
+                    xsd_typenames: xsd_typenames_other,
+                    mm_class: _,
+                },
+            ) => {
+                group_ref == group_ref_other
+                    && attributes == attributes_other
+                    && xsd_typenames == xsd_typenames_other
+            }
+            (
+                ElementDataType::Characters {
+                    attributes,
+                    basetype,
+                },
+                ElementDataType::Characters {
+                    attributes: attributes_other,
+                    basetype: basetype_other,
+                },
+            ) => attributes == attributes_other && basetype == basetype_other,
+            (
+                ElementDataType::Mixed {
+                    group_ref,
+                    attributes,
+                    basetype,
+                    mm_class: _,
+                },
+                ElementDataType::Mixed {
+                    group_ref: group_ref_other,
+                    attributes: attributes_other,
+                    basetype: basetype_other,
+                    mm_class: _,
+                },
+            ) => {
+                group_ref == group_ref_other
+                    && attributes == attributes_other
+                    && basetype == basetype_other
+            }
+            _ => false,
+        }
+    }
+}
+
+impl ElementDataType {
+    // a copy in which the type names are resolved
+    fn resolve(&self, names: &Interner) -> ElementDataType<String> {
+        let resolve_attributes = |attributes: &Vec<Attribute>| {
+            attributes.iter().map(|attr| attr.resolve(names)).collect()
+        };
+        match self {
+            ElementDataType::Elements {
+                group_ref,
+                attributes,
+                xsd_typenames,
+                mm_class,
+            } => ElementDataType::Elements {
+                group_ref: names.resolve(*group_ref).to_string(),
+                attributes: resolve_attributes(attributes),
+                xsd_typenames: xsd_typenames.clone(),
+                mm_class: mm_class.clone(),
+            },
+            ElementDataType::Characters {
+                attributes,
+                basetype,
+            } => ElementDataType::Characters {
+                attributes: resolve_attributes(attributes),
+                basetype: names.resolve(*basetype).to_string(),
+            },
+            ElementDataType::Mixed {
+                group_ref,
+                attributes,
+                basetype,
+                mm_class,
+            } => ElementDataType::Mixed {
+                group_ref: names.resolve(*group_ref).to_string(),
+                attributes: resolve_attributes(attributes),
+                basetype: names.resolve(*basetype).to_string(),
+                mm_class: mm_class.clone(),
+            },
+        }
+    }
+
+    fn group_ref(&self) -> Option<Symbol> {
+        match self {
+            ElementDataType::Elements { group_ref, .. }
+            | ElementDataType::Mixed { group_ref, .. } => Some(*group_ref),
+            ElementDataType::Characters { .. } => None,
+        }
+    }
+
+    fn attributes(&self) -> &Vec<Attribute> {
+        match self {
+            ElementDataType::Elements { attributes, .. }
+            | ElementDataType::Characters { attributes, .. }
+            | ElementDataType::Mixed { attributes, .. } => attributes,
+        }
+    }
+
+    fn basetype(&self) -> Option<Symbol> {
+        match self {
+            ElementDataType::Characters { basetype, .. }
+            | ElementDataType::Mixed { basetype, .. } => Some(*basetype),
+            ElementDataType::Elements { .. } => None,
+        }
+    }
+}
+
+impl<N> ElementCollection<N> {
+    fn items(&self) -> &Vec<ElementCollectionItem<N>> {
+        match self {
+            ElementCollection::Choice { sub_elements, .. }
+            | ElementCollection::Sequence { sub_elements, .. } => sub_elements,
+        }
+    }
+}
+
+impl ElementCollection {
+    // a copy in which the type names are resolved, for the generator
+    fn resolve(&self, names: &Interner) -> ElementCollection<String> {
+        let resolve_items = |sub_elements: &Vec<ElementCollectionItem>| {
+            sub_elements
+                .iter()
+                .map(|item| item.resolve(names))
+                .collect()
+        };
+        match self {
+            ElementCollection::Choice {
+                name,
+                sub_elements,
+                amount,
+            } => ElementCollection::Choice {
+                name: name.clone(),
+                sub_elements: resolve_items(sub_elements),
+                amount: *amount,
+            },
+            ElementCollection::Sequence { name, sub_elements } => ElementCollection::Sequence {
+                name: name.clone(),
+                sub_elements: resolve_items(sub_elements),
+            },
+        }
+    }
+}
+
+impl ElementCollectionItem {
+    // the name of the element, or the name of the referenced group
+    fn name<'a>(&'a self, names: &'a Interner) -> &'a str {
+        match self {
+            ElementCollectionItem::Element(Element { name, .. }) => name,
+            ElementCollectionItem::GroupRef(group_ref) => names.resolve(*group_ref),
+        }
+    }
+
+    fn resolve(&self, names: &Interner) -> ElementCollectionItem<String> {
+        match self {
+            ElementCollectionItem::Element(elem) => {
+                ElementCollectionItem::Element(elem.resolve(names))
+            }
+            ElementCollectionItem::GroupRef(group_ref) => {
+                ElementCollectionItem::GroupRef(names.resolve(*group_ref).to_string())
+            }
+        }
+    }
+}
+
+impl Element {
+    fn resolve(&self, names: &Interner) -> Element<String> {
+        Element {
+            name: self.name.clone(),
+            typeref: names.resolve(self.typeref).to_string(),
+            amount: self.amount,
+            version_info: self.version_info,
+            splittable_ver: self.splittable_ver,
+            ordered: self.ordered,
+            restrict_std: self.restrict_std,
+            docstring: self.docstring.clone(),
+        }
+    }
+}
+
+impl Attribute {
+    fn resolve(&self, names: &Interner) -> Attribute<String> {
+        Attribute {
+            name: self.name.clone(),
+            attr_type: names.resolve(self.attr_type).to_string(),
+            required: self.required,
+            version_info: self.version_info,
+            docstring: self.docstring.clone(),
+        }
+    }
+}
+
+impl PartialEq for AutosarDataTypes {
+    fn eq(&self, other: &Self) -> bool {
+        let names = (&self.names, &other.names);
+        same_types(
+            (&self.character_types, &other.character_types),
+            names,
+            |chartype, chartype_other| chartype == chartype_other,
+        ) && same_types(
+            (&self.element_types, &other.element_types),
+            names,
+            |elemtype, elemtype_other| {
+                elemtype.resolve(&self.names) == elemtype_other.resolve(&other.names)
+            },
+        ) && same_types(
+            (&self.group_types, &other.group_types),
+            names,
+            |group, group_other| group.resolve(&self.names) == group_other.resolve(&other.names),
+        )
+    }
+}
+
+// both maps contain the same type names, and the types with the same name are equal
+fn same_types<T>(
+    (types, types_other): (&FxHashMap<Symbol, T>, &FxHashMap<Symbol, T>),
+    (names, names_other): (&Interner, &Interner),
+    is_equal: impl Fn(&T, &T) -> bool,
+) -> bool {
+    types.len() == types_other.len()
+        && types.iter().all(|(typename, value)| {
+            names_other
+                .get(names.resolve(*typename))
+                .and_then(|typename_other| types_other.get(&typename_other))
+                .is_some_and(|value_other| is_equal(value, value_other))
+        })
+}
+
+// the derived Debug output would contain the full schema; instead only print the sizes and a few type names
+impl std::fmt::Debug for AutosarDataTypes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut sample: Vec<&str> = self
+            .element_types
+            .keys()
+            .map(|typename| self.names.resolve(*typename))
+            .collect();
+        sample.sort();
+        sample.truncate(5);
+        f.debug_struct("AutosarDataTypes")
+            .field("element_types", &self.element_types.len())
+            .field("character_types", &self.character_types.len())
+            .field("group_types", &self.group_types.len())
+            .field("sample", &sample)
+            .finish()
+    }
+}
+
+impl AutosarDataTypes {
+    fn new() -> Self {
+        let mut adt = Self {
+            names: Interner::default(),
+            character_types: FxHashMap::default(),
+            element_types: FxHashMap::default(),
+            group_types: FxHashMap::default(),
+        };
+
+        adt.character_types.insert(
+            adt.names.intern("xsd:string"),
+            CharacterDataType::String {
+                max_length: None,
+                preserve_whitespace: false,
+            },
+        );
+        adt.character_types.insert(
+            adt.names.intern("xsd:NMTOKEN"),
+            CharacterDataType::String {
+                max_length: None,
+                preserve_whitespace: false,
+            },
+        );
+        adt.character_types.insert(
+            adt.names.intern("xsd:NMTOKENS"),
+            CharacterDataType::String {
+                max_length: None,
+                preserve_whitespace: false,
+            },
+        );
+        adt.character_types.insert(
+            adt.names.intern("xsd:unsignedInt"),
+            CharacterDataType::UnsignedInteger,
+        );
+        adt.character_types
+            .insert(adt.names.intern("xsd:double"), CharacterDataType::Double);
+        adt.character_types.insert(
+            adt.names.intern("xsd:dateTime"),
+            CharacterDataType::DateTime { format: None },
+        );
+
+        adt
+    }
+
+    // the element type with the name `typename`
+    fn element_type(&self, typename: &str) -> Option<&ElementDataType> {
+        self.element_types.get(&self.names.get(typename)?)
+    }
+
+    // the character type with the name `typename`
+    fn character_type(&self, typename: &str) -> Option<&CharacterDataType> {
+        self.character_types.get(&self.names.get(typename)?)
+    }
+
+    // the group with the name `groupname`
+    fn group_type(&self, groupname: &str) -> Option<&ElementCollection> {
+        self.group_types.get(&self.names.get(groupname)?)
+    }
+
+    /// merge `other` into this schema and return the result
+    ///
+    /// This is a consuming wrapper around `merge::merge`, so that merges can be chained:
+    /// `let merged = a.merge_with(&b, policy)?.merge_with(&c, policy)?;`
+    pub fn merge_with(
+        mut self,
+        other: &AutosarDataTypes,
+        policy: &mut dyn merge::MergePolicy,
+    ) -> Result<Self, String> {
+        merge::merge(&mut self, other, policy)?;
+        Ok(self)
+    }
+
+    /// find all paths from the root type AR:AUTOSAR to the element type `target_typename`, for debugging the schema
+    ///
+    /// Each path is the list of element types from AR:AUTOSAR to the target; the groups in between are traversed,
+    /// but they are not part of the path. A type appears at most once in each path, and the paths are limited to
+    /// `MAX_PATH_LENGTH` types. The number of paths grows very quickly in the highly connected parts of the schema,
+    /// so the search stops after `max_paths` paths; the returned flag is true if there may be more paths.
+    pub fn find_all_paths_to(
+        &self,
+        target_typename: &str,
+        max_paths: usize,
+    ) -> (Vec<Vec<String>>, bool) {
+        let sub_types = self.sub_element_types();
+
+        // only the types from which the target can be reached are expanded, which are found by walking backwards
+        let mut parent_types: FxHashMap<&str, Vec<&str>> = FxHashMap::default();
+        for (typename, types) in &sub_types {
+            for sub_type in types {
+                parent_types.entry(sub_type).or_default().push(typename);
+            }
+        }
+        let mut reaches_target = HashSet::from([target_typename]);
+        let mut queue = VecDeque::from([target_typename]);
+        while let Some(typename) = queue.pop_front() {
+            for parent in parent_types.get(typename).into_iter().flatten() {
+                if reaches_target.insert(parent) {
+                    queue.push_back(parent);
+                }
+            }
+        }
+
+        let mut search = PathSearch {
+            target_typename,
+            sub_types: &sub_types,
+            reaches_target: &reaches_target,
+            max_paths,
+            paths: Vec::new(),
+            truncated: false,
+        };
+        if reaches_target.contains("AR:AUTOSAR") {
+            search.collect_paths(&mut vec!["AR:AUTOSAR"]);
+        }
+        (search.paths, search.truncated)
+    }
+
+    // the element types of the sub elements of each element type, including those in referenced groups
+    fn sub_element_types(&self) -> FxHashMap<&str, BTreeSet<&str>> {
+        let mut sub_types = FxHashMap::default();
+        for (typename, elemtype) in &self.element_types {
+            let Some(group_ref) = elemtype.group_ref() else {
+                continue;
+            };
+            let mut types = BTreeSet::new();
+            let mut visited_groups = HashSet::new();
+            let mut pending_groups = vec![group_ref];
+            while let Some(groupname) = pending_groups.pop() {
+                if !visited_groups.insert(groupname) {
+                    continue;
+                }
+                let Some(group) = self.group_types.get(&groupname) else {
+                    continue;
+                };
+                for item in group.items() {
+                    match item {
+                        ElementCollectionItem::Element(elem) => {
+                            types.insert(self.names.resolve(elem.typeref));
+                        }
+                        ElementCollectionItem::GroupRef(gref) => pending_groups.push(*gref),
+                    }
+                }
+            }
+            sub_types.insert(self.names.resolve(*typename), types);
+        }
+        sub_types
+    }
+
+    /// combined version mask of each element type, from all elements that use the type
+    pub(crate) fn element_type_versions(&self) -> FxHashMap<Symbol, usize> {
+        let mut type_versions: FxHashMap<Symbol, usize> = FxHashMap::default();
+        for group in self.group_types.values() {
+            for item in group.items() {
+                if let ElementCollectionItem::Element(elem) = item {
+                    *type_versions.entry(elem.typeref).or_default() |= elem.version_info;
+                }
+            }
+        }
+        type_versions
+    }
+
+    /// count the element types that are available in each version
+    ///
+    /// The result contains a tuple (version bit index, exclusive count, total count) for each version in which
+    /// there are any element types. An element type is available in all versions of the elements that use it, so
+    /// an element type that is used in several groups is only counted once. The exclusive count is the number of
+    /// element types which are only available in that version.
+    ///
+    /// ```
+    /// use autosar_xsd_mangler::phases::{self, Input};
+    /// use std::path::Path;
+    ///
+    /// let input = Input::find(Path::new("tests/fixtures/single/mini.xsd"))?;
+    /// let schema = phases::merge(&phases::flatten(&phases::parse(&input)?)?)?;
+    /// for (bit_idx, exclusive_count, total_count) in schema.types().element_count_by_version() {
+    ///     println!("version {bit_idx}: {total_count} element types, {exclusive_count} only in this version");
+    /// }
+    /// assert_eq!(schema.types().element_count_by_version(), vec![(0, 9, 9)]);
+    /// # Ok::<(), String>(())
+    /// ```
+    pub fn element_count_by_version(&self) -> Vec<(usize, usize, usize)> {
+        let mut counts = [(0, 0); usize::BITS as usize];
+        for version_mask in self.element_type_versions().into_values() {
+            for (bit_idx, (exclusive_count, total_count)) in counts.iter_mut().enumerate() {
+                if version_mask & (1 << bit_idx) != 0 {
+                    *total_count += 1;
+                    if version_mask.count_ones() == 1 {
+                        *exclusive_count += 1;
+                    }
+                }
+            }
+        }
+
+        counts
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, total_count))| *total_count > 0)
+            .map(|(bit_idx, (exclusive_count, total_count))| {
+                (bit_idx, *exclusive_count, *total_count)
+            })
+            .collect()
+    }
+}
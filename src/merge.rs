@@ -1,77 +1,278 @@
+use std::collections::hash_map::Entry;
 use std::collections::HashSet;
 
+mod policy;
+
+pub use policy::{MergePolicy, MergePolicyKind};
+
+use super::flatten::{describe_character_type, describe_element_type};
 use super::{
     Attribute, AutosarDataTypes, CharacterDataType, Element, ElementCollection,
-    ElementCollectionItem, ElementDataType, EnumDefinition, FxHashMap,
+    ElementCollectionItem, ElementDataType, EnumDefinition, FxHashMap, Interner, Symbol,
 };
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+// a pair of type names: the symbol in the merged schema and the symbol in the input schema
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 enum ElemOrGroup {
-    Element(String, String),
-    Group(String, String),
+    Element(Symbol, Symbol),
+    Group(Symbol, Symbol),
+}
+
+/// the names of the types that are only present in one of two schemas
+#[derive(Debug, Default)]
+pub struct SchemaDiff {
+    pub element_types: TypeNameDiff,
+    pub character_types: TypeNameDiff,
+    pub group_types: TypeNameDiff,
+}
+
+#[derive(Debug, Default)]
+pub struct TypeNameDiff {
+    /// type names that are present in schema a, but not in schema b
+    pub added: Vec<String>,
+    /// type names that are present in schema b, but not in schema a
+    pub removed: Vec<String>,
 }
 
 #[derive(Debug)]
 struct MergeItems {
     elem_types: Vec<ElemOrGroup>,
-    char_types: Vec<(String, String)>,
+    char_types: Vec<(Symbol, Symbol)>,
+}
+
+/// merge a list of schemas into a single schema
+///
+/// The schemas must be ordered from newest to oldest: the first schema is the base of the
+/// merge, and each following (older) schema is merged into it.
+pub fn merge_all(
+    schemas: &[AutosarDataTypes],
+    policy: &mut dyn MergePolicy,
+) -> Result<AutosarDataTypes, String> {
+    let Some((base, older_schemas)) = schemas.split_first() else {
+        return Err("Error: there are no schemas to merge".to_string());
+    };
+
+    let mut merged = base.clone();
+    super::sanity_check(&merged);
+    for input in older_schemas {
+        merged = merged.merge_with(input, policy)?;
+        super::sanity_check(&merged);
+    }
+
+    Ok(merged)
+}
+
+/// compare the order of the sub elements in the groups of two merged schemas
+///
+/// This is used to check if the result of the merge depends on the order of the input schemas. Only groups
+/// which are present in both schemas are compared, and only the order of the sub elements that exist in both.
+/// Returns a description of the first difference in each group whose sub elements are ordered differently,
+/// sorted by group name.
+pub(crate) fn compare_group_order(a: &AutosarDataTypes, b: &AutosarDataTypes) -> Vec<String> {
+    let mut groupnames: Vec<&str> = a
+        .group_types
+        .keys()
+        .map(|groupname| a.names.resolve(*groupname))
+        .filter(|groupname| b.group_type(groupname).is_some())
+        .collect();
+    groupnames.sort();
+
+    let mut differences = Vec::new();
+    for groupname in groupnames {
+        let names_a: Vec<&str> = a
+            .group_type(groupname)
+            .unwrap()
+            .items()
+            .iter()
+            .map(|item| item.name(&a.names))
+            .collect();
+        let names_b: Vec<&str> = b
+            .group_type(groupname)
+            .unwrap()
+            .items()
+            .iter()
+            .map(|item| item.name(&b.names))
+            .collect();
+        // both lists contain each name as often as it occurs in both groups, so they have the same length
+        let common_a = common_names(&names_a, &names_b);
+        let common_b = common_names(&names_b, &names_a);
+        // only the first difference is reported, since everything after it is usually shifted
+        if let Some(pos) = common_a
+            .iter()
+            .zip(&common_b)
+            .position(|(name_a, name_b)| name_a != name_b)
+        {
+            let end = (pos + 3).min(common_a.len());
+            differences.push(format!(
+                "{groupname}: from position {pos}: [{}, ...] vs. [{}, ...]",
+                common_a[pos..end].join(", "),
+                common_b[pos..end].join(", ")
+            ));
+        }
+    }
+    differences
+}
+
+// the names in `names` that also occur in `other`; a name that occurs several times is kept as often as it occurs
+// in `other`, counted from the start
+fn common_names<'a>(names: &[&'a str], other: &[&str]) -> Vec<&'a str> {
+    let mut remaining: FxHashMap<&str, usize> = FxHashMap::default();
+    for name in other {
+        *remaining.entry(name).or_default() += 1;
+    }
+    names
+        .iter()
+        .copied()
+        .filter(|name| match remaining.get_mut(name) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        })
+        .collect()
+}
+
+/// compare the type names of two schemas
+///
+/// Types are compared by name only, so a type that exists in both schemas with different content is not listed.
+/// All lists in the result are sorted.
+pub fn diff(a: &AutosarDataTypes, b: &AutosarDataTypes) -> SchemaDiff {
+    SchemaDiff {
+        element_types: diff_names((&a.element_types, &a.names), (&b.element_types, &b.names)),
+        character_types: diff_names(
+            (&a.character_types, &a.names),
+            (&b.character_types, &b.names),
+        ),
+        group_types: diff_names((&a.group_types, &a.names), (&b.group_types, &b.names)),
+    }
+}
+
+/// check that the newer schema contains all types of the older schema
+///
+/// AUTOSAR usually only adds to the schema, so types that disappear can point to a problem with the
+/// flattening of one of the versions. Returns the kind and name of each type of the older schema that is
+/// missing in the newer schema, sorted by kind and then by name.
+pub fn verify_superset(newer: &AutosarDataTypes, older: &AutosarDataTypes) -> Vec<String> {
+    let SchemaDiff {
+        element_types,
+        character_types,
+        group_types,
+    } = diff(newer, older);
+    let missing_element_types = element_types
+        .removed
+        .into_iter()
+        .map(|name| format!("element type {name}"));
+    let missing_character_types = character_types
+        .removed
+        .into_iter()
+        .map(|name| format!("character type {name}"));
+    let missing_group_types = group_types
+        .removed
+        .into_iter()
+        .map(|name| format!("group {name}"));
+    missing_element_types
+        .chain(missing_character_types)
+        .chain(missing_group_types)
+        .collect()
+}
+
+fn diff_names<T>(
+    a: (&FxHashMap<Symbol, T>, &Interner),
+    b: (&FxHashMap<Symbol, T>, &Interner),
+) -> TypeNameDiff {
+    // the names of the types in `types` that are not in `other`
+    let missing_names =
+        |(types, names): (&FxHashMap<Symbol, T>, &Interner),
+         (other_types, other_names): (&FxHashMap<Symbol, T>, &Interner)| {
+            let mut missing: Vec<String> = types
+                .keys()
+                .map(|typename| names.resolve(*typename))
+                .filter(|typename| {
+                    other_names
+                        .get(typename)
+                        .is_none_or(|other_typename| !other_types.contains_key(&other_typename))
+                })
+                .map(ToString::to_string)
+                .collect();
+            missing.sort();
+            missing
+        };
+    TypeNameDiff {
+        added: missing_names(a, b),
+        removed: missing_names(b, a),
+    }
 }
 
 // merge the content of of input_xsd into merged_xsd
-// merged_xsd is modified, input_xsd is not
+// merged_xsd is modified, input_xsd is not; conflicts are resolved by the policy
 pub(crate) fn merge(
     merged_xsd: &mut AutosarDataTypes,
     input_xsd: &AutosarDataTypes,
+    policy: &mut dyn MergePolicy,
 ) -> Result<(), String> {
     // begin the merge at the top-level AR:AUTOSAR type, which must exist by definition
+    let Some(root_input) = input_xsd.names.get("AR:AUTOSAR") else {
+        return Err("Error: the input schema has no element type AR:AUTOSAR".to_string());
+    };
     let mut merge_queue = MergeItems::from_vecs(
         vec![ElemOrGroup::Element(
-            "AR:AUTOSAR".to_owned(),
-            "AR:AUTOSAR".to_owned(),
+            merged_xsd.names.intern("AR:AUTOSAR"),
+            root_input,
         )],
         Vec::new(),
     );
 
+    // older types that got a new name, because their class differs from the merged type of the same name
+    let mut renamed_types: FxHashMap<Symbol, Symbol> = FxHashMap::default();
     let mut already_checked: HashSet<ElemOrGroup> = HashSet::new();
     // while let Some((typename_merged, typename_input)) = merge_queue.elem_types.pop() {
     while let Some(elem_or_group) = merge_queue.elem_types.pop() {
-        if already_checked.get(&elem_or_group).is_none() {
-            match &elem_or_group {
+        if !already_checked.contains(&elem_or_group) {
+            match elem_or_group {
                 ElemOrGroup::Element(typename_merged, typename_input) => {
                     // typename_merged might not exist in merged_xsd if an element requiring this type was only just copied by the merge
-                    if merged_xsd.element_types.get(typename_merged).is_none() {
-                        if let Some(input_type) = input_xsd.element_types.get(typename_input) {
+                    if !merged_xsd.element_types.contains_key(&typename_merged) {
+                        if let Some(input_type) = input_xsd.element_types.get(&typename_input) {
+                            let merged_type = import_element_type(
+                                input_type,
+                                &mut merged_xsd.names,
+                                &input_xsd.names,
+                            );
                             merged_xsd
                                 .element_types
-                                .insert(typename_merged.clone(), input_type.clone());
+                                .insert(typename_merged, merged_type);
                         }
                     }
-                    if merged_xsd.element_types.get(typename_merged).is_some() {
+                    if merged_xsd.element_types.contains_key(&typename_merged) {
                         let mut additional_items = merge_elem_types(
                             merged_xsd,
                             typename_merged,
                             input_xsd,
                             typename_input,
-                        );
+                            policy,
+                        )?;
                         merge_queue.append(&mut additional_items);
                     }
                     already_checked.insert(elem_or_group);
                 }
                 ElemOrGroup::Group(typename_merged, typename_input) => {
                     // typename_merged might not exist in merged_xsd if an element requiring this type was only just copied by the merge
-                    if merged_xsd.group_types.get(typename_merged).is_none() {
-                        if let Some(input_type) = input_xsd.group_types.get(typename_input) {
-                            merged_xsd
-                                .group_types
-                                .insert(typename_merged.clone(), input_type.clone());
+                    if !merged_xsd.group_types.contains_key(&typename_merged) {
+                        if let Some(input_type) = input_xsd.group_types.get(&typename_input) {
+                            let merged_type =
+                                import_group(input_type, &mut merged_xsd.names, &input_xsd.names);
+                            merged_xsd.group_types.insert(typename_merged, merged_type);
                         }
                     }
-                    if merged_xsd.group_types.get(typename_merged).is_some() {
+                    if merged_xsd.group_types.contains_key(&typename_merged) {
                         let mut additional_items = merge_group_types(
                             merged_xsd,
                             typename_merged,
                             input_xsd,
                             typename_input,
+                            &mut renamed_types,
+                            policy,
                         )?;
                         merge_queue.append(&mut additional_items);
                     }
@@ -83,20 +284,21 @@ pub(crate) fn merge(
 
     let mut already_checked = HashSet::new();
     while let Some((typename_merged, typename_input)) = merge_queue.char_types.pop() {
-        if already_checked
-            .get(&(typename_merged.clone(), typename_input.clone()))
-            .is_none()
-        {
-            if merged_xsd.character_types.get(&typename_merged).is_none() {
+        if !already_checked.contains(&(typename_merged, typename_input)) {
+            if let Entry::Vacant(entry) = merged_xsd.character_types.entry(typename_merged) {
                 if let Some(input_type) = input_xsd.character_types.get(&typename_input) {
-                    merged_xsd
-                        .character_types
-                        .insert(typename_merged.clone(), input_type.clone());
+                    entry.insert(input_type.clone());
                 }
             }
 
-            if merged_xsd.character_types.get(&typename_merged).is_some() {
-                merge_char_types(merged_xsd, &typename_merged, input_xsd, &typename_input);
+            if merged_xsd.character_types.contains_key(&typename_merged) {
+                merge_char_types(
+                    merged_xsd,
+                    typename_merged,
+                    input_xsd,
+                    typename_input,
+                    policy,
+                )?;
             }
 
             already_checked.insert((typename_merged, typename_input));
@@ -106,37 +308,219 @@ pub(crate) fn merge(
     Ok(())
 }
 
+/// find the types of an extension schema that redefine a type of the standard schema
+///
+/// An extension may add elements, attributes and enum items to a standard type, but it may not change the kind
+/// of a type, the restriction of a character type, or the type of an existing element or attribute.
+/// Returns one description per conflict; the list is sorted.
+pub(crate) fn find_conflicts(
+    standard: &AutosarDataTypes,
+    extension: &AutosarDataTypes,
+) -> Vec<String> {
+    let mut conflicts = Vec::new();
+
+    for (typename, chartype_ext) in &extension.character_types {
+        let typename = extension.names.resolve(*typename);
+        if let Some(chartype) = standard.character_type(typename) {
+            let compatible = match (chartype, chartype_ext) {
+                (CharacterDataType::Enum(_), CharacterDataType::Enum(_)) => true,
+                (a, b) => a == b,
+            };
+            if !compatible {
+                conflicts.push(format!("character type {typename} is redefined"));
+            }
+        }
+    }
+
+    for (typename, elemtype_ext) in &extension.element_types {
+        let typename = extension.names.resolve(*typename);
+        let Some(elemtype) = standard.element_type(typename) else {
+            continue;
+        };
+        if std::mem::discriminant(elemtype) != std::mem::discriminant(elemtype_ext)
+            || elemtype
+                .basetype()
+                .map(|basetype| standard.names.resolve(basetype))
+                != elemtype_ext
+                    .basetype()
+                    .map(|basetype| extension.names.resolve(basetype))
+        {
+            conflicts.push(format!("element type {typename} is redefined"));
+        }
+        for attr_ext in elemtype_ext.attributes() {
+            if let Some(attr) = elemtype
+                .attributes()
+                .iter()
+                .find(|attr| attr.name == attr_ext.name)
+            {
+                let attr_type = standard.names.resolve(attr.attr_type);
+                let attr_type_ext = extension.names.resolve(attr_ext.attr_type);
+                if attr_type != attr_type_ext {
+                    conflicts.push(format!(
+                        "attribute {} of {typename} changes its type from {attr_type} to {attr_type_ext}",
+                        attr.name
+                    ));
+                }
+            }
+        }
+    }
+
+    for (groupname, group_ext) in &extension.group_types {
+        let groupname = extension.names.resolve(*groupname);
+        let Some(group) = standard.group_type(groupname) else {
+            continue;
+        };
+        for item_ext in group_ext.items() {
+            if let ElementCollectionItem::Element(elem_ext) = item_ext {
+                let existing = group.items().iter().find_map(|item| match item {
+                    ElementCollectionItem::Element(elem) if elem.name == elem_ext.name => {
+                        Some(elem)
+                    }
+                    _ => None,
+                });
+                if let Some(elem) = existing {
+                    let typeref = standard.names.resolve(elem.typeref);
+                    let typeref_ext = extension.names.resolve(elem_ext.typeref);
+                    if typeref != typeref_ext {
+                        conflicts.push(format!(
+                            "element {} in {groupname} changes its type from {typeref} to {typeref_ext}",
+                            elem.name
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    conflicts.sort();
+    conflicts
+}
+
+/// add the version `new_version` to every element, attribute and enum item that is valid in `base_version`
+///
+/// Both versions are given as bit masks. This is used for an extension schema, which contains everything
+/// of the newest standard version in addition to its own definitions.
+pub(crate) fn extend_version(
+    schema: &mut AutosarDataTypes,
+    base_version: usize,
+    new_version: usize,
+) {
+    let extend = |version_info: &mut usize| {
+        if *version_info & base_version != 0 {
+            *version_info |= new_version;
+        }
+    };
+
+    for group in schema.group_types.values_mut() {
+        let (ElementCollection::Choice { sub_elements, .. }
+        | ElementCollection::Sequence { sub_elements, .. }) = group;
+        for item in sub_elements {
+            if let ElementCollectionItem::Element(elem) = item {
+                extend(&mut elem.version_info);
+                extend(&mut elem.splittable_ver);
+            }
+        }
+    }
+    for elemtype in schema.element_types.values_mut() {
+        let (ElementDataType::Elements { attributes, .. }
+        | ElementDataType::Characters { attributes, .. }
+        | ElementDataType::Mixed { attributes, .. }) = elemtype;
+        for attr in attributes {
+            extend(&mut attr.version_info);
+        }
+    }
+    for chartype in schema.character_types.values_mut() {
+        if let CharacterDataType::Enum(enumdef) = chartype {
+            for (_, version, _) in &mut enumdef.enumitems {
+                extend(version);
+            }
+        }
+    }
+}
+
+/// combine the elements that appear more than once in the same group with the same type
+///
+/// The version masks of each later copy are added to the first element, and the copy is removed.
+/// Elements with the same name but different types are left alone; sanity_check reports them.
+/// Returns the number of removed elements.
+pub(crate) fn merge_duplicate_elements(schema: &mut AutosarDataTypes) -> usize {
+    let mut removed_count = 0;
+    for group in schema.group_types.values_mut() {
+        let (ElementCollection::Choice { sub_elements, .. }
+        | ElementCollection::Sequence { sub_elements, .. }) = group;
+        let mut idx = 0;
+        while idx < sub_elements.len() {
+            let duplicate_pos = if let ElementCollectionItem::Element(elem) = &sub_elements[idx] {
+                sub_elements[idx + 1..].iter().position(|item| {
+                    matches!(item, ElementCollectionItem::Element(other)
+                        if other.name == elem.name && other.typeref == elem.typeref)
+                })
+            } else {
+                None
+            };
+            if let Some(pos) = duplicate_pos {
+                let duplicate = sub_elements.remove(idx + 1 + pos);
+                if let (
+                    ElementCollectionItem::Element(elem),
+                    ElementCollectionItem::Element(duplicate),
+                ) = (&mut sub_elements[idx], duplicate)
+                {
+                    elem.version_info |= duplicate.version_info;
+                    elem.splittable_ver |= duplicate.splittable_ver;
+                }
+                removed_count += 1;
+            } else {
+                idx += 1;
+            }
+        }
+    }
+    removed_count
+}
+
 fn merge_char_types(
     merged_xsd: &mut AutosarDataTypes,
-    typename: &str,
+    typename: Symbol,
     input_xsd: &AutosarDataTypes,
-    typename_input: &str,
-) {
-    let a = merged_xsd.character_types.get_mut(typename).unwrap();
-    let b = input_xsd.character_types.get(typename_input).unwrap();
+    typename_input: Symbol,
+    policy: &mut dyn MergePolicy,
+) -> Result<(), String> {
+    let a = merged_xsd.character_types.get_mut(&typename).unwrap();
+    let b = input_xsd.character_types.get(&typename_input).unwrap();
+    let typename = merged_xsd.names.resolve(typename);
 
     match (a, b) {
         (CharacterDataType::Enum(enumdef), CharacterDataType::Enum(enumdef_new)) => {
-            merge_enums(enumdef, enumdef_new);
+            merge_enums(enumdef, enumdef_new, typename, policy)?;
         }
         (CharacterDataType::Pattern { .. }, CharacterDataType::Pattern { .. })
         | (CharacterDataType::String { .. }, CharacterDataType::String { .. })
         | (CharacterDataType::UnsignedInteger, CharacterDataType::UnsignedInteger)
-        | (CharacterDataType::Double, CharacterDataType::Double) => {}
-        (_aa, _bb) => {
-            // println!("mixed character types: {typename}={_aa:#?} - {typename_input}={_bb:#?}");
+        | (CharacterDataType::Double, CharacterDataType::Double)
+        | (CharacterDataType::DateTime { .. }, CharacterDataType::DateTime { .. }) => {}
+        (chartype, chartype_new) => {
+            // only one definition can be kept under this name
+            println!(
+                "Warning: the character type {} can't be merged, {} is replaced by {}",
+                input_xsd.names.resolve(typename_input),
+                describe_character_type(chartype_new),
+                describe_character_type(chartype)
+            );
         }
     }
+    Ok(())
 }
 
 fn merge_elem_types(
     merged_xsd: &mut AutosarDataTypes,
-    typename: &str,
+    typename: Symbol,
     input_xsd: &AutosarDataTypes,
-    typename_input: &str,
-) -> MergeItems {
-    let a = merged_xsd.element_types.get_mut(typename).unwrap();
-    let b = input_xsd.element_types.get(typename_input).unwrap();
+    typename_input: Symbol,
+    policy: &mut dyn MergePolicy,
+) -> Result<MergeItems, String> {
+    let a = merged_xsd.element_types.get_mut(&typename).unwrap();
+    let b = input_xsd.element_types.get(&typename_input).unwrap();
+    let names = &mut merged_xsd.names;
+    let names_input = &input_xsd.names;
     let mut result = MergeItems::new();
 
     match (a, b) {
@@ -145,23 +529,30 @@ fn merge_elem_types(
                 group_ref,
                 attributes,
                 xsd_typenames,
-                ..
+                mm_class,
             },
             ElementDataType::Elements {
                 group_ref: group_ref_new,
                 attributes: attributes_new,
                 xsd_typenames: xsd_typenames_new,
-                ..
+                mm_class: mm_class_new,
             },
         ) => {
             result
                 .elem_types
-                .push(ElemOrGroup::Group(group_ref.clone(), group_ref_new.clone()));
-            result.append(&mut merge_attributes(attributes, attributes_new));
+                .push(ElemOrGroup::Group(*group_ref, *group_ref_new));
+            result.append(&mut merge_attributes(
+                attributes,
+                attributes_new,
+                typename,
+                (names, names_input),
+                policy,
+            )?);
             for xtn in xsd_typenames_new {
                 // most of these are duplicates, but that doesn't matter
                 xsd_typenames.insert(xtn.to_owned());
             }
+            merge_mm_class(mm_class, mm_class_new);
         }
         (
             ElementDataType::Characters {
@@ -175,32 +566,41 @@ fn merge_elem_types(
                 ..
             },
         ) => {
-            result.append(&mut merge_attributes(attributes, attributes_new));
-            result
-                .char_types
-                .push(((*basetype).to_string(), basetype_new.to_string()));
+            result.append(&mut merge_attributes(
+                attributes,
+                attributes_new,
+                typename,
+                (names, names_input),
+                policy,
+            )?);
+            result.char_types.push((*basetype, *basetype_new));
         }
         (
             ElementDataType::Mixed {
                 group_ref,
                 attributes,
                 basetype,
-                ..
+                mm_class,
             },
             ElementDataType::Mixed {
                 group_ref: group_ref_new,
                 attributes: attributes_new,
                 basetype: basetype_new,
-                ..
+                mm_class: mm_class_new,
             },
         ) => {
             result
                 .elem_types
-                .push(ElemOrGroup::Group(group_ref.clone(), group_ref_new.clone()));
-            result.append(&mut merge_attributes(attributes, attributes_new));
-            result
-                .char_types
-                .push(((*basetype).to_string(), basetype_new.to_string()));
+                .push(ElemOrGroup::Group(*group_ref, *group_ref_new));
+            result.append(&mut merge_attributes(
+                attributes,
+                attributes_new,
+                typename,
+                (names, names_input),
+                policy,
+            )?);
+            merge_mm_class(mm_class, mm_class_new);
+            result.char_types.push((*basetype, *basetype_new));
         }
         (
             ElementDataType::Mixed { attributes, .. },
@@ -209,24 +609,54 @@ fn merge_elem_types(
                 ..
             },
         ) => {
-            result.append(&mut merge_attributes(attributes, attributes_new));
+            result.append(&mut merge_attributes(
+                attributes,
+                attributes_new,
+                typename,
+                (names, names_input),
+                policy,
+            )?);
         }
-        (_aa, _bb) => {
-            // println!("mixed element types: {typename}={_aa:#?} - {typename_input}={_bb:#?}");
+        (elemtype, elemtype_new) => {
+            // merge_group_types renames older types whose class changed, so this is a bug
+            return Err(format!(
+                "Error: merge of the element type {} = {} with {} = {} is not possible",
+                names.resolve(typename),
+                describe_element_type(elemtype, names),
+                names_input.resolve(typename_input),
+                describe_element_type(elemtype_new, names_input)
+            ));
         }
     }
 
-    result
+    Ok(result)
+}
+
+// the class names of the newer version are kept, and class names that only exist in the older version are appended
+fn merge_mm_class(mm_class: &mut Option<Vec<String>>, mm_class_new: &Option<Vec<String>>) {
+    match (mm_class, mm_class_new) {
+        (Some(classes), Some(classes_new)) => {
+            for class_name in classes_new {
+                if !classes.contains(class_name) {
+                    classes.push(class_name.clone());
+                }
+            }
+        }
+        (mm_class @ None, Some(_)) => mm_class.clone_from(mm_class_new),
+        (_, None) => {}
+    }
 }
 
 fn merge_group_types(
     merged_xsd: &mut AutosarDataTypes,
-    typename: &str,
+    typename: Symbol,
     input_xsd: &AutosarDataTypes,
-    typename_input: &str,
+    typename_input: Symbol,
+    renamed_types: &mut FxHashMap<Symbol, Symbol>,
+    policy: &mut dyn MergePolicy,
 ) -> Result<MergeItems, String> {
-    let element_collection = merged_xsd.group_types.get_mut(typename).unwrap();
-    let element_collection_new = input_xsd.group_types.get(typename_input).unwrap();
+    let element_collection = merged_xsd.group_types.get_mut(&typename).unwrap();
+    let element_collection_new = input_xsd.group_types.get(&typename_input).unwrap();
     let mut insert_pos: usize = 0;
 
     // never insert any element in position 0 ahead of the SHORT-NAME
@@ -248,12 +678,12 @@ fn merge_group_types(
                     .iter()
                     .enumerate()
                     .find(|(_idx, e)| {
-                        e.name() == newelem.name()
+                        e.name(&merged_xsd.names) == newelem.name(&input_xsd.names)
                             && element_is_compatible(
                                 e,
                                 newelem,
-                                &merged_xsd.character_types,
-                                &input_xsd.character_types,
+                                (&merged_xsd.element_types, &merged_xsd.character_types),
+                                (&input_xsd.element_types, &input_xsd.character_types),
                             )
                     })
                     // .find(|(_idx, e)| e.name() == newelem.name())
@@ -268,11 +698,42 @@ fn merge_group_types(
                             ElementCollectionItem::Element(new_elem),
                         ) => {
                             cur_elem.version_info |= new_elem.version_info;
+                            // splittability can be added or removed between versions, so only the
+                            // bits of the versions where the element is splittable are combined
                             cur_elem.splittable_ver |= new_elem.splittable_ver;
-                            typesvec.elem_types.push(ElemOrGroup::Element(
-                                cur_elem.typeref.clone(),
-                                new_elem.typeref.clone(),
-                            ));
+                            if cur_elem.amount != new_elem.amount {
+                                cur_elem.amount = policy.resolve_amount(
+                                    &format!(
+                                        "element {} in {}",
+                                        cur_elem.name,
+                                        merged_xsd.names.resolve(typename)
+                                    ),
+                                    cur_elem.amount,
+                                    new_elem.amount,
+                                )?;
+                            }
+                            if cur_elem.restrict_std != new_elem.restrict_std {
+                                cur_elem.restrict_std = policy.resolve_restrict_std(
+                                    &format!(
+                                        "element {} in {}",
+                                        cur_elem.name,
+                                        merged_xsd.names.resolve(typename)
+                                    ),
+                                    cur_elem.restrict_std,
+                                    new_elem.restrict_std,
+                                )?;
+                            }
+                            // the docstring of cur_elem is from a newer version, so it is only replaced if it is missing
+                            if cur_elem
+                                .docstring
+                                .as_deref()
+                                .is_none_or(|doc| doc.trim().is_empty())
+                            {
+                                cur_elem.docstring.clone_from(&new_elem.docstring);
+                            }
+                            typesvec
+                                .elem_types
+                                .push(ElemOrGroup::Element(cur_elem.typeref, new_elem.typeref));
                         }
                         (
                             ElementCollectionItem::GroupRef(cur_group),
@@ -280,7 +741,7 @@ fn merge_group_types(
                         ) => {
                             typesvec
                                 .elem_types
-                                .push(ElemOrGroup::Group(cur_group.clone(), new_group.clone()));
+                                .push(ElemOrGroup::Group(*cur_group, *new_group));
                         }
                         (a, b) => {
                             return Err(format!(
@@ -296,19 +757,44 @@ fn merge_group_types(
                     //                 // }
                     insert_pos = find_pos + 1;
                 } else {
-                    sub_elements.insert(insert_pos, newelem.clone());
-                    match &newelem {
+                    let mut inserted = newelem.clone();
+                    match &mut inserted {
                         ElementCollectionItem::Element(Element { typeref, .. }) => {
+                            let typeref_input = *typeref;
+                            *typeref = merged_xsd.names.translate(typeref_input, &input_xsd.names);
+                            // the type of an element that changed its class often keeps its name, e.g. the
+                            // anonymous {NAME}-TYPE types. The older type gets a name of its own, otherwise the
+                            // inserted element would refer to the newer type and the older shape would be lost
+                            if merged_xsd.element_types.contains_key(typeref)
+                                && !types_are_compatible(
+                                    *typeref,
+                                    typeref_input,
+                                    (&merged_xsd.element_types, &merged_xsd.character_types),
+                                    (&input_xsd.element_types, &input_xsd.character_types),
+                                )
+                            {
+                                *typeref =
+                                    *renamed_types.entry(typeref_input).or_insert_with(|| {
+                                        unused_typename(
+                                            input_xsd.names.resolve(typeref_input),
+                                            (&mut merged_xsd.names, &merged_xsd.element_types),
+                                            input_xsd,
+                                        )
+                                    });
+                            }
                             typesvec
                                 .elem_types
-                                .push(ElemOrGroup::Element(typeref.clone(), typeref.clone()));
+                                .push(ElemOrGroup::Element(*typeref, typeref_input));
                         }
                         ElementCollectionItem::GroupRef(typeref) => {
+                            let typeref_input = *typeref;
+                            *typeref = merged_xsd.names.translate(typeref_input, &input_xsd.names);
                             typesvec
                                 .elem_types
-                                .push(ElemOrGroup::Group(typeref.clone(), typeref.clone()));
+                                .push(ElemOrGroup::Group(*typeref, typeref_input));
                         }
                     }
+                    sub_elements.insert(insert_pos, inserted);
 
                     insert_pos += 1;
                 }
@@ -321,7 +807,10 @@ fn merge_group_types(
 fn merge_attributes(
     attributes: &mut Vec<Attribute>,
     attributes_new: &Vec<Attribute>,
-) -> MergeItems {
+    typename: Symbol,
+    (names, names_input): (&mut Interner, &Interner),
+    policy: &mut dyn MergePolicy,
+) -> Result<MergeItems, String> {
     let mut result = MergeItems::new();
     let mut insert_pos = 0;
     for newattr in attributes_new {
@@ -332,23 +821,40 @@ fn merge_attributes(
             .map(|(idx, _att)| idx)
         {
             attributes[find_pos].version_info |= newattr.version_info;
+            if attributes[find_pos].required != newattr.required {
+                attributes[find_pos].required = policy.resolve_required(
+                    &format!("attribute {} of {}", newattr.name, names.resolve(typename)),
+                    attributes[find_pos].required,
+                    newattr.required,
+                )?;
+            }
+            // the documentation of the newer version is kept, unless only the older version has any
+            if attributes[find_pos].docstring.is_none() {
+                attributes[find_pos]
+                    .docstring
+                    .clone_from(&newattr.docstring);
+            }
 
-            result.char_types.push((
-                attributes[find_pos].attr_type.clone(),
-                newattr.attr_type.clone(),
-            ));
+            result
+                .char_types
+                .push((attributes[find_pos].attr_type, newattr.attr_type));
 
             insert_pos = find_pos + 1;
         } else {
-            attributes.insert(insert_pos, newattr.clone());
+            attributes.insert(insert_pos, import_attribute(newattr, names, names_input));
             insert_pos += 1;
         }
     }
 
-    result
+    Ok(result)
 }
 
-fn merge_enums(enumdef: &mut EnumDefinition, enumdef_new: &EnumDefinition) {
+fn merge_enums(
+    enumdef: &mut EnumDefinition,
+    enumdef_new: &EnumDefinition,
+    typename: &str,
+    policy: &mut dyn MergePolicy,
+) -> Result<(), String> {
     let EnumDefinition { name, enumitems } = enumdef;
     let EnumDefinition {
         name: name_new,
@@ -360,27 +866,43 @@ fn merge_enums(enumdef: &mut EnumDefinition, enumdef_new: &EnumDefinition) {
     }
 
     let mut insert_pos = 0;
-    for (newitem, newver) in enumitems_new {
+    for (newitem, newver, newdoc) in enumitems_new {
         if let Some(find_pos) = enumitems
             .iter()
             .enumerate()
-            .find(|(_idx, (enval, _))| enval == newitem)
+            .find(|(_idx, (enval, _, _))| enval == newitem)
             .map(|(idx, _)| idx)
         {
-            enumitems[find_pos].1 |= newver;
+            let (_, version, docstring) = &mut enumitems[find_pos];
+            *version |= newver;
+            // the existing docstring is from a newer version, so it is only replaced if it is missing
+            if docstring.as_deref().is_none_or(|doc| doc.trim().is_empty()) {
+                docstring.clone_from(newdoc);
+            }
+            if find_pos < insert_pos {
+                policy.resolve_enum_order(&format!("enum {typename}"), newitem)?;
+            }
             insert_pos = find_pos + 1;
         } else {
-            enumitems.insert(insert_pos, (newitem.clone(), *newver));
+            enumitems.insert(insert_pos, (newitem.clone(), *newver, newdoc.clone()));
             insert_pos += 1;
         }
     }
+    Ok(())
 }
 
+// two same-named elements are only compatible if their types are compatible
 fn element_is_compatible(
     item: &ElementCollectionItem,
     item_new: &ElementCollectionItem,
-    character_types: &FxHashMap<String, CharacterDataType>,
-    character_types_new: &FxHashMap<String, CharacterDataType>,
+    types: (
+        &FxHashMap<Symbol, ElementDataType>,
+        &FxHashMap<Symbol, CharacterDataType>,
+    ),
+    types_new: (
+        &FxHashMap<Symbol, ElementDataType>,
+        &FxHashMap<Symbol, CharacterDataType>,
+    ),
 ) -> bool {
     match (item, item_new) {
         (
@@ -389,31 +911,177 @@ fn element_is_compatible(
                 typeref: typeref_new,
                 ..
             }),
-        ) => {
-            matches!(
-                (
-                    character_types.get(typeref),
-                    character_types_new.get(typeref_new),
-                ),
-                (
-                    Some(CharacterDataType::Pattern { .. }),
-                    Some(CharacterDataType::Pattern { .. }),
-                ) | (
-                    Some(CharacterDataType::Enum(_)),
-                    Some(CharacterDataType::Enum(_))
-                ) | (
-                    Some(CharacterDataType::String { .. }),
-                    Some(CharacterDataType::String { .. }),
-                ) | (
-                    Some(CharacterDataType::UnsignedInteger),
-                    Some(CharacterDataType::UnsignedInteger),
-                ) | (
-                    Some(CharacterDataType::Double),
-                    Some(CharacterDataType::Double)
-                ) | (None, None)
-            )
+        ) => types_are_compatible(*typeref, *typeref_new, types, types_new),
+        _ => true,
+    }
+}
+
+// two element types are only compatible if they have the same class, and if both have compatible character types
+// in case they contain character data directly
+fn types_are_compatible(
+    typeref: Symbol,
+    typeref_new: Symbol,
+    (element_types, character_types): (
+        &FxHashMap<Symbol, ElementDataType>,
+        &FxHashMap<Symbol, CharacterDataType>,
+    ),
+    (element_types_new, character_types_new): (
+        &FxHashMap<Symbol, ElementDataType>,
+        &FxHashMap<Symbol, CharacterDataType>,
+    ),
+) -> bool {
+    let class = element_types.get(&typeref).map(ElementTypeClass::of);
+    let class_new = element_types_new
+        .get(&typeref_new)
+        .map(ElementTypeClass::of);
+    // merge_elem_types can merge the attributes of a Mixed type with those of a Characters type,
+    // but a change from or to an Elements type is a different element that only shares the name
+    let compatible_class = match (class, class_new) {
+        (Some(class), Some(class_new)) => {
+            class == class_new
+                || (class == ElementTypeClass::Mixed && class_new == ElementTypeClass::Characters)
         }
         _ => true,
+    };
+    compatible_class
+        && matches!(
+            (
+                character_types.get(&typeref),
+                character_types_new.get(&typeref_new),
+            ),
+            (
+                Some(CharacterDataType::Pattern { .. }),
+                Some(CharacterDataType::Pattern { .. }),
+            ) | (
+                Some(CharacterDataType::Enum(_)),
+                Some(CharacterDataType::Enum(_))
+            ) | (
+                Some(CharacterDataType::String { .. }),
+                Some(CharacterDataType::String { .. }),
+            ) | (
+                Some(CharacterDataType::UnsignedInteger),
+                Some(CharacterDataType::UnsignedInteger),
+            ) | (
+                Some(CharacterDataType::Double),
+                Some(CharacterDataType::Double)
+            ) | (
+                Some(CharacterDataType::DateTime { .. }),
+                Some(CharacterDataType::DateTime { .. })
+            ) | (None, None)
+        )
+}
+
+// a new name for an element type of the input schema, which is not used in either schema
+fn unused_typename(
+    typename: &str,
+    (names, element_types): (&mut Interner, &FxHashMap<Symbol, ElementDataType>),
+    input_xsd: &AutosarDataTypes,
+) -> Symbol {
+    let is_unused = |name: &String| {
+        names
+            .get(name)
+            .is_none_or(|symbol| !element_types.contains_key(&symbol))
+            && input_xsd.element_type(name).is_none()
+    };
+    let name = format!("{typename}--CLASS-CHANGE");
+    let name = if is_unused(&name) {
+        name
+    } else {
+        (2..)
+            .map(|idx| format!("{typename}--CLASS-CHANGE-{idx}"))
+            .find(is_unused)
+            .unwrap()
+    };
+    names.intern(&name)
+}
+
+// a type that is copied from the input schema refers to other types by their symbols in the merged schema
+fn import_element_type(
+    elemtype: &ElementDataType,
+    names: &mut Interner,
+    names_input: &Interner,
+) -> ElementDataType {
+    let mut elemtype = elemtype.clone();
+    match &mut elemtype {
+        ElementDataType::Elements {
+            group_ref,
+            attributes,
+            ..
+        } => {
+            *group_ref = names.translate(*group_ref, names_input);
+            import_attributes(attributes, names, names_input);
+        }
+        ElementDataType::Characters {
+            attributes,
+            basetype,
+        } => {
+            *basetype = names.translate(*basetype, names_input);
+            import_attributes(attributes, names, names_input);
+        }
+        ElementDataType::Mixed {
+            group_ref,
+            attributes,
+            basetype,
+            ..
+        } => {
+            *group_ref = names.translate(*group_ref, names_input);
+            *basetype = names.translate(*basetype, names_input);
+            import_attributes(attributes, names, names_input);
+        }
+    }
+    elemtype
+}
+
+fn import_group(
+    group: &ElementCollection,
+    names: &mut Interner,
+    names_input: &Interner,
+) -> ElementCollection {
+    let mut group = group.clone();
+    let (ElementCollection::Choice { sub_elements, .. }
+    | ElementCollection::Sequence { sub_elements, .. }) = &mut group;
+    for item in sub_elements {
+        match item {
+            ElementCollectionItem::Element(Element { typeref, .. })
+            | ElementCollectionItem::GroupRef(typeref) => {
+                *typeref = names.translate(*typeref, names_input);
+            }
+        }
+    }
+    group
+}
+
+fn import_attributes(attributes: &mut [Attribute], names: &mut Interner, names_input: &Interner) {
+    for attribute in attributes {
+        attribute.attr_type = names.translate(attribute.attr_type, names_input);
+    }
+}
+
+fn import_attribute(
+    attribute: &Attribute,
+    names: &mut Interner,
+    names_input: &Interner,
+) -> Attribute {
+    Attribute {
+        attr_type: names.translate(attribute.attr_type, names_input),
+        ..attribute.clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ElementTypeClass {
+    Elements,
+    Characters,
+    Mixed,
+}
+
+impl ElementTypeClass {
+    fn of(element_type: &ElementDataType) -> Self {
+        match element_type {
+            ElementDataType::Elements { .. } => ElementTypeClass::Elements,
+            ElementDataType::Characters { .. } => ElementTypeClass::Characters,
+            ElementDataType::Mixed { .. } => ElementTypeClass::Mixed,
+        }
     }
 }
 
@@ -425,7 +1093,7 @@ impl MergeItems {
         }
     }
 
-    fn from_vecs(elem_types: Vec<ElemOrGroup>, char_types: Vec<(String, String)>) -> Self {
+    fn from_vecs(elem_types: Vec<ElemOrGroup>, char_types: Vec<(Symbol, Symbol)>) -> Self {
         Self {
             elem_types,
             char_types,
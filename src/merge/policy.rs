@@ -0,0 +1,218 @@
+use crate::{ElementAmount, XsdRestrictToStandard};
+
+/// the merge policies that can be selected with --merge-policy
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum MergePolicyKind {
+    /// keep the value of the merge base; this is the default
+    #[default]
+    LatestWins,
+    /// choose the value which accepts the most documents
+    Lenient,
+    /// every conflict is an error
+    Strict,
+}
+
+impl MergePolicyKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "latest-wins" => Some(MergePolicyKind::LatestWins),
+            "lenient" => Some(MergePolicyKind::Lenient),
+            "strict" => Some(MergePolicyKind::Strict),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MergePolicyKind::LatestWins => "latest-wins",
+            MergePolicyKind::Lenient => "lenient",
+            MergePolicyKind::Strict => "strict",
+        }
+    }
+
+    /// create the policy; each conflict is described to `report`, together with its resolution
+    pub fn build<'a>(self, report: Box<dyn FnMut(String) + 'a>) -> Box<dyn MergePolicy + 'a> {
+        match self {
+            MergePolicyKind::LatestWins => Box::new(LatestWins { report }),
+            MergePolicyKind::Lenient => Box::new(Lenient { report }),
+            MergePolicyKind::Strict => Box::new(Strict { report }),
+        }
+    }
+}
+
+/// the decisions of the merge where two versions of the schema disagree
+///
+/// The merge base is the newer version, or the standard schema when an extension is merged. The provided methods
+/// keep the value of the merge base without a report, which has always been the behaviour of the merge; only the
+/// policies that decide differently report the conflicts.
+/// `context` describes the conflicting item, e.g. "attribute T of AR:SYSTEM-SIGNAL".
+pub trait MergePolicy {
+    /// describe a conflict and its resolution in the merge report
+    fn report(&mut self, message: String);
+
+    /// an attribute is required in one version and optional in the other
+    fn resolve_required(
+        &mut self,
+        _context: &str,
+        required: bool,
+        _required_new: bool,
+    ) -> Result<bool, String> {
+        Ok(required)
+    }
+
+    /// an element is restricted to different platforms in the two versions
+    fn resolve_restrict_std(
+        &mut self,
+        _context: &str,
+        restrict_std: XsdRestrictToStandard,
+        _restrict_std_new: XsdRestrictToStandard,
+    ) -> Result<XsdRestrictToStandard, String> {
+        Ok(restrict_std)
+    }
+
+    /// an element has a different multiplicity in the two versions
+    fn resolve_amount(
+        &mut self,
+        _context: &str,
+        amount: ElementAmount,
+        _amount_new: ElementAmount,
+    ) -> Result<ElementAmount, String> {
+        Ok(amount)
+    }
+
+    /// the enum item `item` is placed before items which precede it in the merge base
+    ///
+    /// The order of the merge base is always kept; an error stops the merge.
+    fn resolve_enum_order(&mut self, _context: &str, _item: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+struct LatestWins<'a> {
+    report: Box<dyn FnMut(String) + 'a>,
+}
+
+impl MergePolicy for LatestWins<'_> {
+    fn report(&mut self, message: String) {
+        (self.report)(message);
+    }
+}
+
+struct Lenient<'a> {
+    report: Box<dyn FnMut(String) + 'a>,
+}
+
+impl MergePolicy for Lenient<'_> {
+    fn report(&mut self, message: String) {
+        (self.report)(message);
+    }
+
+    // the attribute is optional if it is optional in any version
+    fn resolve_required(
+        &mut self,
+        context: &str,
+        required: bool,
+        required_new: bool,
+    ) -> Result<bool, String> {
+        let resolved = required && required_new;
+        self.report(format!(
+            "{context}: required {required} / {required_new}, using {resolved}"
+        ));
+        Ok(resolved)
+    }
+
+    // an element that is restricted differently in the two versions is allowed everywhere
+    fn resolve_restrict_std(
+        &mut self,
+        context: &str,
+        restrict_std: XsdRestrictToStandard,
+        restrict_std_new: XsdRestrictToStandard,
+    ) -> Result<XsdRestrictToStandard, String> {
+        let resolved = XsdRestrictToStandard::NotSet;
+        self.report(format!(
+            "{context}: restrict_std {restrict_std:?} / {restrict_std_new:?}, using {resolved:?}"
+        ));
+        Ok(resolved)
+    }
+
+    // the multiplicity is widened so that it covers both versions
+    fn resolve_amount(
+        &mut self,
+        context: &str,
+        amount: ElementAmount,
+        amount_new: ElementAmount,
+    ) -> Result<ElementAmount, String> {
+        let resolved = match (amount, amount_new) {
+            (
+                ElementAmount::One | ElementAmount::ZeroOrOne,
+                ElementAmount::One | ElementAmount::ZeroOrOne,
+            ) => ElementAmount::ZeroOrOne,
+            _ => ElementAmount::Any,
+        };
+        self.report(format!(
+            "{context}: amount {amount:?} / {amount_new:?}, using {resolved:?}"
+        ));
+        Ok(resolved)
+    }
+
+    // the order of enum items is not relevant for the accepted documents, so it is kept
+    fn resolve_enum_order(&mut self, context: &str, item: &str) -> Result<(), String> {
+        self.report(format!(
+            "{context}: the item {item} is ordered differently, keeping the order of the merge base"
+        ));
+        Ok(())
+    }
+}
+
+struct Strict<'a> {
+    report: Box<dyn FnMut(String) + 'a>,
+}
+
+impl Strict<'_> {
+    // the conflict is still reported, so that the merge report shows where the merge stopped
+    fn fail<T>(&mut self, message: String) -> Result<T, String> {
+        (self.report)(message.clone());
+        Err(format!(
+            "Error: merge conflict (strict merge policy): {message}"
+        ))
+    }
+}
+
+impl MergePolicy for Strict<'_> {
+    fn report(&mut self, message: String) {
+        (self.report)(message);
+    }
+
+    fn resolve_required(
+        &mut self,
+        context: &str,
+        required: bool,
+        required_new: bool,
+    ) -> Result<bool, String> {
+        self.fail(format!("{context}: required {required} / {required_new}"))
+    }
+
+    fn resolve_restrict_std(
+        &mut self,
+        context: &str,
+        restrict_std: XsdRestrictToStandard,
+        restrict_std_new: XsdRestrictToStandard,
+    ) -> Result<XsdRestrictToStandard, String> {
+        self.fail(format!(
+            "{context}: restrict_std {restrict_std:?} / {restrict_std_new:?}"
+        ))
+    }
+
+    fn resolve_amount(
+        &mut self,
+        context: &str,
+        amount: ElementAmount,
+        amount_new: ElementAmount,
+    ) -> Result<ElementAmount, String> {
+        self.fail(format!("{context}: amount {amount:?} / {amount_new:?}"))
+    }
+
+    fn resolve_enum_order(&mut self, context: &str, item: &str) -> Result<(), String> {
+        self.fail(format!("{context}: the item {item} is ordered differently"))
+    }
+}
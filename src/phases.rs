@@ -0,0 +1,179 @@
+//! The processing phases of the tool, which can be run one at a time
+//!
+//! This is the interface of the benchmarks: each phase takes the result of the previous one, so that it can be
+//! measured on its own. The phases are the same as in [`crate::run`], but without the optional checks and reports.
+
+use crate::xsd::Xsd;
+use crate::{
+    config, dedup, flatten, generator, merge, AutosarDataTypes, CmdlineOptions, XsdFileInfo,
+};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub use crate::generator::GeneratorReport;
+
+/// the xsd files of one run, together with their versions
+pub struct Input {
+    xsd_config: Vec<XsdFileInfo>,
+    input_files: Vec<(usize, PathBuf)>,
+    missing_versions: Vec<usize>,
+}
+
+/// the parsed, but not yet flattened xsd files, from oldest to newest
+pub struct ParsedXsd(Vec<Xsd>);
+
+/// the flattened schema of each xsd file, from oldest to newest
+#[derive(Clone)]
+pub struct FlatSchemas(Vec<AutosarDataTypes>);
+
+/// the schema after the merge of all versions
+#[derive(Clone)]
+pub struct Schema(AutosarDataTypes);
+
+impl Input {
+    /// find the xsd files: `path` is either a single xsd file like with --single, or a directory of xsd files
+    ///
+    /// The configured versions whose xsd file is missing in the directory are skipped, like with --skip-missing.
+    pub fn find(path: &Path) -> Result<Self, String> {
+        let (xsd_config, input_files, missing_versions) = if path.is_dir() {
+            let options = CmdlineOptions {
+                skip_missing: true,
+                ..Default::default()
+            };
+            crate::configured_input_files(path, &options)?
+        } else {
+            let (xsd_config, input_files) = crate::single_input_file(path)?;
+            (xsd_config, input_files, Vec::new())
+        };
+        config::check_release_order(&xsd_config)?;
+        Ok(Self {
+            xsd_config,
+            input_files,
+            missing_versions,
+        })
+    }
+
+    /// the number of xsd files that were found
+    pub fn file_count(&self) -> usize {
+        self.input_files.len()
+    }
+}
+
+impl ParsedXsd {
+    /// the number of parsed xsd files
+    pub fn file_count(&self) -> usize {
+        self.0.len()
+    }
+
+    /// the number of named types of the xsd file `idx`, including the built-in base types
+    pub fn type_count(&self, idx: usize) -> usize {
+        self.0[idx].type_count()
+    }
+
+    /// the number of named groups of the xsd file `idx`, including the groups of anonymous model groups
+    pub fn group_count(&self, idx: usize) -> usize {
+        self.0[idx].group_count()
+    }
+
+    /// the number of named attribute groups of the xsd file `idx`
+    pub fn attribute_group_count(&self, idx: usize) -> usize {
+        self.0[idx].attribute_group_count()
+    }
+
+    /// the number of top-level elements of the xsd file `idx`
+    pub fn root_element_count(&self, idx: usize) -> usize {
+        self.0[idx].root_element_count()
+    }
+}
+
+impl FlatSchemas {
+    /// the flattened schema of each xsd file, from oldest to newest
+    pub fn schemas(&self) -> &[AutosarDataTypes] {
+        &self.0
+    }
+}
+
+impl Schema {
+    /// the merged schema
+    pub fn types(&self) -> &AutosarDataTypes {
+        &self.0
+    }
+}
+
+impl From<AutosarDataTypes> for Schema {
+    /// use a schema that was merged with [`crate::merge_all`] or [`AutosarDataTypes::merge_with`] in the later phases
+    fn from(types: AutosarDataTypes) -> Self {
+        Self(types)
+    }
+}
+
+/// parse phase: load each xsd file
+pub fn parse(input: &Input) -> Result<ParsedXsd, String> {
+    let xsd = input
+        .input_files
+        .iter()
+        .map(|(index, filepath)| Xsd::load(filepath, 1 << index))
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(ParsedXsd(xsd))
+}
+
+/// parse and flatten a single xsd document from any reader, e.g. from memory
+///
+/// This is the entry point of the fuzz target: every input must either succeed or return an error, but it must
+/// never panic. Includes are resolved relative to the current directory.
+pub fn parse_and_flatten_reader(reader: impl Read) -> Result<(), String> {
+    let xsd = Xsd::load_from_reader(reader, Path::new("input.xsd"), 1)?;
+    flatten::flatten_schema(&xsd)?;
+    Ok(())
+}
+
+/// flatten phase: resolve the references between the types of each xsd file
+pub fn flatten(parsed: &ParsedXsd) -> Result<FlatSchemas, String> {
+    let schemas = parsed
+        .0
+        .iter()
+        .map(flatten::flatten_schema)
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(FlatSchemas(schemas))
+}
+
+/// merge phase: merge all versions, with the newest version as the merge base and the default merge policy
+pub fn merge(schemas: &FlatSchemas) -> Result<Schema, String> {
+    let newest_first: Vec<AutosarDataTypes> = schemas.0.iter().rev().cloned().collect();
+    let mut policy = merge::MergePolicyKind::default().build(Box::new(|_| {}));
+    Ok(Schema(merge::merge_all(&newest_first, policy.as_mut())?))
+}
+
+/// dedup phase: combine the identical types of the merged schema
+pub fn dedup(schema: &mut Schema) {
+    dedup::dedup_types(&mut schema.0);
+}
+
+/// generate phase: write the generated code to the directory gen/ in the current directory
+///
+/// The output does not contain a timestamp, so that it only depends on the input. The returned report lists the
+/// written files and the sizes of the generated tables.
+pub fn generate(input: &Input, schema: &Schema) -> Result<GeneratorReport, String> {
+    generate_into(input, schema, Path::new("gen"))
+}
+
+/// generate phase like [`generate`], but the generated code is written to `output_dir`, which is created if necessary
+pub fn generate_into(
+    input: &Input,
+    schema: &Schema,
+    output_dir: &Path,
+) -> Result<GeneratorReport, String> {
+    let options = generator::GeneratorOptions {
+        input_files: input
+            .input_files
+            .iter()
+            .map(|(_, filepath)| filepath.clone())
+            .collect(),
+        no_timestamp: true,
+        missing_versions: input.missing_versions.clone(),
+        output_dir: Some(output_dir.to_path_buf()),
+        ..Default::default()
+    };
+    let schema_hash = crate::schema_hash(&input.input_files)?;
+    generator::generate(&input.xsd_config, &schema.0, &options, &schema_hash)
+}
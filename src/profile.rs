@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+/// wall-clock time and peak memory use of the processing phases, which are printed with --profile
+pub(crate) struct Profile {
+    enabled: bool,
+    // in the order in which the phases ran first; a phase that runs several times, e.g. once per xsd file, is summed up
+    phases: Vec<PhaseProfile>,
+}
+
+struct PhaseProfile {
+    name: &'static str,
+    elapsed: Duration,
+    peak_rss_kib: Option<u64>,
+}
+
+impl Profile {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            phases: Vec::new(),
+        }
+    }
+
+    /// run `phase` and record its wall-clock time and peak memory use under `name`
+    pub(crate) fn time<T>(&mut self, name: &'static str, phase: impl FnOnce() -> T) -> T {
+        if !self.enabled {
+            return phase();
+        }
+
+        reset_peak_rss();
+        let start = Instant::now();
+        let result = phase();
+        let elapsed = start.elapsed();
+        let peak_rss_kib = peak_rss_kib();
+
+        if let Some(phase_profile) = self.phases.iter_mut().find(|item| item.name == name) {
+            phase_profile.elapsed += elapsed;
+            phase_profile.peak_rss_kib = phase_profile.peak_rss_kib.max(peak_rss_kib);
+        } else {
+            self.phases.push(PhaseProfile {
+                name,
+                elapsed,
+                peak_rss_kib,
+            });
+        }
+        result
+    }
+
+    pub(crate) fn print(&self) {
+        if !self.enabled {
+            return;
+        }
+        println!("profile:");
+        for phase_profile in &self.phases {
+            let peak_rss = phase_profile
+                .peak_rss_kib
+                .map_or("unknown".to_string(), |kib| {
+                    format!("{:.1} MiB", kib as f64 / 1024.0)
+                });
+            println!(
+                "    {:<14}{:>9.3} s, peak RSS {peak_rss}",
+                phase_profile.name,
+                phase_profile.elapsed.as_secs_f64()
+            );
+        }
+    }
+}
+
+// the peak RSS is only known on linux, where it is part of /proc/self/status
+fn peak_rss_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    line["VmHWM:".len()..]
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+// writing 5 to clear_refs resets the peak RSS to the current RSS, so that each phase gets its own peak.
+// If this is not possible, the peak RSS of a phase is the peak since the start of the program.
+fn reset_peak_rss() {
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
@@ -0,0 +1,104 @@
+//! Single steps of the code generator, for the snapshot tests of the generated code
+//!
+//! The functions run one generator step on hand-written input and return the generated source, without the header
+//! and without writing any files. They are not a stable interface: the module only exists with the feature
+//! `testing`, which the tests and benchmarks of this crate enable through their dev-dependency on the crate itself.
+
+use crate::generator::{
+    element_types, identifier_enums, pack_version_sequences, packing, perfect_hash,
+    try_name_to_identifier, version_sequences, xsd_versions, GeneratorOptions,
+};
+use crate::{phases, xsd, Platforms, XsdFileInfo, AUTOSAR_NAMESPACE};
+use std::collections::HashMap;
+
+/// generate the enum `enum_name` of the items `item_names`, like `ElementName` in elementname.rs
+///
+/// All items are valid in a single version, and they have no docstrings.
+pub fn generate_enum(enum_name: &str, item_names: &[&str]) -> Result<String, String> {
+    let phash = perfect_hash::find_perfect_hash(item_names)
+        .map_err(|err| format!("Error: {enum_name}: {err}"))?;
+    Ok(identifier_enums::generate_enum(
+        enum_name,
+        &format!("Enum of all items of {enum_name}"),
+        item_names,
+        None,
+        None,
+        None,
+        1,
+        &phash,
+        &GeneratorOptions::default(),
+    ))
+}
+
+/// generate hashfunc.rs, which contains the hash function that the generated enums use
+pub fn generate_hashfunc_module() -> String {
+    identifier_enums::generate_hashfunc_module()
+}
+
+/// generate autosarversion.rs for the versions, which are given as (xsd file name, identifier, description)
+///
+/// The schema hash is all zeros.
+pub fn generate_autosar_version(versions: &[(&'static str, &'static str, &'static str)]) -> String {
+    let xsd_config: Vec<XsdFileInfo> = versions
+        .iter()
+        .map(|&(name, ident, desc)| XsdFileInfo {
+            name,
+            ident,
+            desc,
+            platforms: Platforms::ClassicAndAdaptive,
+            namespace: AUTOSAR_NAMESPACE,
+        })
+        .collect();
+    xsd_versions::generate(&xsd_config, &GeneratorOptions::default(), &[0; 32], "")
+}
+
+/// convert an xsd name to the identifier of its enum variant, e.g. "SHORT-NAME" -> "ShortName"
+pub fn name_to_identifier(name: &str) -> Result<String, String> {
+    try_name_to_identifier(name)
+}
+
+/// check if two element names share a prefix, which decides how anonymous complex types are named
+pub fn names_share_prefix(name_1: &str, name_2: &str) -> bool {
+    xsd::names_share_prefix(name_1, name_2)
+}
+
+/// build the entries of REF_ITEMS from the ref targets of each data type, like the generator of specification.rs
+///
+/// Returns the entries and the ref_info range of each data type.
+pub fn ref_items(ref_targets: &[Vec<String>]) -> (Vec<String>, Vec<(usize, usize)>) {
+    let mut ref_items = element_types::RefItems::default();
+    let ranges = ref_targets
+        .iter()
+        .map(|targets| ref_items.insert(targets.clone()))
+        .collect();
+    (ref_items.items, ranges)
+}
+
+/// the lists of versions that are packed into VERSION_INFO, for a schema after the dedup phase
+pub fn version_info_sequences(schema: &phases::Schema) -> Result<Vec<Vec<usize>>, String> {
+    version_sequences(schema.types())
+}
+
+/// pack the sequences into one array like VERSION_INFO, and return the array and the offset of each sequence
+pub fn pack_sequences(sequences: &[&[usize]]) -> (Vec<usize>, Vec<usize>) {
+    packing::pack_sequences(sequences)
+}
+
+/// the offset of the list of versions of each data type in VERSION_INFO
+pub type VersionOffsets = HashMap<String, usize>;
+
+/// pack the lists of versions of the sub elements and of the attributes of each data type into VERSION_INFO
+///
+/// Returns the array, and the offset of the lists of the sub elements and of the attributes of each data type.
+pub fn pack_versions(
+    subelement_sequences: &[(String, Vec<usize>)],
+    attribute_sequences: &[(String, Vec<usize>)],
+) -> Result<(Vec<usize>, VersionOffsets, VersionOffsets), String> {
+    let (versions, subelement_offsets, attribute_offsets) =
+        pack_version_sequences(subelement_sequences, attribute_sequences)?;
+    Ok((
+        versions,
+        subelement_offsets.into_iter().collect(),
+        attribute_offsets.into_iter().collect(),
+    ))
+}
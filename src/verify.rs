@@ -0,0 +1,260 @@
+use crate::generator::fullmatch_pattern;
+use crate::{AutosarDataTypes, CharacterDataType, ElementCollectionItem, Symbol};
+use std::collections::{HashSet, VecDeque};
+
+// a pair of type names (merged schema, input schema) which is checked by verify_merge
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+enum TypePair {
+    Element(Symbol, Symbol),
+    Group(Symbol, Symbol),
+    Character(Symbol, Symbol),
+}
+
+/// verify the merged schema before any code is generated from it
+///
+/// This is run by --verify. The checks of the patterns are also part of the generation, since the generated
+/// validators would silently misbehave if a pattern was broken.
+pub(crate) fn verify(autosar_schema: &AutosarDataTypes) -> Result<(), String> {
+    verify_patterns(autosar_schema)?;
+    println!("verification passed");
+    Ok(())
+}
+
+/// check that every pattern compiles, in the anchored form that is used by the generator
+///
+/// A pattern that only matches strings which are longer than the max_length of its type is reported with a warning.
+/// Returns the number of warnings.
+pub(crate) fn verify_patterns(autosar_schema: &AutosarDataTypes) -> Result<usize, String> {
+    let mut typenames: Vec<&str> = autosar_schema
+        .character_types
+        .keys()
+        .map(|typename| autosar_schema.names.resolve(*typename))
+        .collect();
+    typenames.sort();
+
+    let mut failures = Vec::new();
+    let mut warning_count = 0;
+    for typename in typenames {
+        let Some(CharacterDataType::Pattern {
+            pattern,
+            max_length,
+        }) = autosar_schema.character_type(typename)
+        else {
+            continue;
+        };
+        let fullmatch = fullmatch_pattern(pattern);
+        if let Err(err) = regex::Regex::new(&fullmatch) {
+            // the error message of the regex crate spans several lines, which are indented below the type
+            let err_lines: Vec<String> = err
+                .to_string()
+                .lines()
+                .map(|line| format!("        {line}\n"))
+                .collect();
+            failures.push(format!("    {typename}: {pattern}\n{}", err_lines.concat()));
+            continue;
+        }
+        let min_length = regex_syntax::parse(&fullmatch)
+            .ok()
+            .and_then(|hir| hir.properties().minimum_len());
+        if let (Some(min_length), Some(max_length)) = (min_length, max_length) {
+            if min_length > *max_length {
+                println!("Warning: the pattern of {typename} only matches strings of at least {min_length} characters, but its max_length is {max_length}: {pattern}");
+                warning_count += 1;
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(warning_count)
+    } else {
+        Err(format!(
+            "Error: {} patterns failed to compile:\n{}",
+            failures.len(),
+            failures.concat()
+        ))
+    }
+}
+
+/// check that every item of an input schema is part of the merged schema, with the version bits of the input
+///
+/// This is run by --verify-merge. The schemas are traversed in the same way as the merge: starting at AR:AUTOSAR,
+/// the elements of the groups are paired by name, and the attributes and enum items are paired by their names.
+/// Returns a description of each item that is missing or lacks a version bit, together with the path of element
+/// names from the root element to the item.
+pub(crate) fn verify_merge(merged: &AutosarDataTypes, input: &AutosarDataTypes) -> Vec<String> {
+    let mut missing = Vec::new();
+    let Some(input_root) = input.names.get("AR:AUTOSAR") else {
+        return missing;
+    };
+    if !input.element_types.contains_key(&input_root) {
+        return missing;
+    }
+    let Some(merged_root) = merged.names.get("AR:AUTOSAR") else {
+        missing.push("AUTOSAR: element type AR:AUTOSAR".to_string());
+        return missing;
+    };
+    let mut visited = HashSet::new();
+    // breadth first, so that the shortest path to each item is reported
+    let mut queue = VecDeque::from([(
+        "AUTOSAR".to_string(),
+        TypePair::Element(merged_root, input_root),
+    )]);
+
+    while let Some((path, pair)) = queue.pop_front() {
+        if !visited.insert(pair) {
+            continue;
+        }
+        match pair {
+            TypePair::Element(merged_typename, input_typename) => {
+                let Some(input_type) = input.element_types.get(&input_typename) else {
+                    continue;
+                };
+                let Some(merged_type) = merged.element_types.get(&merged_typename) else {
+                    missing.push(format!(
+                        "{path}: element type {}",
+                        input.names.resolve(input_typename)
+                    ));
+                    continue;
+                };
+                for attr in input_type.attributes() {
+                    match merged_type
+                        .attributes()
+                        .iter()
+                        .find(|merged_attr| merged_attr.name == attr.name)
+                    {
+                        Some(merged_attr)
+                            if merged_attr.version_info & attr.version_info
+                                == attr.version_info =>
+                        {
+                            queue.push_back((
+                                format!("{path}@{}", attr.name),
+                                TypePair::Character(merged_attr.attr_type, attr.attr_type),
+                            ));
+                        }
+                        Some(_) => {
+                            missing.push(format!("{path}: version of attribute {}", attr.name))
+                        }
+                        None => missing.push(format!("{path}: attribute {}", attr.name)),
+                    }
+                }
+                match (merged_type.basetype(), input_type.basetype()) {
+                    (Some(merged_basetype), Some(input_basetype)) => queue.push_back((
+                        path.clone(),
+                        TypePair::Character(merged_basetype, input_basetype),
+                    )),
+                    (None, Some(input_basetype)) => {
+                        missing.push(format!(
+                            "{path}: character content {}",
+                            input.names.resolve(input_basetype)
+                        ));
+                    }
+                    _ => {}
+                }
+                match (merged_type.group_ref(), input_type.group_ref()) {
+                    (Some(merged_group), Some(input_group)) => {
+                        queue.push_back((path, TypePair::Group(merged_group, input_group)));
+                    }
+                    (None, Some(input_group)) => {
+                        missing.push(format!(
+                            "{path}: sub elements of {}",
+                            input.names.resolve(input_group)
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+            TypePair::Group(merged_groupname, input_groupname) => {
+                let (Some(merged_group), Some(input_group)) = (
+                    merged.group_types.get(&merged_groupname),
+                    input.group_types.get(&input_groupname),
+                ) else {
+                    continue;
+                };
+                for item in input_group.items() {
+                    match item {
+                        ElementCollectionItem::Element(element) => {
+                            let mut same_name = merged_group
+                                .items()
+                                .iter()
+                                .filter_map(|merged_item| match merged_item {
+                                    ElementCollectionItem::Element(merged_element)
+                                        if merged_element.name == element.name =>
+                                    {
+                                        Some(merged_element)
+                                    }
+                                    _ => None,
+                                })
+                                .peekable();
+                            if same_name.peek().is_none() {
+                                missing.push(format!("{path}: element {}", element.name));
+                                continue;
+                            }
+                            // there can be several elements with the same name, but with different versions
+                            if let Some(merged_element) = same_name.find(|merged_element| {
+                                merged_element.version_info & element.version_info
+                                    == element.version_info
+                            }) {
+                                queue.push_back((
+                                    format!("{path}/{}", element.name),
+                                    TypePair::Element(merged_element.typeref, element.typeref),
+                                ));
+                            } else {
+                                missing
+                                    .push(format!("{path}: version of element {}", element.name));
+                            }
+                        }
+                        ElementCollectionItem::GroupRef(group_ref) => {
+                            let group_name = input.names.resolve(*group_ref);
+                            if let Some(merged_ref) = merged_group.items().iter().find_map(
+                                |merged_item| match merged_item {
+                                    ElementCollectionItem::GroupRef(merged_ref)
+                                        if merged.names.resolve(*merged_ref) == group_name =>
+                                    {
+                                        Some(*merged_ref)
+                                    }
+                                    _ => None,
+                                },
+                            ) {
+                                queue.push_back((
+                                    path.clone(),
+                                    TypePair::Group(merged_ref, *group_ref),
+                                ));
+                            } else {
+                                missing.push(format!("{path}: group {group_name}"));
+                            }
+                        }
+                    }
+                }
+            }
+            TypePair::Character(merged_typename, input_typename) => {
+                let Some(CharacterDataType::Enum(input_enum)) =
+                    input.character_types.get(&input_typename)
+                else {
+                    continue;
+                };
+                let Some(CharacterDataType::Enum(merged_enum)) =
+                    merged.character_types.get(&merged_typename)
+                else {
+                    missing.push(format!(
+                        "{path}: enum {}",
+                        input.names.resolve(input_typename)
+                    ));
+                    continue;
+                };
+                for (item, version, _) in &input_enum.enumitems {
+                    match merged_enum
+                        .enumitems
+                        .iter()
+                        .find(|(merged_item, _, _)| merged_item == item)
+                    {
+                        Some((_, merged_version, _)) if merged_version & version == *version => {}
+                        Some(_) => missing.push(format!("{path}: version of enum item {item}")),
+                        None => missing.push(format!("{path}: enum item {item}")),
+                    }
+                }
+            }
+        }
+    }
+
+    missing
+}
@@ -1,29 +1,39 @@
-use std::io::BufReader;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashSet;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
 use std::{collections::HashMap, fs::File};
 use xml::ParserConfig;
 use xml::{
     attribute::OwnedAttribute,
     common::{Position, TextPosition, XmlVersion},
     name::OwnedName,
+    namespace::Namespace,
     reader::{EventReader, XmlEvent},
 };
 
-#[derive(Debug, Eq, PartialEq)]
+/// the namespace of the xml schema definitions, usually with the prefix xsd
+const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdAttribute {
     pub(crate) name: String,
     pub(crate) typeref: String,
     pub(crate) required: bool,
+    pub(crate) doctext: Option<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct XsdAttributeGroup {
     pub(crate) attributes: Vec<XsdAttribute>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum XsdRestriction {
     EnumValues {
-        enumvalues: Vec<String>,
+        /// the value of each enumeration, together with its documentation
+        enumvalues: Vec<(String, Option<String>)>,
     },
     Pattern {
         pattern: String,
@@ -35,19 +45,27 @@ pub(crate) enum XsdRestriction {
     Literal,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdExtension {
     pub(crate) basetype: String,
     pub(crate) attributes: Vec<XsdAttribute>,
     pub(crate) attribute_groups: Vec<String>,
+    /// the content group of an extension inside of complexContent
+    ///
+    /// After loading it refers to a generated group that contains the content of the base type followed by
+    /// the content of the extension.
+    pub(crate) group: Option<String>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdSimpleContent {
+    /// the base type and the attributes; a restriction is stored as an extension without additional attributes
     pub(crate) extension: XsdExtension,
+    /// the name of the simple type that holds the facets of a restriction, which replace the content of the base type
+    pub(crate) restriction: Option<String>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum XsdModelGroupItem {
     Group(String),
     //Sequence(Box<XsdSequence>),
@@ -55,31 +73,36 @@ pub(crate) enum XsdModelGroupItem {
     Element(XsdElement),
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdSequence {
     pub(crate) items: Vec<XsdModelGroupItem>,
 }
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdChoice {
     pub(crate) min_occurs: usize,
     pub(crate) max_occurs: usize,
     pub(crate) items: Vec<XsdModelGroupItem>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum XsdGroupItem {
     Choice(XsdChoice),
     Sequence(XsdSequence),
     None,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdGroup {
     pub(crate) item: XsdGroupItem,
+    /// the tags of the annotation of the group definition, which apply to all elements in the group
+    pub(crate) tags: HashMap<String, String>,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Default, Clone, Copy, PartialOrd, Ord)]
-pub(crate) enum XsdRestrictToStandard {
+/// the platforms to which an element is restricted by the xsd
+#[derive(
+    Debug, Eq, PartialEq, Hash, Default, Clone, Copy, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum XsdRestrictToStandard {
     #[default]
     NotSet,
     ClassicPlatform,
@@ -87,7 +110,10 @@ pub(crate) enum XsdRestrictToStandard {
     Both,
 }
 
-#[derive(Debug, Eq, PartialEq, Hash)]
+/// (language, text) of each documentation in an annotation; the language is given by xml:lang
+pub(crate) type XsdDocText = Vec<(Option<String>, String)>;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub(crate) struct XsdElement {
     pub(crate) name: String,
     pub(crate) typeref: String,
@@ -96,33 +122,37 @@ pub(crate) struct XsdElement {
     pub(crate) ordered: bool,
     pub(crate) splittable: bool,
     pub(crate) restrict_std: XsdRestrictToStandard,
-    pub(crate) doctext: Option<String>,
+    pub(crate) doctext: XsdDocText,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum XsdComplexTypeItem {
     SimpleContent(XsdSimpleContent),
+    Extension(XsdExtension),
     Group(String),
     None,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) struct XsdComplexType {
     pub(crate) name: String,
     pub(crate) item: XsdComplexTypeItem,
+    pub(crate) attributes: Vec<XsdAttribute>,
     pub(crate) attribute_groups: Vec<String>,
     pub(crate) mixed_content: bool,
-    pub(crate) mm_class: Option<String>,
-    pub(crate) doctext: Option<String>,
+    pub(crate) mm_class: Option<Vec<String>>,
+    pub(crate) doctext: XsdDocText,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub(crate) enum XsdSimpleType {
     Restriction(XsdRestriction),
     // Extension - this variant exists in the xsd specification, but is not used in the Autosar xsd files
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
+// the types are only stored once in a HashMap, so the size of the complex variant doesn't matter
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum XsdType {
     Base(String),
     Simple(XsdSimpleType),
@@ -136,6 +166,17 @@ pub(crate) struct Xsd {
     pub(crate) types: HashMap<String, XsdType>,
     pub(crate) attribute_groups: HashMap<String, XsdAttributeGroup>,
     pub(crate) version_info: usize,
+    /// the targetNamespace of the main schema file
+    pub(crate) target_namespace: Option<String>,
+    /// the types of the elements that can be referenced, according to the <keyref> identity constraints
+    pub(crate) reference_targets: HashSet<String>,
+    loaded_files: HashSet<PathBuf>,
+    // the names of the <key> and <unique> identity constraints, with the names of the elements that they select
+    identity_constraints: HashMap<String, Vec<String>>,
+    // the names of the identity constraints that are referred to by a <keyref>
+    keyref_refers: HashSet<String>,
+    // the names of the types that were imported from the standard schema by load_extension; they are already resolved
+    imported_types: HashSet<String>,
 }
 
 struct StartElementInfo {
@@ -146,27 +187,101 @@ struct StartElementInfo {
 
 impl Xsd {
     /// load and parse an xsd schema document for the Autosar standard
-    pub(crate) fn load(file: File, version_info: usize) -> Result<Xsd, String> {
-        let file = BufReader::new(file);
-        // let mut parser = EventReader::new(file);
-        let mut parser = ParserConfig::new()
-            .trim_whitespace(true)
-            .ignore_comments(false)
-            .coalesce_characters(false)
-            .create_reader(file);
+    pub(crate) fn load(filepath: &Path, version_info: usize) -> Result<Xsd, String> {
+        let mut data = Xsd::with_base_types(version_info);
+        load_schema_file(filepath, &mut data)?;
+        data.resolve()?;
+        Ok(data)
+    }
 
+    /// parse an xsd schema document from any reader, e.g. from memory
+    ///
+    /// `filepath` is only used to find the files of includes and redefines; it does not need to exist.
+    pub(crate) fn load_from_reader<R: Read>(
+        reader: R,
+        filepath: &Path,
+        version_info: usize,
+    ) -> Result<Xsd, String> {
+        let mut data = Xsd::with_base_types(version_info);
+        let mut parser = create_parser(reader);
+        parse_schema(&mut parser, &mut data, filepath)?;
+        data.resolve()?;
+        Ok(data)
+    }
+
+    /// load and parse a vendor extension schema, which is layered on top of the standard schema `standard`
+    ///
+    /// An extension imports the AUTOSAR namespace instead of defining the whole schema: it has no root element,
+    /// and it refers to the types of the standard which it doesn't define itself. These references are resolved
+    /// against `standard`, normally the newest standard version. The definitions of the extension replace those of
+    /// the standard, so the result is the schema of the standard as it is modified by the extension.
+    pub(crate) fn load_extension(
+        filepath: &Path,
+        version_info: usize,
+        standard: &Xsd,
+    ) -> Result<Xsd, String> {
+        let mut data = Xsd::with_base_types(version_info);
+        load_schema_file(filepath, &mut data)?;
+        data.import(standard);
+        data.resolve()?;
+        Ok(data)
+    }
+
+    // add all definitions of the standard schema that are not replaced by this schema
+    fn import(&mut self, standard: &Xsd) {
+        for (name, xsd_type) in &standard.types {
+            if let Entry::Vacant(entry) = self.types.entry(name.clone()) {
+                entry.insert(xsd_type.clone());
+                self.imported_types.insert(name.clone());
+            }
+        }
+        for (name, group) in &standard.groups {
+            self.groups
+                .entry(name.clone())
+                .or_insert_with(|| group.clone());
+        }
+        for (name, attribute_group) in &standard.attribute_groups {
+            self.attribute_groups
+                .entry(name.clone())
+                .or_insert_with(|| attribute_group.clone());
+        }
+        for (name, selected_names) in &standard.identity_constraints {
+            self.identity_constraints
+                .entry(name.clone())
+                .or_insert_with(|| selected_names.clone());
+        }
+        self.keyref_refers
+            .extend(standard.keyref_refers.iter().cloned());
+        self.reference_targets
+            .extend(standard.reference_targets.iter().cloned());
+        // the extension can only be used inside of the documents of the standard
+        if self.root_elements.is_empty() {
+            self.root_elements.clone_from(&standard.root_elements);
+        }
+    }
+
+    fn with_base_types(version_info: usize) -> Xsd {
         let mut data = Xsd {
             attribute_groups: HashMap::new(),
             groups: HashMap::new(),
             types: HashMap::new(),
             root_elements: Vec::new(),
             version_info,
+            target_namespace: None,
+            reference_targets: HashSet::new(),
+            loaded_files: HashSet::new(),
+            identity_constraints: HashMap::new(),
+            keyref_refers: HashSet::new(),
+            imported_types: HashSet::new(),
         };
         // create the base type for the xml:space attribute directly instead of parsing xml.xsd
         data.types.insert(
             "XML:SPACE".to_string(),
             XsdType::Simple(XsdSimpleType::Restriction(XsdRestriction::EnumValues {
-                enumvalues: vec!["default".to_string(), "preserve".to_string()],
+                enumvalues: vec![
+                    ("default".to_string(), None),
+                    ("preserve".to_string(), None),
+                ],
             })),
         );
         data.types.insert(
@@ -189,14 +304,105 @@ impl Xsd {
             "xsd:double".to_string(),
             XsdType::Base("xsd:double".to_string()),
         );
+        data.types.insert(
+            "xsd:dateTime".to_string(),
+            XsdType::Base("xsd:dateTime".to_string()),
+        );
+        data
+    }
+
+    // the base type of an extension may be defined after the extension, so this is only possible after parsing
+    fn resolve(&mut self) -> Result<(), String> {
+        resolve_complex_content_extensions(self)?;
+        resolve_reference_targets(self);
+        Ok(())
+    }
 
-        parse_schema(&mut parser, &mut data)?;
+    /// number of named types, including the built-in base types
+    pub(crate) fn type_count(&self) -> usize {
+        self.types.len()
+    }
 
-        Ok(data)
+    /// number of named groups, including the groups that were created for anonymous model groups
+    pub(crate) fn group_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// number of named attribute groups
+    pub(crate) fn attribute_group_count(&self) -> usize {
+        self.attribute_groups.len()
+    }
+
+    /// number of top-level elements
+    pub(crate) fn root_element_count(&self) -> usize {
+        self.root_elements.len()
     }
 }
 
-fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Result<(), String> {
+/// the xsd file `filepath` followed by all files that it includes or redefines, directly or indirectly
+///
+/// Only the include and redefine elements are read, so this is much faster than loading the schema.
+pub(crate) fn schema_files(filepath: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut loaded_files = HashSet::new();
+    let mut pending_files = vec![filepath.to_path_buf()];
+    while let Some(filepath) = pending_files.pop() {
+        let canonical_path = filepath
+            .canonicalize()
+            .map_err(|err| format!("Error: could not open {}: {err}", filepath.display()))?;
+        if !loaded_files.insert(canonical_path) {
+            continue;
+        }
+
+        let file = File::open(&filepath)
+            .map_err(|err| format!("Error: could not open {}: {err}", filepath.display()))?;
+        for event in create_parser(BufReader::new(file)) {
+            let event = event
+                .map_err(|err| format!("Error: failed to parse {}: {err}", filepath.display()))?;
+            if let XmlEvent::StartElement {
+                name, attributes, ..
+            } = event
+            {
+                if name.local_name == "include" || name.local_name == "redefine" {
+                    if let Some(location) = get_attribute_value("schemaLocation", &attributes) {
+                        pending_files.push(referenced_schema_path(&filepath, location));
+                    }
+                }
+            }
+        }
+        files.push(filepath);
+    }
+    Ok(files)
+}
+
+fn load_schema_file(filepath: &Path, data: &mut Xsd) -> Result<(), String> {
+    let canonical_path = filepath
+        .canonicalize()
+        .map_err(|err| format!("Error: could not open {}: {err}", filepath.display()))?;
+    if !data.loaded_files.insert(canonical_path) {
+        // this file was already loaded through a different include or redefine
+        return Ok(());
+    }
+
+    let file = File::open(filepath)
+        .map_err(|err| format!("Error: could not open {}: {err}", filepath.display()))?;
+    let mut parser = create_parser(BufReader::new(file));
+    parse_schema(&mut parser, data, filepath)
+}
+
+fn create_parser<R: Read>(reader: R) -> EventReader<R> {
+    ParserConfig::new()
+        .trim_whitespace(true)
+        .ignore_comments(false)
+        .coalesce_characters(false)
+        .create_reader(reader)
+}
+
+fn parse_schema<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    filepath: &Path,
+) -> Result<(), String> {
     let head = get_next_event(parser)?;
     if let XmlEvent::StartDocument {
         version: XmlVersion::Version10,
@@ -213,6 +419,7 @@ fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Re
     let schema = get_next_event(parser)?;
     if let XmlEvent::StartElement {
         name: OwnedName { local_name, .. },
+        attributes,
         ..
     } = schema
     {
@@ -221,6 +428,11 @@ fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Re
                 "Error: not a valid xsd document, found element <{local_name}> where <schema> was expected"
             ));
         }
+        // the main schema file is loaded first; included files don't change the namespace
+        if data.target_namespace.is_none() {
+            data.target_namespace =
+                get_attribute_value("targetNamespace", &attributes).map(str::to_string);
+        }
     } else {
         return Err(format!(
             "Error: not a valid xsd document. Found {schema:?} where element <schema> was expected"
@@ -230,8 +442,20 @@ fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Re
     while let Some(element_info) = get_next_element(parser, "schema")? {
         match element_info.name.as_ref() {
             "import" => {
-                // no handling of imports, just consume the EndElement
-                get_next_event(parser)?;
+                // imported definitions are only used by extensions, which get them from the standard schema
+                get_element_end_tag(parser, "import")?;
+            }
+            "include" => {
+                let location = get_required_attribute_value(
+                    "schemaLocation",
+                    &element_info.attributes,
+                    &parser.position(),
+                )?;
+                get_element_end_tag(parser, "include")?;
+                load_schema_file(&referenced_schema_path(filepath, location), data)?;
+            }
+            "redefine" => {
+                parse_redefine(parser, data, &element_info.attributes, filepath)?;
             }
             "group" => {
                 parse_group(parser, data, &element_info.attributes, vec![])?;
@@ -259,6 +483,10 @@ fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Re
                 let element = parse_element(parser, data, &element_info.attributes, vec![])?;
                 data.root_elements.push(element);
             }
+            "notation" => {
+                // notations (e.g. of MIME types) don't affect the elements, so they are skipped together with their annotation
+                get_element_end_tag(parser, "notation")?;
+            }
             _ => {
                 return Err(format!(
                     "Error: found unexpected start of element tag \"{}\" at {}",
@@ -279,8 +507,199 @@ fn parse_schema(parser: &mut EventReader<BufReader<File>>, data: &mut Xsd) -> Re
     }
 }
 
-fn parse_element(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_redefine<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    attributes: &Vec<OwnedAttribute>,
+    filepath: &Path,
+) -> Result<(), String> {
+    let location = get_required_attribute_value("schemaLocation", attributes, &parser.position())?;
+    // the referenced schema is loaded first, then the definitions inside <redefine> replace its content
+    load_schema_file(&referenced_schema_path(filepath, location), data)?;
+
+    while let Some(element_info) = get_next_element(parser, "redefine")? {
+        match element_info.name.as_ref() {
+            "annotation" => {
+                skip_annotation(parser)?;
+            }
+            "simpleType" => {
+                // a redefined simple type restricts the original definition, which it references by its own name
+                let name = get_required_attribute_value(
+                    "name",
+                    &element_info.attributes,
+                    &parser.position(),
+                )?;
+                let typeref = format!("AR:{name}");
+                let base_typeref = format!("{typeref}/REDEFINE-BASE");
+                let original_type = data.types.remove(&typeref);
+                parse_simple_type(parser, data, &element_info.attributes)?;
+                if let Some(XsdType::Simple(XsdSimpleType::Restriction(XsdRestriction::Plain {
+                    basetype,
+                }))) = data.types.get_mut(&typeref)
+                {
+                    if *basetype == typeref {
+                        basetype.clone_from(&base_typeref);
+                        insert_redefine_base(
+                            &mut data.types,
+                            base_typeref,
+                            original_type,
+                            "simpleType",
+                            name,
+                        )?;
+                    }
+                }
+            }
+            "attributeGroup" => {
+                parse_attribute_group(parser, data, &element_info.attributes)?;
+            }
+            "complexType" => {
+                // the original definition is renamed, so that parse_complex_type doesn't warn about replacing it, and
+                // so that the groups generated for the redefinition don't overwrite those of the original
+                let name = get_required_attribute_value(
+                    "name",
+                    &element_info.attributes,
+                    &parser.position(),
+                )?;
+                let typeref = format!("AR:{name}");
+                let base_name = format!("{name}/REDEFINE-BASE");
+                let has_original = rename_complex_type(data, name, &base_name);
+                parse_complex_type(
+                    parser,
+                    data,
+                    &element_info.attributes,
+                    element_info.comment,
+                    vec![],
+                )?;
+                let references_itself = match data.types.get_mut(&typeref) {
+                    Some(XsdType::Complex(complex_type)) => {
+                        complex_type.replace_basetype(&typeref, &format!("AR:{base_name}"))
+                    }
+                    _ => false,
+                };
+                if references_itself && !has_original {
+                    return Err(format!(
+                        "Error: redefined complexType {name} references itself, but there is no original definition"
+                    ));
+                } else if !references_itself && has_original {
+                    remove_complex_type(data, &base_name);
+                }
+            }
+            "group" => {
+                // inside of the redefined group, a reference to the group itself refers to the original definition
+                let name = get_required_attribute_value(
+                    "name",
+                    &element_info.attributes,
+                    &parser.position(),
+                )?;
+                let typeref = format!("AR:{name}");
+                let base_typeref = format!("{typeref}/REDEFINE-BASE");
+                let original_group = data.groups.remove(&typeref);
+                parse_group(parser, data, &element_info.attributes, vec![])?;
+                if let Some(group) = data.groups.get_mut(&typeref) {
+                    if group.item.replace_group_ref(&typeref, &base_typeref) {
+                        insert_redefine_base(
+                            &mut data.groups,
+                            base_typeref,
+                            original_group,
+                            "group",
+                            name,
+                        )?;
+                    }
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Error: found unexpected start of element tag \"{}\" at {}",
+                    element_info.name,
+                    parser.position()
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// keep the original definition of a redefined type or group, which the redefinition references as its base
+fn insert_redefine_base<T>(
+    definitions: &mut HashMap<String, T>,
+    base_typeref: String,
+    original: Option<T>,
+    kind: &str,
+    name: &str,
+) -> Result<(), String> {
+    if let Some(original) = original {
+        definitions.insert(base_typeref, original);
+        Ok(())
+    } else {
+        Err(format!(
+            "Error: redefined {kind} {name} references itself, but there is no original definition"
+        ))
+    }
+}
+
+// the names of the groups and of the restriction type that parse_complex_type generates for the complex type `name`
+fn generated_definitions(name: &str) -> ([String; 2], String) {
+    (
+        [format!("{name}/ELEMENTGROUP"), format!("{name}/EXTENSION")],
+        format!("AR:{name}--RESTRICTION"),
+    )
+}
+
+// rename the complex type `name`, together with the definitions that were generated for it, to `new_name`
+//
+// returns false if there is no complex type `name`
+fn rename_complex_type(data: &mut Xsd, name: &str, new_name: &str) -> bool {
+    let Some(XsdType::Complex(mut complex_type)) = data.types.remove(&format!("AR:{name}")) else {
+        return false;
+    };
+    let (groups, restriction_typeref) = generated_definitions(name);
+    let (new_groups, new_restriction_typeref) = generated_definitions(new_name);
+    for (group_name, new_group_name) in groups.iter().zip(&new_groups) {
+        if let Some(group) = data.groups.remove(group_name) {
+            data.groups.insert(new_group_name.clone(), group);
+        }
+    }
+    if let Some(restriction_type) = data.types.remove(&restriction_typeref) {
+        data.types
+            .insert(new_restriction_typeref.clone(), restriction_type);
+    }
+
+    complex_type.name = new_name.to_string();
+    match &mut complex_type.item {
+        XsdComplexTypeItem::Group(group)
+        | XsdComplexTypeItem::Extension(XsdExtension {
+            group: Some(group), ..
+        }) => {
+            if let Some(pos) = groups.iter().position(|group_name| group_name == group) {
+                group.clone_from(&new_groups[pos]);
+            }
+        }
+        XsdComplexTypeItem::SimpleContent(XsdSimpleContent {
+            restriction: Some(restriction),
+            ..
+        }) if *restriction == restriction_typeref => {
+            *restriction = new_restriction_typeref;
+        }
+        _ => {}
+    }
+    data.types
+        .insert(format!("AR:{new_name}"), XsdType::Complex(complex_type));
+    true
+}
+
+// remove the complex type `name` together with the definitions that were generated for it
+fn remove_complex_type(data: &mut Xsd, name: &str) {
+    let (groups, restriction_typeref) = generated_definitions(name);
+    for group_name in &groups {
+        data.groups.remove(group_name);
+    }
+    data.types.remove(&restriction_typeref);
+    data.types.remove(&format!("AR:{name}"));
+}
+
+fn parse_element<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
     mut prev_names: Vec<String>,
@@ -293,7 +712,7 @@ fn parse_element(
     let max_occurs = parse_occurs_attribute(attr_max_occurs)?;
     let min_occurs = parse_occurs_attribute(attr_min_occurs)?;
     let mut mm_attributes = HashMap::new();
-    let mut doctext = None;
+    let mut doctext = Vec::new();
 
     if let Some(typeref) = attr_typeref {
         while let Some(element_info) = get_next_element(parser, "element")? {
@@ -301,6 +720,14 @@ fn parse_element(
                 "annotation" => {
                     (mm_attributes, doctext) = parse_annotation(parser)?;
                 }
+                "key" | "keyref" | "unique" => {
+                    parse_identity_constraint(
+                        parser,
+                        data,
+                        &element_info.name,
+                        &element_info.attributes,
+                    )?;
+                }
                 _ => {
                     return Err(format!(
                         "Error: found unexpected start of element tag \"{}\" at {}",
@@ -313,7 +740,7 @@ fn parse_element(
         let ordered = mm_attributes
             .get("pureMM.isOrdered")
             .is_some_and(|val| val == "true");
-        let splittable = mm_attributes.get("atpSplitable").is_some();
+        let splittable = mm_attributes.contains_key("atpSplitable");
         let restrict_std = get_restrict_to_standard(&mm_attributes);
 
         Ok(XsdElement {
@@ -348,6 +775,14 @@ fn parse_element(
                         prev_names.clone(),
                     )?);
                 }
+                "key" | "keyref" | "unique" => {
+                    parse_identity_constraint(
+                        parser,
+                        data,
+                        &element_info.name,
+                        &element_info.attributes,
+                    )?;
+                }
                 _ => {
                     return Err(format!(
                         "Error: found unexpected start of element tag \"{}\" at {}",
@@ -362,7 +797,7 @@ fn parse_element(
             let ordered = mm_attributes
                 .get("pureMM.isOrdered")
                 .is_some_and(|val| val == "true");
-            let splittable = mm_attributes.get("atpSplitable").is_some();
+            let splittable = mm_attributes.contains_key("atpSplitable");
             let restrict_std = get_restrict_to_standard(&mm_attributes);
 
             Ok(XsdElement {
@@ -384,8 +819,121 @@ fn parse_element(
     }
 }
 
-fn parse_group(
-    parser: &mut EventReader<BufReader<File>>,
+// parse one of the identity constraints <key>, <keyref> or <unique> inside of an element declaration
+//
+// Only the element names of the selector are used: the keys that are referred to by a keyref select the elements
+// which can be the target of a reference. The fields are not needed for this.
+fn parse_identity_constraint<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    constraint: &str,
+    attributes: &Vec<OwnedAttribute>,
+) -> Result<(), String> {
+    let name = get_required_attribute_value("name", attributes, &parser.position())?;
+    let refer = if constraint == "keyref" {
+        Some(get_required_attribute_value(
+            "refer",
+            attributes,
+            &parser.position(),
+        )?)
+    } else {
+        None
+    };
+
+    let mut selected_names = Vec::new();
+    while let Some(element_info) = get_next_element(parser, constraint)? {
+        match element_info.name.as_ref() {
+            "annotation" => {
+                skip_annotation(parser)?;
+            }
+            "selector" => {
+                let xpath = get_required_attribute_value(
+                    "xpath",
+                    &element_info.attributes,
+                    &parser.position(),
+                )?;
+                selected_names = selector_element_names(xpath);
+                get_element_end_tag(parser, "selector")?;
+            }
+            "field" => {
+                get_element_end_tag(parser, "field")?;
+            }
+            _ => {
+                return Err(format!(
+                    "Error: found unexpected start of element tag \"{}\" at {}",
+                    element_info.name,
+                    parser.position()
+                ));
+            }
+        }
+    }
+
+    if let Some(refer) = refer {
+        // refer is a QName, while the name of the constraint has no namespace prefix
+        let refer = refer.rsplit_once(':').map_or(refer, |(_, local)| local);
+        data.keyref_refers.insert(refer.to_string());
+    } else {
+        data.identity_constraints
+            .insert(name.to_string(), selected_names);
+    }
+    Ok(())
+}
+
+// the names of the elements that are selected by the xpath of a selector, e.g. ".//AR:I-SIGNAL | AR:ELEMENTS/AR:SYSTEM-SIGNAL"
+fn selector_element_names(xpath: &str) -> Vec<String> {
+    xpath
+        .split('|')
+        .filter_map(|path| path.trim().rsplit('/').next())
+        .map(|step| step.rsplit_once(':').map_or(step, |(_, local)| local))
+        .filter(|name| !name.is_empty() && *name != "." && *name != "*")
+        .map(str::to_string)
+        .collect()
+}
+
+// the types of the elements that are selected by the keys which are referred to by a keyref are the reference targets
+fn resolve_reference_targets(data: &mut Xsd) {
+    let mut target_names = HashSet::new();
+    for refer in &data.keyref_refers {
+        if let Some(selected_names) = data.identity_constraints.get(refer) {
+            target_names.extend(selected_names.iter().map(String::as_str));
+        } else {
+            println!("Warning: a keyref refers to the unknown identity constraint {refer}");
+        }
+    }
+    if target_names.is_empty() {
+        return;
+    }
+
+    let mut elements: Vec<&XsdElement> = data.root_elements.iter().collect();
+    for group in data.groups.values() {
+        match &group.item {
+            XsdGroupItem::Sequence(XsdSequence { items })
+            | XsdGroupItem::Choice(XsdChoice { items, .. }) => {
+                collect_elements(items, &mut elements)
+            }
+            XsdGroupItem::None => {}
+        }
+    }
+    let reference_targets: HashSet<String> = elements
+        .into_iter()
+        .filter(|element| target_names.contains(element.name.as_str()))
+        .map(|element| element.typeref.clone())
+        .collect();
+    data.reference_targets = reference_targets;
+}
+
+fn collect_elements<'a>(items: &'a [XsdModelGroupItem], elements: &mut Vec<&'a XsdElement>) {
+    for item in items {
+        match item {
+            XsdModelGroupItem::Element(element) => elements.push(element),
+            XsdModelGroupItem::Choice(choice) => collect_elements(&choice.items, elements),
+            XsdModelGroupItem::Group(_) => {}
+        }
+    }
+}
+
+fn parse_group<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
     mut prev_names: Vec<String>,
@@ -400,17 +948,42 @@ fn parse_group(
         ))
     } else if let Some(typeref) = attr_typeref {
         get_element_end_tag(parser, "group")?;
-        Ok(typeref.to_owned())
+        let min_occurs = parse_occurs_attribute(get_attribute_value("minOccurs", attributes))?;
+        let max_occurs = parse_occurs_attribute(get_attribute_value("maxOccurs", attributes))?;
+        if min_occurs == 1 && max_occurs == 1 {
+            Ok(typeref.to_owned())
+        } else {
+            // the occurs values only apply to this reference, not to the group itself.
+            // The reference is replaced by a synthetic group, which wraps the group in a choice with these values
+            let max_str = if max_occurs == usize::MAX {
+                "UNBOUNDED".to_string()
+            } else {
+                max_occurs.to_string()
+            };
+            let wrapper_name = format!("{typeref}--OCCURS-{min_occurs}-{max_str}");
+            data.groups
+                .entry(wrapper_name.clone())
+                .or_insert_with(|| XsdGroup {
+                    item: XsdGroupItem::Choice(XsdChoice {
+                        min_occurs,
+                        max_occurs,
+                        items: vec![XsdModelGroupItem::Group(typeref.to_owned())],
+                    }),
+                    tags: HashMap::new(),
+                });
+            Ok(wrapper_name)
+        }
     } else if let Some(name) = attr_name {
         let mut sequence: Option<XsdSequence> = None;
         let mut choice: Option<XsdChoice> = None;
+        let mut tags = HashMap::new();
 
         extend_prev_names(&mut prev_names, Some(name));
 
         while let Some(element_info) = get_next_element(parser, "group")? {
             match element_info.name.as_ref() {
                 "annotation" => {
-                    skip_annotation(parser)?;
+                    (tags, _) = parse_annotation(parser)?;
                 }
                 "sequence" => {
                     sequence = Some(parse_sequence(parser, data, &prev_names)?);
@@ -446,7 +1019,7 @@ fn parse_group(
         };
 
         let typeref = format!("AR:{name}");
-        data.groups.insert(typeref.clone(), XsdGroup { item });
+        data.groups.insert(typeref.clone(), XsdGroup { item, tags });
 
         Ok(typeref)
     } else {
@@ -457,8 +1030,8 @@ fn parse_group(
     }
 }
 
-fn parse_simple_type(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_simple_type<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
 ) -> Result<String, String> {
@@ -499,8 +1072,8 @@ fn parse_simple_type(
     }
 }
 
-fn parse_complex_type(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_complex_type<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
     comment: Option<String>,
@@ -524,21 +1097,35 @@ fn parse_complex_type(
     } else if num_prev_names == 1 {
         format!("{}-TYPE", prev_names[0])
     } else {
-        todo!()
+        return Err(format!(
+            "Error: complex type without a name and without a containing element at {}",
+            parser.position()
+        ));
     };
 
     let mm_class = mm_class_from_comment(comment);
 
     extend_prev_names(&mut prev_names, attr_name);
 
-    let mut doctext = None;
+    let mut attributes = Vec::new();
+    let mut doctext = Vec::new();
     while let Some(element_info) = get_next_element(parser, "complexType")? {
         match element_info.name.as_ref() {
             "annotation" => {
                 (_, doctext) = parse_annotation(parser)?;
             }
             "simpleContent" => {
-                item = XsdComplexTypeItem::SimpleContent(parse_simple_content(parser, data)?);
+                item =
+                    XsdComplexTypeItem::SimpleContent(parse_simple_content(parser, data, &name)?);
+                item_count += 1;
+            }
+            "complexContent" => {
+                item = XsdComplexTypeItem::Extension(parse_complex_content(
+                    parser,
+                    data,
+                    &name,
+                    &prev_names,
+                )?);
                 item_count += 1;
             }
             "group" => {
@@ -554,6 +1141,7 @@ fn parse_complex_type(
                 let sequence = parse_sequence(parser, data, &prev_names)?;
                 let group = XsdGroup {
                     item: XsdGroupItem::Sequence(sequence),
+                    tags: HashMap::new(),
                 };
                 let generated_group_name = format!("{name}/ELEMENTGROUP");
                 data.groups.insert(generated_group_name.clone(), group);
@@ -564,12 +1152,17 @@ fn parse_complex_type(
                 let choice = parse_choice(parser, data, &element_info.attributes, &prev_names)?;
                 let group = XsdGroup {
                     item: XsdGroupItem::Choice(choice),
+                    tags: HashMap::new(),
                 };
                 let generated_group_name = format!("{name}/ELEMENTGROUP");
                 data.groups.insert(generated_group_name.clone(), group);
                 item = XsdComplexTypeItem::Group(generated_group_name);
                 item_count += 1;
             }
+            "attribute" => {
+                attributes.push(parse_attribute(parser, &element_info.attributes)?);
+                // like attribute groups, attributes can be combined with all other items
+            }
             "attributeGroup" => {
                 attribute_groups.push(parse_attribute_group(
                     parser,
@@ -598,6 +1191,7 @@ fn parse_complex_type(
     let newtype = XsdType::Complex(XsdComplexType {
         name,
         item,
+        attributes,
         attribute_groups,
         mixed_content,
         mm_class,
@@ -615,8 +1209,8 @@ fn parse_complex_type(
     Ok(typeref)
 }
 
-fn parse_attribute_group(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_attribute_group<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
 ) -> Result<String, String> {
@@ -662,8 +1256,8 @@ fn parse_attribute_group(
     }
 }
 
-fn parse_attribute(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_attribute<R: Read>(
+    parser: &mut EventReader<R>,
     attributes: &Vec<OwnedAttribute>,
 ) -> Result<XsdAttribute, String> {
     let attr_name = get_attribute_value("name", attributes);
@@ -688,29 +1282,95 @@ fn parse_attribute(
     } else if let (Some(name), Some(typeref)) = (attr_name, attr_typeref) {
         (name.to_owned(), typeref.to_owned())
     } else {
-        todo!()
+        return Err(format!(
+            "Error: attribute without a ref or without a name and type at {}",
+            parser.position()
+        ));
     };
 
-    get_element_end_tag(parser, "attribute")?;
+    let mut doctext = None;
+    while let Some(element_info) = get_next_element(parser, "attribute")? {
+        if element_info.name == "annotation" {
+            let (_, documentation) = parse_annotation(parser)?;
+            doctext = preferred_doctext(&documentation);
+        } else {
+            return Err(format!(
+                "Error: found unexpected start of element tag \"{}\" at {}",
+                element_info.name,
+                parser.position()
+            ));
+        }
+    }
 
     Ok(XsdAttribute {
         name,
         typeref,
         required,
+        doctext,
     })
 }
 
-fn parse_simple_content(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_simple_content<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
+    name: &str,
 ) -> Result<XsdSimpleContent, String> {
     let mut extension = None;
+    let mut restriction = None;
 
     while let Some(element_info) = get_next_element(parser, "simpleContent")? {
         match element_info.name.as_ref() {
             "extension" => {
                 extension = Some(parse_extension(parser, data, &element_info.attributes)?);
             }
+            "restriction" => {
+                let (base_extension, restriction_type) =
+                    parse_simple_content_restriction(parser, data, &element_info.attributes, name)?;
+                extension = Some(base_extension);
+                restriction = restriction_type;
+            }
+            _ => {
+                return Err(format!(
+                    "Error: found unexpected start of element tag \"{}\" at {}",
+                    element_info.name,
+                    parser.position()
+                ));
+            }
+        }
+    }
+
+    if let Some(extension) = extension {
+        Ok(XsdSimpleContent {
+            extension,
+            restriction,
+        })
+    } else {
+        Err(format!(
+            "Error: simpleContent at {} has no extension or restriction",
+            parser.position()
+        ))
+    }
+}
+
+fn parse_complex_content<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    name: &str,
+    prev_names: &[String],
+) -> Result<XsdExtension, String> {
+    let mut extension = None;
+
+    while let Some(element_info) = get_next_element(parser, "complexContent")? {
+        match element_info.name.as_ref() {
+            "extension" => {
+                extension = Some(parse_complex_extension(
+                    parser,
+                    data,
+                    &element_info.attributes,
+                    name,
+                    prev_names,
+                )?);
+            }
             _ => {
                 return Err(format!(
                     "Error: found unexpected start of element tag \"{}\" at {}",
@@ -722,17 +1382,186 @@ fn parse_simple_content(
     }
 
     if let Some(extension) = extension {
-        Ok(XsdSimpleContent { extension })
+        Ok(extension)
     } else {
         Err(format!(
-            "Error: simpleContent at {} has no extension",
+            "Error: complexContent at {} has no extension",
             parser.position()
         ))
     }
 }
 
-fn parse_sequence(
-    parser: &mut EventReader<BufReader<File>>,
+// an extension inside of complexContent may have its own content in addition to attributes
+fn parse_complex_extension<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    attributes: &Vec<OwnedAttribute>,
+    name: &str,
+    prev_names: &[String],
+) -> Result<XsdExtension, String> {
+    let basetype = get_required_attribute_value("base", attributes, &parser.position())?;
+    let mut attributes = Vec::new();
+    let mut attribute_groups = Vec::new();
+    let mut group = None;
+    let generated_group_name = format!("{name}/EXTENSION");
+
+    while let Some(element_info) = get_next_element(parser, "extension")? {
+        if group.is_some() && matches!(element_info.name.as_ref(), "sequence" | "choice" | "group")
+        {
+            return Err(format!(
+                "Error: extension has mutually exclusive child elements at {}",
+                parser.position()
+            ));
+        }
+        match element_info.name.as_ref() {
+            "attribute" => {
+                attributes.push(parse_attribute(parser, &element_info.attributes)?);
+            }
+            "attributeGroup" => {
+                attribute_groups.push(parse_attribute_group(
+                    parser,
+                    data,
+                    &element_info.attributes,
+                )?);
+            }
+            "group" => {
+                group = Some(parse_group(
+                    parser,
+                    data,
+                    &element_info.attributes,
+                    prev_names.to_vec(),
+                )?);
+            }
+            "sequence" => {
+                let sequence = parse_sequence(parser, data, prev_names)?;
+                let xsd_group = XsdGroup {
+                    item: XsdGroupItem::Sequence(sequence),
+                    tags: HashMap::new(),
+                };
+                data.groups.insert(generated_group_name.clone(), xsd_group);
+                group = Some(generated_group_name.clone());
+            }
+            "choice" => {
+                let choice = parse_choice(parser, data, &element_info.attributes, prev_names)?;
+                let xsd_group = XsdGroup {
+                    item: XsdGroupItem::Choice(choice),
+                    tags: HashMap::new(),
+                };
+                data.groups.insert(generated_group_name.clone(), xsd_group);
+                group = Some(generated_group_name.clone());
+            }
+            _ => {
+                return Err(format!(
+                    "Error: found unexpected start of element tag \"{}\" at {}",
+                    element_info.name,
+                    parser.position()
+                ));
+            }
+        }
+    }
+
+    Ok(XsdExtension {
+        basetype: basetype.to_owned(),
+        attributes,
+        attribute_groups,
+        group,
+    })
+}
+
+// combine the content of the base type of each complexContent extension with the content of the extension
+fn resolve_complex_content_extensions(data: &mut Xsd) -> Result<(), String> {
+    let mut extensions: Vec<(String, String, String)> = data
+        .types
+        .iter()
+        .filter(|(typeref, _)| !data.imported_types.contains(*typeref))
+        .filter_map(|(_, xsd_type)| match xsd_type {
+            XsdType::Complex(XsdComplexType {
+                name,
+                item:
+                    XsdComplexTypeItem::Extension(XsdExtension {
+                        basetype,
+                        group: Some(group),
+                        ..
+                    }),
+                ..
+            }) => Some((name.clone(), basetype.clone(), group.clone())),
+            _ => None,
+        })
+        .collect();
+    // sorted, so that the errors don't depend on the order of the HashMap
+    extensions.sort();
+
+    for (name, basetype, group) in extensions {
+        let mut items = Vec::new();
+        if let Some(base_group) = content_group(data, &basetype, &mut vec![name.clone()])? {
+            items.push(XsdModelGroupItem::Group(base_group));
+        }
+        items.push(XsdModelGroupItem::Group(group));
+
+        let combined_group_name = format!("{name}/ELEMENTGROUP");
+        data.groups.insert(
+            combined_group_name.clone(),
+            XsdGroup {
+                item: XsdGroupItem::Sequence(XsdSequence { items }),
+                tags: HashMap::new(),
+            },
+        );
+        if let Some(XsdType::Complex(XsdComplexType {
+            item: XsdComplexTypeItem::Extension(extension),
+            ..
+        })) = data.types.get_mut(&format!("AR:{name}"))
+        {
+            extension.group = Some(combined_group_name);
+        }
+    }
+
+    Ok(())
+}
+
+// get the name of the group that holds the (combined) content of a complex type
+fn content_group(
+    data: &Xsd,
+    typeref: &str,
+    visited: &mut Vec<String>,
+) -> Result<Option<String>, String> {
+    match data.types.get(typeref) {
+        Some(XsdType::Complex(complex_type)) => {
+            if visited.contains(&complex_type.name) {
+                return Err(format!(
+                    "Error: complexType {} is derived from itself",
+                    complex_type.name
+                ));
+            }
+            visited.push(complex_type.name.clone());
+            match &complex_type.item {
+                XsdComplexTypeItem::Group(group) => Ok(Some(group.clone())),
+                // the combined group of an extension with its own content always has this name
+                XsdComplexTypeItem::Extension(XsdExtension {
+                    basetype,
+                    group: Some(_),
+                    ..
+                }) => {
+                    // the base types are still checked, because a cycle would make the flattening recurse forever
+                    content_group(data, basetype, visited)?;
+                    Ok(Some(format!("{}/ELEMENTGROUP", complex_type.name)))
+                }
+                XsdComplexTypeItem::Extension(XsdExtension { basetype, .. }) => {
+                    content_group(data, basetype, visited)
+                }
+                XsdComplexTypeItem::SimpleContent(_) | XsdComplexTypeItem::None => Ok(None),
+            }
+        }
+        Some(_) => Err(format!(
+            "Error: the base type {typeref} of a complexContent extension is not a complexType"
+        )),
+        None => Err(format!(
+            "Error: unknown base type {typeref} of a complexContent extension"
+        )),
+    }
+}
+
+fn parse_sequence<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     prev_names: &[String],
 ) -> Result<XsdSequence, String> {
@@ -772,6 +1601,7 @@ fn parse_sequence(
                     // now wrap the choice in a group and add it to the list of groups
                     let newgroup = XsdGroup {
                         item: XsdGroupItem::Choice(choice_item),
+                        tags: HashMap::new(),
                     };
                     if let Some(existing_group) = data.groups.get(&choice_name) {
                         if newgroup != *existing_group {
@@ -797,8 +1627,8 @@ fn parse_sequence(
     Ok(XsdSequence { items })
 }
 
-fn parse_choice(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_choice<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
     prev_names: &[String],
@@ -853,11 +1683,82 @@ fn parse_choice(
     })
 }
 
-fn parse_restriction(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_restriction<R: Read>(
+    parser: &mut EventReader<R>,
+    attributes: &Vec<OwnedAttribute>,
+) -> Result<XsdRestriction, String> {
+    parse_restriction_facets(parser, attributes, |parser, element_info| {
+        Err(format!(
+            "Error: found unexpected start of element tag \"{}\" at {}",
+            element_info.name,
+            parser.position()
+        ))
+    })
+}
+
+// a restriction inside of simpleContent restricts the character content of its base type with facets;
+// it may also contain attributes, which are added to those of the base type
+fn parse_simple_content_restriction<R: Read>(
+    parser: &mut EventReader<R>,
+    data: &mut Xsd,
+    attributes: &Vec<OwnedAttribute>,
+    name: &str,
+) -> Result<(XsdExtension, Option<String>), String> {
+    let basetype = get_required_attribute_value("base", attributes, &parser.position())?;
+    let mut restriction_attributes = Vec::new();
+    let mut attribute_groups = Vec::new();
+
+    let restriction = parse_restriction_facets(parser, attributes, |parser, element_info| {
+        match element_info.name.as_ref() {
+            "attribute" => {
+                restriction_attributes.push(parse_attribute(parser, &element_info.attributes)?);
+            }
+            "attributeGroup" => {
+                attribute_groups.push(parse_attribute_group(
+                    parser,
+                    data,
+                    &element_info.attributes,
+                )?);
+            }
+            _ => {
+                return Err(format!(
+                    "Error: found unexpected start of element tag \"{}\" at {}",
+                    element_info.name,
+                    parser.position()
+                ));
+            }
+        }
+        Ok(())
+    })?;
+
+    // without any facets the content of the base type is used unchanged
+    let restriction_type = if let XsdRestriction::Plain { .. } = restriction {
+        None
+    } else {
+        let restriction_typeref = format!("AR:{name}--RESTRICTION");
+        data.types.insert(
+            restriction_typeref.clone(),
+            XsdType::Simple(XsdSimpleType::Restriction(restriction)),
+        );
+        Some(restriction_typeref)
+    };
+
+    let extension = XsdExtension {
+        basetype: basetype.to_owned(),
+        attributes: restriction_attributes,
+        attribute_groups,
+        group: None,
+    };
+    Ok((extension, restriction_type))
+}
+
+// parse the facets of a restriction; all other child elements are passed to parse_other
+fn parse_restriction_facets<R: Read>(
+    parser: &mut EventReader<R>,
     attributes: &Vec<OwnedAttribute>,
+    mut parse_other: impl FnMut(&mut EventReader<R>, &StartElementInfo) -> Result<(), String>,
 ) -> Result<XsdRestriction, String> {
-    let mut enumvalues: Vec<String> = Vec::new();
+    let mut enumvalues: Vec<(String, Option<String>)> = Vec::new();
     let mut pattern: Option<String> = None;
     let mut max_length: Option<usize> = None;
     let mut literal = false;
@@ -871,7 +1772,23 @@ fn parse_restriction(
                     &element_info.attributes,
                     &parser.position(),
                 )?;
-                enumvalues.push(attrval.to_owned());
+                // the documentation of an enumeration value is placed in an annotation inside of it
+                let mut doctext = None;
+                while let Some(sub_element_info) = get_next_element(parser, "enumeration")? {
+                    if sub_element_info.name == "annotation" {
+                        let (_, documentation) = parse_annotation(parser)?;
+                        doctext = preferred_doctext(&documentation);
+                    } else {
+                        return Err(format!(
+                            "Error: found unexpected start of element tag \"{}\" at {}",
+                            sub_element_info.name,
+                            parser.position()
+                        ));
+                    }
+                }
+                enumvalues.push((attrval.to_owned(), doctext));
+                // get_next_element has already consumed the end tag of the enumeration
+                continue;
             }
             "pattern" => {
                 let attrval = get_required_attribute_value(
@@ -908,11 +1825,9 @@ fn parse_restriction(
                 }
             }
             _ => {
-                return Err(format!(
-                    "Error: found unexpected start of element tag \"{}\" at {}",
-                    element_info.name,
-                    parser.position()
-                ));
+                // parse_other consumes the whole element, including its end tag
+                parse_other(parser, &element_info)?;
+                continue;
             }
         }
         get_element_end_tag(parser, &element_info.name)?;
@@ -943,8 +1858,8 @@ fn parse_restriction(
     }
 }
 
-fn parse_extension(
-    parser: &mut EventReader<BufReader<File>>,
+fn parse_extension<R: Read>(
+    parser: &mut EventReader<R>,
     data: &mut Xsd,
     attributes: &Vec<OwnedAttribute>,
 ) -> Result<XsdExtension, String> {
@@ -978,10 +1893,11 @@ fn parse_extension(
         basetype: basetype.to_owned(),
         attributes,
         attribute_groups,
+        group: None,
     })
 }
 
-fn skip_annotation(parser: &mut EventReader<BufReader<File>>) -> Result<(), String> {
+fn skip_annotation<R: Read>(parser: &mut EventReader<R>) -> Result<(), String> {
     let mut element_stack: Vec<String> = vec!["annotation".to_string()];
     while !element_stack.is_empty() {
         let next_element = get_next_event(parser)?;
@@ -1011,15 +1927,22 @@ fn skip_annotation(parser: &mut EventReader<BufReader<File>>) -> Result<(), Stri
     Ok(())
 }
 
-fn parse_annotation(
-    parser: &mut EventReader<BufReader<File>>,
-) -> Result<(HashMap<String, String>, Option<String>), String> {
+/// parse an annotation, which contains the tags in its appinfo and the documentation
+///
+/// There can be one documentation for each language; the language (xml:lang) of each documentation is
+/// returned together with its text.
+fn parse_annotation<R: Read>(
+    parser: &mut EventReader<R>,
+) -> Result<(HashMap<String, String>, XsdDocText), String> {
     let mut tagmap = HashMap::<String, String>::new();
-    let mut docstring = None;
+    let mut docstrings = Vec::new();
     while let Some(element_info) = get_next_element(parser, "annotation")? {
         match element_info.name.as_ref() {
             "documentation" => {
-                docstring = parse_docstring(parser);
+                if let Some(docstring) = parse_docstring(parser) {
+                    let language = get_attribute_value("lang", &element_info.attributes);
+                    docstrings.push((language.map(str::to_owned), docstring));
+                }
             }
             "appinfo" => {
                 let source = get_required_attribute_value(
@@ -1028,18 +1951,33 @@ fn parse_annotation(
                     &parser.position(),
                 )?;
                 if let XmlEvent::Characters(text) = get_next_event(parser)? {
-                    if source == "tags" {
+                    // later versions also put tags into <appinfo source="atp"> and <appinfo source="Blueprint">
+                    if source == "tags" || source == "atp" || source == "Blueprint" {
                         let separated_tags: Vec<&str> = text.split(';').collect();
                         for tag in &separated_tags {
-                            let taglen = tag.len();
-                            if let Some(equalspos) = tag.find('=') {
-                                let tagname = tag[0..equalspos].to_string();
-                                let tagval = tag[equalspos + 2..taglen - 1].to_string();
-                                tagmap.insert(tagname, tagval);
+                            // each tag has the form name="value"; the quotes around the value are removed
+                            if let Some((tagname, quoted_value)) = tag.split_once('=') {
+                                if let Some(tagval) = quoted_value
+                                    .len()
+                                    .checked_sub(1)
+                                    .and_then(|end| quoted_value.get(1..end))
+                                {
+                                    merge_tag(
+                                        &mut tagmap,
+                                        tagname.to_string(),
+                                        tagval.to_string(),
+                                        &parser.position(),
+                                    );
+                                }
+                            }
+                        }
+                    } else if source == "stereotypes" {
+                        // a list of stereotypes like "atpSplitable;atpVariation", each is stored as a tag
+                        for stereotype in text.split(';').map(str::trim) {
+                            if !stereotype.is_empty() {
+                                tagmap.insert(stereotype.to_string(), "true".to_string());
                             }
                         }
-                    } else if source == "stereotypes" && text == "atpSplitable" {
-                        tagmap.insert("atpSplitable".to_string(), "true".to_string());
                     }
                 } else {
                     return Err("Error: expected characters inside <appinfo>".to_string());
@@ -1056,10 +1994,48 @@ fn parse_annotation(
         }
     }
 
-    Ok((tagmap, docstring))
+    Ok((tagmap, docstrings))
+}
+
+/// select the documentation that is used in the generated code: the English one, or the first one if there is none
+pub(crate) fn preferred_doctext(doctext: &[(Option<String>, String)]) -> Option<String> {
+    doctext
+        .iter()
+        .find(|(language, _)| {
+            language.as_deref().is_some_and(|language| {
+                language.eq_ignore_ascii_case("en")
+                    || language.to_ascii_lowercase().starts_with("en-")
+            })
+        })
+        .or(doctext.first())
+        .map(|(_, text)| text.clone())
 }
 
-fn parse_docstring(parser: &mut EventReader<BufReader<File>>) -> Option<String> {
+// add a tag to the tag map; an element can have several <appinfo source="tags"> blocks,
+// and a later block must not silently replace a different value from an earlier block
+fn merge_tag(
+    tagmap: &mut HashMap<String, String>,
+    tagname: String,
+    tagval: String,
+    position: &TextPosition,
+) {
+    match tagmap.entry(tagname) {
+        Entry::Vacant(entry) => {
+            entry.insert(tagval);
+        }
+        Entry::Occupied(entry) => {
+            if *entry.get() != tagval {
+                println!(
+                    "Warning: conflicting values \"{}\" and \"{tagval}\" for tag {} at {position}, keeping the first value",
+                    entry.get(),
+                    entry.key()
+                );
+            }
+        }
+    }
+}
+
+fn parse_docstring<R: Read>(parser: &mut EventReader<R>) -> Option<String> {
     if let Ok(XmlEvent::Characters(doctext)) = get_next_event(parser) {
         get_next_element(parser, "documentation").ok()?;
         return Some(doctext);
@@ -1067,7 +2043,7 @@ fn parse_docstring(parser: &mut EventReader<BufReader<File>>) -> Option<String>
     None
 }
 
-fn get_element_end_tag(parser: &mut EventReader<BufReader<File>>, tag: &str) -> Result<(), String> {
+fn get_element_end_tag<R: Read>(parser: &mut EventReader<R>, tag: &str) -> Result<(), String> {
     let event = get_next_event(parser)?;
     if let XmlEvent::EndElement {
         name: OwnedName { local_name, .. },
@@ -1095,7 +2071,7 @@ fn get_element_end_tag(parser: &mut EventReader<BufReader<File>>, tag: &str) ->
     ))
 }
 
-fn get_next_event(parser: &mut EventReader<BufReader<File>>) -> Result<XmlEvent, String> {
+fn get_next_event<R: Read>(parser: &mut EventReader<R>) -> Result<XmlEvent, String> {
     let mut next_element = parser.next().map_err(|err| format!("Error: {err}"))?;
 
     let mut done = false;
@@ -1110,8 +2086,8 @@ fn get_next_event(parser: &mut EventReader<BufReader<File>>) -> Result<XmlEvent,
     Ok(next_element)
 }
 
-fn get_next_element(
-    parser: &mut EventReader<BufReader<File>>,
+fn get_next_element<R: Read>(
+    parser: &mut EventReader<R>,
     parent_element: &str,
 ) -> Result<Option<StartElementInfo>, String> {
     let mut element_comment = None;
@@ -1120,9 +2096,10 @@ fn get_next_element(
         match cur_event {
             XmlEvent::StartElement {
                 name: OwnedName { local_name, .. },
-                attributes: elem_attributes,
-                ..
+                attributes: mut elem_attributes,
+                namespace,
             } => {
+                normalize_references(&mut elem_attributes, &namespace);
                 return Ok(Some(StartElementInfo {
                     name: local_name,
                     attributes: elem_attributes,
@@ -1157,6 +2134,26 @@ fn get_next_element(
     }
 }
 
+// All definitions are stored with the prefix AR, whatever the targetNamespace of the file is. A vendor extension
+// has its own namespace, so its references to its own definitions use a different prefix, which is replaced here.
+fn normalize_references(attributes: &mut [OwnedAttribute], namespace: &Namespace) {
+    for attribute in attributes {
+        if !matches!(attribute.name.local_name.as_str(), "type" | "base" | "ref") {
+            continue;
+        }
+        let Some((prefix, local_name)) = attribute.value.split_once(':') else {
+            continue;
+        };
+        if prefix != "AR"
+            && namespace
+                .get(prefix)
+                .is_some_and(|uri| uri != XSD_NAMESPACE && uri != xml::namespace::NS_XML_URI)
+        {
+            attribute.value = format!("AR:{local_name}");
+        }
+    }
+}
+
 fn get_attribute_value<'a>(key: &str, attributes: &'a Vec<OwnedAttribute>) -> Option<&'a str> {
     for OwnedAttribute {
         name: OwnedName { local_name, .. },
@@ -1187,7 +2184,7 @@ fn get_required_attribute_value<'a>(
 fn parse_occurs_attribute(attr_occurs: Option<&str>) -> Result<usize, String> {
     if let Some(occurs_str) = attr_occurs {
         if occurs_str == "unbounded" {
-            Ok(std::usize::MAX)
+            Ok(usize::MAX)
         } else {
             match occurs_str.parse() {
                 Ok(val) => Ok(val),
@@ -1203,7 +2200,7 @@ fn extend_prev_names(prev_names: &mut Vec<String>, attr_name: Option<&str>) {
     if let Some(name) = attr_name {
         let len = prev_names.len();
         if len > 1 {
-            if prev_names[len - 1].contains(name) || name.contains(&prev_names[len - 1]) {
+            if names_share_prefix(&prev_names[len - 1], name) {
                 prev_names.pop();
                 prev_names.push((*name).to_owned());
             } else {
@@ -1215,11 +2212,45 @@ fn extend_prev_names(prev_names: &mut Vec<String>, attr_name: Option<&str>) {
     }
 }
 
-fn mm_class_from_comment(comment_opt: Option<String>) -> Option<String> {
+// Two names share a prefix if all dash-delimited parts of the shorter name match the parts of the longer one,
+// e.g. "AR-PACKAGE" and "AR-PACKAGES" or "SW-COMPONENT" and "SW-COMPONENT-TYPE".
+// A plain substring check would also match unrelated names like "DATA" and "META-DATA".
+pub(crate) fn names_share_prefix(name_1: &str, name_2: &str) -> bool {
+    name_1
+        .split('-')
+        .zip(name_2.split('-'))
+        .all(|(part_1, part_2)| {
+            // allow plural forms, e.g. PACKAGE and PACKAGES
+            part_1 == part_2
+                || part_1.strip_suffix('S') == Some(part_2)
+                || part_2.strip_suffix('S') == Some(part_1)
+        })
+}
+
+fn replace_group_refs(items: &mut [XsdModelGroupItem], old_ref: &str, new_ref: &str) -> bool {
+    let mut replaced = false;
+    for item in items {
+        replaced |= item.replace_group_ref(old_ref, new_ref);
+    }
+    replaced
+}
+
+fn referenced_schema_path(filepath: &Path, location: &str) -> PathBuf {
+    filepath.parent().unwrap_or(Path::new("")).join(location)
+}
+
+// some complex types are used for several classes, which are listed in the comment as "Foo / Bar"
+fn mm_class_from_comment(comment_opt: Option<String>) -> Option<Vec<String>> {
     let comment = comment_opt?;
 
-    let mm_class = comment.strip_prefix(" complex type for class ")?;
-    Some(mm_class.to_string())
+    let mm_classes = comment.strip_prefix(" complex type for class ")?;
+    Some(
+        mm_classes
+            .split(" / ")
+            .map(|mm_class| mm_class.trim().to_string())
+            .filter(|mm_class| !mm_class.is_empty())
+            .collect(),
+    )
 }
 
 fn get_restrict_to_standard(mm_attributes: &HashMap<String, String>) -> XsdRestrictToStandard {
@@ -1245,4 +2276,53 @@ impl XsdModelGroupItem {
             XsdModelGroupItem::Element(_) => "ELEMENT".to_string(),
         }
     }
+
+    fn replace_group_ref(&mut self, old_ref: &str, new_ref: &str) -> bool {
+        match self {
+            XsdModelGroupItem::Group(groupref) => {
+                if groupref == old_ref {
+                    *groupref = new_ref.to_string();
+                    true
+                } else {
+                    false
+                }
+            }
+            XsdModelGroupItem::Choice(choice) => {
+                replace_group_refs(&mut choice.items, old_ref, new_ref)
+            }
+            XsdModelGroupItem::Element(_) => false,
+        }
+    }
+}
+
+impl XsdComplexType {
+    /// replace the base type `old_ref` of an extension or of simple content by `new_ref`
+    ///
+    /// returns true if the base type was replaced
+    fn replace_basetype(&mut self, old_ref: &str, new_ref: &str) -> bool {
+        match &mut self.item {
+            XsdComplexTypeItem::Extension(extension)
+            | XsdComplexTypeItem::SimpleContent(XsdSimpleContent { extension, .. })
+                if extension.basetype == old_ref =>
+            {
+                extension.basetype = new_ref.to_string();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl XsdGroupItem {
+    /// replace all references to the group `old_ref` by references to `new_ref`
+    ///
+    /// returns true if any reference was replaced
+    fn replace_group_ref(&mut self, old_ref: &str, new_ref: &str) -> bool {
+        let items = match self {
+            XsdGroupItem::Choice(XsdChoice { items, .. })
+            | XsdGroupItem::Sequence(XsdSequence { items }) => items,
+            XsdGroupItem::None => return false,
+        };
+        replace_group_refs(items, old_ref, new_ref)
+    }
 }
@@ -0,0 +1,173 @@
+// helpers for the integration tests that run the generator binary; not every test file uses all of them
+#![allow(dead_code)]
+
+use std::ffi::OsStr;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// a fresh directory in the system temp dir, which is removed when it is dropped, also if an assert fails
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("autosar-xsd-mangler-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+// the path of a file or directory in tests/fixtures
+pub fn fixture(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(path)
+}
+
+// the content of a file in tests/fixtures
+pub fn read_fixture(path: &str) -> String {
+    std::fs::read_to_string(fixture(path)).unwrap()
+}
+
+// the miniature schema in tests/fixtures/single, which most tests modify
+pub fn mini_xsd() -> String {
+    read_fixture("single/mini.xsd")
+}
+
+// run the generator in `workdir`; returns the exit status and the output
+pub fn run_generator<I, S>(workdir: &Path, args: I) -> (bool, String)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let output = Command::new(env!("CARGO_BIN_EXE_autosar-xsd-mangler"))
+        .arg("--no-timestamp")
+        .args(args)
+        .current_dir(workdir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    (output.status.success(), stdout)
+}
+
+// run the generator with --single on the miniature schema in tests/fixtures/single, in a fresh output directory
+pub fn run_single_mode(name: &str) -> TempDir {
+    run_single_mode_with_args(name, &[]).0
+}
+
+// same as run_single_mode, with additional command line arguments; the output is also returned
+pub fn run_single_mode_with_args(name: &str, args: &[&str]) -> (TempDir, String) {
+    let (workdir, success, stdout) = run_single_file(name, &fixture("single/mini.xsd"), args);
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("Error"), "{stdout}");
+    (workdir, stdout)
+}
+
+// run the generator with --single on any xsd file; returns the output directory, the exit status and the output
+pub fn run_single_file(name: &str, xsd_file: &Path, args: &[&str]) -> (TempDir, bool, String) {
+    let workdir = TempDir::new(name);
+    let single = format!("--single={}", xsd_file.display());
+    let (success, stdout) = run_generator(&workdir, [single.as_str()].iter().chain(args));
+    (workdir, success, stdout)
+}
+
+// run the generator with --single on a modified miniature schema, which is written to xsd/mini.xsd in the output
+// directory together with the config.toml of the miniature schema
+pub fn run_single_xsd(name: &str, xsd: &str, args: &[&str]) -> (TempDir, bool, String) {
+    let workdir = TempDir::new(name);
+    let xsd_dir = workdir.join("xsd");
+    std::fs::create_dir_all(&xsd_dir).unwrap();
+    std::fs::copy(fixture("single/config.toml"), xsd_dir.join("config.toml")).unwrap();
+    std::fs::write(xsd_dir.join("mini.xsd"), xsd).unwrap();
+    let single = format!("--single={}", xsd_dir.join("mini.xsd").display());
+    let (success, stdout) = run_generator(&workdir, [single.as_str()].iter().chain(args));
+    (workdir, success, stdout)
+}
+
+// run the generator on the two versions AUTOSAR_00051.xsd ("older") and AUTOSAR_00052.xsd ("newer") of a schema
+pub fn run_two_versions(name: &str, older: &str, newer: &str, args: &[&str]) -> (TempDir, String) {
+    run_versions(name, &[("older", older), ("newer", newer)], args)
+}
+
+// run the generator on the versions AUTOSAR_00051.xsd, AUTOSAR_00052.xsd, ... of a schema, which are given
+// from oldest to newest with their descriptions
+pub fn run_versions(name: &str, versions: &[(&str, &str)], args: &[&str]) -> (TempDir, String) {
+    let workdir = TempDir::new(name);
+    let xsd_dir = workdir.join("xsd");
+    std::fs::create_dir_all(&xsd_dir).unwrap();
+    let mut config = String::new();
+    for (idx, (desc, content)) in versions.iter().enumerate() {
+        let number = 51 + idx;
+        std::fs::write(xsd_dir.join(format!("AUTOSAR_000{number}.xsd")), content).unwrap();
+        config.push_str(&format!(
+            "[[xsd]]\nname = \"AUTOSAR_000{number}.xsd\"\nident = \"Autosar_000{number}\"\ndesc = \"{desc}\"\n\n"
+        ));
+    }
+    std::fs::write(xsd_dir.join("config.toml"), config).unwrap();
+
+    let (_, stdout) = run_generator(
+        &workdir,
+        args.iter().map(OsStr::new).chain([xsd_dir.as_os_str()]),
+    );
+    (workdir, stdout)
+}
+
+// run the generator on the miniature schema, with the vendor extension `extension` on top of it
+pub fn run_with_extension(name: &str, extension: &str, args: &[&str]) -> (TempDir, String) {
+    let workdir = TempDir::new(&format!("extension-{name}"));
+    let extension_path = workdir.join("oem_extension.xsd");
+    std::fs::write(&extension_path, extension).unwrap();
+
+    let single = format!("--single={}", fixture("single/mini.xsd").display());
+    let extension = format!("--extension={}", extension_path.display());
+    let (_, stdout) = run_generator(&workdir, [single.as_str(), &extension].iter().chain(args));
+    (workdir, stdout)
+}
+
+pub fn read_generated(workdir: &Path, filename: &str) -> String {
+    std::fs::read_to_string(workdir.join("gen").join(filename)).unwrap()
+}
+
+// remove the element `name` of the miniature schema, including its nested annotation or type definition
+pub fn without_element(xsd: &str, name: &str) -> String {
+    let start = xsd
+        .find(&format!("      <xsd:element name=\"{name}\""))
+        .unwrap();
+    let end = start + xsd[start..].find("</xsd:element>\n").unwrap() + "</xsd:element>\n".len();
+    format!("{}{}", &xsd[..start], &xsd[end..])
+}
+
+// the names of the entries of ELEMENTS, together with their values in ELEMENT_LAST_VERSION
+pub fn element_last_versions(specification: &str) -> Vec<(String, String)> {
+    let names = specification.lines().filter_map(|line| {
+        line.split_once("element!(")?
+            .1
+            .split_once(',')
+            .map(|(name, _)| name)
+    });
+    let last_version = specification
+        .lines()
+        .find(|line| line.starts_with("pub(crate) const ELEMENT_LAST_VERSION:"))
+        .unwrap();
+    let (_, values) = last_version.split_once(" = [").unwrap();
+    names
+        .zip(values.trim_end_matches("];").split(", "))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
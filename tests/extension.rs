@@ -0,0 +1,91 @@
+mod common;
+
+use common::{element_last_versions, read_fixture, read_generated, run_with_extension};
+
+fn oem_extension() -> String {
+    read_fixture("extension/oem_extension.xsd")
+}
+
+#[test]
+fn extension_imports_the_standard() {
+    // the extension has no root element; its references to the standard types are resolved against mini.xsd
+    let (workdir, stdout) =
+        run_with_extension("import", &oem_extension(), &["--emit-csv=availability.csv"]);
+    assert!(!stdout.contains("Error"), "{stdout}");
+    assert!(
+        stdout.contains("merging extension: oem_extension.xsd\n"),
+        "{stdout}"
+    );
+
+    // the new element and the new enum item are only valid in the extension, the standard content in both versions
+    let csv = std::fs::read_to_string(workdir.join("availability.csv")).unwrap();
+    assert!(
+        csv.starts_with("type,element,element type,multiplicity,splittable,restrict_std,miniature test schema,vendor extension oem_extension.xsd\r\n"),
+        "{csv}"
+    );
+    assert!(
+        csv.contains("AR:SYSTEM-SIGNAL,OEM-INFO,AR:OEM-INFO,0..1,no,NotRestricted,no,yes"),
+        "{csv}"
+    );
+    assert!(
+        csv.contains("AR:OEM-INFO,SUPPLIER,AR:IDENTIFIER,0..1,no,NotRestricted,no,yes\r\n"),
+        "{csv}"
+    );
+    assert!(
+        csv.contains(
+            "AR:SYSTEM-SIGNAL,SIGNAL-KIND,AR:SIGNAL-KIND,0..1,no,NotRestricted,yes,yes\r\n"
+        ),
+        "{csv}"
+    );
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains("(EnumItem::SystemSignal, 0x3), (EnumItem::SystemSignalGroup, 0x3), (EnumItem::OemSignal, 0x2)"),
+        "{specification}"
+    );
+}
+
+#[test]
+fn extension_is_not_the_last_version_of_standard_elements() {
+    let (workdir, stdout) =
+        run_with_extension("last-version", &oem_extension(), &["--removed-report"]);
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // only the elements of the extension have the extension as their last version
+    let specification = read_generated(&workdir, "specification.rs");
+    let last_versions = element_last_versions(&specification);
+    assert!(!last_versions.is_empty(), "{specification}");
+    for (name, last_version) in last_versions {
+        let expected = match name.as_str() {
+            "OemInfo" | "Supplier" => "0x2",
+            _ => "0x1",
+        };
+        assert_eq!(last_version, expected, "{name}");
+    }
+    let report = read_generated(&workdir, "removed_elements.txt");
+    assert!(report.contains("# 0 elements\n"), "{report}");
+}
+
+#[test]
+fn extension_conflicts_are_reported() {
+    // the extension changes the type of the standard element DYNAMIC-LENGTH and the kind of the type BOOLEAN--SIMPLE
+    let extension = oem_extension()
+        .replace(
+            r#"<xsd:element name="DYNAMIC-LENGTH" type="AR:BOOLEAN" minOccurs="0"/>"#,
+            r#"<xsd:element name="DYNAMIC-LENGTH" type="AR:IDENTIFIER" minOccurs="0"/>"#,
+        )
+        .replace(
+            "</xsd:schema>",
+            "  <xsd:simpleType name=\"BOOLEAN--SIMPLE\">\n    <xsd:restriction base=\"xsd:unsignedInt\"/>\n  </xsd:simpleType>\n</xsd:schema>",
+        );
+    assert_ne!(extension, oem_extension());
+    let (workdir, stdout) = run_with_extension("conflict", &extension, &[]);
+    assert!(
+        stdout.contains(
+            "Error: the extension oem_extension.xsd redefines standard types:\n    \
+             character type AR:BOOLEAN--SIMPLE is redefined\n    \
+             element DYNAMIC-LENGTH in SYSTEM-SIGNAL/ELEMENTGROUP changes its type from AR:BOOLEAN to AR:IDENTIFIER"
+        ),
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen/specification.rs").exists());
+}
@@ -0,0 +1,451 @@
+mod common;
+
+use common::{
+    fixture, mini_xsd, read_fixture, read_generated, run_single_file, run_single_mode,
+    run_single_mode_with_args, run_single_xsd, TempDir,
+};
+
+#[test]
+fn direct_attributes_precede_attribute_groups() {
+    let workdir = run_single_mode("attributes");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // SYSTEM-SIGNAL declares the attribute T directly, after its attribute group;
+    // the direct attributes are always placed before the attributes of the attribute groups
+    let attribute_names: Vec<&str> = specification
+        .lines()
+        .skip_while(|line| !line.starts_with("pub(crate) const ATTRIBUTES:"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("];"))
+        .filter_map(|line| line.trim().strip_prefix("(AttributeName::"))
+        .filter_map(|line| line.split_once(',').map(|(name, _)| name))
+        .collect();
+    let t_pos = attribute_names
+        .iter()
+        .position(|name| *name == "T")
+        .unwrap();
+    assert_eq!(attribute_names.get(t_pos + 1), Some(&"Uuid"));
+    assert_eq!(
+        attribute_names.iter().filter(|name| **name == "T").count(),
+        1
+    );
+}
+
+#[test]
+fn simple_content_restriction_keeps_the_attributes() {
+    let workdir = run_single_mode("restriction");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // SIGNAL-KIND restricts the content of AR:IDENTIFIER to an enumeration, and keeps its attributes
+    let character_data: Vec<&str> = specification
+        .lines()
+        .skip_while(|line| !line.starts_with("pub(crate) const CHARACTER_DATA:"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("];"))
+        .collect();
+    let signal_kind = specification
+        .lines()
+        .find(|line| line.ends_with("// SIGNAL-KIND"))
+        .unwrap();
+    let (_, chardata_idx) = signal_kind.split_once("character_data: Some(").unwrap();
+    let (chardata_idx, _) = chardata_idx.split_once(')').unwrap();
+    let chardata_idx: usize = chardata_idx.parse().unwrap();
+    assert_eq!(
+        character_data[chardata_idx].trim(),
+        "character_data!(Enum{items: &[(EnumItem::StaticKind, 0x1), (EnumItem::DynamicKind, 0x1)]}),"
+    );
+    assert!(signal_kind.contains("mode: Characters"));
+    assert!(!signal_kind.contains("attributes: (0, 0)"));
+}
+
+#[test]
+fn optional_group_ref_makes_its_elements_optional() {
+    let workdir = run_single_mode("groupref");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // I-SIGNAL references the group SIGNAL-TIMING with minOccurs="0", so its mandatory elements become optional
+    for element_name in ["CycleTime", "StartOffset"] {
+        let element = specification
+            .lines()
+            .find(|line| line.contains(&format!("element!({element_name}, ")))
+            .unwrap();
+        assert!(element.contains(", ZeroOrOne, "), "{element}");
+    }
+}
+
+#[test]
+fn group_tags_apply_to_the_group_elements() {
+    let workdir = run_single_mode("grouptags");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // the group SIGNAL-TIMING is ordered and splittable, while its elements have no tags of their own
+    for element_name in ["CycleTime", "StartOffset"] {
+        let element = specification
+            .lines()
+            .find(|line| line.contains(&format!("element!({element_name}, ")))
+            .unwrap();
+        assert!(element.contains(", true, 0x1, "), "{element}");
+    }
+    // the other elements of I-SIGNAL are not affected
+    let element = specification
+        .lines()
+        .find(|line| line.contains("element!(SystemSignalRef, "))
+        .unwrap();
+    assert!(element.contains(", false, 0x0, "), "{element}");
+}
+
+#[test]
+fn stereotype_lists_and_atp_tags() {
+    // the same group tags as in group_tags_apply_to_the_group_elements, but in the form used by later versions
+    let mini = mini_xsd();
+    let modified = mini.replace(
+        r#"      <xsd:appinfo source="tags">pureMM.isOrdered="true"</xsd:appinfo>
+      <xsd:appinfo source="stereotypes">atpSplitable</xsd:appinfo>"#,
+        r#"      <xsd:appinfo source="atp">pureMM.isOrdered="true"</xsd:appinfo>
+      <xsd:appinfo source="Blueprint">blueprintDerivationGuide="none"</xsd:appinfo>
+      <xsd:appinfo source="stereotypes">atpVariation; atpSplitable;</xsd:appinfo>"#,
+    );
+    assert_ne!(mini, modified);
+
+    let (workdir, success, stdout) = run_single_xsd("stereotypes", &modified, &[]);
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    let specification = read_generated(&workdir, "specification.rs");
+    for element_name in ["CycleTime", "StartOffset"] {
+        let element = specification
+            .lines()
+            .find(|line| line.contains(&format!("element!({element_name}, ")))
+            .unwrap();
+        assert!(element.contains(", true, 0x1, "), "{element}");
+    }
+}
+
+#[test]
+fn duplicate_elements_are_reported_and_merged() {
+    // SYSTEM-SIGNAL contains DYNAMIC-LENGTH directly and through the group SIGNAL-LENGTH
+    let (_workdir, stdout) = run_single_mode_with_args("duplicates", &[]);
+    assert!(
+        stdout.contains("the element DYNAMIC-LENGTH appears twice"),
+        "{stdout}"
+    );
+
+    let (workdir, stdout) = run_single_mode_with_args("duplicates-merged", &["--merge-duplicates"]);
+    assert!(stdout.contains("merged 1 duplicate elements"), "{stdout}");
+    let specification = read_generated(&workdir, "specification.rs");
+    let dynamic_length_count = specification
+        .lines()
+        .filter(|line| line.contains("element!(DynamicLength, "))
+        .count();
+    assert_eq!(dynamic_length_count, 1);
+}
+
+#[test]
+fn english_docstrings_are_preferred() {
+    let workdir = run_single_mode("doclang");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // SIGNAL-KIND is documented in German and English; the English text is used
+    assert!(specification.contains("    \"Kind of the signal\",\n"));
+    assert!(!specification.contains("Art des Signals"));
+}
+
+#[test]
+fn mm_class_names_are_parsed_from_the_comment() {
+    let workdir = run_single_mode("mm-class");
+    let html = read_generated(&workdir, "schema_reference.html");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // the comment of SYSTEM-SIGNAL names two classes, which are shown in the html reference, but not in DATATYPES
+    assert!(
+        html.contains("<p>Content: Sequence, class: SystemSignal / SystemSignalVariant</p>"),
+        "{html}"
+    );
+    assert!(
+        specification.contains(" // SYSTEM-SIGNAL\n"),
+        "{specification}"
+    );
+    assert!(!specification.contains("SystemSignalVariant"));
+}
+
+#[test]
+fn unmatched_dest_values_are_reported() {
+    // SYSTEM-SIGNAL-GROUP is a DEST value of SYSTEM-SIGNAL-REF, but there is no such element type
+    let (workdir, stdout) = run_single_mode_with_args("dest", &[]);
+    assert!(
+        stdout.contains(
+            "Warning: the DEST value SYSTEM-SIGNAL-GROUP of AR:SYSTEM-SIGNAL--SUBTYPES-ENUM"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        !stdout.contains("the DEST value SYSTEM-SIGNAL of"),
+        "{stdout}"
+    );
+
+    let stats = read_generated(&workdir, "schema_stats.txt");
+    assert!(stats.contains(
+        "## DEST values without target types\n1 unmatched\nSYSTEM-SIGNAL-GROUP (AR:SYSTEM-SIGNAL--SUBTYPES-ENUM)\n"
+    ));
+}
+
+#[test]
+fn keyref_targets_are_referenceable() {
+    // SYSTEM-SIGNAL-GROUP is not referrable, but a keyref makes it a reference target
+    let mini = mini_xsd();
+    let with_group_type = mini
+        .replace(
+            r#"            <xsd:element name="I-SIGNAL" type="AR:I-SIGNAL"/>"#,
+            r#"            <xsd:element name="I-SIGNAL" type="AR:I-SIGNAL"/>
+            <xsd:element name="SYSTEM-SIGNAL-GROUP" type="AR:SYSTEM-SIGNAL-GROUP"/>"#,
+        )
+        .replace(
+            r#"  <xsd:complexType name="I-SIGNAL""#,
+            r#"  <xsd:complexType name="SYSTEM-SIGNAL-GROUP" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:element name="LABEL" type="AR:IDENTIFIER" minOccurs="0"/>
+    </xsd:sequence>
+  </xsd:complexType>
+  <xsd:complexType name="I-SIGNAL""#,
+        );
+    let with_keyref = with_group_type.replace(
+        r#"  <xsd:element name="AUTOSAR" type="AR:AUTOSAR"/>"#,
+        r#"  <xsd:element name="AUTOSAR" type="AR:AUTOSAR">
+    <xsd:key name="signalGroupKey">
+      <xsd:selector xpath=".//AR:SYSTEM-SIGNAL-GROUP"/>
+      <xsd:field xpath="AR:LABEL"/>
+    </xsd:key>
+    <xsd:keyref name="signalRef" refer="AR:signalGroupKey">
+      <xsd:selector xpath=".//AR:SYSTEM-SIGNAL-REF"/>
+      <xsd:field xpath="."/>
+    </xsd:keyref>
+  </xsd:element>"#,
+    );
+    assert_ne!(with_group_type, with_keyref);
+
+    let (_workdir, success, stdout) = run_single_xsd("keyref-group", &with_group_type, &[]);
+    assert!(success, "{stdout}");
+    assert!(
+        stdout.contains("Warning: the DEST value SYSTEM-SIGNAL-GROUP of"),
+        "{stdout}"
+    );
+    let (_workdir, success, stdout) = run_single_xsd("keyref", &with_keyref, &[]);
+    assert!(success, "{stdout}");
+    assert!(
+        !stdout.contains("Warning: the DEST value SYSTEM-SIGNAL-GROUP of"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn notation_is_skipped() {
+    let mini = mini_xsd();
+    let with_notation = mini.replace(
+        r#"  <xsd:attributeGroup name="AR-OBJECT">"#,
+        r#"  <xsd:notation name="png" public="image/png" system="png.exe"/>
+  <xsd:notation name="jpeg" public="image/jpeg">
+    <xsd:annotation>
+      <xsd:documentation>JPEG image</xsd:documentation>
+    </xsd:annotation>
+  </xsd:notation>
+  <xsd:attributeGroup name="AR-OBJECT">"#,
+    );
+    assert_ne!(mini, with_notation);
+
+    let (workdir, success, stdout) = run_single_xsd("notation", &with_notation, &[]);
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("Error"), "{stdout}");
+    assert!(workdir.join("gen/specification.rs").exists(), "{stdout}");
+}
+
+#[test]
+fn fuzz_corpus_does_not_panic() {
+    // the inputs of the fuzz corpus must either load or be rejected with an error
+    let corpus_dir =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/xsd_parser");
+    for entry in std::fs::read_dir(corpus_dir).unwrap() {
+        let path = entry.unwrap().path();
+        let xsd = std::fs::read(&path).unwrap();
+        let result = autosar_xsd_mangler::phases::parse_and_flatten_reader(&xsd[..]);
+        match path.file_name().unwrap().to_str().unwrap() {
+            "mini.xsd" | "short_group_name.xsd" => assert!(result.is_ok(), "{}", path.display()),
+            _ => assert!(
+                result.is_err_and(|err| err.starts_with("Error: ")),
+                "{}",
+                path.display()
+            ),
+        }
+    }
+}
+
+#[test]
+fn redefinitions_are_derived_from_the_original() {
+    // redefine.xsd includes oem-types.xsd and redefines the types and groups of mini.xsd in terms of themselves
+    let (workdir, success, stdout) = run_single_file(
+        "redefine",
+        &fixture("redefine/redefine.xsd"),
+        &["--emit-csv=availability.csv"],
+    );
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // the redefined group SIGNAL-LENGTH appends MAX-LENGTH to the original group, and the complexContent extension of
+    // SYSTEM-SIGNAL appends OEM-NOTE, whose type comes from the included file, to the original content
+    let csv = std::fs::read_to_string(workdir.join("availability.csv")).unwrap();
+    assert!(
+        csv.contains(
+            "AR:SYSTEM-SIGNAL,SHORT-NAME,AR:IDENTIFIER,1,no,NotRestricted,yes\r\n\
+             AR:SYSTEM-SIGNAL,DYNAMIC-LENGTH,AR:BOOLEAN,0..1,no,NotRestricted,yes\r\n\
+             AR:SYSTEM-SIGNAL,DYNAMIC-LENGTH,AR:BOOLEAN,0..1,no,NotRestricted,yes\r\n\
+             AR:SYSTEM-SIGNAL,MAX-LENGTH,AR:BOOLEAN,0..1,no,NotRestricted,yes\r\n\
+             AR:SYSTEM-SIGNAL,SIGNAL-KIND,AR:SIGNAL-KIND,0..1,no,NotRestricted,yes\r\n\
+             AR:SYSTEM-SIGNAL,OEM-NOTE,AR:OEM-NOTE,0..1,no,NotRestricted,yes\r\n"
+        ),
+        "{csv}"
+    );
+
+    // both extensions keep the attributes of the original definition, and BOOLEAN keeps its character content;
+    // OEM-ID has the type of the redefined STRING--SIMPLE, which is a restriction of the original
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(specification.contains(
+        "    (AttributeName::T, 4, false),\n    (AttributeName::Uuid, 4, false),\n    (AttributeName::OemId, 4, false)\n"
+    ), "{specification}");
+    assert!(specification.contains("    character_data!(Pattern{check_fn: validate_regex_6, regex: r\"0|1|true|false\", max_length: None}),\n"));
+    assert!(specification.contains(
+        "attributes: (8, 10), attributes_ver: 0, character_data: Some(0), mode: Characters, ref_info: (0, 0)), // CYCLE-TIME, DYNAMIC-LENGTH, MAX-LENGTH, START-OFFSET\n"
+    ));
+    assert!(specification
+        .contains("    character_data!(String{preserve_whitespace: false, max_length: None}),\n"));
+    assert!(!specification.contains("REDEFINE-BASE"));
+}
+
+#[test]
+fn redefinition_without_original_is_an_error() {
+    let xsd_dir = TempDir::new("redefine-missing-xsd");
+    for filename in ["config.toml", "oem-types.xsd"] {
+        std::fs::copy(fixture("redefine").join(filename), xsd_dir.join(filename)).unwrap();
+    }
+    // OEM-SIGNAL is derived from itself, but mini.xsd has no original definition of it
+    let redefine = read_fixture("redefine/redefine.xsd")
+        .replace(
+            "../single/mini.xsd",
+            &fixture("single/mini.xsd").display().to_string(),
+        )
+        .replace("\"SYSTEM-SIGNAL\"", "\"OEM-SIGNAL\"")
+        .replace("\"AR:SYSTEM-SIGNAL\"", "\"AR:OEM-SIGNAL\"");
+    std::fs::write(xsd_dir.join("redefine.xsd"), redefine).unwrap();
+
+    let (_workdir, success, stdout) =
+        run_single_file("redefine-missing", &xsd_dir.join("redefine.xsd"), &[]);
+    assert!(!success);
+    assert!(
+        stdout.contains(
+            "Error: redefined complexType OEM-SIGNAL references itself, but there is no original definition"
+        ),
+        "{stdout}"
+    );
+}
+
+// I-SIGNAL of the miniature schema, rewritten as a complexContent extension of SIGNAL-BASE, which is itself an
+// extension of IDENTIFIABLE. Each level contributes a part of the original sequence and one of the attributes.
+const I_SIGNAL_EXTENSION: &str = r#"  <xsd:complexType name="IDENTIFIABLE" abstract="true" mixed="false">
+    <xsd:sequence>
+      <xsd:group ref="AR:REFERRABLE"/>
+    </xsd:sequence>
+    <xsd:attributeGroup ref="AR:AR-OBJECT"/>
+  </xsd:complexType>
+  <xsd:complexType name="SIGNAL-BASE" abstract="true" mixed="false">
+    <xsd:complexContent>
+      <xsd:extension base="AR:IDENTIFIABLE">
+        <xsd:sequence>
+          <xsd:element name="DYNAMIC-LENGTH" type="AR:BOOLEAN" minOccurs="0"/>
+        </xsd:sequence>
+        <xsd:attribute name="T" type="AR:STRING--SIMPLE"/>
+      </xsd:extension>
+    </xsd:complexContent>
+  </xsd:complexType>
+  <xsd:complexType name="I-SIGNAL" abstract="false" mixed="false">
+    <xsd:complexContent>
+      <xsd:extension base="AR:SIGNAL-BASE">
+        <xsd:sequence>
+          <xsd:element name="SYSTEM-SIGNAL-REF" minOccurs="0">
+            <xsd:complexType>
+              <xsd:simpleContent>
+                <xsd:extension base="AR:REF">
+                  <xsd:attribute name="DEST" type="AR:SYSTEM-SIGNAL--SUBTYPES-ENUM" use="required"/>
+                </xsd:extension>
+              </xsd:simpleContent>
+            </xsd:complexType>
+          </xsd:element>
+          <xsd:group ref="AR:SIGNAL-TIMING" minOccurs="0"/>
+        </xsd:sequence>
+        <xsd:attribute name="S" type="AR:STRING--SIMPLE"/>
+      </xsd:extension>
+    </xsd:complexContent>
+  </xsd:complexType>
+"#;
+
+#[test]
+fn complex_content_extension_inherits_content_and_attributes() {
+    let mini = mini_xsd();
+    let start = mini.find("  <xsd:complexType name=\"I-SIGNAL\"").unwrap();
+    let end = start
+        + mini[start..].find("\n  </xsd:complexType>\n").unwrap()
+        + "\n  </xsd:complexType>\n".len();
+    let xsd = format!("{}{I_SIGNAL_EXTENSION}{}", &mini[..start], &mini[end..]);
+
+    let (workdir, success, stdout) =
+        run_single_xsd("complex-content", &xsd, &["--emit-csv=availability.csv"]);
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // the content of the base types comes first, followed by the content of each extension
+    let csv = std::fs::read_to_string(workdir.join("availability.csv")).unwrap();
+    assert!(
+        csv.contains(
+            "AR:I-SIGNAL,SHORT-NAME,AR:IDENTIFIER,1,no,NotRestricted,yes\r\n\
+             AR:I-SIGNAL,DYNAMIC-LENGTH,AR:BOOLEAN,0..1,no,NotRestricted,yes\r\n\
+             AR:I-SIGNAL,SYSTEM-SIGNAL-REF,AR:I-SIGNAL-SYSTEM-SIGNAL-REF-TYPE,0..1,no,NotRestricted,yes\r\n\
+             AR:I-SIGNAL,CYCLE-TIME,AR:BOOLEAN,0..1,yes,NotRestricted,yes\r\n\
+             AR:I-SIGNAL,START-OFFSET,AR:BOOLEAN,0..1,yes,NotRestricted,yes\r\n"
+        ),
+        "{csv}"
+    );
+
+    // I-SIGNAL has the attributes of both base types in addition to its own attribute S
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(specification.contains(
+        "    (AttributeName::Uuid, 4, false),\n    (AttributeName::T, 4, false),\n    (AttributeName::S, 4, false),\n"
+    ), "{specification}");
+    assert!(
+        specification.contains("attributes: (0, 3), attributes_ver: 0, character_data: None, mode: Sequence, ref_info: (0, 0)), // I-SIGNAL\n"),
+        "{specification}"
+    );
+}
+
+#[test]
+fn repeated_tags_keep_the_first_value() {
+    // SIGNAL-KIND has two <appinfo source="tags"> blocks: both set pureMM.isOrdered, with different values, and only
+    // the second one sets mmt.RestrictToStandards
+    let (workdir, success, stdout) = run_single_file("tags", &fixture("tags/mini.xsd"), &[]);
+    assert!(success, "{stdout}");
+    assert!(
+        stdout.contains(
+            "Warning: conflicting values \"true\" and \"false\" for tag pureMM.isOrdered at "
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains(", keeping the first value\n"), "{stdout}");
+    assert_eq!(stdout.matches("Warning: conflicting values").count(), 1);
+
+    // the tags of both blocks are used, and the first value of pureMM.isOrdered is kept
+    let specification = read_generated(&workdir, "specification.rs");
+    let signal_kind = specification
+        .lines()
+        .find(|line| line.contains("element!(SignalKind, "))
+        .unwrap();
+    assert!(
+        signal_kind.contains(", true, 0x0, ClassicPlatform, "),
+        "{signal_kind}"
+    );
+}
@@ -0,0 +1,103 @@
+use autosar_xsd_mangler::snapshot;
+use std::path::Path;
+use std::process::Command;
+
+// the snapshots in tests/snapshots contain the complete generated source; after an intended change of the generated
+// code they are updated with `INSTA_UPDATE=always cargo test --test generated_enums` or with `cargo insta review`
+
+#[test]
+fn element_name_enum() {
+    let generated = snapshot::generate_enum(
+        "ElementName",
+        &[
+            "AR-PACKAGE",
+            "AR-PACKAGES",
+            "AUTOSAR",
+            "ELEMENTS",
+            "SHORT-NAME",
+            "SYSTEM-SIGNAL",
+        ],
+    )
+    .unwrap();
+    insta::assert_snapshot!(generated);
+}
+
+#[test]
+fn enum_item_enum() {
+    // EnumItem uses a different doc comment for xml_name; the item names are not all uppercase
+    let generated =
+        snapshot::generate_enum("EnumItem", &["APPLICATION", "default", "SENSOR-ACTUATOR"])
+            .unwrap();
+    insta::assert_snapshot!(generated);
+}
+
+#[test]
+fn autosar_version_enum() {
+    let generated = snapshot::generate_autosar_version(&[
+        ("AUTOSAR_00050.xsd", "Autosar_00050", "AUTOSAR R21-11"),
+        ("AUTOSAR_00051.xsd", "Autosar_00051", "AUTOSAR R22-11"),
+    ]);
+    insta::assert_snapshot!(generated);
+}
+
+#[test]
+fn enum_of_65536_items_uses_u32() {
+    // the generated test of try_from needs COUNT to be out of range, which it isn't in a u16 with 65536 items
+    let names: Vec<String> = (0..65_536).map(|idx| format!("ITEM-{idx}")).collect();
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let generated = snapshot::generate_enum("LargeEnum", &name_refs).unwrap();
+    assert!(generated.contains("#[repr(u32)]"));
+    assert!(generated.contains("assert!(LargeEnum::try_from(LargeEnum::COUNT as u32).is_err());"));
+}
+
+#[test]
+fn large_enum_uses_u32() {
+    // more than 65535 items don't fit into a u16 repr; the generated code is compiled to check that every name can
+    // be looked up, because a snapshot of a file of this size can't be reviewed
+    let names: Vec<String> = (0..70_000).map(|idx| format!("ITEM-{idx}")).collect();
+    let name_refs: Vec<&str> = names.iter().map(String::as_str).collect();
+    let generated = snapshot::generate_enum("LargeEnum", &name_refs).unwrap();
+    assert!(generated.contains("#[repr(u32)]"));
+    assert!(generated.contains("const DISPLACEMENTS: [(u32, u32); "));
+
+    let workdir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("large_enum_uses_u32");
+    std::fs::create_dir_all(&workdir).unwrap();
+    std::fs::write(
+        workdir.join("hashfunc.rs"),
+        snapshot::generate_hashfunc_module(),
+    )
+    .unwrap();
+    std::fs::write(workdir.join("large_enum.rs"), generated).unwrap();
+    std::fs::write(
+        workdir.join("main.rs"),
+        format!(
+            r#"mod hashfunc;
+mod large_enum;
+
+const NAMES: [&str; {}] = {name_refs:?};
+
+fn main() {{
+    for name in NAMES {{
+        let item = large_enum::LargeEnum::from_bytes(name.as_bytes()).unwrap();
+        assert!((item as usize) < NAMES.len());
+        assert_eq!(item.to_str(), name);
+    }}
+    assert!(large_enum::LargeEnum::from_bytes(b"ITEM-70000").is_err());
+}}
+"#,
+            name_refs.len()
+        ),
+    )
+    .unwrap();
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let status = Command::new(rustc)
+        .args(["--edition=2021", "-Adead_code", "-o"])
+        .arg(workdir.join("large_enum"))
+        .arg(workdir.join("main.rs"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let status = Command::new(workdir.join("large_enum")).status().unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,71 @@
+use autosar_xsd_mangler::phases;
+use std::path::{Path, PathBuf};
+
+// the generated files that are compared with the golden files in tests/golden/<case>/
+const GOLDEN_FILES: &[&str] = &[
+    "attributename.rs",
+    "autosarversion.rs",
+    "elementname.rs",
+    "enumitem.rs",
+    "specification.rs",
+];
+
+// run all phases on the fixture in tests/fixtures/golden/<case>, and write the generated code to a fresh directory
+fn generate_case(case: &str, input: &str) -> PathBuf {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/golden")
+        .join(case)
+        .join(input);
+    let output_dir = std::env::temp_dir().join(format!(
+        "autosar-xsd-mangler-golden-{case}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&output_dir);
+
+    let input = phases::Input::find(&fixture).unwrap();
+    let parsed = phases::parse(&input).unwrap();
+    let flat = phases::flatten(&parsed).unwrap();
+    let mut schema = phases::merge(&flat).unwrap();
+    phases::dedup(&mut schema);
+    phases::generate_into(&input, &schema, &output_dir).unwrap();
+    output_dir
+}
+
+// compare the generated files with the golden files; with BLESS=1 the golden files are replaced instead
+fn check_golden(case: &str, output_dir: &Path) {
+    let golden_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(case);
+    let bless = std::env::var_os("BLESS").is_some_and(|value| value == "1");
+
+    let mut mismatches = Vec::new();
+    for filename in GOLDEN_FILES {
+        let generated = std::fs::read_to_string(output_dir.join(filename)).unwrap();
+        let golden_path = golden_dir.join(filename);
+        if bless {
+            std::fs::create_dir_all(&golden_dir).unwrap();
+            std::fs::write(&golden_path, &generated).unwrap();
+        } else if std::fs::read_to_string(&golden_path).ok().as_ref() != Some(&generated) {
+            mismatches.push(golden_path.display().to_string());
+        }
+    }
+    std::fs::remove_dir_all(output_dir).unwrap();
+
+    assert!(
+        mismatches.is_empty(),
+        "the generated code differs from the golden files; rerun with BLESS=1 to update them:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+#[test]
+fn golden_single_version() {
+    let output_dir = generate_case("single", "golden.xsd");
+    check_golden("single", &output_dir);
+}
+
+#[test]
+fn golden_two_versions() {
+    let output_dir = generate_case("two_versions", "");
+    check_golden("two_versions", &output_dir);
+}
@@ -0,0 +1,152 @@
+// This file is @generated by autosar-xsd-mangler 0.6.0
+// Do not edit it manually, regenerate it instead.
+//
+// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.
+//
+// Input files:
+//   golden.xsd  sha256: 073754a408960b9fe3c0c9a6b66efaed6754c56cc645fdfaee6ccaefc050e182
+
+use num_derive::FromPrimitive;
+use num_traits::cast::FromPrimitive;
+
+#[derive(Debug)]
+/// Error type returned when `from_str()` / `parse()` for `AutosarVersion` fails
+pub struct ParseAutosarVersionError;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, FromPrimitive)]
+#[repr(u32)]
+#[non_exhaustive]
+/// Enum of all Autosar versions
+pub enum AutosarVersion {
+    /// golden test schema - xsd file name: `golden.xsd`
+    Golden = 0x1,
+}
+
+impl AutosarVersion {
+    /// get the name of the xsd file matching the Autosar version
+    #[must_use]
+    pub fn filename(&self) -> &'static str {
+        match self {
+            Self::Golden => "golden.xsd",
+        }
+    }
+
+    /// Human readable description of the Autosar version
+    ///
+    /// This is particularly useful for the later versions, where the xsd files are just sequentially numbered.
+    /// For example `Autosar_00050` -> "AUTOSAR R21-11"
+    #[must_use]
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Golden => "golden test schema",
+        }
+    }
+
+    /// the xml namespace of the Autosar version, taken from the targetNamespace of the xsd file
+    #[must_use]
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            Self::Golden => "http://autosar.org/schema/r4.0",
+        }
+    }
+
+    /// the value of the xsi:schemaLocation attribute of an arxml file for the Autosar version
+    ///
+    /// For example `Autosar_00051` -> "http://autosar.org/schema/r4.0 AUTOSAR_00051.xsd"
+    #[must_use]
+    pub fn schema_location(&self) -> &'static str {
+        match self {
+            Self::Golden => "http://autosar.org/schema/r4.0 golden.xsd",
+        }
+    }
+
+    /// make an `AutosarVersion` from a u32 value
+    ///
+    /// All `AutosarVersion`s are associated with a power of two u32 value, for example `Autosar_4_3_0` == 0x100
+    /// If the given value is a valid constant of `AutosarVersion`, the enum value will be returnd
+    ///
+    /// This is useful in order to decode version masks
+    #[must_use]
+    pub fn from_val(n: u32) -> Option<Self> {
+        Self::from_u32(n)
+    }
+
+    /// parse the human readable version strings that are used by AUTOSAR tools, e.g. in the ADMIN-DATA of a file
+    ///
+    /// The accepted strings are derived from the descriptions of the versions, for example
+    /// "4.3.0", "AUTOSAR 4.3.0", "R22-11" and "AUTOSAR R22-11". Use `from_str()` to parse the name of an xsd file.
+    #[must_use]
+    pub fn from_schema_version_string(input: &str) -> Option<Self> {
+        match input.trim() {
+            "golden test schema" => Some(Self::Golden),
+
+            _ => None,
+        }
+    }
+
+    /// `AutosarVersion::LATEST` is an alias of which ever is the latest standard version
+    pub const LATEST: AutosarVersion = AutosarVersion::Golden;
+
+    /// a version mask that contains all Autosar versions for which the specification contains data
+    pub const ALL_VERSIONS_MASK: u32 = 0x1;
+
+    /// all Autosar versions, from oldest to newest
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        &[Self::Golden]
+    }
+
+    /// make a version mask that contains all versions from `first` to `last`, inclusive
+    ///
+    /// The mask is empty if `first` is newer than `last`
+    #[must_use]
+    pub const fn mask_from_range(first: Self, last: Self) -> u32 {
+        let first = first as u32;
+        let last = last as u32;
+        if first > last {
+            0
+        } else {
+            // all bits up to and including last, minus all bits below first
+            (last | (last - 1)) & !(first - 1)
+        }
+    }
+
+    /// check if the Autosar version describes the classic platform
+    #[must_use]
+    pub fn is_classic(&self) -> bool {
+        matches!(self, Self::Golden)
+    }
+
+    /// check if the Autosar version describes the adaptive platform
+    #[must_use]
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, Self::Golden)
+    }
+}
+
+/// SHA-256 fingerprint of the xsd files that the specification was generated from
+///
+/// The hash covers the contents of all input xsd files, concatenated in the order of their file names.
+/// It can be compared with a known value to check that the specification matches the expected schema files.
+pub const SCHEMA_HASH: [u8; 32] = [
+    0x07, 0x37, 0x54, 0xa4, 0x08, 0x96, 0x0b, 0x9f, 0xe3, 0xc0, 0xc9, 0xa6, 0xb6, 0x6e, 0xfa, 0xed,
+    0x67, 0x54, 0xc5, 0x6c, 0xc6, 0x45, 0xfd, 0xfa, 0xee, 0x6c, 0xca, 0xef, 0xc0, 0x50, 0xe1, 0x82,
+];
+
+impl core::str::FromStr for AutosarVersion {
+    type Err = ParseAutosarVersionError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "golden.xsd" => Ok(Self::Golden),
+
+            _ => Err(ParseAutosarVersionError),
+        }
+    }
+}
+
+impl core::fmt::Display for AutosarVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.describe())
+    }
+}
@@ -0,0 +1,429 @@
+// This file is @generated by autosar-xsd-mangler 0.6.0
+// Do not edit it manually, regenerate it instead.
+//
+// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.
+//
+// Input files:
+//   golden.xsd  sha256: 073754a408960b9fe3c0c9a6b66efaed6754c56cc645fdfaee6ccaefc050e182
+
+use crate::regex::*;
+use crate::*;
+
+#[cfg(feature = "docstrings")]
+macro_rules! element {
+    ($namepart:ident, $etype:literal, $mult:ident $(($count:literal))?, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
+        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult $(($count))?, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict, docstring: $docid}
+    };
+}
+#[cfg(not(feature = "docstrings"))]
+macro_rules! element {
+    ($namepart:ident, $etype:literal, $mult:ident $(($count:literal))?, $ordered:literal, $splittable:literal, $stdrestrict:ident, $docid:expr) => {
+        ElementDefinition{name: ElementName::$namepart, elemtype: $etype, multiplicity: ElementMultiplicity::$mult $(($count))?, ordered: $ordered, splittable: $splittable, restrict_std: StdRestrict::$stdrestrict}
+    };
+}
+
+macro_rules! e {
+    ($idx:literal) => {
+        SubElement::Element($idx)
+    };
+}
+
+macro_rules! g {
+    ($idx:literal) => {
+        SubElement::Group($idx)
+    };
+}
+
+macro_rules! spec {
+    (sub_elements: $sub_elements:expr, sub_element_ver: $sub_element_ver:literal, attributes: $attributes:expr, attributes_ver: $attributes_ver:literal, character_data: $character_data:expr, mode: $mode:ident, ref_info: $ref_info:expr) => {
+        ElementSpec {
+            sub_elements: $sub_elements,
+            sub_element_ver: $sub_element_ver,
+            attributes: $attributes,
+            attributes_ver: $attributes_ver,
+            character_data: $character_data,
+            mode: ContentMode::$mode,
+            ref_info: $ref_info,
+        }
+    };
+}
+
+macro_rules! character_data {
+    (Pattern{check_fn: $check_fn:ident, regex: $regex:literal, max_length: $max_length:expr}) => {
+        CharacterDataSpec::Pattern {
+            check_fn: $check_fn,
+            regex: $regex,
+            max_length: $max_length,
+        }
+    };
+    (Enum{items: $items:expr}) => {
+        CharacterDataSpec::Enum { items: $items }
+    };
+    (String{preserve_whitespace: $preserve_whitespace:literal, max_length: $max_length:expr}) => {
+        CharacterDataSpec::String {
+            preserve_whitespace: $preserve_whitespace,
+            max_length: $max_length,
+        }
+    };
+    (UnsignedInteger) => {
+        CharacterDataSpec::UnsignedInteger
+    };
+    (Double) => {
+        CharacterDataSpec::Double
+    };
+    (DateTime{format: $format:expr}) => {
+        CharacterDataSpec::DateTime { format: $format }
+    };
+}
+
+#[rustfmt::skip]
+#[allow(deprecated)]
+pub(crate) const CHARACTER_DATA: [CharacterDataSpec; 9] = [
+    character_data!(Enum{items: &[(EnumItem::Application, 0x1), (EnumItem::SensorActuator, 0x1), (EnumItem::Service, 0x1)]}),
+    character_data!(Enum{items: &[(EnumItem::DataType, 0x1)]}),
+    character_data!(Pattern{check_fn: validate_regex_8, regex: r"[a-zA-Z][a-zA-Z0-9_]*", max_length: Some(128)}),
+    character_data!(Pattern{check_fn: validate_regex_24, regex: r"/?[a-zA-Z][a-zA-Z0-9_]{0,127}(/[a-zA-Z][a-zA-Z0-9_]{0,127})*", max_length: None}),
+    character_data!(String{preserve_whitespace: true, max_length: None}),
+    character_data!(DateTime{format: None}),
+    character_data!(Double),
+    character_data!(String{preserve_whitespace: false, max_length: None}),
+    character_data!(UnsignedInteger),
+];
+pub(crate) const REFERENCE_TYPE_IDX: u16 = 3;
+pub(crate) const IDENTIFIER_TYPE_IDX: u16 = 2;
+pub(crate) const STRING_PLAIN_IDX: u16 = 7;
+pub(crate) const STRING_PRESERVE_WHITESPACE_IDX: u16 = 4;
+
+#[rustfmt::skip]
+pub(crate) const ELEMENTS: [ElementDefinition; 10] = [
+    /*    0 */ element!(Autosar, 2, One, false, 0xFFFFFFFF, NotRestricted, Some(0)),
+    /*    1 */ element!(ArPackage, 0, One, false, 0x0, NotRestricted, None),
+    /*    2 */ element!(ArPackages, 3, ZeroOrOne, false, 0x0, NotRestricted, None),
+    /*    3 */ element!(BaseSize, 7, ZeroOrOne, false, 0x0, NotRestricted, None),
+    /*    4 */ element!(Category, 4, ZeroOrOne, false, 0x0, NotRestricted, None),
+    /*    5 */ element!(DataType, 5, One, false, 0x0, NotRestricted, None),
+    /*    6 */ element!(DataTypeRef, 9, ZeroOrOne, false, 0x0, NotRestricted, None),
+    /*    7 */ element!(Elements, 1, ZeroOrOne, false, 0x0, NotRestricted, None),
+    /*    8 */ element!(ShortName, 6, One, false, 0x0, NotRestricted, None),
+    /*    9 */ element!(SwComponent, 8, One, false, 0x0, NotRestricted, None),
+];
+
+pub(crate) const AUTOSAR_ELEMENT: u16 = 0;
+const _: () = assert!((AUTOSAR_ELEMENT as usize) < ELEMENTS.len());
+
+// the versions in which each element in ELEMENTS is splittable
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const SPLITTABLE_VERSIONS: [u32; 10] = [0xFFFFFFFF, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
+
+// bitset of the ordered elements in ELEMENTS: bit (idx % 32) of ORDERED_ELEMENTS[idx / 32] is set if ELEMENTS[idx] is ordered
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const ORDERED_ELEMENTS: [u32; 1] = [0x00000000];
+
+#[cfg(test)]
+mod element_flags_test {
+    use super::*;
+
+    #[test]
+    fn element_flags_consistency() {
+        assert_eq!(SPLITTABLE_VERSIONS.len(), ELEMENTS.len());
+        assert!(ORDERED_ELEMENTS.len() * 32 >= ELEMENTS.len());
+        assert!(ORDERED_ELEMENTS.len() * 32 < ELEMENTS.len() + 32);
+        for (idx, elem) in ELEMENTS.iter().enumerate() {
+            assert_eq!(SPLITTABLE_VERSIONS[idx], elem.splittable);
+            let ordered = ORDERED_ELEMENTS[idx / 32] & (1 << (idx % 32)) != 0;
+            assert_eq!(ordered, elem.ordered);
+        }
+        // bits beyond the end of ELEMENTS must not be set
+        for idx in ELEMENTS.len()..ORDERED_ELEMENTS.len() * 32 {
+            assert_eq!(ORDERED_ELEMENTS[idx / 32] & (1 << (idx % 32)), 0);
+        }
+    }
+}
+
+#[rustfmt::skip]
+pub(crate) const SUBELEMENTS: [SubElement; 11] = [
+    e!(8), e!(4), e!(6), e!(8), e!(7), e!(9), e!(5), e!(8), e!(3), e!(2), e!(1),
+];
+
+// for each element definition in ELEMENTS: the range of PARENT_TYPE_ITEMS which lists the indices
+// of all data types in DATATYPES that can contain the element
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const PARENT_TYPES: [(u16, u16); 10] = [(0, 0), (0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (3, 4), (5, 6), (6, 9), (4, 5)];
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const PARENT_TYPE_ITEMS: [u16; 9] = [3, 2, 5, 8, 1, 0, 0, 5, 8];
+
+#[cfg(test)]
+mod parent_types_test {
+    use super::*;
+
+    fn parent_type_names(name: ElementName) -> Vec<&'static str> {
+        let mut names = Vec::new();
+        for (idx, elem) in ELEMENTS.iter().enumerate() {
+            if elem.name == name {
+                let (low, high) = PARENT_TYPES[idx];
+                for type_idx in &PARENT_TYPE_ITEMS[low as usize..high as usize] {
+                    names.push(DATATYPE_NAMES[*type_idx as usize]);
+                }
+            }
+        }
+        names
+    }
+
+    #[test]
+    fn parent_types() {
+        assert!(parent_type_names(ElementName::Autosar).is_empty());
+        assert!(parent_type_names(ElementName::ArPackages).contains(&"AUTOSAR"));
+        assert!(parent_type_names(ElementName::ArPackage).contains(&"AR-PACKAGES"));
+        assert!(parent_type_names(ElementName::ShortName).contains(&"AR-PACKAGE"));
+        assert!(parent_type_names(ElementName::Elements).contains(&"AR-PACKAGE"));
+    }
+}
+
+#[rustfmt::skip]
+pub(crate) const ATTRIBUTES: [(AttributeName, u16, bool); 8] = [
+    (AttributeName::Uuid, 4, false),
+    (AttributeName::S, 4, false),
+    (AttributeName::xmlns, 7, true),
+    (AttributeName::xmlnsXsi, 7, true),
+    (AttributeName::xsiSchemalocation, 7, true),
+    (AttributeName::Uuid, 4, false),
+    (AttributeName::S, 4, false),
+    (AttributeName::Dest, 1, true)
+];
+
+impl AttributeName {
+    /// check if this attribute is required in elements with the specification `spec`
+    ///
+    /// Returns false if the attribute is optional or not allowed at all.
+    #[must_use]
+    pub(crate) fn is_required_in(self, spec: &ElementSpec) -> bool {
+        let (low, high) = spec.attributes;
+        ATTRIBUTES[low as usize..high as usize]
+            .iter()
+            .find(|(name, _, _)| *name == self)
+            .is_some_and(|(_, _, required)| *required)
+    }
+
+    /// iterate over the names of all attributes that are allowed in elements with the specification `spec`
+    pub(crate) fn for_element(spec: &ElementSpec) -> impl Iterator<Item = AttributeName> {
+        let (low, high) = spec.attributes;
+        ATTRIBUTES[low as usize..high as usize]
+            .iter()
+            .map(|(name, _, _)| *name)
+    }
+}
+
+#[cfg(test)]
+mod is_required_in_test {
+    use super::*;
+
+    #[test]
+    fn is_required_in() {
+        for spec in &DATATYPES {
+            let (low, high) = spec.attributes;
+            for (name, _, required) in &ATTRIBUTES[low as usize..high as usize] {
+                assert_eq!(name.is_required_in(spec), *required);
+            }
+        }
+    }
+
+    #[test]
+    fn for_element() {
+        for spec in &DATATYPES {
+            let (low, high) = spec.attributes;
+            assert!(
+                AttributeName::for_element(spec).eq(ATTRIBUTES[low as usize..high as usize]
+                    .iter()
+                    .map(|(name, _, _)| *name))
+            );
+        }
+    }
+}
+
+#[cfg(feature = "docstrings")]
+#[rustfmt::skip]
+pub(crate) const ATTRIBUTE_DOCSTRINGS: [Option<u16>; 8] = [None, None, None, None, None, None, None, None];
+
+#[rustfmt::skip]
+pub(crate) static VERSION_INFO: [u32; 5] = [0x1, 0x1, 0x1, 0x1, 0x1];
+
+pub const ELEMENT_COUNT_PER_VERSION: [u32; 1] = [10];
+
+#[rustfmt::skip]
+pub(crate) const DATATYPES: [ElementSpec; 10] = [
+    /*    0 */ spec!(sub_elements: (3, 5), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: None, mode: Sequence, ref_info: (0, 0)), // AR-PACKAGE
+    /*    1 */ spec!(sub_elements: (5, 7), sub_element_ver: 0, attributes: (0, 0), attributes_ver: 0, character_data: None, mode: Bag, ref_info: (0, 0)), // ELEMENTS
+    /*    2 */ spec!(sub_elements: (9, 10), sub_element_ver: 0, attributes: (0, 5), attributes_ver: 0, character_data: None, mode: Sequence, ref_info: (0, 0)), // AUTOSAR
+    /*    3 */ spec!(sub_elements: (10, 11), sub_element_ver: 0, attributes: (0, 0), attributes_ver: 0, character_data: None, mode: Bag, ref_info: (0, 0)), // AR-PACKAGES
+    /*    4 */ spec!(sub_elements: (0, 0), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: Some(0), mode: Characters, ref_info: (0, 0)), // CATEGORY
+    /*    5 */ spec!(sub_elements: (7, 9), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: None, mode: Sequence, ref_info: (0, 1)), // DATA-TYPE
+    /*    6 */ spec!(sub_elements: (0, 0), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: Some(2), mode: Characters, ref_info: (0, 0)), // SHORT-NAME
+    /*    7 */ spec!(sub_elements: (0, 0), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: Some(8), mode: Characters, ref_info: (0, 0)), // BASE-SIZE
+    /*    8 */ spec!(sub_elements: (0, 3), sub_element_ver: 0, attributes: (0, 2), attributes_ver: 0, character_data: None, mode: Sequence, ref_info: (0, 0)), // SW-COMPONENT
+    /*    9 */ spec!(sub_elements: (0, 0), sub_element_ver: 0, attributes: (5, 8), attributes_ver: 0, character_data: Some(3), mode: Characters, ref_info: (0, 0)), // DATA-TYPE-REF
+];
+
+#[allow(dead_code)]
+pub(crate) const DATATYPE_NAMES: [&str; 10] = [
+    "AR-PACKAGE",
+    "ELEMENTS",
+    "AUTOSAR",
+    "AR-PACKAGES",
+    "CATEGORY",
+    "DATA-TYPE",
+    "SHORT-NAME",
+    "BASE-SIZE",
+    "SW-COMPONENT",
+    "DATA-TYPE-REF",
+];
+
+#[allow(deprecated)]
+pub(crate) const REF_ITEMS: [EnumItem; 1] = [EnumItem::DataType];
+
+/// the largest number of sub elements of any data type
+#[allow(dead_code)]
+pub(crate) const MAX_SUBELEMENT_COUNT: u16 = 3;
+
+/// the largest number of attributes of any data type
+#[allow(dead_code)]
+pub(crate) const MAX_ATTRIBUTE_COUNT: u16 = 5;
+
+/// get the number of sub elements of a data type, which is a useful capacity for its list of child elements
+///
+/// A sub element may also be a group, so this is only a hint. Use the mode of the data type to find out if
+/// the sub elements form a sequence, a choice or a bag.
+#[allow(dead_code)]
+pub(crate) const fn expected_children(type_idx: u16) -> u16 {
+    let (low, high) = DATATYPES[type_idx as usize].sub_elements;
+    high - low
+}
+
+#[cfg(test)]
+mod size_hints_test {
+    use super::*;
+
+    #[test]
+    fn size_hints() {
+        let mut max_subelements = 0;
+        let mut max_attributes = 0;
+        for (type_idx, spec) in DATATYPES.iter().enumerate() {
+            assert_eq!(
+                expected_children(type_idx as u16),
+                spec.sub_elements.1 - spec.sub_elements.0
+            );
+            max_subelements = max_subelements.max(spec.sub_elements.1 - spec.sub_elements.0);
+            max_attributes = max_attributes.max(spec.attributes.1 - spec.attributes.0);
+        }
+        assert_eq!(MAX_SUBELEMENT_COUNT, max_subelements);
+        assert_eq!(MAX_ATTRIBUTE_COUNT, max_attributes);
+    }
+}
+
+// for each EnumItem, by its value: the range of REF_TARGET_TYPE_ITEMS which lists the indices of all
+// data types in DATATYPES that a reference with this DEST value may point to
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const REF_TARGET_TYPES: [(u16, u16); 4] = [(0, 0), (0, 1), (0, 0), (0, 0)];
+
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const REF_TARGET_TYPE_ITEMS: [u16; 1] = [5];
+
+#[cfg(test)]
+mod ref_target_types_test {
+    use super::*;
+
+    #[test]
+    fn ref_target_types() {
+        assert_eq!(REF_TARGET_TYPES.len(), EnumItem::COUNT);
+        // every ref_info entry of a data type is listed in REF_TARGET_TYPES
+        for (type_idx, spec) in DATATYPES.iter().enumerate() {
+            for item in &REF_ITEMS[spec.ref_info.0 as usize..spec.ref_info.1 as usize] {
+                let (low, high) = REF_TARGET_TYPES[*item as usize];
+                assert!(
+                    REF_TARGET_TYPE_ITEMS[low as usize..high as usize].contains(&(type_idx as u16))
+                );
+            }
+        }
+        // every entry of REF_TARGET_TYPES is part of the ref_info of the data type
+        for item in EnumItem::variants() {
+            let (low, high) = REF_TARGET_TYPES[item as usize];
+            for type_idx in &REF_TARGET_TYPE_ITEMS[low as usize..high as usize] {
+                let (ref_low, ref_high) = DATATYPES[*type_idx as usize].ref_info;
+                assert!(REF_ITEMS[ref_low as usize..ref_high as usize].contains(&item));
+            }
+        }
+    }
+}
+
+// stable ids of the types in DATATYPES: the FNV-1a hash of the type name, which does not change when types are added or removed
+#[rustfmt::skip]
+#[allow(dead_code)]
+pub(crate) const TYPE_STABLE_IDS: [u32; 10] = [0x76F4D992, 0x6BD8D193, 0xD3B5676F, 0x09D0E33C, 0xDE93FE1E, 0xBE621047, 0x0B6C0813, 0x14153FB4, 0xFFD203F0, 0xE43D7A39];
+
+// (stable id, index in DATATYPES), sorted by the stable id
+#[rustfmt::skip]
+const TYPE_STABLE_ID_LOOKUP: [(u32, u16); 10] = [(0x09D0E33C, 3), (0x0B6C0813, 6), (0x14153FB4, 7), (0x6BD8D193, 1), (0x76F4D992, 0), (0xBE621047, 5), (0xD3B5676F, 2), (0xDE93FE1E, 4), (0xE43D7A39, 9), (0xFFD203F0, 8)];
+
+/// get the index in DATATYPES of the type with the given stable id
+#[allow(dead_code)]
+pub(crate) fn stable_id_to_spec_index(id: u32) -> Option<usize> {
+    TYPE_STABLE_ID_LOOKUP
+        .binary_search_by_key(&id, |(stable_id, _)| *stable_id)
+        .ok()
+        .map(|pos| TYPE_STABLE_ID_LOOKUP[pos].1 as usize)
+}
+
+#[cfg(test)]
+mod stable_id_test {
+    use super::*;
+
+    #[test]
+    fn stable_id_lookup() {
+        assert_eq!(TYPE_STABLE_IDS.len(), DATATYPES.len());
+        for (idx, id) in TYPE_STABLE_IDS.iter().enumerate() {
+            assert_eq!(stable_id_to_spec_index(*id), Some(idx));
+        }
+    }
+}
+
+#[cfg(feature = "docstrings")]
+pub(crate) const ELEMENT_DOCSTRINGS: [&'static str; 1] =
+    ["Root element of an AUTOSAR description."];
+
+// every data type with sub elements has a mode for elements, and a character type only if it is Mixed
+const _: () = {
+    let mut idx = 0;
+    while idx < DATATYPES.len() {
+        let spec = &DATATYPES[idx];
+        let has_sub_elements = spec.sub_elements.0 < spec.sub_elements.1;
+        match spec.mode {
+            ContentMode::Sequence | ContentMode::Choice | ContentMode::Bag => {
+                assert!(
+                    has_sub_elements,
+                    "a Sequence, Choice or Bag data type has no sub elements"
+                );
+            }
+            ContentMode::Characters => {
+                assert!(!has_sub_elements, "a Characters data type has sub elements");
+                assert!(
+                    spec.character_data.is_some(),
+                    "a Characters data type has no character type"
+                );
+            }
+            ContentMode::Mixed => {
+                assert!(has_sub_elements, "a Mixed data type has no sub elements");
+                assert!(
+                    spec.character_data.is_some(),
+                    "a Mixed data type has no character type"
+                );
+            }
+        }
+        idx += 1;
+    }
+};
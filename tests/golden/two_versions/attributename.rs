@@ -0,0 +1,275 @@
+// This file is @generated by autosar-xsd-mangler 0.6.0
+// Do not edit it manually, regenerate it instead.
+//
+// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.
+//
+// Input files:
+//   AUTOSAR_00001.xsd  sha256: 073754a408960b9fe3c0c9a6b66efaed6754c56cc645fdfaee6ccaefc050e182
+//   AUTOSAR_00002.xsd  sha256: 562eb9c52846315bb17769a4a52656227c1c459dd1b74bddcebd6670e1ae031f
+
+use crate::hashfunc::hashfunc;
+
+#[derive(Debug)]
+/// The error type `ParseAttributeNameError` is returned when `from_str()` / `parse()` fails for `AttributeName`
+pub struct ParseAttributeNameError;
+
+#[allow(dead_code, non_camel_case_types)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+/// Enum of all attribute names in Autosar
+pub enum AttributeName {
+    /// DEST
+    Dest = 2,
+    /// S
+    S = 5,
+    /// T
+    T = 3,
+    /// UUID
+    Uuid = 6,
+    /// xmlns
+    xmlns = 4,
+    /// xmlns:xsi
+    xmlnsXsi = 1,
+    /// xsi:schemaLocation
+    xsiSchemalocation = 0,
+}
+
+impl AttributeName {
+    #[rustfmt::skip]
+    const NAMES: &'static str = "xsi:schemaLocationxmlns:xsiDESTTxmlnsSUUID";
+    #[rustfmt::skip]
+    const NAME_OFFSETS: [u32; 8] = [0, 18, 27, 31, 32, 37, 38, 42];
+
+    fn item_name(idx: usize) -> &'static str {
+        let start = AttributeName::NAME_OFFSETS[idx] as usize;
+        let end = AttributeName::NAME_OFFSETS[idx + 1] as usize;
+        &AttributeName::NAMES[start..end]
+    }
+
+    /// number of items in `AttributeName`
+    pub const COUNT: usize = 7;
+
+    #[rustfmt::skip]
+    const DISPLACEMENTS: [(u16, u16); 2] = [(4, 0), (1, 5)];
+
+    /// derive an enum entry from an input string using a perfect hash function
+    ///
+    /// # Errors
+    ///
+    /// [`ParseAttributeNameError`]: The input string did not match the name of any enum item
+    pub fn from_bytes(input: &[u8]) -> Result<Self, ParseAttributeNameError> {
+        static DISPLACEMENTS: [(u16, u16); 2] = AttributeName::DISPLACEMENTS;
+        let (g, f1, f2) = hashfunc(input);
+        let (d1, d2) = DISPLACEMENTS[(g % 2) as usize];
+        let item_idx = u32::from(d2)
+            .wrapping_add(f1.wrapping_mul(u32::from(d1)))
+            .wrapping_add(f2) as usize
+            % 7;
+        if AttributeName::item_name(item_idx).as_bytes() != input {
+            return Err(ParseAttributeNameError);
+        }
+        Ok(unsafe { core::mem::transmute::<u16, Self>(item_idx as u16) })
+    }
+
+    /// derive an enum entry from an input string, in a const context
+    ///
+    /// This is the const version of `from_bytes`, e.g. for constants and const generic arguments:
+    /// `const ITEM: AttributeName = AttributeName::from_bytes_checked(b"DEST").unwrap();`
+    #[must_use]
+    pub const fn from_bytes_checked(input: &[u8]) -> Option<Self> {
+        let (g, f1, f2) = hashfunc(input);
+        let (d1, d2) = AttributeName::DISPLACEMENTS[(g % 2) as usize];
+        let item_idx = (d2 as u32)
+            .wrapping_add(f1.wrapping_mul(d1 as u32))
+            .wrapping_add(f2) as usize
+            % 7;
+        let name = AttributeName::NAMES.as_bytes();
+        let start = AttributeName::NAME_OFFSETS[item_idx] as usize;
+        let end = AttributeName::NAME_OFFSETS[item_idx + 1] as usize;
+        if end - start != input.len() {
+            return None;
+        }
+        let mut pos = 0;
+        while pos < input.len() {
+            if name[start + pos] != input[pos] {
+                return None;
+            }
+            pos += 1;
+        }
+        Some(unsafe { core::mem::transmute::<u16, Self>(item_idx as u16) })
+    }
+
+    /// derive an enum entry from an input string, ignoring case and accepting '_' in place of '-'
+    ///
+    /// This is intended for user input, e.g. "ar_package" will be accepted for AR-PACKAGE.
+    /// Items whose names contain lowercase characters are only found if the input matches them exactly.
+    ///
+    /// # Errors
+    ///
+    /// [`ParseAttributeNameError`]: The input string did not match the name of any enum item
+    pub fn from_str_relaxed(input: &str) -> Result<Self, ParseAttributeNameError> {
+        if let Ok(item) = Self::from_bytes(input.as_bytes()) {
+            return Ok(item);
+        }
+        // no item name is longer than the buffer, so longer inputs can be rejected immediately
+        let mut buffer = [0u8; 18];
+        if input.len() > buffer.len() {
+            return Err(ParseAttributeNameError);
+        }
+        for (dest, src) in buffer.iter_mut().zip(input.bytes()) {
+            *dest = if src == b'_' {
+                b'-'
+            } else {
+                src.to_ascii_uppercase()
+            };
+        }
+        Self::from_bytes(&buffer[..input.len()])
+    }
+
+    /// get the str corresponding to an item
+    ///
+    /// The returned &str has static lifetime, becasue it is a reference to an entry in a list of constants
+    #[must_use]
+    pub fn to_str(&self) -> &'static str {
+        AttributeName::item_name(*self as usize)
+    }
+
+    /// Returns the XML attribute name as it appears in AUTOSAR files, e.g., `DEST`.
+    #[must_use]
+    #[inline]
+    pub fn xml_name(self) -> &'static str {
+        self.to_str()
+    }
+
+    #[rustfmt::skip]
+    const VERSION_INFO: [u32; 7] = [0x3, 0x3, 0x3, 0x2, 0x3, 0x3, 0x3];
+
+    /// get the bitmask of all Autosar versions in which this item is valid
+    ///
+    /// An item is valid in a version if `item.version_info() & version as u32 != 0`
+    #[must_use]
+    pub fn version_info(self) -> u32 {
+        AttributeName::VERSION_INFO[self as usize]
+    }
+
+    /// check if this item is valid in the given Autosar version
+    #[must_use]
+    pub fn is_valid_for_version(self, version: crate::AutosarVersion) -> bool {
+        self.version_info() & version as u32 != 0
+    }
+
+    /// check if this item is only valid in some of the Autosar versions
+    ///
+    /// Tools that work independently of the version can use this to filter out version specific items.
+    #[must_use]
+    pub fn is_version_specific(self) -> bool {
+        self.version_info() != 0x3
+    }
+
+    /// iterate over all items of `AttributeName`
+    ///
+    /// The items are returned in the order of their numerical values, which is not alphabetical
+    pub fn variants() -> impl ExactSizeIterator<Item = Self> {
+        (0..Self::COUNT).map(|idx| unsafe { core::mem::transmute::<u16, Self>(idx as u16) })
+    }
+}
+
+impl core::str::FromStr for AttributeName {
+    type Err = ParseAttributeNameError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(input.as_bytes())
+    }
+}
+
+impl TryFrom<u16> for AttributeName {
+    type Error = ParseAttributeNameError;
+    /// get the enum item with the numerical value `value`, e.g. to restore an item that was stored as an integer
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if (value as usize) < Self::COUNT {
+            Ok(unsafe { core::mem::transmute::<u16, Self>(value) })
+        } else {
+            Err(ParseAttributeNameError)
+        }
+    }
+}
+
+impl core::fmt::Debug for AttributeName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(AttributeName::item_name(*self as usize))
+    }
+}
+
+impl core::fmt::Display for AttributeName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(AttributeName::item_name(*self as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn variants() {
+        assert_eq!(AttributeName::variants().len(), AttributeName::COUNT);
+        for item in AttributeName::variants() {
+            assert_eq!(
+                AttributeName::from_bytes(item.to_str().as_bytes()).unwrap(),
+                item
+            );
+            assert_eq!(item.xml_name(), item.to_str());
+        }
+    }
+
+    #[test]
+    fn known_indices() {
+        assert_eq!(AttributeName::from_bytes(b"DEST").unwrap() as usize, 2);
+        assert_eq!(AttributeName::from_bytes(b"S").unwrap() as usize, 5);
+        assert_eq!(AttributeName::from_bytes(b"T").unwrap() as usize, 3);
+        assert_eq!(AttributeName::from_bytes(b"UUID").unwrap() as usize, 6);
+    }
+
+    #[test]
+    fn from_bytes_checked() {
+        const ITEM: Option<AttributeName> = AttributeName::from_bytes_checked(b"DEST");
+        assert_eq!(ITEM.map(AttributeName::xml_name), Some("DEST"));
+        for item in AttributeName::variants() {
+            assert_eq!(
+                AttributeName::from_bytes_checked(item.to_str().as_bytes()),
+                Some(item)
+            );
+        }
+        assert_eq!(
+            AttributeName::from_bytes_checked("A".repeat(18 + 1).as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn try_from_int() {
+        for item in AttributeName::variants() {
+            assert_eq!(AttributeName::try_from(item as u16).unwrap(), item);
+        }
+        assert!(AttributeName::try_from(AttributeName::COUNT as u16).is_err());
+        assert!(AttributeName::try_from(u16::MAX).is_err());
+    }
+
+    #[test]
+    fn from_str_relaxed() {
+        for item in AttributeName::variants() {
+            let name = item.to_str();
+            assert_eq!(AttributeName::from_str_relaxed(name).unwrap(), item);
+            let relaxed_name = name.to_ascii_lowercase().replace('-', "_");
+            if !name.bytes().any(|c| c.is_ascii_lowercase() || c == b'_')
+                && AttributeName::from_bytes(relaxed_name.as_bytes()).is_err()
+            {
+                assert_eq!(
+                    AttributeName::from_str_relaxed(&relaxed_name).unwrap(),
+                    item
+                );
+            }
+        }
+        assert!(AttributeName::from_str_relaxed(&"A".repeat(18 + 1)).is_err());
+    }
+}
@@ -0,0 +1,161 @@
+// This file is @generated by autosar-xsd-mangler 0.6.0
+// Do not edit it manually, regenerate it instead.
+//
+// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.
+//
+// Input files:
+//   AUTOSAR_00001.xsd  sha256: 073754a408960b9fe3c0c9a6b66efaed6754c56cc645fdfaee6ccaefc050e182
+//   AUTOSAR_00002.xsd  sha256: 562eb9c52846315bb17769a4a52656227c1c459dd1b74bddcebd6670e1ae031f
+
+use num_derive::FromPrimitive;
+use num_traits::cast::FromPrimitive;
+
+#[derive(Debug)]
+/// Error type returned when `from_str()` / `parse()` for `AutosarVersion` fails
+pub struct ParseAutosarVersionError;
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Eq, PartialEq, Ord, PartialOrd, Clone, Copy, Hash, FromPrimitive)]
+#[repr(u32)]
+#[non_exhaustive]
+/// Enum of all Autosar versions
+pub enum AutosarVersion {
+    /// first golden version - xsd file name: `AUTOSAR_00001.xsd`
+    Autosar_00001 = 0x1,
+    /// second golden version - xsd file name: `AUTOSAR_00002.xsd`
+    Autosar_00002 = 0x2,
+}
+
+impl AutosarVersion {
+    /// get the name of the xsd file matching the Autosar version
+    #[must_use]
+    pub fn filename(&self) -> &'static str {
+        match self {
+            Self::Autosar_00001 => "AUTOSAR_00001.xsd",
+            Self::Autosar_00002 => "AUTOSAR_00002.xsd",
+        }
+    }
+
+    /// Human readable description of the Autosar version
+    ///
+    /// This is particularly useful for the later versions, where the xsd files are just sequentially numbered.
+    /// For example `Autosar_00050` -> "AUTOSAR R21-11"
+    #[must_use]
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Autosar_00001 => "first golden version",
+            Self::Autosar_00002 => "second golden version",
+        }
+    }
+
+    /// the xml namespace of the Autosar version, taken from the targetNamespace of the xsd file
+    #[must_use]
+    pub fn namespace(&self) -> &'static str {
+        match self {
+            Self::Autosar_00001 => "http://autosar.org/schema/r4.0",
+            Self::Autosar_00002 => "http://autosar.org/schema/r4.0",
+        }
+    }
+
+    /// the value of the xsi:schemaLocation attribute of an arxml file for the Autosar version
+    ///
+    /// For example `Autosar_00051` -> "http://autosar.org/schema/r4.0 AUTOSAR_00051.xsd"
+    #[must_use]
+    pub fn schema_location(&self) -> &'static str {
+        match self {
+            Self::Autosar_00001 => "http://autosar.org/schema/r4.0 AUTOSAR_00001.xsd",
+            Self::Autosar_00002 => "http://autosar.org/schema/r4.0 AUTOSAR_00002.xsd",
+        }
+    }
+
+    /// make an `AutosarVersion` from a u32 value
+    ///
+    /// All `AutosarVersion`s are associated with a power of two u32 value, for example `Autosar_4_3_0` == 0x100
+    /// If the given value is a valid constant of `AutosarVersion`, the enum value will be returnd
+    ///
+    /// This is useful in order to decode version masks
+    #[must_use]
+    pub fn from_val(n: u32) -> Option<Self> {
+        Self::from_u32(n)
+    }
+
+    /// parse the human readable version strings that are used by AUTOSAR tools, e.g. in the ADMIN-DATA of a file
+    ///
+    /// The accepted strings are derived from the descriptions of the versions, for example
+    /// "4.3.0", "AUTOSAR 4.3.0", "R22-11" and "AUTOSAR R22-11". Use `from_str()` to parse the name of an xsd file.
+    #[must_use]
+    pub fn from_schema_version_string(input: &str) -> Option<Self> {
+        match input.trim() {
+            "first golden version" => Some(Self::Autosar_00001),
+            "second golden version" => Some(Self::Autosar_00002),
+
+            _ => None,
+        }
+    }
+
+    /// `AutosarVersion::LATEST` is an alias of which ever is the latest standard version
+    pub const LATEST: AutosarVersion = AutosarVersion::Autosar_00002;
+
+    /// a version mask that contains all Autosar versions for which the specification contains data
+    pub const ALL_VERSIONS_MASK: u32 = 0x3;
+
+    /// all Autosar versions, from oldest to newest
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        &[Self::Autosar_00001, Self::Autosar_00002]
+    }
+
+    /// make a version mask that contains all versions from `first` to `last`, inclusive
+    ///
+    /// The mask is empty if `first` is newer than `last`
+    #[must_use]
+    pub const fn mask_from_range(first: Self, last: Self) -> u32 {
+        let first = first as u32;
+        let last = last as u32;
+        if first > last {
+            0
+        } else {
+            // all bits up to and including last, minus all bits below first
+            (last | (last - 1)) & !(first - 1)
+        }
+    }
+
+    /// check if the Autosar version describes the classic platform
+    #[must_use]
+    pub fn is_classic(&self) -> bool {
+        matches!(self, Self::Autosar_00001 | Self::Autosar_00002)
+    }
+
+    /// check if the Autosar version describes the adaptive platform
+    #[must_use]
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, Self::Autosar_00001 | Self::Autosar_00002)
+    }
+}
+
+/// SHA-256 fingerprint of the xsd files that the specification was generated from
+///
+/// The hash covers the contents of all input xsd files, concatenated in the order of their file names.
+/// It can be compared with a known value to check that the specification matches the expected schema files.
+pub const SCHEMA_HASH: [u8; 32] = [
+    0x01, 0x77, 0xc1, 0xdf, 0x51, 0x09, 0x5c, 0x2d, 0x7d, 0x93, 0x19, 0x6a, 0xee, 0x96, 0xfd, 0xb3,
+    0x26, 0xd8, 0x4b, 0x9e, 0x28, 0x3a, 0x44, 0xe5, 0x56, 0x94, 0xaa, 0x1e, 0xc6, 0x6c, 0xa7, 0x53,
+];
+
+impl core::str::FromStr for AutosarVersion {
+    type Err = ParseAutosarVersionError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "AUTOSAR_00001.xsd" => Ok(Self::Autosar_00001),
+            "AUTOSAR_00002.xsd" => Ok(Self::Autosar_00002),
+
+            _ => Err(ParseAutosarVersionError),
+        }
+    }
+}
+
+impl core::fmt::Display for AutosarVersion {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.describe())
+    }
+}
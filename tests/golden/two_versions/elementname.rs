@@ -0,0 +1,255 @@
+// This file is @generated by autosar-xsd-mangler 0.6.0
+// Do not edit it manually, regenerate it instead.
+//
+// no_std: the generated code only uses `core`, except for paths.rs, which also uses `alloc::vec::Vec`.
+//
+// Input files:
+//   AUTOSAR_00001.xsd  sha256: 073754a408960b9fe3c0c9a6b66efaed6754c56cc645fdfaee6ccaefc050e182
+//   AUTOSAR_00002.xsd  sha256: 562eb9c52846315bb17769a4a52656227c1c459dd1b74bddcebd6670e1ae031f
+
+use crate::hashfunc::hashfunc;
+
+#[derive(Debug)]
+/// The error type `ParseElementNameError` is returned when `from_str()` / `parse()` fails for `ElementName`
+pub struct ParseElementNameError;
+
+#[allow(dead_code, non_camel_case_types)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+#[repr(u16)]
+/// Enum of all element names in Autosar
+pub enum ElementName {
+    /// AR-PACKAGE
+    ArPackage = 10,
+    /// AR-PACKAGES
+    ArPackages = 4,
+    /// AUTOSAR
+    Autosar = 8,
+    /// BASE-SIZE
+    BaseSize = 3,
+    /// BIT-SIZE
+    BitSize = 6,
+    /// CATEGORY
+    Category = 9,
+    /// DATA-TYPE
+    DataType = 7,
+    /// DATA-TYPE-REF
+    DataTypeRef = 2,
+    /// ELEMENTS
+    Elements = 1,
+    /// SHORT-NAME
+    ShortName = 5,
+    /// SW-COMPONENT
+    SwComponent = 0,
+}
+
+impl ElementName {
+    #[rustfmt::skip]
+    const NAMES: &'static str = "SW-COMPONENTELEMENTSDATA-TYPE-REFBASE-SIZEAR-PACKAGESSHORT-NAMEBIT-SIZEDATA-TYPEAUTOSARCATEGORYAR-PACKAGE";
+    #[rustfmt::skip]
+    const NAME_OFFSETS: [u32; 12] = [0, 12, 20, 33, 42, 53, 63, 71, 80, 87, 95, 105];
+
+    fn item_name(idx: usize) -> &'static str {
+        let start = ElementName::NAME_OFFSETS[idx] as usize;
+        let end = ElementName::NAME_OFFSETS[idx + 1] as usize;
+        &ElementName::NAMES[start..end]
+    }
+
+    /// number of items in `ElementName`
+    pub const COUNT: usize = 11;
+
+    #[rustfmt::skip]
+    const DISPLACEMENTS: [(u16, u16); 3] = [(1, 3), (6, 0), (2, 2)];
+
+    /// derive an enum entry from an input string using a perfect hash function
+    ///
+    /// # Errors
+    ///
+    /// [`ParseElementNameError`]: The input string did not match the name of any enum item
+    pub fn from_bytes(input: &[u8]) -> Result<Self, ParseElementNameError> {
+        static DISPLACEMENTS: [(u16, u16); 3] = ElementName::DISPLACEMENTS;
+        let (g, f1, f2) = hashfunc(input);
+        let (d1, d2) = DISPLACEMENTS[(g % 3) as usize];
+        let item_idx = u32::from(d2)
+            .wrapping_add(f1.wrapping_mul(u32::from(d1)))
+            .wrapping_add(f2) as usize
+            % 11;
+        if ElementName::item_name(item_idx).as_bytes() != input {
+            return Err(ParseElementNameError);
+        }
+        Ok(unsafe { core::mem::transmute::<u16, Self>(item_idx as u16) })
+    }
+
+    /// derive an enum entry from an input string, in a const context
+    ///
+    /// This is the const version of `from_bytes`, e.g. for constants and const generic arguments:
+    /// `const ITEM: ElementName = ElementName::from_bytes_checked(b"AR-PACKAGE").unwrap();`
+    #[must_use]
+    pub const fn from_bytes_checked(input: &[u8]) -> Option<Self> {
+        let (g, f1, f2) = hashfunc(input);
+        let (d1, d2) = ElementName::DISPLACEMENTS[(g % 3) as usize];
+        let item_idx = (d2 as u32)
+            .wrapping_add(f1.wrapping_mul(d1 as u32))
+            .wrapping_add(f2) as usize
+            % 11;
+        let name = ElementName::NAMES.as_bytes();
+        let start = ElementName::NAME_OFFSETS[item_idx] as usize;
+        let end = ElementName::NAME_OFFSETS[item_idx + 1] as usize;
+        if end - start != input.len() {
+            return None;
+        }
+        let mut pos = 0;
+        while pos < input.len() {
+            if name[start + pos] != input[pos] {
+                return None;
+            }
+            pos += 1;
+        }
+        Some(unsafe { core::mem::transmute::<u16, Self>(item_idx as u16) })
+    }
+
+    /// derive an enum entry from an input string, ignoring case and accepting '_' in place of '-'
+    ///
+    /// This is intended for user input, e.g. "ar_package" will be accepted for AR-PACKAGE.
+    /// Items whose names contain lowercase characters are only found if the input matches them exactly.
+    ///
+    /// # Errors
+    ///
+    /// [`ParseElementNameError`]: The input string did not match the name of any enum item
+    pub fn from_str_relaxed(input: &str) -> Result<Self, ParseElementNameError> {
+        if let Ok(item) = Self::from_bytes(input.as_bytes()) {
+            return Ok(item);
+        }
+        // no item name is longer than the buffer, so longer inputs can be rejected immediately
+        let mut buffer = [0u8; 13];
+        if input.len() > buffer.len() {
+            return Err(ParseElementNameError);
+        }
+        for (dest, src) in buffer.iter_mut().zip(input.bytes()) {
+            *dest = if src == b'_' {
+                b'-'
+            } else {
+                src.to_ascii_uppercase()
+            };
+        }
+        Self::from_bytes(&buffer[..input.len()])
+    }
+
+    /// get the str corresponding to an item
+    ///
+    /// The returned &str has static lifetime, becasue it is a reference to an entry in a list of constants
+    #[must_use]
+    pub fn to_str(&self) -> &'static str {
+        ElementName::item_name(*self as usize)
+    }
+
+    /// Returns the XML element name as it appears in AUTOSAR files, e.g., `SHORT-NAME`.
+    #[must_use]
+    #[inline]
+    pub fn xml_name(self) -> &'static str {
+        self.to_str()
+    }
+
+    /// iterate over all items of `ElementName`
+    ///
+    /// The items are returned in the order of their numerical values, which is not alphabetical
+    pub fn variants() -> impl ExactSizeIterator<Item = Self> {
+        (0..Self::COUNT).map(|idx| unsafe { core::mem::transmute::<u16, Self>(idx as u16) })
+    }
+}
+
+impl core::str::FromStr for ElementName {
+    type Err = ParseElementNameError;
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(input.as_bytes())
+    }
+}
+
+impl TryFrom<u16> for ElementName {
+    type Error = ParseElementNameError;
+    /// get the enum item with the numerical value `value`, e.g. to restore an item that was stored as an integer
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        if (value as usize) < Self::COUNT {
+            Ok(unsafe { core::mem::transmute::<u16, Self>(value) })
+        } else {
+            Err(ParseElementNameError)
+        }
+    }
+}
+
+impl core::fmt::Debug for ElementName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(ElementName::item_name(*self as usize))
+    }
+}
+
+impl core::fmt::Display for ElementName {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(ElementName::item_name(*self as usize))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn variants() {
+        assert_eq!(ElementName::variants().len(), ElementName::COUNT);
+        for item in ElementName::variants() {
+            assert_eq!(
+                ElementName::from_bytes(item.to_str().as_bytes()).unwrap(),
+                item
+            );
+            assert_eq!(item.xml_name(), item.to_str());
+        }
+    }
+
+    #[test]
+    fn known_indices() {
+        assert_eq!(ElementName::from_bytes(b"AR-PACKAGE").unwrap() as usize, 10);
+        assert_eq!(ElementName::from_bytes(b"AR-PACKAGES").unwrap() as usize, 4);
+        assert_eq!(ElementName::from_bytes(b"AUTOSAR").unwrap() as usize, 8);
+        assert_eq!(ElementName::from_bytes(b"BASE-SIZE").unwrap() as usize, 3);
+    }
+
+    #[test]
+    fn from_bytes_checked() {
+        const ITEM: Option<ElementName> = ElementName::from_bytes_checked(b"AR-PACKAGE");
+        assert_eq!(ITEM.map(ElementName::xml_name), Some("AR-PACKAGE"));
+        for item in ElementName::variants() {
+            assert_eq!(
+                ElementName::from_bytes_checked(item.to_str().as_bytes()),
+                Some(item)
+            );
+        }
+        assert_eq!(
+            ElementName::from_bytes_checked("A".repeat(13 + 1).as_bytes()),
+            None
+        );
+    }
+
+    #[test]
+    fn try_from_int() {
+        for item in ElementName::variants() {
+            assert_eq!(ElementName::try_from(item as u16).unwrap(), item);
+        }
+        assert!(ElementName::try_from(ElementName::COUNT as u16).is_err());
+        assert!(ElementName::try_from(u16::MAX).is_err());
+    }
+
+    #[test]
+    fn from_str_relaxed() {
+        for item in ElementName::variants() {
+            let name = item.to_str();
+            assert_eq!(ElementName::from_str_relaxed(name).unwrap(), item);
+            let relaxed_name = name.to_ascii_lowercase().replace('-', "_");
+            if !name.bytes().any(|c| c.is_ascii_lowercase() || c == b'_')
+                && ElementName::from_bytes(relaxed_name.as_bytes()).is_err()
+            {
+                assert_eq!(ElementName::from_str_relaxed(&relaxed_name).unwrap(), item);
+            }
+        }
+        assert!(ElementName::from_str_relaxed(&"A".repeat(13 + 1)).is_err());
+    }
+}
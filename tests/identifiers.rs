@@ -0,0 +1,145 @@
+use autosar_xsd_mangler::{phases, snapshot::name_to_identifier};
+use std::path::Path;
+
+fn ident(name: &str) -> String {
+    name_to_identifier(name).unwrap()
+}
+
+#[test]
+fn words_are_capitalized() {
+    assert_eq!(ident("AUTOSAR"), "Autosar");
+    assert_eq!(ident("SHORT-NAME"), "ShortName");
+    assert_eq!(ident("I-SIGNAL-I-PDU"), "ISignalIPdu");
+    // ':' starts a new word, but it is not a separator like '-' otherwise
+    assert_eq!(ident("AR:PACKAGE"), "ArPackage");
+    // consecutive separators are collapsed
+    assert_eq!(ident("A--B"), "AB");
+    assert_eq!(ident("A-B-"), "AB");
+}
+
+#[test]
+fn lowercase_names_are_kept() {
+    // only the letters after the first one of each word are converted to lowercase
+    assert_eq!(ident("default"), "default");
+    assert_eq!(ident("a:b"), "aB");
+    assert_eq!(ident("mixedCase-NAME"), "mixedcaseName");
+}
+
+#[test]
+fn digits() {
+    // digits that were separated by '-' are still separated, all others are joined
+    assert_eq!(ident("ISO-8859-1"), "Iso8859_1");
+    assert_eq!(ident("UTF-8"), "Utf8");
+    assert_eq!(ident("V1-2"), "V1_2");
+    assert_eq!(ident("X-1-A-2"), "X1A2");
+    // identifiers can't start with a digit
+    assert_eq!(ident("1-BASED"), "N1Based");
+    assert_eq!(ident("42"), "N42");
+}
+
+#[test]
+fn identifier_table() {
+    let table = [
+        // leading digits get the prefix N
+        ("0", "N0"),
+        ("1-BASED", "N1Based"),
+        ("2D", "N2d"),
+        ("3-D-SHAPE", "N3DShape"),
+        ("42-A", "N42A"),
+        ("1:A", "N1A"),
+        // all caps
+        ("AUTOSAR", "Autosar"),
+        ("SHORT-NAME", "ShortName"),
+        ("CAN-FD", "CanFd"),
+        ("I-PDU", "IPdu"),
+        // mixed case: the first letter of each word is kept, the others are lowercase
+        ("mixedCase-NAME", "mixedcaseName"),
+        ("camelCase", "camelcase"),
+        ("Pascal-Case", "PascalCase"),
+        ("aB-cD", "abcd"),
+        // colons
+        ("AR:PACKAGE", "ArPackage"),
+        ("xml:lang", "xmlLang"),
+        ("A:B:C", "ABC"),
+        ("A:1", "A1"),
+        ("A:-B", "AB"),
+        // double hyphens
+        ("A--B", "AB"),
+        ("STATIC--KIND", "StaticKind"),
+        ("1--2", "N1_2"),
+        ("A--1", "A1"),
+        ("--A", "_A"),
+    ];
+    for (name, identifier) in table {
+        assert_eq!(ident(name), identifier, "{name}");
+    }
+}
+
+#[test]
+fn leading_non_letters() {
+    assert_eq!(ident("-A"), "_A");
+    assert_eq!(ident("_X"), "_X");
+    assert_eq!(ident(":A"), "_A");
+}
+
+#[test]
+fn non_ascii_characters_are_dropped() {
+    assert_eq!(ident("BÄR"), "Br");
+    assert_eq!(ident("ÄB"), "_B");
+    assert_eq!(ident("A B"), "Ab");
+}
+
+#[test]
+fn keywords_are_escaped() {
+    assert_eq!(ident("SELF"), "Self_");
+    assert_eq!(ident("self"), "self_");
+    assert_eq!(ident("type"), "type_");
+    assert_eq!(ident("MATCH"), "Match");
+    // CamelCase identifiers are not keywords, even if the lowercase word is
+    assert_eq!(ident("TYPE"), "Type");
+}
+
+#[test]
+fn names_without_letters_or_digits_are_rejected() {
+    for name in ["", "-", "--", ":", "_", "ÄÖÜ", " "] {
+        let err = name_to_identifier(name).unwrap_err();
+        assert!(
+            err.contains("no identifier can be derived"),
+            "{name:?}: {err}"
+        );
+    }
+}
+
+#[test]
+fn identifier_collisions_are_rejected() {
+    // the enum items "STATIC-KIND" and "STATIC--KIND" would both become the variant StaticKind
+    let fixture_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/single");
+    let mini = std::fs::read_to_string(fixture_dir.join("mini.xsd")).unwrap();
+    let with_collision = mini.replace(
+        r#"<xsd:enumeration value="DYNAMIC-KIND"/>"#,
+        r#"<xsd:enumeration value="DYNAMIC-KIND"/>
+        <xsd:enumeration value="STATIC--KIND"/>"#,
+    );
+    assert_ne!(mini, with_collision);
+
+    let workdir = std::env::temp_dir().join(format!(
+        "autosar-xsd-mangler-collision-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&workdir);
+    std::fs::create_dir_all(&workdir).unwrap();
+    std::fs::copy(fixture_dir.join("config.toml"), workdir.join("config.toml")).unwrap();
+    std::fs::write(workdir.join("mini.xsd"), with_collision).unwrap();
+
+    let input = phases::Input::find(&workdir.join("mini.xsd")).unwrap();
+    let flat = phases::flatten(&phases::parse(&input).unwrap()).unwrap();
+    let mut schema = phases::merge(&flat).unwrap();
+    phases::dedup(&mut schema);
+    let err = phases::generate_into(&input, &schema, &workdir.join("gen")).unwrap_err();
+    assert_eq!(
+        err,
+        "Error: EnumItem: the items \"STATIC--KIND\" and \"STATIC-KIND\" both have the identifier StaticKind"
+    );
+
+    std::fs::remove_dir_all(workdir).unwrap();
+}
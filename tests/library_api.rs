@@ -0,0 +1,131 @@
+use autosar_xsd_mangler::{
+    diff, merge_all, phases, verify_superset, AutosarDataTypes, MergePolicyKind,
+};
+use std::path::Path;
+
+// the flattened schemas of a fixture in tests/fixtures, from oldest to newest
+fn flat_schemas(fixture: &str) -> Vec<AutosarDataTypes> {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(fixture);
+    let input = phases::Input::find(&fixture).unwrap();
+    let parsed = phases::parse(&input).unwrap();
+    phases::flatten(&parsed).unwrap().schemas().to_vec()
+}
+
+#[test]
+fn merge_all_is_the_merge_phase() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/golden/two_versions");
+    let input = phases::Input::find(&fixture).unwrap();
+    let flat = phases::flatten(&phases::parse(&input).unwrap()).unwrap();
+    let merged_phase = phases::merge(&flat).unwrap();
+
+    let newest_first: Vec<AutosarDataTypes> = flat.schemas().iter().rev().cloned().collect();
+    let mut policy = MergePolicyKind::LatestWins.build(Box::new(|_| {}));
+    let merged = merge_all(&newest_first, policy.as_mut()).unwrap();
+    assert!(merged == *merged_phase.types());
+
+    let mut policy = MergePolicyKind::LatestWins.build(Box::new(|_| {}));
+    let chained = newest_first[0]
+        .clone()
+        .merge_with(&newest_first[1], policy.as_mut())
+        .unwrap();
+    assert!(chained == merged);
+
+    // a schema that was merged like this can be used in the later phases
+    let mut schema = phases::Schema::from(chained);
+    phases::dedup(&mut schema);
+
+    let mut policy = MergePolicyKind::LatestWins.build(Box::new(|_| {}));
+    assert!(merge_all(&[], policy.as_mut()).is_err());
+}
+
+#[test]
+fn diff_and_verify_superset() {
+    let two_versions = flat_schemas("golden/two_versions");
+    let newer = &two_versions[1];
+    let mini = &flat_schemas("single/mini.xsd")[0];
+
+    let same = diff(newer, newer);
+    assert!(same.element_types.added.is_empty() && same.element_types.removed.is_empty());
+    assert!(verify_superset(newer, newer).is_empty());
+
+    // everything that is removed in one direction is added in the other
+    let forward = diff(newer, mini);
+    let backward = diff(mini, newer);
+    assert!(!forward.element_types.added.is_empty());
+    assert!(!forward.element_types.removed.is_empty());
+    assert_eq!(forward.element_types.added, backward.element_types.removed);
+    assert_eq!(forward.element_types.removed, backward.element_types.added);
+    assert_eq!(forward.group_types.removed, backward.group_types.added);
+
+    let missing = verify_superset(newer, mini);
+    assert_eq!(
+        missing.len(),
+        forward.element_types.removed.len()
+            + forward.character_types.removed.len()
+            + forward.group_types.removed.len()
+    );
+    assert!(missing.contains(&format!(
+        "element type {}",
+        forward.element_types.removed[0]
+    )));
+}
+
+#[test]
+fn find_all_paths_to() {
+    let two_versions = flat_schemas("golden/two_versions");
+    let newer = &two_versions[1];
+    let (paths, truncated) = newer.find_all_paths_to("AR:SW-COMPONENT", 10);
+    assert_eq!(
+        paths,
+        [[
+            "AR:AUTOSAR",
+            "AR:AUTOSAR-AR-PACKAGES-TYPE",
+            "AR:AR-PACKAGE",
+            "AR:AR-PACKAGE-ELEMENTS-TYPE",
+            "AR:SW-COMPONENT"
+        ]]
+    );
+    assert!(!truncated);
+    assert_eq!(
+        newer.find_all_paths_to("AR:AUTOSAR", 10).0,
+        [["AR:AUTOSAR"]]
+    );
+    assert!(newer.find_all_paths_to("AR:NOT-A-TYPE", 10).0.is_empty());
+
+    // SHORT-NAME is used in several types, so there are several paths to its type
+    let mini = &flat_schemas("single/mini.xsd")[0];
+    let (all_paths, truncated) = mini.find_all_paths_to("AR:IDENTIFIER", 100);
+    assert!(!truncated);
+    assert_eq!(
+        all_paths.iter().map(|path| path.join(" -> ")).collect::<Vec<_>>(),
+        [
+            "AR:AUTOSAR -> AR:AUTOSAR-AR-PACKAGES-TYPE -> AR:AR-PACKAGE -> AR:AR-PACKAGE-ELEMENTS-TYPE -> AR:I-SIGNAL -> AR:IDENTIFIER",
+            "AR:AUTOSAR -> AR:AUTOSAR-AR-PACKAGES-TYPE -> AR:AR-PACKAGE -> AR:AR-PACKAGE-ELEMENTS-TYPE -> AR:SYSTEM-SIGNAL -> AR:IDENTIFIER",
+            "AR:AUTOSAR -> AR:AUTOSAR-AR-PACKAGES-TYPE -> AR:AR-PACKAGE -> AR:IDENTIFIER"
+        ]
+    );
+    // the search is only truncated if there are more paths than the limit
+    assert_eq!(
+        mini.find_all_paths_to("AR:IDENTIFIER", 3),
+        (all_paths.clone(), false)
+    );
+    let (paths, truncated) = mini.find_all_paths_to("AR:IDENTIFIER", 2);
+    assert!(truncated);
+    assert_eq!(paths, all_paths[..2]);
+}
+
+#[test]
+fn parsed_xsd_counts() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/single/mini.xsd");
+    let input = phases::Input::find(&fixture).unwrap();
+    let parsed = phases::parse(&input).unwrap();
+    assert_eq!(parsed.file_count(), 1);
+    // 13 named simple and complex types, and 11 built-in base types
+    assert_eq!(parsed.type_count(0), 24);
+    // 3 named groups, and the groups that were created for the anonymous model groups
+    assert_eq!(parsed.group_count(0), 10);
+    assert_eq!(parsed.attribute_group_count(0), 1);
+    assert_eq!(parsed.root_element_count(0), 1);
+}
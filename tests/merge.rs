@@ -0,0 +1,435 @@
+mod common;
+
+use common::{
+    mini_xsd, read_fixture, read_generated, run_generator, run_single_mode_with_args,
+    run_single_xsd, run_two_versions, run_versions, without_element, TempDir,
+};
+
+#[test]
+fn release_order_is_checked() {
+    // two copies of the miniature schema, configured from newest to oldest
+    let workdir = TempDir::new("order");
+    let xsd_dir = workdir.join("xsd");
+    std::fs::create_dir_all(&xsd_dir).unwrap();
+    std::fs::write(xsd_dir.join("AUTOSAR_00051.xsd"), mini_xsd()).unwrap();
+    std::fs::write(xsd_dir.join("AUTOSAR_00052.xsd"), mini_xsd()).unwrap();
+    std::fs::write(
+        xsd_dir.join("config.toml"),
+        "[[xsd]]\nname = \"AUTOSAR_00052.xsd\"\nident = \"Autosar_00052\"\ndesc = \"newer\"\n\n\
+         [[xsd]]\nname = \"AUTOSAR_00051.xsd\"\nident = \"Autosar_00051\"\ndesc = \"older\"\n",
+    )
+    .unwrap();
+
+    let (success, stdout) = run_generator(&workdir, [&xsd_dir]);
+    assert!(!success, "{stdout}");
+    assert!(
+        stdout.contains("Error: the xsd files must be configured from oldest to newest, but AUTOSAR_00052.xsd is listed before AUTOSAR_00051.xsd"),
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen").exists());
+}
+
+#[test]
+fn merge_order_of_a_single_version() {
+    let (_workdir, stdout) = run_single_mode_with_args("mergeorder", &["--verify-merge-order"]);
+    assert!(
+        stdout.contains("merge order verification: 0 groups are ordered differently"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn merge_order_with_repeated_elements() {
+    // DYNAMIC-LENGTH occurs once in SYSTEM-SIGNAL/ELEMENTGROUP of the older version, and twice in the newer version
+    let newer = mini_xsd();
+    let older = newer.replace(
+        "      <xsd:element name=\"DYNAMIC-LENGTH\" type=\"AR:BOOLEAN\" minOccurs=\"0\"/>\n      <xsd:group ref=\"AR:SIGNAL-LENGTH\"/>",
+        "      <xsd:group ref=\"AR:SIGNAL-LENGTH\"/>",
+    );
+    assert_ne!(older, newer);
+
+    let (workdir, stdout) = run_two_versions(
+        "mergeorder-repeated",
+        &older,
+        &newer,
+        &["--verify-merge-order"],
+    );
+    assert!(
+        stdout.contains("merge order verification: 0 groups are ordered differently"),
+        "{stdout}"
+    );
+    assert!(workdir.join("gen/specification.rs").exists(), "{stdout}");
+}
+
+#[test]
+fn removed_types_are_reported() {
+    // the newer version is the miniature schema without the element SIGNAL-KIND
+    let older = mini_xsd();
+    let newer = without_element(&older, "SIGNAL-KIND");
+
+    let (_workdir, stdout) = run_two_versions("superset", &older, &newer, &[]);
+    assert!(
+        stdout.contains("Warning: the element type AR:SIGNAL-KIND of older is missing in newer"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+}
+
+#[test]
+fn element_count_per_version() {
+    // the newer version is the miniature schema without the element SIGNAL-KIND. BOOLEAN is the type of several
+    // elements, but it is only counted once
+    let older = mini_xsd();
+    let newer = without_element(&older, "SIGNAL-KIND");
+
+    let (workdir, stdout) = run_two_versions("element-count", &older, &newer, &[]);
+    assert!(
+        stdout.contains("older: 9 element types, 1 of them only in this version"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("newer: 8 element types, 0 of them only in this version"),
+        "{stdout}"
+    );
+    // ELEMENT_COUNT_PER_VERSION counts the entries of ELEMENTS instead, including the root element AUTOSAR
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains("pub const ELEMENT_COUNT_PER_VERSION: [u32; 2] = [12, 11];"),
+        "{specification}"
+    );
+}
+
+// the two versions of the class_change fixture: SIGNAL-KIND is an enum in the older version, and an element with sub
+// elements of the type SIGNAL-KIND-SPEC in the newer version
+fn class_change_versions() -> (String, String) {
+    fixture_versions("class_change")
+}
+
+// the same change as in class_change_versions, but the type of SIGNAL-KIND is AR:SIGNAL-KIND in both versions
+fn class_change_same_name_versions() -> (String, String) {
+    fixture_versions("class_change_same_name")
+}
+
+fn fixture_versions(dir: &str) -> (String, String) {
+    (
+        read_fixture(&format!("{dir}/AUTOSAR_00051.xsd")),
+        read_fixture(&format!("{dir}/AUTOSAR_00052.xsd")),
+    )
+}
+
+#[test]
+fn element_class_change_is_not_merged() {
+    let (older, newer) = class_change_versions();
+    let (workdir, stdout) = run_two_versions("class-change", &older, &newer, &["--removed-report"]);
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // both shapes of the element survive the merge
+    let specification = read_generated(&workdir, "specification.rs");
+    let signal_kind_specs: Vec<&str> = specification
+        .lines()
+        .filter(|line| line.starts_with("    /*") && line.ends_with(" // SIGNAL-KIND"))
+        .collect();
+    assert_eq!(signal_kind_specs.len(), 2, "{specification}");
+    assert!(signal_kind_specs
+        .iter()
+        .any(|line| line.contains("mode: Characters")));
+    assert!(signal_kind_specs
+        .iter()
+        .any(|line| line.contains("mode: Sequence")));
+
+    // the enum shape only exists in the older version, while the new shape is part of the latest version
+    let report = read_generated(&workdir, "removed_elements.txt");
+    assert!(report.contains("# 1 elements\n"), "{report}");
+    assert!(
+        report.contains("SIGNAL-KIND (AR:SIGNAL-KIND): older; last version: older\n"),
+        "{report}"
+    );
+    assert!(!report.contains("AR:SIGNAL-KIND-SPEC"), "{report}");
+}
+
+#[test]
+fn element_class_change_keeps_the_type_name() {
+    let (older, newer) = class_change_same_name_versions();
+    let (workdir, stdout) = run_two_versions(
+        "class-change-same-name",
+        &older,
+        &newer,
+        &["--removed-report"],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // the older type is renamed, so that the enum shape doesn't resolve to the newer type of the same name
+    let specification = read_generated(&workdir, "specification.rs");
+    let signal_kind_specs: Vec<&str> = specification
+        .lines()
+        .filter(|line| line.starts_with("    /*") && line.ends_with(" // SIGNAL-KIND"))
+        .collect();
+    assert_eq!(signal_kind_specs.len(), 2, "{specification}");
+    assert!(signal_kind_specs
+        .iter()
+        .any(|line| line.contains("mode: Characters")));
+    assert!(signal_kind_specs
+        .iter()
+        .any(|line| line.contains("mode: Sequence")));
+
+    let report = read_generated(&workdir, "removed_elements.txt");
+    assert!(report.contains("# 1 elements\n"), "{report}");
+    assert!(
+        report.contains("SIGNAL-KIND (AR:SIGNAL-KIND--CLASS-CHANGE): older; last version: older\n"),
+        "{report}"
+    );
+}
+
+#[test]
+fn merge_verification_reports_dropped_items() {
+    // the two shapes of SIGNAL-KIND use different types, so both are kept
+    let (older, newer) = class_change_versions();
+    let (_workdir, stdout) = run_two_versions("verify-merge", &older, &newer, &["--verify-merge"]);
+    assert!(!stdout.contains("Error"), "{stdout}");
+    assert!(
+        stdout.contains("merge verification: 0 items of older are missing in the merged schema\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("merge verification: 0 items of newer are missing in the merged schema\n"),
+        "{stdout}"
+    );
+
+    // the type of SIGNAL-KIND keeps its name, but the enum shape of the older version is still kept
+    let (older, newer) = class_change_same_name_versions();
+    let (_workdir, stdout) = run_two_versions(
+        "verify-merge-same-name",
+        &older,
+        &newer,
+        &["--verify-merge"],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+    assert!(
+        stdout.contains("merge verification: 0 items of older are missing in the merged schema\n"),
+        "{stdout}"
+    );
+
+    // a character type can only have one definition, so the enum of the older version is lost
+    let newer = mini_xsd();
+    let older = newer.replace(
+        r#"<xsd:pattern value="/?[a-zA-Z][a-zA-Z0-9_]{0,127}(/[a-zA-Z][a-zA-Z0-9_]{0,127})*"/>"#,
+        r#"<xsd:enumeration value="/PACKAGE/SIGNAL"/>"#,
+    );
+    assert_ne!(older, newer);
+    let (_workdir, stdout) =
+        run_two_versions("verify-merge-chartype", &older, &newer, &["--verify-merge"]);
+    assert!(
+        stdout.contains("Warning: the character type AR:REF--SIMPLE can't be merged, enum(1 items) is replaced by pattern("),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("merge verification: 0 items of newer are missing in the merged schema\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("merge verification: 1 items of older are missing in the merged schema\n"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("    AUTOSAR/AR-PACKAGES/AR-PACKAGE/ELEMENTS/I-SIGNAL/SYSTEM-SIGNAL-REF: enum AR:REF--SIMPLE\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn element_docstrings_are_merged() {
+    // three versions of the miniature schema with different documentation of SIGNAL-KIND and CYCLE-TIME;
+    // the newest version has no documentation of SIGNAL-KIND
+    // the german documentation is removed, so that the english documentation is used
+    let base = mini_xsd().replace(
+        r#"<xsd:documentation xml:lang="de">Art des Signals</xsd:documentation>"#,
+        "",
+    );
+    let signal_kind_doc =
+        r#"<xsd:documentation xml:lang="en">Kind of the signal</xsd:documentation>"#;
+    let cycle_time =
+        r#"<xsd:element name="CYCLE-TIME" type="AR:BOOLEAN" minOccurs="1" maxOccurs="1"/>"#;
+    let make_version = |signal_kind: Option<&str>, cycle_time_doc: &str| {
+        let signal_kind_doc_new = signal_kind.map_or(String::new(), |doc| {
+            format!(r#"<xsd:documentation xml:lang="en">{doc}</xsd:documentation>"#)
+        });
+        let cycle_time_new = format!(
+            "<xsd:element name=\"CYCLE-TIME\" type=\"AR:BOOLEAN\" minOccurs=\"1\" maxOccurs=\"1\"><xsd:annotation><xsd:documentation xml:lang=\"en\">{cycle_time_doc}</xsd:documentation></xsd:annotation></xsd:element>"
+        );
+        base.replace(signal_kind_doc, &signal_kind_doc_new)
+            .replace(cycle_time, &cycle_time_new)
+    };
+    let oldest = make_version(Some("Kind of the signal, oldest"), "Cycle time, oldest");
+    let middle = make_version(Some("Kind of the signal, middle"), "Cycle time, middle");
+    let newest = make_version(None, "Cycle time, newest");
+
+    let (workdir, stdout) = run_versions(
+        "docstrings",
+        &[
+            ("oldest", &oldest),
+            ("middle", &middle),
+            ("newest", &newest),
+        ],
+        &[],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // the newest available documentation is kept
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains("\"Kind of the signal, middle\""),
+        "{specification}"
+    );
+    assert!(
+        specification.contains("\"Cycle time, newest\""),
+        "{specification}"
+    );
+    assert!(!specification.contains("oldest"), "{specification}");
+    assert!(
+        !specification.contains("\"Cycle time, middle\""),
+        "{specification}"
+    );
+}
+
+#[test]
+fn mm_class_does_not_prevent_dedup() {
+    // two element types that only differ in their class
+    let with_notes = mini_xsd()
+        .replace(
+            r#"      <xsd:group ref="AR:SIGNAL-TIMING" minOccurs="0"/>"#,
+            r#"      <xsd:group ref="AR:SIGNAL-TIMING" minOccurs="0"/>
+      <xsd:element name="I-SIGNAL-NOTE" type="AR:I-SIGNAL-NOTE" minOccurs="0"/>"#,
+        )
+        .replace(
+            r#"      <xsd:group ref="AR:SIGNAL-LENGTH"/>"#,
+            r#"      <xsd:group ref="AR:SIGNAL-LENGTH"/>
+      <xsd:element name="SYSTEM-SIGNAL-NOTE" type="AR:SYSTEM-SIGNAL-NOTE" minOccurs="0"/>"#,
+        )
+        .replace(
+            r#"  <xsd:complexType name="REF" "#,
+            r#"  <!-- complex type for class ISignalNote -->
+  <xsd:complexType name="I-SIGNAL-NOTE" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:element name="TEXT" type="AR:IDENTIFIER" minOccurs="0"/>
+    </xsd:sequence>
+  </xsd:complexType>
+  <!-- complex type for class SystemSignalNote -->
+  <xsd:complexType name="SYSTEM-SIGNAL-NOTE" abstract="false" mixed="false">
+    <xsd:sequence>
+      <xsd:element name="TEXT" type="AR:IDENTIFIER" minOccurs="0"/>
+    </xsd:sequence>
+  </xsd:complexType>
+  <xsd:complexType name="REF" "#,
+        );
+    assert_eq!(with_notes.matches("-NOTE").count(), 6);
+
+    let (workdir, success, stdout) = run_single_xsd("mm-class-dedup", &with_notes, &[]);
+    assert!(success, "{stdout}");
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains(" // I-SIGNAL-NOTE, SYSTEM-SIGNAL-NOTE\n"),
+        "{specification}"
+    );
+}
+
+// conflict: the attribute T of SYSTEM-SIGNAL is optional in the older version and required in the newer version
+const REQUIRED_CONFLICT: (&str, &str) = (
+    r#"<xsd:attribute name="T" type="AR:STRING--SIMPLE"/>"#,
+    r#"<xsd:attribute name="T" type="AR:STRING--SIMPLE" use="required"/>"#,
+);
+
+// conflict: the element SHORT-NAME is optional in the older version and required in the newer version
+const AMOUNT_CONFLICT: (&str, &str) = (
+    r#"<xsd:element name="SHORT-NAME" type="AR:IDENTIFIER" minOccurs="0" maxOccurs="1"/>"#,
+    r#"<xsd:element name="SHORT-NAME" type="AR:IDENTIFIER" minOccurs="1" maxOccurs="1"/>"#,
+);
+
+// run the generator on two versions of the miniature schema; each conflict is a pair of the optional text of the
+// older version and the required text of the newer version
+fn run_merge_conflicts(
+    name: &str,
+    merge_policy: &str,
+    conflicts: &[(&str, &str)],
+) -> (TempDir, String) {
+    let mini = mini_xsd();
+    let mut older = mini.clone();
+    let mut newer = mini;
+    for (optional, required) in conflicts {
+        newer = newer.replace(optional, required);
+        older = older.replace(required, optional);
+    }
+    assert_ne!(older, newer);
+    run_two_versions(
+        &format!("policy-{name}"),
+        &older,
+        &newer,
+        &[&format!("--merge-policy={merge_policy}")],
+    )
+}
+
+#[test]
+fn latest_wins_keeps_the_newer_version() {
+    let (workdir, stdout) = run_merge_conflicts(
+        "latest-wins",
+        "latest-wins",
+        &[REQUIRED_CONFLICT, AMOUNT_CONFLICT],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains("(AttributeName::T, 4, true),"),
+        "{specification}"
+    );
+    assert!(
+        specification.contains("element!(ShortName, 7, One, false, 0x0, NotRestricted, None),"),
+        "{specification}"
+    );
+    // the default policy doesn't compare the values, so there is nothing to report
+    assert!(!workdir.join("gen/merge_report.txt").exists());
+}
+
+#[test]
+fn lenient_widens_required_and_amount() {
+    let (workdir, stdout) =
+        run_merge_conflicts("lenient", "lenient", &[REQUIRED_CONFLICT, AMOUNT_CONFLICT]);
+    assert!(!stdout.contains("Error"), "{stdout}");
+    let specification = read_generated(&workdir, "specification.rs");
+    assert!(
+        specification.contains("(AttributeName::T, 4, false),"),
+        "{specification}"
+    );
+    assert!(
+        specification
+            .contains("element!(ShortName, 7, ZeroOrOne, false, 0x0, NotRestricted, None),"),
+        "{specification}"
+    );
+    let report = read_generated(&workdir, "merge_report.txt");
+    assert!(report.starts_with("# merge conflicts, resolved with the merge policy lenient\n"));
+    assert!(
+        report.contains("attribute T of AR:SYSTEM-SIGNAL: required true / false, using false\n"),
+        "{report}"
+    );
+    assert!(
+        report.contains(
+            "element SHORT-NAME in SYSTEM-SIGNAL/ELEMENTGROUP: amount One / ZeroOrOne, using ZeroOrOne\n"
+        ),
+        "{report}"
+    );
+}
+
+#[test]
+fn strict_aborts_on_conflicts() {
+    let (workdir, stdout) = run_merge_conflicts("strict-required", "strict", &[REQUIRED_CONFLICT]);
+    assert!(
+        stdout.contains("Error: merge conflict (strict merge policy): attribute T of AR:SYSTEM-SIGNAL: required true / false"),
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen/specification.rs").exists());
+
+    let (workdir, stdout) = run_merge_conflicts("strict-amount", "strict", &[AMOUNT_CONFLICT]);
+    assert!(
+        stdout.contains("Error: merge conflict (strict merge policy): element SHORT-NAME in AR-PACKAGE/ELEMENTGROUP: amount One / ZeroOrOne"),
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen/specification.rs").exists());
+}
@@ -0,0 +1,210 @@
+mod common;
+
+use common::{read_generated, run_single_mode, run_single_mode_with_args};
+use std::process::Command;
+
+#[test]
+fn html_reference_links_the_types() {
+    let workdir = run_single_mode("html");
+    let html = read_generated(&workdir, "schema_reference.html");
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(!html.contains("<script"));
+
+    // find the anchor of a type from its heading
+    let anchor_of = |typename: &str| {
+        let heading_end = html.find(&format!("\">{typename}</h3>")).unwrap();
+        let heading_start = html[..heading_end].rfind("<h3 id=\"").unwrap() + "<h3 id=\"".len();
+        html[heading_start..heading_end].to_string()
+    };
+    let system_signal = anchor_of("AR:SYSTEM-SIGNAL");
+    let signal_kind = anchor_of("AR:SIGNAL-KIND");
+
+    // the index links to the types of the elements, and SYSTEM-SIGNAL links to its sub element SIGNAL-KIND,
+    // which links back to SYSTEM-SIGNAL as its parent
+    assert!(html.contains(&format!(
+        "<li><a href=\"#{system_signal}\">SYSTEM-SIGNAL</a></li>"
+    )));
+    assert!(html.contains(&format!(
+        "<td class=\"name\">SIGNAL-KIND</td><td class=\"typename\"><a href=\"#{signal_kind}\">AR:SIGNAL-KIND</a></td>"
+    )));
+    let signal_kind_section = html
+        .split(&format!("<h3 id=\"{signal_kind}\">"))
+        .nth(1)
+        .unwrap();
+    let signal_kind_section = signal_kind_section.split("<h3").next().unwrap();
+    assert!(signal_kind_section.contains(&format!(
+        "<p>Contained in: <a href=\"#{system_signal}\">AR:SYSTEM-SIGNAL</a></p>"
+    )));
+    assert!(signal_kind_section.contains("<p>Content: Characters, character data: "));
+}
+
+#[test]
+fn html_site_links_the_type_pages() {
+    let (workdir, _) = run_single_mode_with_args("html-site", &["--emit-html=docs"]);
+    let site_dir = workdir.join("docs");
+    let read_page = |path: &str| std::fs::read_to_string(site_dir.join(path)).unwrap();
+
+    let index = read_page("index.html");
+    assert!(index.contains("<li>miniature test schema (mini.xsd)</li>"));
+    assert!(index.contains(
+        "<li><a href=\"types/AR_SYSTEM-SIGNAL.html\">AR:SYSTEM-SIGNAL</a> (SYSTEM-SIGNAL)</li>"
+    ));
+    assert!(site_dir.join("style.css").exists());
+
+    // the page of a type links to the pages of its sub elements and of its parents, and shows their docstrings
+    let system_signal = read_page("types/AR_SYSTEM-SIGNAL.html");
+    assert!(system_signal.contains("<link rel=\"stylesheet\" href=\"../style.css\">"));
+    assert!(system_signal.contains(
+        "<td class=\"name\">SIGNAL-KIND</td><td class=\"typename\"><a href=\"AR_SIGNAL-KIND.html\">AR:SIGNAL-KIND</a></td>"
+    ));
+    assert!(system_signal
+        .contains("<p>Referenced with DEST: <span class=\"typename\">SYSTEM-SIGNAL</span></p>"));
+    assert!(!system_signal.contains("<script"));
+    let signal_kind = read_page("types/AR_SIGNAL-KIND.html");
+    assert!(signal_kind.contains("<p class=\"doc\">Kind of the signal</p>"));
+    assert!(signal_kind.contains(
+        "<h2>Contained in</h2>\n<p><a href=\"AR_SYSTEM-SIGNAL.html\">AR:SYSTEM-SIGNAL</a></p>"
+    ));
+    assert!(signal_kind
+        .contains("<tr><td>miniature test schema</td><td class=\"available\">yes</td></tr>"));
+
+    // the reference type lists the types that each DEST value may point to
+    let reference = read_page("types/AR_I-SIGNAL-SYSTEM-SIGNAL-REF-TYPE.html");
+    assert!(reference.contains(
+        "<tr><td class=\"name\">SYSTEM-SIGNAL</td><td>all</td><td><a href=\"AR_SYSTEM-SIGNAL.html\">AR:SYSTEM-SIGNAL</a></td></tr>"
+    ));
+    assert!(reference
+        .contains("<tr><td class=\"name\">SYSTEM-SIGNAL-GROUP</td><td>all</td><td></td></tr>"));
+}
+
+#[test]
+fn availability_csv_lists_the_elements() {
+    let (workdir, _) = run_single_mode_with_args("csv", &["--emit-csv=availability.csv"]);
+    let csv = std::fs::read_to_string(workdir.join("availability.csv")).unwrap();
+    let lines: Vec<&str> = csv.split_terminator("\r\n").collect();
+
+    // the version columns are headed by the descriptions from the configuration
+    assert_eq!(
+        lines[0],
+        "type,element,element type,multiplicity,splittable,restrict_std,miniature test schema"
+    );
+    assert!(lines
+        .contains(&"AR:AUTOSAR-AR-PACKAGES-TYPE,AR-PACKAGE,AR:AR-PACKAGE,1,yes,NotRestricted,yes"));
+    // the elements of the group SIGNAL-TIMING are listed as part of I-SIGNAL
+    assert!(lines.contains(&"AR:I-SIGNAL,CYCLE-TIME,AR:BOOLEAN,0..1,yes,NotRestricted,yes"));
+    assert!(
+        lines.contains(&"AR:SYSTEM-SIGNAL,SIGNAL-KIND,AR:SIGNAL-KIND,0..1,no,NotRestricted,yes")
+    );
+    assert!(!lines
+        .iter()
+        .any(|line| line.starts_with("AR:SIGNAL-TIMING,")));
+}
+
+#[test]
+fn rust_module_contains_all_modules() {
+    let (workdir, _) =
+        run_single_mode_with_args("rust-module", &["--emit-rust-module=generated.rs"]);
+    let generated = std::fs::read_to_string(workdir.join("generated.rs")).unwrap();
+
+    // all rust modules are in the combined file, and only the reports are written to gen/
+    assert!(!workdir.join("gen/specification.rs").exists());
+    assert!(workdir.join("gen/schema_stats.txt").exists());
+    for module in [
+        "autosarversion",
+        "hashfunc",
+        "elementname",
+        "attributename",
+        "enumitem",
+        "specification",
+        "paths",
+    ] {
+        assert!(
+            generated.contains(&format!("\n// ---- module {module} ----\n")),
+            "{module}"
+        );
+    }
+
+    // the header and the use declarations are only at the top, and the test modules have distinct names
+    assert!(generated.starts_with("// This file is @generated by autosar-xsd-mangler"));
+    assert_eq!(generated.matches("// This file is @generated").count(), 1);
+    assert_eq!(generated.matches("\nuse crate::*;\n").count(), 1);
+    assert!(!generated.contains("use crate::hashfunc::"));
+    assert!(!generated.contains("\nmod test {"));
+    assert!(generated.contains("\nmod hashfunc_test {"));
+    let first_item = generated.find("\n// ---- module ").unwrap();
+    assert!(!generated[first_item..].contains("\nuse "));
+}
+
+#[test]
+fn paths_module_compiles_without_std() {
+    // paths.rs only needs a few items of the specification, which are replaced by stubs in a no_std library
+    let workdir = run_single_mode("paths-no-std");
+    std::fs::copy(workdir.join("gen/paths.rs"), workdir.join("paths.rs")).unwrap();
+    std::fs::write(
+        workdir.join("lib.rs"),
+        r#"#![no_std]
+mod paths;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ElementName {
+    Autosar,
+}
+pub struct ElementDefinition {
+    pub name: ElementName,
+    pub elemtype: u16,
+}
+pub struct DataTypeSpec {
+    pub sub_elements: (u16, u16),
+}
+pub enum SubElement {
+    Element(u16),
+    Group(u16),
+}
+pub const AUTOSAR_ELEMENT: u16 = 0;
+pub static ELEMENTS: [ElementDefinition; 1] = [ElementDefinition { name: ElementName::Autosar, elemtype: 0 }];
+pub static DATATYPES: [DataTypeSpec; 1] = [DataTypeSpec { sub_elements: (0, 0) }];
+pub static SUBELEMENTS: [SubElement; 0] = [];
+"#,
+    )
+    .unwrap();
+
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args([
+            "--edition=2021",
+            "--crate-type=lib",
+            "-Adead_code",
+            "--out-dir",
+        ])
+        .arg(&*workdir)
+        .arg(workdir.join("lib.rs"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn find_paths_prints_the_paths() {
+    let (workdir, stdout) =
+        run_single_mode_with_args("find-paths", &["--find-paths=AR:SYSTEM-SIGNAL"]);
+
+    // SYSTEM-SIGNAL is only reachable through the elements of a package; nothing is generated
+    let paths: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| !line.contains(" paths from AR:AUTOSAR to AR:SYSTEM-SIGNAL"))
+        .take_while(|line| line.starts_with("    AR:") || line.contains(" paths from "))
+        .collect();
+    assert_eq!(
+        paths,
+        [
+            "1 paths from AR:AUTOSAR to AR:SYSTEM-SIGNAL, with at most 20 types:",
+            "    AR:AUTOSAR -> AR:AUTOSAR-AR-PACKAGES-TYPE -> AR:AR-PACKAGE -> AR:AR-PACKAGE-ELEMENTS-TYPE -> AR:SYSTEM-SIGNAL"
+        ],
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen/specification.rs").exists());
+}
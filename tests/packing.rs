@@ -0,0 +1,117 @@
+// the packing only depends on std and rustc-hash, so its module is compiled directly into this test
+#[path = "../src/generator/packing.rs"]
+#[allow(dead_code)]
+mod packing;
+
+use autosar_xsd_mangler::phases::{self, Input};
+use autosar_xsd_mangler::snapshot;
+use packing::{pack_sequences, SuffixArray};
+use proptest::prelude::*;
+use std::path::Path;
+
+// all start positions of the pattern in the text, found by comparing the pattern at every position
+fn brute_force_find(text: &[usize], pattern: &[usize]) -> Vec<usize> {
+    (0..text.len())
+        .filter(|pos| text[*pos..].starts_with(pattern))
+        .collect()
+}
+
+proptest! {
+    // the small alphabet makes repeated and overlapping occurrences likely
+    #[test]
+    fn suffix_array_finds_all_occurrences(
+        text in prop::collection::vec(0..3usize, 0..80),
+        pattern in prop::collection::vec(0..3usize, 1..6),
+    ) {
+        let suffix_array = SuffixArray::new(text.clone());
+        let mut found = suffix_array.find(&pattern).to_vec();
+        found.sort_unstable();
+        prop_assert_eq!(found, brute_force_find(&text, &pattern));
+    }
+
+    #[test]
+    fn packed_sequences_are_found_at_their_first_occurrence(
+        sequences in prop::collection::vec(prop::collection::vec(0..4usize, 0..8), 0..40),
+    ) {
+        let sequence_refs: Vec<&[usize]> = sequences.iter().map(Vec::as_slice).collect();
+        let (packed, offsets) = pack_sequences(&sequence_refs);
+        prop_assert_eq!(offsets.len(), sequences.len());
+        for (sequence, offset) in sequences.iter().zip(offsets) {
+            if sequence.is_empty() {
+                prop_assert_eq!(offset, 0);
+            } else {
+                prop_assert_eq!(Some(offset), brute_force_find(&packed, sequence).first().copied());
+            }
+        }
+
+        // sequences that are contained in another sequence don't need any space of their own
+        let mut unique: Vec<&[usize]> = sequence_refs.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        let required_len: usize = unique
+            .iter()
+            .filter(|seq| {
+                !unique.iter().any(|other| {
+                    other.len() > seq.len() && !brute_force_find(other, seq).is_empty()
+                })
+            })
+            .map(|seq| seq.len())
+            .sum();
+        prop_assert!(packed.len() <= required_len);
+    }
+}
+
+#[test]
+fn pack_versions_of_sub_elements_and_attributes() {
+    // [1, 3] is contained in [1, 1, 3], the attributes of B repeat the sub elements of A, and [3, 7] overlaps the end
+    // of [1, 1, 3]; empty lists are placed at the start
+    let subelement_sequences = vec![
+        ("A".to_string(), vec![1, 1, 3]),
+        ("B".to_string(), vec![1, 3]),
+        ("C".to_string(), vec![]),
+    ];
+    let attribute_sequences = vec![
+        ("A".to_string(), vec![3, 7]),
+        ("B".to_string(), vec![1, 1, 3]),
+    ];
+    let (versions, subelement_offsets, attribute_offsets) =
+        snapshot::pack_versions(&subelement_sequences, &attribute_sequences).unwrap();
+    assert_eq!(versions, [1, 1, 3, 7]);
+
+    assert_eq!(subelement_offsets.len(), 3);
+    assert_eq!(attribute_offsets.len(), 2);
+    for (sequences, offsets) in [
+        (&subelement_sequences, &subelement_offsets),
+        (&attribute_sequences, &attribute_offsets),
+    ] {
+        for (typename, sequence) in sequences {
+            let offset = offsets[typename];
+            assert_eq!(
+                &versions[offset..offset + sequence.len()],
+                sequence,
+                "{typename}"
+            );
+        }
+    }
+    assert_eq!(subelement_offsets["C"], 0);
+}
+
+#[test]
+fn packed_versions_of_mini_schema() {
+    // every list of versions of the miniature schema can be re-expanded from the packed array
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/single/mini.xsd");
+    let input = Input::find(&fixture).unwrap();
+    let parsed = phases::parse(&input).unwrap();
+    let flat = phases::flatten(&parsed).unwrap();
+    let mut schema = phases::merge(&flat).unwrap();
+    phases::dedup(&mut schema);
+
+    let sequences = snapshot::version_info_sequences(&schema).unwrap();
+    assert!(!sequences.is_empty());
+    let sequence_refs: Vec<&[usize]> = sequences.iter().map(Vec::as_slice).collect();
+    let (packed, offsets) = snapshot::pack_sequences(&sequence_refs);
+    assert!(packed.len() < sequences.iter().map(Vec::len).sum());
+    for (sequence, offset) in sequences.iter().zip(offsets) {
+        assert_eq!(&packed[offset..offset + sequence.len()], sequence);
+    }
+}
@@ -0,0 +1,112 @@
+// the perfect hash generator only depends on std, so its module is compiled directly into this test
+#[path = "../src/generator/perfect_hash.rs"]
+mod perfect_hash;
+
+use perfect_hash::{find_perfect_hash, get_index, DEFAULT_HASH_CONSTANTS};
+use proptest::prelude::*;
+use std::collections::HashSet;
+
+const FIXED_ITEMS: [&str; 12] = [
+    "AR-PACKAGE",
+    "AR-PACKAGES",
+    "AUTOSAR",
+    "CATEGORY",
+    "DYNAMIC-LENGTH",
+    "ELEMENTS",
+    "I-SIGNAL",
+    "LONG-NAME",
+    "SHORT-NAME",
+    "SYSTEM-SIGNAL",
+    "SYSTEM-SIGNAL-REF",
+    "UUID",
+];
+
+// check that a perfect hash is found and that get_index maps the items onto 0..len without collisions
+fn assert_bijection(items: &[&str]) {
+    let phash = find_perfect_hash(items).unwrap();
+    let indices: HashSet<usize> = items
+        .iter()
+        .map(|item| get_index(item, &phash, items.len()))
+        .collect();
+    assert_eq!(indices.len(), items.len());
+    assert!(indices.iter().all(|idx| *idx < items.len()));
+}
+
+proptest! {
+    #[test]
+    fn perfect_hash_is_bijective(items in prop::collection::hash_set("[ -~]{1,40}", 1..300)) {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        assert_bijection(&items);
+    }
+
+    // every size from 1 to 70 is tried, which includes the sizes just above and below a multiple of each lambda
+    #[test]
+    fn perfect_hash_at_bucket_boundaries(
+        items in (1..=70usize).prop_flat_map(|len| prop::collection::hash_set("[A-Z][A-Z0-9-]{0,30}", len))
+    ) {
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        assert_bijection(&items);
+    }
+
+    #[test]
+    fn perfect_hash_rejects_duplicates(
+        items in prop::collection::hash_set("[a-z]{1,12}", 1..50),
+        duplicate_idx in any::<prop::sample::Index>()
+    ) {
+        let mut items: Vec<&str> = items.iter().map(String::as_str).collect();
+        items.push(items[duplicate_idx.index(items.len())]);
+        let err = find_perfect_hash(&items).err().unwrap();
+        prop_assert!(err.contains("is not unique"), "{}", err);
+    }
+}
+
+// small tables whose size is a power of two used to fail with all fixed sets of hash constants
+#[test]
+fn perfect_hash_small_power_of_two_sizes() {
+    assert_bijection(&["C8", "Y-6Q-91Q--CE14---K-Z---H02Y9--7"]);
+
+    // xorshift, so that the sets are the same in every run
+    let mut state = 0x2545_F491_4F6C_DD1Du64;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for size in [2, 4, 8, 16] {
+        for _ in 0..500 {
+            let mut items = HashSet::new();
+            while items.len() < size {
+                let len = 1 + (next() % 20) as usize;
+                let item: String = (0..len)
+                    .map(|_| char::from(b' ' + (next() % 95) as u8))
+                    .collect();
+                items.insert(item);
+            }
+            let items: Vec<&str> = items.iter().map(String::as_str).collect();
+            assert_bijection(&items);
+        }
+    }
+}
+
+#[test]
+fn perfect_hash_is_stable() {
+    // the generated enums contain these values; a change of the hash function or of the search changes them
+    let phash = find_perfect_hash(&FIXED_ITEMS).unwrap();
+    assert_eq!(phash.constants, DEFAULT_HASH_CONSTANTS);
+    assert_eq!(phash.lambda, 2);
+    assert_eq!(
+        phash.disps,
+        [(1, 1), (0, 1), (0, 0), (0, 9), (0, 0), (1, 6)]
+    );
+    assert_eq!(
+        perfect_hash::hashfunc(b"SHORT-NAME", &DEFAULT_HASH_CONSTANTS),
+        (0xA1EF_40C5, 0xE28B_77A6, 0x4364_3763)
+    );
+
+    let indices: Vec<usize> = FIXED_ITEMS
+        .iter()
+        .map(|item| get_index(item, &phash, FIXED_ITEMS.len()))
+        .collect();
+    assert_eq!(indices, [0, 2, 6, 11, 8, 10, 7, 1, 4, 5, 3, 9]);
+}
@@ -0,0 +1,90 @@
+use autosar_xsd_mangler::snapshot;
+use std::fmt::Write;
+use std::path::Path;
+
+// ref targets of many data types, like in the real schema: most data types reference a few targets, many of them
+// use the same targets as another data type, and some use only a part of the targets of another data type
+fn ref_targets(type_count: usize, target_count: u64) -> Vec<Vec<String>> {
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    let mut ref_targets: Vec<Vec<String>> = Vec::with_capacity(type_count);
+    for idx in 0..type_count {
+        let targets = if idx > 0 && idx % 4 == 0 {
+            ref_targets[next() as usize % idx].clone()
+        } else if idx > 0 && idx % 5 == 0 {
+            let other = &ref_targets[next() as usize % idx];
+            other[other.len() / 2..].to_vec()
+        } else {
+            let len = next() % 7;
+            let mut targets: Vec<String> = (0..len)
+                .map(|_| format!("EnumItem::Target{}", next() % target_count))
+                .collect();
+            targets.sort();
+            targets.dedup();
+            targets
+        };
+        ref_targets.push(targets);
+    }
+    ref_targets
+}
+
+// the search of the generator before the ref strings were indexed, which scans all items for every data type
+fn linear_scan(ref_targets: &[Vec<String>]) -> (Vec<String>, Vec<(usize, usize)>) {
+    let mut all_refstrings = Vec::<String>::new();
+    let mut ranges = Vec::new();
+    for targets in ref_targets {
+        let mut namevec = targets.clone();
+        let range = if namevec.is_empty() {
+            (0, 0)
+        } else if let Some(existing_pos) = all_refstrings
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| **item == namevec[0])
+            .map(|(pos, _)| pos)
+            .find(|pos| all_refstrings[*pos..].starts_with(&namevec))
+        {
+            (existing_pos, existing_pos + namevec.len())
+        } else {
+            let len = namevec.len();
+            let pos = all_refstrings.len();
+            all_refstrings.append(&mut namevec);
+            (pos, pos + len)
+        };
+        ranges.push(range);
+    }
+    (all_refstrings, ranges)
+}
+
+#[test]
+fn ref_items_are_unchanged_by_the_index() {
+    let ref_targets = ref_targets(2000, 300);
+    assert_eq!(snapshot::ref_items(&ref_targets), linear_scan(&ref_targets));
+}
+
+// the REF_ITEMS and the ref_info ranges of a fixed input must stay byte-identical;
+// with BLESS=1 the golden file is replaced instead
+#[test]
+fn ref_items_golden() {
+    let (items, ranges) = snapshot::ref_items(&ref_targets(200, 40));
+    let mut output = String::new();
+    writeln!(output, "REF_ITEMS:").unwrap();
+    for item in &items {
+        writeln!(output, "    {item}").unwrap();
+    }
+    writeln!(output, "ref_info:").unwrap();
+    for (idx, (low, high)) in ranges.iter().enumerate() {
+        writeln!(output, "    {idx:3}: ({low}, {high})").unwrap();
+    }
+
+    let golden_file = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden/ref_items.txt");
+    if std::env::var_os("BLESS").is_some_and(|value| value == "1") {
+        std::fs::write(&golden_file, &output).unwrap();
+    } else {
+        assert_eq!(output, std::fs::read_to_string(&golden_file).unwrap());
+    }
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::{
+    element_last_versions, mini_xsd, read_generated, run_single_mode_with_args, run_versions,
+    without_element,
+};
+
+#[test]
+fn single_version_has_no_removed_elements() {
+    let (workdir, _) = run_single_mode_with_args("removed", &["--removed-report"]);
+
+    // with a single version, no element can have been removed
+    let report = read_generated(&workdir, "removed_elements.txt");
+    assert!(report.starts_with(
+        "# elements that are not part of the latest version miniature test schema\n# 0 elements\n"
+    ));
+    let specification = read_generated(&workdir, "specification.rs");
+    let last_version = specification
+        .lines()
+        .find(|line| line.starts_with("pub(crate) const ELEMENT_LAST_VERSION:"))
+        .unwrap();
+    let (_, values) = last_version.split_once(" = [").unwrap();
+    assert!(values
+        .trim_end_matches("];")
+        .split(", ")
+        .all(|value| value == "0x1"));
+}
+
+#[test]
+fn removed_report_of_several_versions() {
+    // SIGNAL-KIND is removed after r2, and SYSTEM-SIGNAL-REF is missing in r2, and removed after r3
+    let mini = mini_xsd();
+    let r2 = without_element(&mini, "SYSTEM-SIGNAL-REF");
+    let r3 = without_element(&mini, "SIGNAL-KIND");
+    let r4 = without_element(&r3, "SYSTEM-SIGNAL-REF");
+    let (workdir, stdout) = run_versions(
+        "removed-versions",
+        &[("r1", &mini), ("r2", &r2), ("r3", &r3), ("r4", &r4)],
+        &["--removed-report"],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    let report = read_generated(&workdir, "removed_elements.txt");
+    assert!(
+        report.starts_with("# elements that are not part of the latest version r4\n# 2 elements\n"),
+        "{report}"
+    );
+    assert!(
+        report.contains("SIGNAL-KIND (AR:SIGNAL-KIND): r1 - r2; last version: r2\n"),
+        "{report}"
+    );
+    assert!(
+        report.contains(
+            "SYSTEM-SIGNAL-REF (AR:I-SIGNAL-SYSTEM-SIGNAL-REF-TYPE): r1, r3; last version: r3\n"
+        ),
+        "{report}"
+    );
+
+    let specification = read_generated(&workdir, "specification.rs");
+    let last_versions = element_last_versions(&specification);
+    assert_eq!(last_versions.len(), 12, "{specification}");
+    for (name, last_version) in last_versions {
+        let expected = match name.as_str() {
+            "SignalKind" => "0x2",
+            "SystemSignalRef" => "0x4",
+            _ => "0x8",
+        };
+        assert_eq!(last_version, expected, "{name}");
+    }
+}
+
+#[test]
+fn deprecation_note_is_escaped() {
+    // STATIC-KIND is removed in the newer version, whose description contains a quote and a backslash
+    let older = mini_xsd();
+    let newer = older.replace("        <xsd:enumeration value=\"STATIC-KIND\"/>\n", "");
+    assert_ne!(older, newer);
+    let (workdir, stdout) = run_versions(
+        "deprecation-escaped",
+        &[
+            ("AUTOSAR R1", &older),
+            (r#"AUTOSAR R2 \"beta\" C:\\dir"#, &newer),
+        ],
+        &[],
+    );
+    assert!(!stdout.contains("Error"), "{stdout}");
+
+    // the attribute is split over several lines if the generated code is formatted
+    let enumitem = read_generated(&workdir, "enumitem.rs");
+    assert!(enumitem.contains("#[deprecated("), "{enumitem}");
+    assert!(
+        enumitem.contains(r#"since = "R2 \"beta\" C:\\dir""#),
+        "{enumitem}"
+    );
+    assert!(
+        enumitem.contains(r#"note = "Removed in AUTOSAR R2 \"beta\" C:\\dir""#),
+        "{enumitem}"
+    );
+}
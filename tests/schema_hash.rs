@@ -0,0 +1,132 @@
+mod common;
+
+use common::{fixture, read_generated, run_single_file, run_single_mode, TempDir};
+use sha2::Digest;
+use std::path::{Path, PathBuf};
+
+// the SCHEMA_HASH in the generated autosarversion.rs, formatted like the expected hash of sha256_of
+fn generated_schema_hash(workdir: &Path) -> String {
+    let autosarversion = read_generated(workdir, "autosarversion.rs");
+    let schema_hash = autosarversion
+        .split("pub const SCHEMA_HASH: [u8; 32] = [")
+        .nth(1)
+        .and_then(|rest| rest.split_once("];"))
+        .map(|(values, _)| values.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .unwrap();
+    schema_hash.trim_end_matches(',').to_string()
+}
+
+// the SHA-256 of the concatenated contents of `files`, formatted like the generated SCHEMA_HASH
+fn sha256_of(files: &[PathBuf]) -> String {
+    let mut hasher = sha2::Sha256::new();
+    for file in files {
+        hasher.update(std::fs::read(file).unwrap());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("0x{byte:02x}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[test]
+fn hash_of_a_single_file() {
+    let workdir = run_single_mode("schema-hash");
+
+    // with a single input file, the hash is the SHA-256 of its content
+    assert_eq!(
+        generated_schema_hash(&workdir),
+        sha256_of(&[fixture("single/mini.xsd")])
+    );
+}
+
+#[test]
+fn schema_hash_of_included_files() {
+    let (workdir, success, stdout) = run_single_file(
+        "schema-hash-redefine",
+        &fixture("redefine/redefine.xsd"),
+        &[],
+    );
+    assert!(success, "{stdout}");
+
+    // the included and the redefined file are hashed together with the input file, in the order of the file names
+    let files = [
+        fixture("single/mini.xsd"),
+        fixture("redefine/oem-types.xsd"),
+        fixture("redefine/redefine.xsd"),
+    ];
+    assert_eq!(generated_schema_hash(&workdir), sha256_of(&files));
+}
+
+#[test]
+fn cache_depends_on_the_included_files() {
+    // the redefine fixture is copied together with mini.xsd, so that the included file can be changed
+    let xsd_dir = TempDir::new("cache-xsd");
+    for (dir, filename) in [
+        ("redefine", "config.toml"),
+        ("redefine", "redefine.xsd"),
+        ("redefine", "oem-types.xsd"),
+        ("single", "mini.xsd"),
+    ] {
+        std::fs::create_dir_all(xsd_dir.join(dir)).unwrap();
+        std::fs::copy(
+            fixture(dir).join(filename),
+            xsd_dir.join(dir).join(filename),
+        )
+        .unwrap();
+    }
+    let redefine = xsd_dir.join("redefine/redefine.xsd");
+    let cache_arg = format!("--cache-file={}", xsd_dir.join("schema.cache").display());
+
+    let (_workdir, success, stdout) = run_single_file("cache-store", &redefine, &[&cache_arg]);
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("loaded the merged schema from the cache"));
+    let (_workdir, success, stdout) = run_single_file("cache-load", &redefine, &[&cache_arg]);
+    assert!(success, "{stdout}");
+    assert!(stdout.contains("loaded the merged schema from the cache"));
+
+    // the included file is changed, but it keeps its modification time, so only the hash can detect the change
+    let oem_types = xsd_dir.join("redefine/oem-types.xsd");
+    let mtime = std::fs::metadata(&oem_types).unwrap().modified().unwrap();
+    let changed = std::fs::read_to_string(&oem_types).unwrap().replace(
+        r#"<xsd:extension base="AR:STRING--SIMPLE"/>"#,
+        r#"<xsd:extension base="AR:STRING--SIMPLE"><xsd:attribute name="L" type="AR:STRING--SIMPLE"/></xsd:extension>"#,
+    );
+    std::fs::write(&oem_types, changed).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&oem_types)
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+
+    let (workdir, success, stdout) = run_single_file("cache-changed", &redefine, &[&cache_arg]);
+    assert!(success, "{stdout}");
+    assert!(
+        stdout.contains("does not match the input files"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("loaded the merged schema from the cache"));
+    assert!(read_generated(&workdir, "attributename.rs").contains("    L = "));
+}
+
+#[test]
+fn verbose_flatten_bypasses_the_cache() {
+    let cache_dir = TempDir::new("cache-verbose");
+    let cache_arg = format!("--cache-file={}", cache_dir.join("schema.cache").display());
+    let mini = fixture("single/mini.xsd");
+
+    let (_workdir, success, stdout) = run_single_file("cache-verbose-store", &mini, &[&cache_arg]);
+    assert!(success, "{stdout}");
+
+    // the flattening log is only written while the xsd files are loaded, so the cached schema is not used
+    let (_workdir, success, stdout) = run_single_file(
+        "cache-verbose-load",
+        &mini,
+        &[&cache_arg, "--verbose-flatten"],
+    );
+    assert!(success, "{stdout}");
+    assert!(!stdout.contains("loaded the merged schema from the cache"));
+    assert!(stdout.contains("element type AR:AUTOSAR -> "), "{stdout}");
+}
@@ -0,0 +1,158 @@
+mod common;
+
+use common::{
+    mini_xsd, read_generated, run_single_mode, run_single_mode_with_args, run_single_xsd,
+};
+
+#[test]
+fn single_schema_version() {
+    let workdir = run_single_mode("version");
+    let autosarversion = read_generated(&workdir, "autosarversion.rs");
+
+    // the version is taken from the config.toml next to the xsd file, and it is the only variant
+    assert!(autosarversion.contains("    Mini = 0x1,\n"));
+    assert_eq!(autosarversion.matches(" - xsd file name: ").count(), 1);
+    assert!(autosarversion.contains("pub const LATEST: AutosarVersion = AutosarVersion::Mini;"));
+    assert!(autosarversion.contains("pub const ALL_VERSIONS_MASK: u32 = 0x1;"));
+}
+
+#[test]
+fn single_schema_tables() {
+    let workdir = run_single_mode("tables");
+    let specification = read_generated(&workdir, "specification.rs");
+    let attributename = read_generated(&workdir, "attributename.rs");
+
+    // all version masks contain only the bit of the single version
+    let version_info = specification
+        .lines()
+        .find(|line| line.starts_with("pub(crate) static VERSION_INFO:"))
+        .unwrap();
+    let (_, values) = version_info.split_once(" = [").unwrap();
+    assert!(values
+        .trim_end_matches("];")
+        .split(", ")
+        .all(|value| value == "0x1"));
+
+    // the reference type is found, and the attributes of AR:AUTOSAR are added by the flattening
+    assert!(specification.contains("pub(crate) const REFERENCE_TYPE_IDX: u16 = "));
+    assert!(attributename.contains("    /// xmlns\n"));
+    assert!(attributename.contains("    /// xsi:schemaLocation\n"));
+}
+
+#[test]
+fn preserve_whitespace_string_constant() {
+    let workdir = run_single_mode("preserve-whitespace");
+    let specification = read_generated(&workdir, "specification.rs");
+
+    // the constant is the index of the only string type with preserve_whitespace in CHARACTER_DATA
+    let character_data: Vec<&str> = specification
+        .lines()
+        .skip_while(|line| !line.starts_with("pub(crate) const CHARACTER_DATA:"))
+        .skip(1)
+        .take_while(|line| *line != "];")
+        .collect();
+    let type_idx = character_data
+        .iter()
+        .position(|line| line.contains("String{preserve_whitespace: true, max_length: None}"))
+        .unwrap();
+    assert!(specification.contains(&format!(
+        "pub(crate) const STRING_PRESERVE_WHITESPACE_IDX: u16 = {type_idx};\n"
+    )));
+
+    // a schema without such a type can't be used
+    let xsd = mini_xsd().replace("      <xsd:whiteSpace value=\"preserve\"/>\n", "");
+    let (_workdir, success, stdout) = run_single_xsd("no-preserve-whitespace", &xsd, &[]);
+    assert!(!success);
+    assert!(stdout.contains(
+        "Error: the schema has no string type that preserves whitespace for the constant STRING_PRESERVE_WHITESPACE_IDX"
+    ), "{stdout}");
+}
+
+#[test]
+fn type_counts_are_printed() {
+    let (_workdir, stdout) = run_single_mode_with_args("counts", &[]);
+
+    // the counts of the parsed xsd file are printed after loading it
+    let counts = stdout
+        .lines()
+        .skip_while(|line| *line != "loading mini.xsd")
+        .nth(1)
+        .unwrap();
+    let numbers: Vec<usize> = counts
+        .split(", ")
+        .map(|part| part.trim().split_once(' ').unwrap().0.parse().unwrap())
+        .collect();
+    // 13 named simple and complex types, and the built-in base types
+    assert!(numbers[0] > 13, "{counts}");
+    // 3 named groups, and the groups of the anonymous sequences
+    assert!(numbers[1] >= 3, "{counts}");
+    assert_eq!(numbers[2..], [1, 1], "{counts}");
+}
+
+#[test]
+fn profile_lists_each_phase() {
+    let (_workdir, stdout) = run_single_mode_with_args("profile", &["--profile"]);
+
+    // each phase is listed once, in the order in which it ran
+    let (_, profile) = stdout.split_once("profile:\n").unwrap();
+    let phases: Vec<&str> = profile
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+    assert_eq!(
+        phases,
+        ["parse", "flatten", "merge", "dedup", "generate"],
+        "{stdout}"
+    );
+    assert!(profile.lines().all(|line| line.contains(" s, peak RSS ")));
+}
+
+#[test]
+fn stats_list_the_written_files() {
+    let (workdir, stdout) = run_single_mode_with_args("stats", &["--stats"]);
+
+    // all files are new in the fresh output directory
+    let files: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| *line != "written files:")
+        .skip(1)
+        .take_while(|line| line.starts_with("    "))
+        .map(str::trim)
+        .collect();
+    assert_eq!(files.len(), 10, "{stdout}");
+    assert!(files.contains(&"gen/specification.rs"), "{stdout}");
+    assert!(files.contains(&"gen/schema_stats.txt"), "{stdout}");
+    assert!(files.contains(&"gen/schema_stats.rs"), "{stdout}");
+    let total_bytes: usize = files
+        .iter()
+        .map(|file| std::fs::metadata(workdir.join(file)).unwrap().len() as usize)
+        .sum();
+    assert!(stdout.contains(&format!(
+        "10 files, {total_bytes} bytes; 0 existing files were replaced\n"
+    )));
+    // the mini schema contains 4 enum items: 2 signal kinds and 2 DEST values
+    assert!(
+        stdout.contains(" attribute names, 4 enum items\n"),
+        "{stdout}"
+    );
+
+    // the sizes of the arrays are computed from the item types by the generated test
+    let stats = read_generated(&workdir, "schema_stats.txt");
+    let size_test = read_generated(&workdir, "schema_stats.rs");
+    for line in stats
+        .lines()
+        .skip_while(|line| !line.starts_with("## array lengths"))
+        .skip(2)
+        .take_while(|line| !line.is_empty())
+    {
+        let (name, rest) = line.split_once(" (").unwrap();
+        let (filename, rest) = rest.split_once("): ").unwrap();
+        let (length, item_type) = rest.split_once(" x ").unwrap();
+        let entry = format!("(\"{name}\", \"{filename}\", {length}, size_of::<{item_type}>()),");
+        assert!(size_test.contains(&entry), "{entry}\n{size_test}");
+    }
+    assert!(
+        stats.contains("ELEMENTS (specification.rs): 12 x ElementDefinition\n"),
+        "{stats}"
+    );
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use autosar_xsd_mangler::snapshot::names_share_prefix;
+use common::{mini_xsd, run_single_xsd};
+
+// META-DATA with an anonymous type that contains DATA, which also has an anonymous type
+const NESTED_ANONYMOUS_TYPES: &str = r#"      <xsd:element name="META-DATA" minOccurs="0">
+        <xsd:complexType>
+          <xsd:sequence>
+            <xsd:element name="DATA" minOccurs="0">
+              <xsd:complexType>
+                <xsd:sequence>
+                  <xsd:element name="DYNAMIC-LENGTH" type="AR:BOOLEAN" minOccurs="0"/>
+                </xsd:sequence>
+              </xsd:complexType>
+            </xsd:element>
+          </xsd:sequence>
+        </xsd:complexType>
+      </xsd:element>
+"#;
+
+#[test]
+fn names_with_a_common_prefix() {
+    assert!(names_share_prefix("SW-COMPONENT", "SW-COMPONENT-TYPE"));
+    assert!(names_share_prefix("SW-COMPONENT-TYPE", "SW-COMPONENT"));
+    assert!(names_share_prefix("ELEMENTS", "ELEMENTS"));
+}
+
+#[test]
+fn substrings_are_not_a_common_prefix() {
+    // a substring check matched these
+    assert!(!names_share_prefix("DATA", "META-DATA"));
+    assert!(!names_share_prefix("META-DATA", "DATA"));
+    assert!(!names_share_prefix("SIGNAL", "I-SIGNAL"));
+}
+
+#[test]
+fn plural_names_share_a_prefix() {
+    assert!(names_share_prefix("AR-PACKAGE", "AR-PACKAGES"));
+    assert!(names_share_prefix("AR-PACKAGES", "AR-PACKAGE"));
+    assert!(names_share_prefix("ELEMENTS", "ELEMENT-REF"));
+    // only a single trailing S is a plural
+    assert!(!names_share_prefix("ADDRESS", "ADDRE"));
+}
+
+#[test]
+fn nested_anonymous_type_names() {
+    let anchor = "      <xsd:group ref=\"AR:SIGNAL-TIMING\" minOccurs=\"0\"/>\n";
+    let xsd = mini_xsd().replacen(anchor, &format!("{NESTED_ANONYMOUS_TYPES}{anchor}"), 1);
+    let (workdir, success, stdout) =
+        run_single_xsd("nested-types", &xsd, &["--emit-csv=availability.csv"]);
+    assert!(success, "{stdout}");
+
+    // the name of the type of DATA is built from the names of both containing elements, since META-DATA is not a
+    // longer form of DATA
+    let csv = std::fs::read_to_string(workdir.join("availability.csv")).unwrap();
+    assert!(
+        csv.contains("AR:I-SIGNAL,META-DATA,AR:I-SIGNAL-META-DATA-TYPE,0..1,"),
+        "{csv}"
+    );
+    assert!(
+        csv.contains("AR:I-SIGNAL-META-DATA-TYPE,DATA,AR:META-DATA-DATA-TYPE,0..1,"),
+        "{csv}"
+    );
+}
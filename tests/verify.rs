@@ -0,0 +1,123 @@
+mod common;
+
+use common::{fixture, mini_xsd, run_single_file, run_single_mode_with_args, run_single_xsd};
+
+#[test]
+fn verify_bad_patterns() {
+    // the fixture in tests/fixtures/verify is the miniature schema with a broken pattern and a pattern
+    // whose literal prefix is longer than the max_length of its type
+    let (workdir, success, stdout) =
+        run_single_file("verify", &fixture("verify/mini.xsd"), &["--verify"]);
+    assert!(!success, "{stdout}");
+    assert!(
+        stdout.contains(
+            "Error: 1 patterns failed to compile:\n    AR:BOOLEAN--SIMPLE: 0|1|(true|false\n"
+        ),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Warning: the pattern of AR:IDENTIFIER--SIMPLE only matches strings of at least 11 characters, but its max_length is 8"),
+        "{stdout}"
+    );
+    // --verify does not generate anything
+    assert!(!workdir.join("gen").exists());
+}
+
+#[test]
+fn verify_good_patterns() {
+    let (_workdir, stdout) = run_single_mode_with_args("verify-ok", &["--verify"]);
+    assert!(stdout.contains("verification passed"), "{stdout}");
+    assert!(!stdout.contains("Warning: the pattern"), "{stdout}");
+}
+
+#[test]
+fn validate_only() {
+    // the duplicate DYNAMIC-LENGTH of SYSTEM-SIGNAL fails the validation, unless the duplicates are merged, and the
+    // DEST value SYSTEM-SIGNAL-GROUP without a matching element type is a warning, which fails the validation as well
+    let (workdir, success, stdout) = run_single_file(
+        "validate-only",
+        &fixture("single/mini.xsd"),
+        &["--validate-only"],
+    );
+    assert!(!success, "{stdout}");
+    assert!(
+        stdout.contains("the element DYNAMIC-LENGTH appears twice"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Warning: the DEST value SYSTEM-SIGNAL-GROUP"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains(
+            "Error: the validation failed, the merged schema has 1 problems and there were 1 warnings"
+        ),
+        "{stdout}"
+    );
+    assert!(!workdir.join("gen").exists());
+
+    let (_workdir, success, stdout) = run_single_file(
+        "validate-only-merged",
+        &fixture("single/mini.xsd"),
+        &["--validate-only", "--merge-duplicates"],
+    );
+    assert!(!success, "{stdout}");
+    assert!(
+        stdout.contains(
+            "Error: the validation failed, the merged schema has 0 problems and there were 1 warnings"
+        ),
+        "{stdout}"
+    );
+
+    // without the DEST value, the schema with merged duplicates is valid
+    let without_dest = mini_xsd().replace(
+        "      <xsd:enumeration value=\"SYSTEM-SIGNAL-GROUP\"/>\n",
+        "",
+    );
+    let (workdir, success, stdout) = run_single_xsd(
+        "validate-only-valid",
+        &without_dest,
+        &["--validate-only", "--merge-duplicates"],
+    );
+    assert!(success, "{stdout}");
+    assert!(stdout.contains("validation successful"), "{stdout}");
+    assert!(!workdir.join("gen").exists());
+}
+
+#[test]
+fn validate_only_checks_the_patterns() {
+    let (_workdir, success, stdout) = run_single_file(
+        "validate-only-patterns",
+        &fixture("verify/mini.xsd"),
+        &["--validate-only", "--merge-duplicates"],
+    );
+    assert!(!success, "{stdout}");
+    assert!(
+        stdout.contains("Error: 1 patterns failed to compile:"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn validate_only_rejects_other_modes() {
+    for option in [
+        "--verify",
+        "--find-paths=AR:SYSTEM-SIGNAL",
+        "--emit-csv=a.csv",
+    ] {
+        let (_workdir, success, stdout) = run_single_file(
+            "validate-only-conflict",
+            &fixture("single/mini.xsd"),
+            &["--validate-only", option],
+        );
+        assert!(!success, "{stdout}");
+        let option_name = option.split('=').next().unwrap();
+        assert!(
+            stdout.contains(&format!(
+                "Error: --validate-only can't be combined with {option_name}"
+            )),
+            "{stdout}"
+        );
+        assert!(!stdout.contains("loading"), "{stdout}");
+    }
+}